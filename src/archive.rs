@@ -0,0 +1,389 @@
+//! Session files inside `.tar`, `.tar.gz`/`.tgz` and `.zip` archives.
+//!
+//! A member is addressed with a virtual path `archive.tar.gz!/path/inside`.
+//! Providers read sessions from the filesystem, so [`extract`] streams that
+//! member's directory — Kiro's `<id>.json` / `<id>.jsonl` pair, a Cline task
+//! directory — into a temporary directory that lives as long as the returned
+//! [`Extracted`]. [`scan_sessions`] visits every JSON/JSONL member in one
+//! pass, keeping a single member on disk at a time. Members keep their path
+//! inside the archive, so readers that rely on the layout (Gemini's `chats/`,
+//! Cline's `tasks/<id>/`) work unchanged. Nothing else in the archive is
+//! written out.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tempfile::TempDir;
+
+/// Separates the archive path from the member path in a virtual path.
+pub const MEMBER_SEPARATOR: &str = "!/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(Format::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(Format::Tar)
+    } else if name.ends_with(".zip") {
+        Some(Format::Zip)
+    } else {
+        None
+    }
+}
+
+/// Whether `path` names a supported archive (by extension).
+pub fn is_archive(path: &Path) -> bool {
+    format_of(path).is_some()
+}
+
+/// Split `archive.tar.gz!/path/inside` into the archive and member path.
+pub fn split_virtual_path(path: &Path) -> Option<(PathBuf, String)> {
+    let text = path.to_str()?;
+    let (archive, member) = text.split_once(MEMBER_SEPARATOR)?;
+    let archive = PathBuf::from(archive);
+    if !is_archive(&archive) || member.is_empty() {
+        return None;
+    }
+    Some((archive, normalize_member(member)))
+}
+
+/// The virtual path of `member` inside `archive`.
+pub fn virtual_path(archive: &Path, member: &str) -> PathBuf {
+    PathBuf::from(format!("{}{MEMBER_SEPARATOR}{member}", archive.display()))
+}
+
+/// Member paths as written by `tar -C ~ .` and friends: `./.claude/x` and
+/// `/.claude/x` both become `.claude/x`.
+fn normalize_member(name: &str) -> String {
+    let name = name.replace('\\', "/");
+    let mut name = name.as_str();
+    loop {
+        if let Some(rest) = name.strip_prefix("./") {
+            name = rest;
+        } else if let Some(rest) = name.strip_prefix('/') {
+            name = rest;
+        } else {
+            break;
+        }
+    }
+    name.to_string()
+}
+
+/// Members that may hold a session: JSON and JSONL files.
+fn is_session_member(member: &str) -> bool {
+    let lower = member.to_ascii_lowercase();
+    lower.ends_with(".jsonl") || lower.ends_with(".json")
+}
+
+fn file_name(member: &str) -> &str {
+    member.rsplit('/').next().unwrap_or(member)
+}
+
+fn parent(member: &str) -> &str {
+    member.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Where `member` goes under an extraction directory, or `None` when its
+/// path would escape it (a `..` component).
+fn extraction_path(dir: &Path, member: &str) -> Option<PathBuf> {
+    let mut path = dir.to_path_buf();
+    for component in member.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => return None,
+            name => path.push(name),
+        }
+    }
+    Some(path)
+}
+
+/// Copy `reader` to `path`, creating its parent directories.
+fn write_member(path: &Path, reader: &mut dyn Read) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = File::create(path)?;
+    std::io::copy(reader, &mut out)?;
+    Ok(())
+}
+
+/// Visit every regular file in the archive in order, with a reader over its
+/// contents. `visit` returns `false` to stop early.
+pub(crate) fn for_each_member(
+    archive: &Path,
+    visit: &mut dyn FnMut(&str, &mut dyn Read) -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    let format = format_of(archive).with_context(|| {
+        format!(
+            "{} is not a .tar, .tar.gz or .zip archive",
+            archive.display()
+        )
+    })?;
+    let file = File::open(archive)
+        .with_context(|| format!("failed to open archive {}", archive.display()))?;
+    match format {
+        Format::Tar => visit_tar(tar::Archive::new(BufReader::new(file)), visit),
+        Format::TarGz => visit_tar(
+            tar::Archive::new(flate2::read::GzDecoder::new(BufReader::new(file))),
+            visit,
+        ),
+        Format::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+            for index in 0..zip.len() {
+                let mut entry = zip.by_index(index)?;
+                if !entry.is_file() {
+                    continue;
+                }
+                let name = normalize_member(entry.name());
+                if !visit(&name, &mut entry)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+    .with_context(|| format!("failed to read archive {}", archive.display()))
+}
+
+fn visit_tar<R: Read>(
+    mut archive: tar::Archive<R>,
+    visit: &mut dyn FnMut(&str, &mut dyn Read) -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = normalize_member(&entry.path()?.to_string_lossy());
+        if !visit(&name, &mut entry)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Session-file members whose file name contains `session_id`.
+pub fn find_session(archive: &Path, session_id: &str) -> anyhow::Result<Vec<String>> {
+    let mut found = Vec::new();
+    for_each_member(archive, &mut |member, _| {
+        if is_session_member(member) && file_name(member).contains(session_id) {
+            found.push(member.to_string());
+        }
+        Ok(true)
+    })?;
+    Ok(found)
+}
+
+/// An archive member extracted to a temporary directory, removed on drop.
+#[derive(Debug)]
+pub struct Extracted {
+    /// The archive the member came from.
+    pub archive: PathBuf,
+    /// The extracted member on disk.
+    pub path: PathBuf,
+    /// `archive!/member`.
+    pub virtual_path: PathBuf,
+    _dir: TempDir,
+}
+
+/// Extract `member` and everything else in its directory (and below it), in
+/// one pass over the archive, at their paths inside the archive.
+pub fn extract(archive: &Path, member: &str) -> anyhow::Result<Extracted> {
+    let member = normalize_member(member);
+    let dir = tempfile::Builder::new().prefix("casr-archive-").tempdir()?;
+    let path = extraction_path(dir.path(), &member)
+        .with_context(|| format!("archive member '{member}' escapes the archive"))?;
+    let session_dir = match parent(&member) {
+        "" => String::new(),
+        dir => format!("{dir}/"),
+    };
+    let mut found = false;
+    for_each_member(archive, &mut |name, reader| {
+        if !name.starts_with(&session_dir) {
+            return Ok(true);
+        }
+        let Some(out) = extraction_path(dir.path(), name) else {
+            return Ok(true);
+        };
+        write_member(&out, reader)?;
+        found |= name == member;
+        Ok(true)
+    })?;
+    if !found {
+        anyhow::bail!("no member '{member}' in archive {}", archive.display());
+    }
+    Ok(Extracted {
+        archive: archive.to_path_buf(),
+        path,
+        virtual_path: virtual_path(archive, &member),
+        _dir: dir,
+    })
+}
+
+/// Visit every JSON/JSONL member with a temporary copy of it on disk, at its
+/// path inside the archive, in one pass. Each copy is deleted before the
+/// next member is read.
+pub fn scan_sessions(archive: &Path, mut visit: impl FnMut(&str, &Path)) -> anyhow::Result<()> {
+    let dir = tempfile::Builder::new().prefix("casr-archive-").tempdir()?;
+    for_each_member(archive, &mut |member, reader| {
+        if !is_session_member(member) {
+            return Ok(true);
+        }
+        let Some(path) = extraction_path(dir.path(), member) else {
+            return Ok(true);
+        };
+        write_member(&path, reader)?;
+        visit(member, &path);
+        std::fs::remove_file(&path)?;
+        Ok(true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_tar_gz(path: &Path, files: &[(&str, &str)]) {
+        let gz = flate2::write::GzEncoder::new(
+            File::create(path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn virtual_paths_split_on_the_archive_separator() {
+        let (archive, member) =
+            split_virtual_path(Path::new("/b/laptop.tar.gz!/./.claude/projects/p/s.jsonl"))
+                .unwrap();
+        assert_eq!(archive, PathBuf::from("/b/laptop.tar.gz"));
+        assert_eq!(member, ".claude/projects/p/s.jsonl");
+        assert_eq!(
+            virtual_path(&archive, &member),
+            PathBuf::from("/b/laptop.tar.gz!/.claude/projects/p/s.jsonl")
+        );
+        assert_eq!(split_virtual_path(Path::new("/b/notes.txt!/x")), None);
+        assert!(is_archive(Path::new("snap.TGZ")) && is_archive(Path::new("snap.zip")));
+    }
+
+    #[test]
+    fn extract_writes_the_member_directory_at_its_archive_path() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("kiro.tar.gz");
+        write_tar_gz(
+            &archive,
+            &[
+                ("./.kiro/sessions/cli/abc.json", "{\"meta\":1}"),
+                ("./.kiro/sessions/cli/abc.jsonl", "{\"line\":1}\n"),
+                ("./.kiro/sessions/cli/abc/notes.json", "{}"),
+                ("./.kiro/sessions/other.json", "{}"),
+            ],
+        );
+        assert_eq!(
+            find_session(&archive, "abc").unwrap(),
+            vec![
+                ".kiro/sessions/cli/abc.json",
+                ".kiro/sessions/cli/abc.jsonl"
+            ]
+        );
+
+        let extracted = extract(&archive, ".kiro/sessions/cli/abc.json").unwrap();
+        assert!(extracted.path.ends_with(".kiro/sessions/cli/abc.json"));
+        let dir = extracted.path.parent().unwrap().to_path_buf();
+        assert_eq!(
+            std::fs::read_to_string(&extracted.path).unwrap(),
+            "{\"meta\":1}"
+        );
+        assert!(dir.join("abc.jsonl").is_file());
+        assert!(dir.join("abc/notes.json").is_file());
+        assert!(!dir.parent().unwrap().join("other.json").exists());
+        drop(extracted);
+        assert!(!dir.exists(), "temporary extraction is removed on drop");
+
+        let err = extract(&archive, ".kiro/missing.json").unwrap_err();
+        assert!(err.to_string().contains("no member"), "{err}");
+        let err = extract(&archive, ".kiro/../../etc/passwd.json").unwrap_err();
+        assert!(err.to_string().contains("escapes"), "{err}");
+    }
+
+    #[test]
+    fn scan_sessions_keeps_same_named_members_apart() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("cline.tar.gz");
+        write_tar_gz(
+            &archive,
+            &[
+                ("tasks/1/api_conversation_history.json", "[1]"),
+                ("tasks/2/api_conversation_history.json", "[2]"),
+            ],
+        );
+        let mut seen = Vec::new();
+        scan_sessions(&archive, |member, path| {
+            assert!(path.ends_with(member));
+            seen.push(std::fs::read_to_string(path).unwrap());
+        })
+        .unwrap();
+        assert_eq!(seen, ["[1]", "[2]"]);
+    }
+
+    #[test]
+    fn scan_sessions_visits_json_members_of_a_zip() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("gemini.zip");
+        write_zip(
+            &archive,
+            &[
+                (".gemini/tmp/h/chats/session-1.json", "{\"a\":1}"),
+                (".gemini/settings.txt", "x"),
+                (".gemini/tmp/h/chats/session-2.json", "{\"b\":2}"),
+            ],
+        );
+        let mut seen = Vec::new();
+        scan_sessions(&archive, |member, path| {
+            seen.push((member.to_string(), std::fs::read_to_string(path).unwrap()));
+        })
+        .unwrap();
+        assert_eq!(
+            seen,
+            vec![
+                (
+                    ".gemini/tmp/h/chats/session-1.json".to_string(),
+                    "{\"a\":1}".to_string()
+                ),
+                (
+                    ".gemini/tmp/h/chats/session-2.json".to_string(),
+                    "{\"b\":2}".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,672 @@
+//! `casr backup` / `casr restore`: snapshots of provider session stores.
+//!
+//! A backup is a `.tar.gz` holding every file under each provider's session
+//! roots plus its SQLite databases, copied through the SQLite backup API so a
+//! store that's open in a running agent is captured consistently. The last
+//! member, [`MANIFEST_NAME`], lists each file's original path, size and
+//! sha256. Members are stored under `<provider>/<original absolute path>`,
+//! so `casr list --root backup.tar.gz` can read them like any archive.
+//!
+//! Restore checks every checksum before writing anything, refuses manifest
+//! entries that would land outside their provider's session roots and
+//! databases, refuses to overwrite files that differ from the backup unless
+//! forced, and writes through [`atomic_write`], which keeps a `.bak` of
+//! anything it replaces.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use crate::error::CasrError;
+use crate::pipeline::{atomic_write, find_backup_path};
+use crate::providers::Provider;
+
+/// Name of the manifest member, written last.
+pub const MANIFEST_NAME: &str = "casr-backup.json";
+/// Manifest layout version.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// SQLite sidecar files, superseded by the consistent copy of their database.
+const SQLITE_SIDECARS: &[&str] = &["-wal", "-shm", "-journal"];
+
+/// Contents of a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub casr_version: String,
+    /// Epoch milliseconds.
+    pub created_at: i64,
+    /// Home directory at backup time; restore re-bases paths under it onto
+    /// the current home.
+    pub home: Option<PathBuf>,
+    pub entries: Vec<BackupEntry>,
+}
+
+/// One file in a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// Provider slug.
+    pub provider: String,
+    /// Where the file lived.
+    pub path: PathBuf,
+    /// Archive member holding it.
+    pub member: String,
+    pub size: u64,
+    /// Lowercase hex sha256 of the contents.
+    pub sha256: String,
+    /// A SQLite database copied through the backup API.
+    #[serde(default)]
+    pub sqlite: bool,
+}
+
+impl BackupManifest {
+    /// Per-provider `(files, bytes)` totals.
+    pub fn totals(&self) -> BTreeMap<String, (usize, u64)> {
+        let mut totals: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+        for entry in &self.entries {
+            let total = totals.entry(entry.provider.clone()).or_default();
+            total.0 += 1;
+            total.1 += entry.size;
+        }
+        totals
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// `<provider>/<path without root or prefix>`.
+fn member_name(provider: &str, path: &Path) -> String {
+    let parts: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            Component::Prefix(prefix) => Some(
+                prefix
+                    .as_os_str()
+                    .to_string_lossy()
+                    .replace([':', '\\'], ""),
+            ),
+            _ => None,
+        })
+        .collect();
+    format!("{provider}/{}", parts.join("/"))
+}
+
+/// Files to back up for `provider`: everything under its session roots and
+/// its databases. Returns `(path, is_sqlite)`, sorted.
+fn provider_files(provider: &dyn Provider) -> Vec<(PathBuf, bool)> {
+    let databases: BTreeSet<PathBuf> = provider.database_files().into_iter().collect();
+    let is_sidecar = |path: &Path| {
+        let name = path.to_string_lossy();
+        SQLITE_SIDECARS.iter().any(|suffix| {
+            name.strip_suffix(suffix)
+                .is_some_and(|db| databases.contains(Path::new(db)))
+        })
+    };
+
+    let mut files: BTreeSet<PathBuf> = databases.clone();
+    for root in provider.session_roots() {
+        for entry in walkdir::WalkDir::new(&root)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            if entry.file_type().is_file() && !is_sidecar(entry.path()) {
+                files.insert(entry.into_path());
+            }
+        }
+    }
+    files
+        .into_iter()
+        .map(|path| {
+            let sqlite = databases.contains(&path);
+            (path, sqlite)
+        })
+        .collect()
+}
+
+/// Read a database through the SQLite backup API into memory.
+fn sqlite_snapshot(db: &Path, scratch: &Path) -> anyhow::Result<Vec<u8>> {
+    let conn = rusqlite::Connection::open_with_flags(
+        db,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let copy = scratch.join("snapshot.db");
+    let _ = std::fs::remove_file(&copy);
+    conn.backup(rusqlite::DatabaseName::Main, &copy, None)?;
+    let bytes = std::fs::read(&copy)?;
+    std::fs::remove_file(&copy)?;
+    Ok(bytes)
+}
+
+/// Write a backup of `providers` to `output` (gzip-compressed tar).
+/// Providers with nothing on disk are skipped.
+pub fn create_backup(
+    providers: &[&dyn Provider],
+    output: &Path,
+    force: bool,
+) -> anyhow::Result<BackupManifest> {
+    if output.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            output.display()
+        );
+    }
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let scratch = tempfile::Builder::new().prefix("casr-backup-").tempdir()?;
+    let temp_output = output.with_file_name(format!(
+        ".casr-tmp-{}",
+        uuid::Uuid::new_v4().as_hyphenated()
+    ));
+
+    let write = || -> anyhow::Result<BackupManifest> {
+        let gz = flate2::write::GzEncoder::new(
+            File::create(&temp_output)?,
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        let mut manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            casr_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            home: crate::homes::home_dir(),
+            entries: Vec::new(),
+        };
+
+        for provider in providers {
+            for (path, sqlite) in provider_files(*provider) {
+                let bytes = if sqlite {
+                    sqlite_snapshot(&path, scratch.path())
+                } else {
+                    std::fs::read(&path).map_err(anyhow::Error::from)
+                };
+                // Files can vanish between the walk and the read (an agent
+                // rotating its logs); skip them rather than failing the backup.
+                let bytes = match bytes {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "skipping unreadable file");
+                        continue;
+                    }
+                };
+                let member = member_name(provider.slug(), &path);
+                append(&mut tar, &member, &bytes)?;
+                debug!(member, size = bytes.len(), "backed up");
+                manifest.entries.push(BackupEntry {
+                    provider: provider.slug().to_string(),
+                    path,
+                    member,
+                    size: bytes.len() as u64,
+                    sha256: sha256_hex(&bytes),
+                    sqlite,
+                });
+            }
+        }
+
+        append(
+            &mut tar,
+            MANIFEST_NAME,
+            &serde_json::to_vec_pretty(&manifest)?,
+        )?;
+        tar.into_inner()?.finish()?.sync_all()?;
+        Ok(manifest)
+    };
+
+    let manifest = write().inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_output);
+    })?;
+    std::fs::rename(&temp_output, output)
+        .with_context(|| format!("failed to move backup into place at {}", output.display()))?;
+    info!(
+        output = %output.display(),
+        files = manifest.entries.len(),
+        "backup written"
+    );
+    Ok(manifest)
+}
+
+fn append<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    member: &str,
+    bytes: &[u8],
+) -> anyhow::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, member, bytes)?;
+    Ok(())
+}
+
+/// Read a backup's manifest and check every member against its checksum.
+pub fn read_manifest(archive: &Path) -> anyhow::Result<BackupManifest> {
+    let mut manifest: Option<BackupManifest> = None;
+    let mut digests: BTreeMap<String, String> = BTreeMap::new();
+    crate::archive::for_each_member(archive, &mut |member, reader| {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if member == MANIFEST_NAME {
+            manifest =
+                Some(serde_json::from_slice(&bytes).with_context(|| {
+                    format!("invalid {MANIFEST_NAME} in {}", archive.display())
+                })?);
+        } else {
+            digests.insert(member.to_string(), sha256_hex(&bytes));
+        }
+        Ok(true)
+    })?;
+
+    let manifest = manifest.with_context(|| {
+        format!(
+            "{} is not a casr backup (no {MANIFEST_NAME})",
+            archive.display()
+        )
+    })?;
+    if manifest.format_version > FORMAT_VERSION {
+        anyhow::bail!(
+            "{} was written by a newer casr (backup format {}); upgrade casr to restore it",
+            archive.display(),
+            manifest.format_version
+        );
+    }
+    for entry in &manifest.entries {
+        match digests.get(&entry.member) {
+            None => anyhow::bail!(
+                "{} is incomplete: member {} is missing",
+                archive.display(),
+                entry.member
+            ),
+            Some(digest) if *digest != entry.sha256 => anyhow::bail!(
+                "{} is corrupt: checksum mismatch for {}",
+                archive.display(),
+                entry.member
+            ),
+            Some(_) => {}
+        }
+    }
+    Ok(manifest)
+}
+
+/// Options for [`restore`].
+#[derive(Debug, Clone, Default)]
+pub struct RestoreOptions {
+    /// Only these provider slugs (all when empty).
+    pub providers: Vec<String>,
+    /// Only files whose name contains one of these session IDs (all when
+    /// empty). SQLite stores hold many sessions and are never selected this way.
+    pub sessions: Vec<String>,
+    /// Replace files that differ from the backup, keeping a `.bak`.
+    pub force: bool,
+    pub dry_run: bool,
+}
+
+/// What restore did (or would do) with one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreAction {
+    /// The file was missing and was written.
+    Restored,
+    /// The file differed and was replaced; the old one is kept as `.bak`.
+    Replaced,
+    /// The file already matches the backup.
+    Unchanged,
+}
+
+/// One restored file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestoredFile {
+    pub provider: String,
+    pub path: PathBuf,
+    pub action: RestoreAction,
+    /// Where a replaced file was moved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<PathBuf>,
+}
+
+/// Where `entry` goes on this machine: its original path, re-based onto
+/// the current home when it lived under the backup's home.
+pub fn restore_path(manifest: &BackupManifest, entry: &BackupEntry) -> PathBuf {
+    if let (Some(old_home), Some(home)) = (&manifest.home, crate::homes::home_dir())
+        && let Ok(rel) = entry.path.strip_prefix(old_home)
+    {
+        return home.join(rel);
+    }
+    entry.path.clone()
+}
+
+/// Refuse a restore target that escapes `entry`'s provider storage. The
+/// manifest comes from the archive, so a crafted one could otherwise point
+/// `atomic_write` at any file the user can write.
+fn check_restore_target(
+    providers: &[&dyn Provider],
+    entry: &BackupEntry,
+    target: &Path,
+) -> anyhow::Result<()> {
+    if target
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        anyhow::bail!(
+            "backup entry {} has a path with `..` components: {}",
+            entry.member,
+            target.display()
+        );
+    }
+    let Some(provider) = providers.iter().find(|p| p.slug() == entry.provider) else {
+        anyhow::bail!(
+            "backup entry {} belongs to unknown provider {:?}",
+            entry.member,
+            entry.provider
+        );
+    };
+    let contained = provider
+        .storage_locations()
+        .iter()
+        .any(|location| target.starts_with(location));
+    if !contained {
+        anyhow::bail!(
+            "backup entry {} would restore outside {}'s session storage: {}",
+            entry.member,
+            provider.slug(),
+            target.display()
+        );
+    }
+    Ok(())
+}
+
+fn file_matches(path: &Path, sha256: &str) -> bool {
+    std::fs::read(path).is_ok_and(|bytes| sha256_hex(&bytes) == sha256)
+}
+
+/// Restore files from a `casr backup` archive. `providers` are the known
+/// providers whose current storage locations bound where entries may go.
+///
+/// Every checksum is verified, every destination checked against its
+/// provider's storage, and every destination checked for conflicts before
+/// anything is written.
+pub fn restore(
+    archive: &Path,
+    providers: &[&dyn Provider],
+    opts: &RestoreOptions,
+) -> anyhow::Result<Vec<RestoredFile>> {
+    let manifest = read_manifest(archive)?;
+    // One hostile entry means the whole manifest can't be trusted.
+    for entry in &manifest.entries {
+        check_restore_target(providers, entry, &restore_path(&manifest, entry))?;
+    }
+    let selected: Vec<&BackupEntry> = manifest
+        .entries
+        .iter()
+        .filter(|e| opts.providers.is_empty() || opts.providers.contains(&e.provider))
+        .filter(|e| {
+            opts.sessions.is_empty()
+                || (!e.sqlite
+                    && e.path
+                        .file_name()
+                        .map(|n| n.to_string_lossy())
+                        .is_some_and(|name| opts.sessions.iter().any(|id| name.contains(id))))
+        })
+        .collect();
+
+    // Plan first: a conflict must not leave a half-restored store behind.
+    let mut planned: Vec<(&BackupEntry, PathBuf, RestoreAction)> = Vec::new();
+    for entry in selected {
+        let target = restore_path(&manifest, entry);
+        let action = if !target.exists() {
+            RestoreAction::Restored
+        } else if file_matches(&target, &entry.sha256) {
+            RestoreAction::Unchanged
+        } else if opts.force {
+            RestoreAction::Replaced
+        } else {
+            return Err(CasrError::SessionConflict {
+                session_id: target
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                existing_path: target,
+            }
+            .into());
+        };
+        planned.push((entry, target, action));
+    }
+
+    let mut results: Vec<RestoredFile> = planned
+        .iter()
+        .map(|(entry, target, action)| RestoredFile {
+            provider: entry.provider.clone(),
+            path: target.clone(),
+            action: *action,
+            backup_path: None,
+        })
+        .collect();
+    if opts.dry_run {
+        return Ok(results);
+    }
+
+    let to_write: BTreeMap<&str, usize> = planned
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, action))| *action != RestoreAction::Unchanged)
+        .map(|(index, (entry, _, _))| (entry.member.as_str(), index))
+        .collect();
+    crate::archive::for_each_member(archive, &mut |member, reader| {
+        let Some(&index) = to_write.get(member) else {
+            return Ok(true);
+        };
+        let (entry, target, _) = &planned[index];
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if entry.sqlite {
+            // A stale WAL would be replayed over the restored database.
+            for suffix in SQLITE_SIDECARS {
+                let mut sidecar = target.as_os_str().to_os_string();
+                sidecar.push(suffix);
+                let sidecar = PathBuf::from(sidecar);
+                if sidecar.exists() {
+                    std::fs::rename(&sidecar, find_backup_path(&sidecar))?;
+                }
+            }
+        }
+        let outcome = atomic_write(target, &bytes, opts.force, &entry.provider)?;
+        results[index].backup_path = outcome.backup_path;
+        debug!(path = %target.display(), "restored");
+        Ok(true)
+    })?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::claude_code::ClaudeCode;
+    use crate::providers::opencode::OpenCode;
+
+    fn seed_home(home: &Path) -> (PathBuf, rusqlite::Connection) {
+        let session = home.join(".claude/projects/-work-app/sess-1.jsonl");
+        std::fs::create_dir_all(session.parent().unwrap()).unwrap();
+        std::fs::write(&session, "{\"line\":1}\n").unwrap();
+
+        let db = home.join(".opencode/opencode.db");
+        std::fs::create_dir_all(db.parent().unwrap()).unwrap();
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL; CREATE TABLE session(id TEXT); INSERT INTO session VALUES ('s1');",
+        )
+        .unwrap();
+        // The caller keeps the connection open so the row lives only in the WAL.
+        (session, conn)
+    }
+
+    #[test]
+    fn backup_then_restore_round_trips_files_and_databases() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let output = tmp.path().join("snap.tar.gz");
+        let (session, conn) = seed_home(&home);
+
+        crate::homes::scoped(Some(&home), || {
+            let providers: Vec<&dyn Provider> = vec![&ClaudeCode, &OpenCode];
+            let manifest = create_backup(&providers, &output, false).unwrap();
+            let totals = manifest.totals();
+            assert_eq!(totals["claude-code"].0, 1);
+            let db_entry = manifest
+                .entries
+                .iter()
+                .find(|e| e.provider == "opencode")
+                .expect("database backed up");
+            assert!(db_entry.sqlite);
+            assert!(create_backup(&providers, &output, false).is_err());
+
+            // The snapshot holds the committed row even though it was in the WAL.
+            drop(conn);
+            std::fs::remove_dir_all(&home).unwrap();
+            let restored = restore(&output, &providers, &RestoreOptions::default()).unwrap();
+            assert!(restored.iter().all(|r| r.action == RestoreAction::Restored));
+            let conn = rusqlite::Connection::open(home.join(".opencode/opencode.db")).unwrap();
+            let id: String = conn
+                .query_row("SELECT id FROM session", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(id, "s1");
+            assert_eq!(std::fs::read_to_string(&session).unwrap(), "{\"line\":1}\n");
+
+            // Changed files conflict unless forced; matching ones are left alone.
+            std::fs::write(&session, "changed\n").unwrap();
+            let opts = RestoreOptions {
+                sessions: vec!["sess-1".to_string()],
+                ..RestoreOptions::default()
+            };
+            let err = restore(&output, &providers, &opts).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<CasrError>(),
+                Some(CasrError::SessionConflict { .. })
+            ));
+            let restored = restore(
+                &output,
+                &providers,
+                &RestoreOptions {
+                    force: true,
+                    ..opts.clone()
+                },
+            )
+            .unwrap();
+            assert_eq!(restored.len(), 1, "session filter skips the database");
+            assert_eq!(restored[0].action, RestoreAction::Replaced);
+            let bak = restored[0].backup_path.as_ref().unwrap();
+            assert_eq!(std::fs::read_to_string(bak).unwrap(), "changed\n");
+            let again = restore(&output, &providers, &opts).unwrap();
+            assert_eq!(again[0].action, RestoreAction::Unchanged);
+        });
+    }
+
+    #[test]
+    fn read_manifest_rejects_tampered_members() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("bad.tar.gz");
+        let gz = flate2::write::GzEncoder::new(
+            File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        append(&mut tar, "claude-code/x.jsonl", b"tampered").unwrap();
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            casr_version: "0".to_string(),
+            created_at: 0,
+            home: None,
+            entries: vec![BackupEntry {
+                provider: "claude-code".to_string(),
+                path: PathBuf::from("/x.jsonl"),
+                member: "claude-code/x.jsonl".to_string(),
+                size: 8,
+                sha256: sha256_hex(b"original"),
+                sqlite: false,
+            }],
+        };
+        append(
+            &mut tar,
+            MANIFEST_NAME,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let err = read_manifest(&archive).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn restore_rejects_entries_outside_provider_storage() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let claude_root = home.join(".claude/projects");
+        let hostile = [
+            home.join(".ssh/authorized_keys"),
+            claude_root.join("../../../etc/x"),
+        ];
+
+        crate::homes::scoped(Some(&home), || {
+            let providers: Vec<&dyn Provider> = vec![&ClaudeCode, &OpenCode];
+            for (i, path) in hostile.iter().enumerate() {
+                let archive = tmp.path().join(format!("hostile-{i}.tar.gz"));
+                let gz = flate2::write::GzEncoder::new(
+                    File::create(&archive).unwrap(),
+                    flate2::Compression::default(),
+                );
+                let mut tar = tar::Builder::new(gz);
+                let payload = b"ssh-ed25519 AAAA attacker";
+                append(&mut tar, "claude-code/safe.jsonl", b"{}\n").unwrap();
+                append(&mut tar, "claude-code/x", payload).unwrap();
+                let manifest = BackupManifest {
+                    format_version: FORMAT_VERSION,
+                    casr_version: "0".to_string(),
+                    created_at: 0,
+                    home: Some(home.clone()),
+                    entries: vec![
+                        BackupEntry {
+                            provider: "claude-code".to_string(),
+                            path: claude_root.join("-work/safe.jsonl"),
+                            member: "claude-code/safe.jsonl".to_string(),
+                            size: 3,
+                            sha256: sha256_hex(b"{}\n"),
+                            sqlite: false,
+                        },
+                        BackupEntry {
+                            provider: "claude-code".to_string(),
+                            path: path.clone(),
+                            member: "claude-code/x".to_string(),
+                            size: payload.len() as u64,
+                            sha256: sha256_hex(payload),
+                            sqlite: false,
+                        },
+                    ],
+                };
+                append(
+                    &mut tar,
+                    MANIFEST_NAME,
+                    &serde_json::to_vec(&manifest).unwrap(),
+                )
+                .unwrap();
+                tar.into_inner().unwrap().finish().unwrap();
+
+                let err = restore(&archive, &providers, &RestoreOptions::default()).unwrap_err();
+                assert!(err.to_string().contains("claude-code/x"), "{err}");
+                // Nothing is written, not even the legitimate entry.
+                assert!(!home.exists(), "restore wrote before rejecting");
+                assert!(!tmp.path().join("etc/x").exists());
+            }
+        });
+    }
+}
@@ -17,7 +17,7 @@ use tracing::{debug, info, trace, warn};
 
 use crate::error::{Candidate, CasrError};
 use crate::model::{CanonicalSession, MessageRole};
-use crate::providers::Provider;
+use crate::providers::{Provider, ReadOptions};
 
 // ---------------------------------------------------------------------------
 // Source hint — parsed from `--source` CLI flag
@@ -35,13 +35,15 @@ pub enum SourceHint {
 impl SourceHint {
     /// Parse a `--source` value into a hint.
     ///
-    /// Heuristic: if the value contains a path separator or starts with `.`/`~`/`/`,
-    /// treat it as a path. Otherwise, treat it as a provider alias.
+    /// Heuristic: if the value contains a path separator, starts with `.`/`~`/`/`
+    /// or names an archive (`.tar`, `.tar.gz`, `.zip`), treat it as a path.
+    /// Otherwise, treat it as a provider alias.
     pub fn parse(value: &str) -> Self {
         if value.contains(std::path::MAIN_SEPARATOR)
             || value.starts_with('.')
             || value.starts_with('~')
             || value.starts_with('/')
+            || crate::archive::is_archive(Path::new(value))
         {
             // Expand leading `~/` to the user's home directory.
             let expanded = if let Some(rest) = value.strip_prefix("~/") {
@@ -70,6 +72,34 @@ pub struct ResolvedSession<'a> {
     pub provider: &'a dyn Provider,
     /// Path to the native session file.
     pub path: PathBuf,
+    /// The alternate home (`--home`) the session was found under; `None` for
+    /// the current user's home.
+    pub home: Option<PathBuf>,
+    /// The archive member `path` was extracted from; the extraction is
+    /// removed when this is dropped.
+    pub archive: Option<crate::archive::Extracted>,
+}
+
+impl ResolvedSession<'_> {
+    /// Where the session came from when that isn't the current user's home:
+    /// the `--home` tree or the archive.
+    pub fn origin(&self) -> Option<&Path> {
+        self.home
+            .as_deref()
+            .or_else(|| self.archive.as_ref().map(|a| a.archive.as_path()))
+    }
+
+    /// Read the session, inside its alternate home's scope. Archive members
+    /// report their `archive!/member` path as `source_path`.
+    pub fn read(&self, opts: &ReadOptions) -> anyhow::Result<CanonicalSession> {
+        let mut session = crate::homes::scoped(self.home.as_deref(), || {
+            self.provider.read_session_with(&self.path, opts)
+        })?;
+        if let Some(archive) = &self.archive {
+            session.source_path = archive.virtual_path.clone();
+        }
+        Ok(session)
+    }
 }
 
 impl std::fmt::Debug for ResolvedSession<'_> {
@@ -77,6 +107,8 @@ impl std::fmt::Debug for ResolvedSession<'_> {
         f.debug_struct("ResolvedSession")
             .field("provider", &self.provider.slug())
             .field("path", &self.path)
+            .field("home", &self.home)
+            .field("archive", &self.archive.as_ref().map(|a| &a.virtual_path))
             .finish()
     }
 }
@@ -88,12 +120,28 @@ impl std::fmt::Debug for ResolvedSession<'_> {
 /// Central registry of all known providers.
 pub struct ProviderRegistry {
     providers: Vec<Box<dyn Provider>>,
+    homes: Vec<PathBuf>,
 }
 
 impl ProviderRegistry {
     /// Create a registry with all known providers.
     pub fn new(providers: Vec<Box<dyn Provider>>) -> Self {
-        Self { providers }
+        Self {
+            providers,
+            homes: Vec::new(),
+        }
+    }
+
+    /// Also search these alternate home trees (see [`crate::homes`]) when a
+    /// session isn't found under the current user's home.
+    pub fn with_homes(mut self, homes: Vec<PathBuf>) -> Self {
+        self.homes = homes;
+        self
+    }
+
+    /// Alternate home trees searched after the current user's home.
+    pub fn homes(&self) -> &[PathBuf] {
+        &self.homes
     }
 
     /// Create the default registry with all built-in providers.
@@ -188,16 +236,57 @@ impl ProviderRegistry {
     /// 4. Exactly one match → return it.
     /// 5. Multiple matches → [`CasrError::AmbiguousSessionId`].
     /// 6. No matches → [`CasrError::SessionNotFound`] with diagnostics.
+    ///
+    /// Steps 2–6 run against the current user's home first, then against each
+    /// alternate home in order; the first home with a match wins. Archives
+    /// (as a path hint or a home) are searched by member file name.
     pub fn resolve_session(
         &self,
         session_id: &str,
         source_hint: Option<&SourceHint>,
     ) -> Result<ResolvedSession<'_>, CasrError> {
-        match source_hint {
+        let resolve = || match source_hint {
             Some(SourceHint::Path(path)) => self.resolve_from_path(session_id, path),
             Some(SourceHint::Alias(alias)) => self.resolve_with_alias(session_id, alias),
             None => self.resolve_auto(session_id),
+        };
+        let local = resolve();
+        if !matches!(local, Err(CasrError::SessionNotFound { .. }))
+            || matches!(source_hint, Some(SourceHint::Path(_)))
+        {
+            return local;
+        }
+        for home in &self.homes {
+            if crate::archive::is_archive(home) {
+                let alias_provider = match source_hint {
+                    Some(SourceHint::Alias(alias)) => self.find_by_alias(alias),
+                    _ => None,
+                };
+                match self.resolve_in_archive(session_id, home) {
+                    Ok(resolved)
+                        if alias_provider.is_none_or(|p| p.slug() == resolved.provider.slug()) =>
+                    {
+                        return Ok(resolved);
+                    }
+                    Ok(_) | Err(CasrError::SessionNotFound { .. }) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            match crate::homes::scoped(Some(home), resolve) {
+                Ok(mut resolved) => {
+                    info!(
+                        home = %home.display(),
+                        session_id,
+                        "resolved session under alternate home"
+                    );
+                    resolved.home = Some(home.clone());
+                    return Ok(resolved);
+                }
+                Err(CasrError::SessionNotFound { .. }) => continue,
+                Err(e) => return Err(e),
+            }
         }
+        local
     }
 
     /// Resolve by direct file path — bypass all discovery.
@@ -211,6 +300,13 @@ impl ProviderRegistry {
     ) -> Result<ResolvedSession<'_>, CasrError> {
         debug!(path = %path.display(), "resolving session from explicit path");
 
+        if let Some((archive, member)) = crate::archive::split_virtual_path(path) {
+            return self.resolve_archive_member(session_id, &archive, &member);
+        }
+        if crate::archive::is_archive(path) && path.is_file() {
+            return self.resolve_in_archive(session_id, path);
+        }
+
         // Some providers use "virtual" session paths that are not real files, e.g.
         // `<db-file>/<session-id>` where the *parent* is the real file.
         let parent_is_file = path.parent().is_some_and(|p| p.is_file());
@@ -223,20 +319,30 @@ impl ProviderRegistry {
             });
         }
 
-        // Try to identify the owning provider by checking session roots.
-        for provider in &self.providers {
-            for root in provider.session_roots() {
-                if path.starts_with(&root) {
-                    info!(
-                        provider = provider.name(),
-                        path = %path.display(),
-                        "resolved session from explicit path"
-                    );
-                    return Ok(ResolvedSession {
-                        provider: provider.as_ref(),
-                        path: path.to_path_buf(),
-                    });
-                }
+        // Try to identify the owning provider by checking session roots, in
+        // the current user's home and then in each alternate home.
+        let homes = std::iter::once(None).chain(self.homes.iter().map(Some));
+        for home in homes {
+            let owner = crate::homes::scoped(home.map(PathBuf::as_path), || {
+                self.providers.iter().find(|provider| {
+                    provider
+                        .session_roots()
+                        .iter()
+                        .any(|root| path.starts_with(root))
+                })
+            });
+            if let Some(provider) = owner {
+                info!(
+                    provider = provider.name(),
+                    path = %path.display(),
+                    "resolved session from explicit path"
+                );
+                return Ok(ResolvedSession {
+                    provider: provider.as_ref(),
+                    path: path.to_path_buf(),
+                    home: home.cloned(),
+                    archive: None,
+                });
             }
         }
 
@@ -254,6 +360,8 @@ impl ProviderRegistry {
             return Ok(ResolvedSession {
                 provider,
                 path: path.to_path_buf(),
+                home: None,
+                archive: None,
             });
         }
 
@@ -300,6 +408,8 @@ impl ProviderRegistry {
             return Ok(ResolvedSession {
                 provider,
                 path: path.to_path_buf(),
+                home: None,
+                archive: None,
             });
         }
 
@@ -312,6 +422,128 @@ impl ProviderRegistry {
         })
     }
 
+    /// Resolve `session_id` inside an archive: find the session-file members
+    /// named after it, then identify each one's provider like a direct path.
+    /// When none is, fall back to reading the members whose signature a
+    /// provider recognizes, for files not named after their ID (Gemini's
+    /// `session-<date>-<short>.json`, Cline's `api_conversation_history.json`).
+    fn resolve_in_archive(
+        &self,
+        session_id: &str,
+        archive: &Path,
+    ) -> Result<ResolvedSession<'_>, CasrError> {
+        let archive_error = |e: anyhow::Error| CasrError::SessionReadError {
+            path: archive.to_path_buf(),
+            provider: "(archive)".to_string(),
+            detail: format!("{e:#}"),
+        };
+        let members = crate::archive::find_session(archive, session_id).map_err(archive_error)?;
+        debug!(
+            archive = %archive.display(),
+            ?members,
+            "archive members matching session ID"
+        );
+
+        let mut matches: Vec<ResolvedSession<'_>> = Vec::new();
+        for member in &members {
+            match self.resolve_archive_member(session_id, archive, member) {
+                Ok(resolved) => matches.push(resolved),
+                Err(e) => trace!(member, error = %e, "archive member is not a session"),
+            }
+        }
+        if matches.is_empty() {
+            for member in &self
+                .archive_members_by_content(session_id, archive)
+                .map_err(archive_error)?
+            {
+                match self.resolve_archive_member(session_id, archive, member) {
+                    Ok(resolved) => matches.push(resolved),
+                    Err(e) => trace!(member, error = %e, "archive member is not a session"),
+                }
+            }
+        }
+        // Kiro's `<id>.json` and `<id>.jsonl` are one session.
+        let stem = |m: &ResolvedSession<'_>| {
+            m.archive
+                .as_ref()
+                .map(|a| a.virtual_path.with_extension(""))
+        };
+        matches.dedup_by(|a, b| a.provider.slug() == b.provider.slug() && stem(a) == stem(b));
+
+        match matches.len() {
+            0 => Err(CasrError::SessionNotFound {
+                session_id: session_id.to_string(),
+                providers_checked: vec![format!("(archive {})", archive.display())],
+                sessions_scanned: members.len(),
+            }),
+            1 => Ok(matches.remove(0)),
+            _ => Err(CasrError::AmbiguousSessionId {
+                session_id: session_id.to_string(),
+                candidates: matches
+                    .iter()
+                    .map(|m| Candidate {
+                        provider: m.provider.slug().to_string(),
+                        path: m
+                            .archive
+                            .as_ref()
+                            .map_or_else(|| m.path.clone(), |a| a.virtual_path.clone()),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Session-file members of `archive` that a provider recognizes by
+    /// signature and reads as `session_id`.
+    fn archive_members_by_content(
+        &self,
+        session_id: &str,
+        archive: &Path,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut found = Vec::new();
+        crate::archive::scan_sessions(archive, |member, path| {
+            let Some(provider) = self.infer_provider_for_path(path) else {
+                return;
+            };
+            if provider
+                .read_session(path)
+                .is_ok_and(|session| session.session_id == session_id)
+            {
+                found.push(member.to_string());
+            }
+        })?;
+        debug!(
+            archive = %archive.display(),
+            ?found,
+            "archive members whose content matches session ID"
+        );
+        Ok(found)
+    }
+
+    /// Extract one archive member and identify its provider by its file
+    /// signature, as for any path outside the provider roots.
+    fn resolve_archive_member(
+        &self,
+        session_id: &str,
+        archive: &Path,
+        member: &str,
+    ) -> Result<ResolvedSession<'_>, CasrError> {
+        let extracted =
+            crate::archive::extract(archive, member).map_err(|e| CasrError::SessionReadError {
+                path: crate::archive::virtual_path(archive, member),
+                provider: "(archive)".to_string(),
+                detail: format!("{e:#}"),
+            })?;
+        let mut resolved = self.resolve_from_path(session_id, &extracted.path)?;
+        info!(
+            provider = resolved.provider.name(),
+            path = %extracted.virtual_path.display(),
+            "resolved session inside archive"
+        );
+        resolved.archive = Some(extracted);
+        Ok(resolved)
+    }
+
     /// Resolve by alias hint — only search the specified provider.
     fn resolve_with_alias(
         &self,
@@ -338,7 +570,12 @@ impl ProviderRegistry {
                     session_id,
                     "resolved session via alias hint"
                 );
-                Ok(ResolvedSession { provider, path })
+                Ok(ResolvedSession {
+                    provider,
+                    path,
+                    home: None,
+                    archive: None,
+                })
             }
             None => {
                 let roots: Vec<String> = provider
@@ -411,7 +648,12 @@ impl ProviderRegistry {
                     session_id,
                     "unique session match"
                 );
-                Ok(ResolvedSession { provider, path })
+                Ok(ResolvedSession {
+                    provider,
+                    path,
+                    home: None,
+                    archive: None,
+                })
             }
             _ => {
                 let candidates: Vec<Candidate> = matches
@@ -471,7 +713,9 @@ fn is_plausible_session(session: &CanonicalSession) -> bool {
 }
 
 impl ProviderRegistry {
-    fn infer_provider_for_path(&self, path: &Path) -> Option<&dyn Provider> {
+    /// Guess the provider of a session file from its extension and content
+    /// signature, without parsing it fully.
+    pub fn infer_provider_for_path(&self, path: &Path) -> Option<&dyn Provider> {
         let ext = path.extension()?.to_str()?.to_ascii_lowercase();
         match ext.as_str() {
             "vscdb" => return self.find_by_slug("cursor"),
@@ -558,6 +802,14 @@ impl ProviderRegistry {
                 if value.get("session").is_some() {
                     return self.find_by_slug("codex");
                 }
+
+                // Cline: a task's message array under `tasks/<taskId>/`.
+                if value.is_array()
+                    && path.file_name().and_then(|n| n.to_str())
+                        == Some("api_conversation_history.json")
+                {
+                    return self.find_by_slug("cline");
+                }
             }
             _ => {}
         }
@@ -565,6 +817,51 @@ impl ProviderRegistry {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Session enumeration
+// ---------------------------------------------------------------------------
+
+/// Candidate session files under a provider's session roots, for providers
+/// without a `list_sessions()` index.
+pub fn walk_session_files(provider: &dyn Provider) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for root in provider.session_roots() {
+        let walker = walkdir::WalkDir::new(&root)
+            .max_depth(4)
+            .into_iter()
+            .filter_map(Result::ok);
+
+        for entry in walker {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let ext = path.extension().and_then(|e| e.to_str());
+            if matches!(
+                ext,
+                Some("jsonl")
+                    | Some("json")
+                    | Some("vscdb")
+                    | Some("md")
+                    | Some("db")
+                    | Some("sqlite")
+            ) {
+                paths.push(path.to_path_buf());
+            }
+        }
+    }
+    paths
+}
+
+/// Every session file a provider can see: its `list_sessions()` index when it
+/// has one, otherwise the candidates under its session roots.
+pub fn session_files(provider: &dyn Provider) -> Vec<PathBuf> {
+    match provider.list_sessions() {
+        Some(listed) => listed.into_iter().map(|(_, path)| path).collect(),
+        None => walk_session_files(provider),
+    }
+}
+
 /// Result of probing a provider for installation.
 #[derive(Debug, Clone)]
 pub struct DetectionResult {
@@ -700,10 +997,14 @@ mod tests {
             idx,
             role,
             content: "x".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }
     }
@@ -720,6 +1021,8 @@ mod tests {
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -792,6 +1095,14 @@ mod tests {
         assert_eq!(infer_slug_for_file(tmp.path()).as_deref(), Some("gemini"));
     }
 
+    #[test]
+    fn infer_provider_for_path_json_cline_task_history() {
+        let dir = tempfile::tempdir().expect("tmp");
+        let path = dir.path().join("api_conversation_history.json");
+        std::fs::write(&path, r#"[{"role":"user","content":"hi"}]"#).expect("write");
+        assert_eq!(infer_slug_for_file(&path).as_deref(), Some("cline"));
+    }
+
     #[test]
     fn infer_provider_for_path_json_chatgpt_mapping() {
         let mut tmp = tempfile::NamedTempFile::with_suffix(".json").expect("tmp");
@@ -0,0 +1,361 @@
+//! Environment and data health checks (`casr doctor`).
+//!
+//! Explains why a provider shows no sessions: not detected, storage
+//! missing or unreadable, files that fail to parse, or a damaged SQLite
+//! database. The CLI enumerates session files (the same way `list` does)
+//! and hands them to [`diagnose`] together with the provider's detection.
+//!
+//! The parse sample also totals what readers skipped as unrecognized, which
+//! is the first sign that a provider changed its format.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+
+use crate::discovery::DetectionResult;
+use crate::model::Unrecognized;
+use crate::providers::Provider;
+
+/// Parse errors kept per provider.
+const MAX_PARSE_ERRORS: usize = 3;
+
+/// Environment variable that relocates each provider's storage, for hints.
+const HOME_ENV: &[(&str, &str)] = &[
+    ("claude-code", "CLAUDE_HOME"),
+    ("codex", "CODEX_HOME"),
+    ("gemini", "GEMINI_HOME"),
+    ("antigravity", "GEMINI_HOME"),
+    ("cursor", "CURSOR_HOME"),
+    ("cline", "CLINE_HOME"),
+    ("aider", "AIDER_HOME"),
+    ("amp", "AMP_HOME"),
+    ("opencode", "OPENCODE_HOME"),
+    ("chatgpt", "CHATGPT_HOME"),
+    ("clawdbot", "CLAWDBOT_HOME"),
+    ("vibe", "VIBE_HOME"),
+    ("factory", "FACTORY_HOME"),
+    ("openclaw", "OPENCLAW_HOME"),
+    ("pi-agent", "PI_AGENT_HOME"),
+    ("kiro", "KIRO_HOME"),
+];
+
+/// Overall verdict for one provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Sessions found and everything checked out.
+    Ok,
+    /// Nothing to check: no storage or no sessions.
+    NoData,
+    /// Unreadable storage, parse failures, or a damaged database.
+    Problems,
+}
+
+/// Filesystem state of one session root.
+#[derive(Debug, Clone, Serialize)]
+pub struct RootCheck {
+    pub path: PathBuf,
+    pub exists: bool,
+    pub readable: bool,
+    pub writable: bool,
+}
+
+/// Outcome of parsing a sample of the provider's sessions.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ParseSample {
+    pub sampled: usize,
+    pub parsed: usize,
+    /// First few failures as `path: error`.
+    pub errors: Vec<String>,
+    /// Sampled sessions with at least one unrecognized construct.
+    pub with_unrecognized: usize,
+    /// Unrecognized constructs summed over the sample.
+    #[serde(skip_serializing_if = "Unrecognized::is_empty")]
+    pub unrecognized: Unrecognized,
+}
+
+/// `PRAGMA integrity_check` result for one database.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseCheck {
+    pub path: PathBuf,
+    pub ok: bool,
+    /// `"ok"`, or the integrity errors / open failure.
+    pub detail: String,
+}
+
+/// Everything `doctor` found out about one provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderDiagnosis {
+    pub name: String,
+    pub slug: String,
+    /// Alternate home (`--home`) the provider was diagnosed under; absent
+    /// for the current user's home.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<PathBuf>,
+    pub status: HealthStatus,
+    pub installed: bool,
+    pub version: Option<String>,
+    pub evidence: Vec<String>,
+    pub roots: Vec<RootCheck>,
+    pub session_files: usize,
+    pub parse: ParseSample,
+    pub databases: Vec<DatabaseCheck>,
+    /// Concrete next steps for each problem found.
+    pub hints: Vec<String>,
+}
+
+/// Diagnose `provider` from its detection result and its session paths,
+/// most recent first. Parses at most `sample_size` of them.
+pub fn diagnose(
+    provider: &dyn Provider,
+    detection: DetectionResult,
+    session_paths: &[PathBuf],
+    sample_size: usize,
+) -> ProviderDiagnosis {
+    let roots: Vec<RootCheck> = provider
+        .session_roots()
+        .iter()
+        .map(|root| check_root(root))
+        .collect();
+
+    let mut parse = ParseSample::default();
+    for path in session_paths.iter().take(sample_size) {
+        parse.sampled += 1;
+        match provider.read_session(path) {
+            Ok(session) => {
+                parse.parsed += 1;
+                if let Some(ref unrecognized) = session.unrecognized {
+                    parse.with_unrecognized += 1;
+                    parse.unrecognized.merge(unrecognized);
+                }
+            }
+            Err(e) if parse.errors.len() < MAX_PARSE_ERRORS => {
+                parse.errors.push(format!("{}: {e:#}", path.display()));
+            }
+            Err(_) => {}
+        }
+    }
+
+    let databases: Vec<DatabaseCheck> = provider
+        .database_files()
+        .iter()
+        .map(|db| check_database(db))
+        .collect();
+
+    let mut diagnosis = ProviderDiagnosis {
+        name: provider.name().to_string(),
+        slug: provider.slug().to_string(),
+        home: crate::homes::active(),
+        status: HealthStatus::NoData,
+        installed: detection.installed,
+        version: detection.version,
+        evidence: detection.evidence,
+        roots,
+        session_files: session_paths.len(),
+        parse,
+        databases,
+        hints: Vec::new(),
+    };
+    diagnosis.hints = hints(&diagnosis);
+    let problems = diagnosis.roots.iter().any(|r| r.exists && !r.readable)
+        || diagnosis.parse.parsed < diagnosis.parse.sampled
+        || diagnosis.databases.iter().any(|db| !db.ok);
+    diagnosis.status = if problems {
+        HealthStatus::Problems
+    } else if diagnosis.session_files == 0 {
+        HealthStatus::NoData
+    } else {
+        HealthStatus::Ok
+    };
+    diagnosis
+}
+
+/// Whether `path` exists and can be listed (or opened) and modified.
+pub fn check_root(path: &Path) -> RootCheck {
+    let metadata = std::fs::metadata(path).ok();
+    let readable = match &metadata {
+        Some(m) if m.is_dir() => std::fs::read_dir(path).is_ok(),
+        Some(_) => std::fs::File::open(path).is_ok(),
+        None => false,
+    };
+    RootCheck {
+        path: path.to_path_buf(),
+        exists: metadata.is_some(),
+        readable,
+        writable: metadata.is_some_and(|m| !m.permissions().readonly()),
+    }
+}
+
+/// Open `path` read-only and run `PRAGMA integrity_check`.
+pub fn check_database(path: &Path) -> DatabaseCheck {
+    let result =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).and_then(|conn| {
+            conn.busy_timeout(std::time::Duration::from_secs(5))?;
+            let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<Result<Vec<String>, _>>()
+        });
+    let (ok, detail) = match result {
+        Ok(rows) if rows == ["ok"] => (true, "ok".to_string()),
+        Ok(rows) => (false, rows.join("; ")),
+        Err(e) => (false, e.to_string()),
+    };
+    DatabaseCheck {
+        path: path.to_path_buf(),
+        ok,
+        detail,
+    }
+}
+
+/// Remediation hints for the problems in `d`.
+fn hints(d: &ProviderDiagnosis) -> Vec<String> {
+    let env = HOME_ENV
+        .iter()
+        .find(|(slug, _)| *slug == d.slug)
+        .map(|(_, var)| *var);
+    // Env overrides don't apply to an alternate home; see `crate::homes`.
+    let relocate = match env.filter(|_| d.home.is_none()) {
+        Some(var) => format!("set {var} to the directory that holds its data"),
+        None => "check where it stores its data".to_string(),
+    };
+    let mut hints = Vec::new();
+
+    if !d.roots.iter().any(|r| r.exists) {
+        if d.installed {
+            hints.push(format!(
+                "{} is installed but no session storage was found; if it lives elsewhere, {relocate}.",
+                d.name
+            ));
+        } else {
+            hints.push(format!(
+                "{} is not detected (no binary or data directory). If you use it, {relocate}.",
+                d.name
+            ));
+        }
+    } else if d.session_files == 0 {
+        hints.push(format!(
+            "No {} sessions found yet; start one in {} first.",
+            d.name, d.name
+        ));
+    }
+    if !d.installed && d.session_files > 0 {
+        hints.push(format!(
+            "{} has sessions on disk but is not detected as installed, so `casr list` skips it; \
+install its CLI (or add it to PATH) and use `casr info <id> --source {}` meanwhile.",
+            d.name, d.slug
+        ));
+    }
+    for root in d.roots.iter().filter(|r| r.exists) {
+        if !root.readable {
+            hints.push(format!(
+                "Cannot read {}; fix its permissions (e.g. `chmod u+rx`) or run casr as its owner.",
+                root.path.display()
+            ));
+        } else if !root.writable {
+            hints.push(format!(
+                "{} is read-only: casr can read these sessions, but resuming into {} will fail.",
+                root.path.display(),
+                d.name
+            ));
+        }
+    }
+    let failed = d.parse.sampled - d.parse.parsed;
+    if failed > 0 {
+        hints.push(format!(
+            "{failed} of {} sampled session(s) failed to parse; {} may have changed its format. \
+Please report it with the errors above and `casr selftest` output.",
+            d.parse.sampled, d.name
+        ));
+    }
+    if d.parse.with_unrecognized > 0 {
+        hints.push(format!(
+            "{} of {} parsed session(s) contain constructs casr skips; {} may have added \
+new event or content types. Please report the unrecognized list above.",
+            d.parse.with_unrecognized, d.parse.parsed, d.name
+        ));
+    }
+    for db in d.databases.iter().filter(|db| !db.ok) {
+        hints.push(format!(
+            "{} failed its integrity check; quit {} and restore it from a backup \
+(or salvage it with `sqlite3 <db> .recover`).",
+            db.path.display(),
+            d.name
+        ));
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_root_reports_missing_and_existing_paths() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let present = check_root(tmp.path());
+        assert!(present.exists && present.readable && present.writable);
+        let missing = check_root(&tmp.path().join("absent"));
+        assert!(!missing.exists && !missing.readable && !missing.writable);
+    }
+
+    #[test]
+    fn check_database_flags_non_sqlite_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let good = tmp.path().join("good.db");
+        Connection::open(&good)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        let check = check_database(&good);
+        assert!(check.ok, "{}", check.detail);
+
+        let bad = tmp.path().join("bad.db");
+        std::fs::write(&bad, b"definitely not a sqlite database, just some bytes").unwrap();
+        let check = check_database(&bad);
+        assert!(!check.ok);
+        assert!(!check.detail.is_empty());
+    }
+
+    #[test]
+    fn hints_explain_missing_storage_and_parse_failures() {
+        let mut d = ProviderDiagnosis {
+            name: "Codex".to_string(),
+            slug: "codex".to_string(),
+            home: None,
+            status: HealthStatus::NoData,
+            installed: true,
+            version: None,
+            evidence: Vec::new(),
+            roots: Vec::new(),
+            session_files: 0,
+            parse: ParseSample::default(),
+            databases: Vec::new(),
+            hints: Vec::new(),
+        };
+        let missing = hints(&d);
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0].contains("set CODEX_HOME"), "{missing:?}");
+
+        d.installed = false;
+        d.roots.push(RootCheck {
+            path: PathBuf::from("/home/u/.codex/sessions"),
+            exists: true,
+            readable: true,
+            writable: true,
+        });
+        d.session_files = 4;
+        d.parse = ParseSample {
+            sampled: 4,
+            parsed: 3,
+            errors: vec!["a.jsonl: bad".to_string()],
+            with_unrecognized: 2,
+            ..ParseSample::default()
+        };
+        let found = hints(&d);
+        assert_eq!(found.len(), 3, "{found:?}");
+        assert!(found[0].contains("`casr list` skips it"));
+        assert!(found[1].starts_with("1 of 4 sampled session(s) failed to parse"));
+        assert!(found[2].starts_with("2 of 3 parsed session(s) contain constructs casr skips"));
+    }
+}
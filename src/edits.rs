@@ -0,0 +1,549 @@
+//! Canonical file edits.
+//!
+//! Agents express file modifications in very different shapes: Codex sends
+//! `apply_patch` envelopes, Claude Code `Edit`/`MultiEdit`/`Write` calls,
+//! Cline `replace_in_file` SEARCH/REPLACE diffs, Gemini `replace`, and Aider
+//! writes SEARCH/REPLACE blocks straight into its Markdown replies. This
+//! module parses all of them into [`FileEdit`]s, which readers attach to
+//! [`ToolCall::edits`](crate::model::ToolCall::edits), and renders edits back
+//! into `apply_patch` and SEARCH/REPLACE form for targets that need them.
+
+use serde_json::Value;
+
+use crate::model::{CanonicalSession, EditOp, FileEdit, MessageRole};
+
+const PATH_KEYS: &[&str] = &[
+    "file_path",
+    "filePath",
+    "path",
+    "absolute_path",
+    "target_file",
+];
+const OLD_KEYS: &[&str] = &["old_string", "oldString", "oldText", "old_str"];
+const NEW_KEYS: &[&str] = &["new_string", "newString", "newText", "new_str"];
+const CONTENT_KEYS: &[&str] = &["content", "file_text", "contents"];
+
+fn string_arg<'a>(args: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| args.get(*k).and_then(Value::as_str))
+}
+
+fn replace_all_arg(args: &Value) -> bool {
+    ["replace_all", "replaceAll"]
+        .iter()
+        .any(|k| args.get(*k).and_then(Value::as_bool) == Some(true))
+}
+
+fn edit(path: &str, op: EditOp) -> FileEdit {
+    FileEdit {
+        path: path.to_string(),
+        op,
+        move_to: None,
+    }
+}
+
+/// Parse the file edits performed by a call to tool `name`.
+///
+/// Recognizes edit tools by name across providers and reads their
+/// arguments by the key spellings those providers use. Unknown tools, and
+/// edit calls missing a path or text, yield no edits.
+pub fn parse_tool_edits(name: &str, arguments: &Value) -> Vec<FileEdit> {
+    let path = string_arg(arguments, PATH_KEYS);
+    match name {
+        "apply_patch" => {
+            let patch = arguments
+                .as_str()
+                .or_else(|| string_arg(arguments, &["input", "patch"]));
+            patch.map(parse_apply_patch).unwrap_or_default()
+        }
+        "MultiEdit" => {
+            let (Some(path), Some(Value::Array(items))) = (path, arguments.get("edits")) else {
+                return Vec::new();
+            };
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(edit(
+                        path,
+                        EditOp::Replace {
+                            old: string_arg(item, OLD_KEYS)?.to_string(),
+                            new: string_arg(item, NEW_KEYS)?.to_string(),
+                            replace_all: replace_all_arg(item),
+                        },
+                    ))
+                })
+                .collect()
+        }
+        "replace_in_file" => {
+            let (Some(path), Some(diff)) = (path, string_arg(arguments, &["diff"])) else {
+                return Vec::new();
+            };
+            parse_search_replace_blocks(diff)
+                .into_iter()
+                .map(|(old, new)| {
+                    edit(
+                        path,
+                        EditOp::Replace {
+                            old,
+                            new,
+                            replace_all: false,
+                        },
+                    )
+                })
+                .collect()
+        }
+        "Edit" | "replace" | "edit" | "edit_file" | "str_replace" | "search_replace" => {
+            let (Some(path), Some(old), Some(new)) = (
+                path,
+                string_arg(arguments, OLD_KEYS),
+                string_arg(arguments, NEW_KEYS),
+            ) else {
+                return Vec::new();
+            };
+            vec![edit(
+                path,
+                EditOp::Replace {
+                    old: old.to_string(),
+                    new: new.to_string(),
+                    replace_all: replace_all_arg(arguments),
+                },
+            )]
+        }
+        "Write" | "write_file" | "write" | "write_to_file" | "create_file" => {
+            let (Some(path), Some(content)) = (path, string_arg(arguments, CONTENT_KEYS)) else {
+                return Vec::new();
+            };
+            vec![edit(
+                path,
+                EditOp::Create {
+                    content: content.to_string(),
+                },
+            )]
+        }
+        "delete_file" => path
+            .map(|p| vec![edit(p, EditOp::Delete)])
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse a Codex `apply_patch` envelope (`*** Begin Patch` … `*** End Patch`).
+pub fn parse_apply_patch(patch: &str) -> Vec<FileEdit> {
+    enum Pending<'a> {
+        Add(&'a str, Vec<&'a str>),
+        Update(&'a str, Option<&'a str>, Vec<&'a str>),
+    }
+
+    fn flush(pending: Option<Pending<'_>>, edits: &mut Vec<FileEdit>) {
+        match pending {
+            Some(Pending::Add(path, lines)) => {
+                let mut content: String = lines
+                    .iter()
+                    .map(|l| l.strip_prefix('+').unwrap_or(l))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if !lines.is_empty() {
+                    content.push('\n');
+                }
+                edits.push(edit(path, EditOp::Create { content }));
+            }
+            Some(Pending::Update(path, move_to, lines)) => edits.push(FileEdit {
+                path: path.to_string(),
+                op: EditOp::Hunks {
+                    hunks: lines.join("\n"),
+                },
+                move_to: move_to.map(str::to_string),
+            }),
+            None => {}
+        }
+    }
+
+    let mut edits = Vec::new();
+    let mut pending: Option<Pending<'_>> = None;
+    for line in patch.lines() {
+        if let Some(path) = line.strip_prefix("*** Add File: ") {
+            flush(pending.take(), &mut edits);
+            pending = Some(Pending::Add(path.trim(), Vec::new()));
+        } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+            flush(pending.take(), &mut edits);
+            pending = Some(Pending::Update(path.trim(), None, Vec::new()));
+        } else if let Some(path) = line.strip_prefix("*** Delete File: ") {
+            flush(pending.take(), &mut edits);
+            edits.push(edit(path.trim(), EditOp::Delete));
+        } else if let Some(to) = line.strip_prefix("*** Move to: ") {
+            if let Some(Pending::Update(_, move_to, _)) = &mut pending {
+                *move_to = Some(to.trim());
+            }
+        } else if matches!(line.trim(), "*** Begin Patch" | "*** End Patch") {
+            flush(pending.take(), &mut edits);
+        } else {
+            match &mut pending {
+                Some(Pending::Add(_, lines) | Pending::Update(_, _, lines)) => lines.push(line),
+                None => {}
+            }
+        }
+    }
+    flush(pending, &mut edits);
+    edits
+}
+
+/// Old and new text of `apply_patch` hunks holding a single hunk — the
+/// form an agent's plain search/replace edit tool can express.
+pub fn hunk_replacement(hunks: &str) -> Option<(String, String)> {
+    let mut lines: Vec<&str> = hunks
+        .lines()
+        .filter(|l| l.trim() != "*** End of File")
+        .collect();
+    if lines.first().is_some_and(|l| l.starts_with("@@")) {
+        lines.remove(0);
+    }
+    if lines.is_empty() || lines.iter().any(|l| l.starts_with("@@")) {
+        return None;
+    }
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    for line in lines {
+        match line.split_at_checked(1) {
+            Some(("-", rest)) => old.push(rest),
+            Some(("+", rest)) => new.push(rest),
+            Some((" ", rest)) => {
+                old.push(rest);
+                new.push(rest);
+            }
+            None => {
+                old.push("");
+                new.push("");
+            }
+            _ => return None,
+        }
+    }
+    Some((old.join("\n"), new.join("\n")))
+}
+
+/// Render edits as one Codex `apply_patch` envelope. Consecutive in-place
+/// edits of the same file share one `*** Update File:` section.
+pub fn render_apply_patch(edits: &[FileEdit]) -> String {
+    let prefixed = |text: &str, prefix: char| -> String {
+        text.lines()
+            .map(|line| format!("{prefix}{line}\n"))
+            .collect()
+    };
+    let hunk = |op: &EditOp| -> String {
+        match op {
+            EditOp::Replace { old, new, .. } => {
+                format!("@@\n{}{}", prefixed(old, '-'), prefixed(new, '+'))
+            }
+            EditOp::Hunks { hunks } if hunks.is_empty() => String::new(),
+            EditOp::Hunks { hunks } if hunks.starts_with("@@") => format!("{hunks}\n"),
+            EditOp::Hunks { hunks } => format!("@@\n{hunks}\n"),
+            EditOp::Create { .. } | EditOp::Delete => String::new(),
+        }
+    };
+    let in_place = |e: &FileEdit| matches!(e.op, EditOp::Replace { .. } | EditOp::Hunks { .. });
+
+    let mut out = String::from("*** Begin Patch\n");
+    let mut i = 0;
+    while i < edits.len() {
+        let current = &edits[i];
+        i += 1;
+        match &current.op {
+            EditOp::Create { content } => {
+                out.push_str(&format!("*** Add File: {}\n", current.path));
+                out.push_str(&prefixed(content, '+'));
+            }
+            EditOp::Delete => out.push_str(&format!("*** Delete File: {}\n", current.path)),
+            op => {
+                out.push_str(&format!("*** Update File: {}\n", current.path));
+                if let Some(ref to) = current.move_to {
+                    out.push_str(&format!("*** Move to: {to}\n"));
+                }
+                out.push_str(&hunk(op));
+                while current.move_to.is_none()
+                    && edits.get(i).is_some_and(|next| {
+                        next.path == current.path && next.move_to.is_none() && in_place(next)
+                    })
+                {
+                    out.push_str(&hunk(&edits[i].op));
+                    i += 1;
+                }
+            }
+        }
+    }
+    out.push_str("*** End Patch");
+    out
+}
+
+fn is_search_marker(line: &str) -> bool {
+    let line = line.trim_end();
+    line.ends_with(" SEARCH") && (line.starts_with("<<<<<<<") || line.starts_with("-------"))
+}
+
+fn is_replace_marker(line: &str) -> bool {
+    let line = line.trim_end();
+    line.ends_with(" REPLACE") && (line.starts_with(">>>>>>>") || line.starts_with("+++++++"))
+}
+
+/// `(search, replace)` pairs from SEARCH/REPLACE blocks (Cline diffs, Aider
+/// replies). Accepts both `<<<<<<<`/`>>>>>>>` and `-------`/`+++++++` markers.
+pub fn parse_search_replace_blocks(text: &str) -> Vec<(String, String)> {
+    search_replace_blocks(text)
+        .into_iter()
+        .map(|(_, old, new)| (old, new))
+        .collect()
+}
+
+/// Blocks with the index of the line preceding each SEARCH marker.
+fn search_replace_blocks(text: &str) -> Vec<(usize, String, String)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !is_search_marker(lines[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let Some(divider) = (start + 1..lines.len()).find(|&j| lines[j].trim_end() == "=======")
+        else {
+            break;
+        };
+        let Some(end) = (divider + 1..lines.len()).find(|&j| is_replace_marker(lines[j])) else {
+            break;
+        };
+        blocks.push((
+            start,
+            lines[start + 1..divider].join("\n"),
+            lines[divider + 1..end].join("\n"),
+        ));
+        i = end + 1;
+    }
+    blocks
+}
+
+/// Render one Cline-style SEARCH/REPLACE block.
+pub fn render_search_replace(old: &str, new: &str) -> String {
+    format!("<<<<<<< SEARCH\n{old}\n=======\n{new}\n>>>>>>> REPLACE")
+}
+
+/// Parse Aider-style edit blocks from assistant Markdown: a file path line,
+/// an optional code fence, then a SEARCH/REPLACE block. An empty SEARCH
+/// section creates the file.
+pub fn parse_markdown_edits(text: &str) -> Vec<FileEdit> {
+    let lines: Vec<&str> = text.lines().collect();
+    search_replace_blocks(text)
+        .into_iter()
+        .filter_map(|(marker, old, new)| {
+            let path = lines[..marker]
+                .iter()
+                .rev()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty() && !l.starts_with("```"))?
+                .trim_matches(|c| matches!(c, '`' | '*' | ':'));
+            if path.is_empty() || path.contains(' ') {
+                return None;
+            }
+            let op = if old.is_empty() {
+                EditOp::Create {
+                    content: format!("{new}\n"),
+                }
+            } else {
+                EditOp::Replace {
+                    old,
+                    new,
+                    replace_all: false,
+                }
+            };
+            Some(edit(path, op))
+        })
+        .collect()
+}
+
+/// Every file edit in `session`, in order: parsed edit tool calls plus
+/// SEARCH/REPLACE blocks in assistant text (Aider).
+pub fn session_edits(session: &CanonicalSession) -> Vec<FileEdit> {
+    let mut edits = Vec::new();
+    for msg in &session.messages {
+        for call in &msg.tool_calls {
+            edits.extend(call.edits.iter().cloned());
+        }
+        if msg.role == MessageRole::Assistant {
+            edits.extend(parse_markdown_edits(&msg.content));
+        }
+    }
+    edits
+}
+
+/// Paths `session` modified, in first-touched order (rename targets included).
+pub fn changed_files(session: &CanonicalSession) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for edit in session_edits(session) {
+        for path in std::iter::once(edit.path).chain(edit.move_to) {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn replace(path: &str, old: &str, new: &str) -> FileEdit {
+        edit(
+            path,
+            EditOp::Replace {
+                old: old.to_string(),
+                new: new.to_string(),
+                replace_all: false,
+            },
+        )
+    }
+
+    #[test]
+    fn parses_claude_edit_multiedit_and_write() {
+        assert_eq!(
+            parse_tool_edits(
+                "Edit",
+                &json!({"file_path": "/a.rs", "old_string": "x", "new_string": "y", "replace_all": true})
+            ),
+            vec![edit(
+                "/a.rs",
+                EditOp::Replace {
+                    old: "x".into(),
+                    new: "y".into(),
+                    replace_all: true
+                }
+            )]
+        );
+        assert_eq!(
+            parse_tool_edits(
+                "MultiEdit",
+                &json!({"file_path": "/a.rs", "edits": [
+                    {"old_string": "a", "new_string": "b"},
+                    {"old_string": "c", "new_string": "d"}
+                ]})
+            ),
+            vec![replace("/a.rs", "a", "b"), replace("/a.rs", "c", "d")]
+        );
+        assert_eq!(
+            parse_tool_edits("Write", &json!({"file_path": "/n.txt", "content": "hi\n"})),
+            vec![edit(
+                "/n.txt",
+                EditOp::Create {
+                    content: "hi\n".into()
+                }
+            )]
+        );
+        assert!(parse_tool_edits("Bash", &json!({"command": "ls"})).is_empty());
+        assert!(parse_tool_edits("Edit", &json!({"file_path": "/a.rs"})).is_empty());
+    }
+
+    #[test]
+    fn parses_gemini_cline_and_opencode_edits() {
+        assert_eq!(
+            parse_tool_edits(
+                "replace",
+                &json!({"file_path": "/a", "old_string": "1", "new_string": "2"})
+            ),
+            vec![replace("/a", "1", "2")]
+        );
+        assert_eq!(
+            parse_tool_edits(
+                "edit",
+                &json!({"filePath": "/a", "oldString": "1", "newString": "2"})
+            ),
+            vec![replace("/a", "1", "2")]
+        );
+        let diff = "------- SEARCH\none\n=======\nuno\n+++++++ REPLACE\n\n<<<<<<< SEARCH\ntwo\n=======\ndos\n>>>>>>> REPLACE";
+        assert_eq!(
+            parse_tool_edits(
+                "replace_in_file",
+                &json!({"path": "src/n.rs", "diff": diff})
+            ),
+            vec![
+                replace("src/n.rs", "one", "uno"),
+                replace("src/n.rs", "two", "dos")
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_codex_apply_patch_envelopes() {
+        let patch = "*** Begin Patch\n*** Add File: new.txt\n+hello\n*** Delete File: old.txt\n*** Update File: src/a.rs\n*** Move to: src/b.rs\n@@ fn main\n-old\n+new\n*** End Patch";
+        let edits = parse_tool_edits("apply_patch", &json!({"input": patch}));
+        assert_eq!(
+            edits,
+            vec![
+                edit(
+                    "new.txt",
+                    EditOp::Create {
+                        content: "hello\n".into()
+                    }
+                ),
+                edit("old.txt", EditOp::Delete),
+                FileEdit {
+                    path: "src/a.rs".into(),
+                    op: EditOp::Hunks {
+                        hunks: "@@ fn main\n-old\n+new".into()
+                    },
+                    move_to: Some("src/b.rs".into()),
+                },
+            ]
+        );
+        // Freeform custom-tool form: the patch is the whole argument.
+        assert_eq!(parse_tool_edits("apply_patch", &json!(patch)), edits);
+        assert_eq!(
+            hunk_replacement("@@ fn main\n fn main() {\n-old\n+new\n }"),
+            Some(("fn main() {\nold\n}".into(), "fn main() {\nnew\n}".into()))
+        );
+        assert_eq!(hunk_replacement("@@\n-a\n@@\n-b"), None);
+    }
+
+    #[test]
+    fn render_apply_patch_round_trips_and_groups_same_file_hunks() {
+        let edits = vec![
+            replace("/a.rs", "a", "b"),
+            replace("/a.rs", "c", "d"),
+            edit(
+                "/n.txt",
+                EditOp::Create {
+                    content: "x\ny\n".into(),
+                },
+            ),
+            edit("/gone.txt", EditOp::Delete),
+        ];
+        let patch = render_apply_patch(&edits);
+        assert_eq!(
+            patch,
+            "*** Begin Patch\n*** Update File: /a.rs\n@@\n-a\n+b\n@@\n-c\n+d\n*** Add File: /n.txt\n+x\n+y\n*** Delete File: /gone.txt\n*** End Patch"
+        );
+        let reparsed = parse_apply_patch(&patch);
+        assert_eq!(reparsed[1..], edits[2..]);
+        assert_eq!(
+            reparsed[0].op,
+            EditOp::Hunks {
+                hunks: "@@\n-a\n+b\n@@\n-c\n+d".into()
+            }
+        );
+    }
+
+    #[test]
+    fn parses_aider_markdown_blocks() {
+        let reply = "Here's the fix:\n\nsrc/app.py\n```python\n<<<<<<< SEARCH\nprint('hi')\n=======\nprint('hello')\n>>>>>>> REPLACE\n```\n\nAnd a new file:\n\n`README.md`\n```\n<<<<<<< SEARCH\n=======\n# App\n>>>>>>> REPLACE\n```";
+        assert_eq!(
+            parse_markdown_edits(reply),
+            vec![
+                replace("src/app.py", "print('hi')", "print('hello')"),
+                edit(
+                    "README.md",
+                    EditOp::Create {
+                        content: "# App\n".into()
+                    }
+                ),
+            ]
+        );
+        assert!(parse_markdown_edits("no edits here").is_empty());
+    }
+}
@@ -108,7 +108,65 @@ pub enum CasrError {
         provider: String,
         written_paths: Vec<PathBuf>,
         detail: String,
+        /// Every read-back mismatch found (empty when the read itself failed).
+        mismatches: Vec<String>,
     },
+
+    /// `--strict=budget`: the context budget would drop or truncate history.
+    #[error(
+        "Strict mode (budget): the context budget dropped {reasoning_dropped} reasoning block(s), \
+truncated {tool_outputs_truncated} tool output(s) and dropped {turns_dropped} turn(s). \
+Use --keep-reasoning, --max-tool-output 0 and --max-context-tokens 0 to transfer everything."
+    )]
+    StrictBudget {
+        reasoning_dropped: usize,
+        tool_outputs_truncated: usize,
+        turns_dropped: usize,
+    },
+
+    /// `--strict=tools`: tool results reference tool calls the session lacks.
+    #[error(
+        "Strict mode (tools): tool result(s) reference unknown tool call id(s): {}. \
+The source session is missing the matching tool calls.",
+        call_ids.join(", ")
+    )]
+    StrictOrphanToolResults { call_ids: Vec<String> },
+
+    /// `--strict=roles`: the target stored messages under another role.
+    #[error(
+        "Strict mode (roles): {provider} collapsed message roles ({}); {rollback}.",
+        roles.join(", ")
+    )]
+    StrictRolesCollapsed {
+        provider: String,
+        /// Collapses as `"from → to"`.
+        roles: Vec<String>,
+        rollback: String,
+    },
+
+    /// `--strict=metadata`: the target dropped message metadata.
+    #[error(
+        "Strict mode (metadata): {provider} cannot store message metadata key(s) {}; {rollback}.",
+        keys.join(", ")
+    )]
+    StrictMetadataDropped {
+        provider: String,
+        keys: Vec<String>,
+        rollback: String,
+    },
+
+    /// `casr edit` cannot apply the requested edits to this session.
+    #[error("Cannot edit session '{session_id}': {reason}")]
+    EditRejected { session_id: String, reason: String },
+
+    /// `casr sync-back` cannot carry this session's new turns back to the
+    /// session it was converted from.
+    #[error("Cannot sync session '{session_id}' back: {reason}")]
+    SyncBackRejected { session_id: String, reason: String },
+
+    /// `resume --append-to` cannot extend this target session.
+    #[error("Cannot append to session '{session_id}': {reason}")]
+    AppendRejected { session_id: String, reason: String },
 }
 
 #[cfg(test)]
@@ -236,10 +294,24 @@ mod tests {
             provider: "gemini".to_string(),
             written_paths: vec![PathBuf::from("/tmp/session.json")],
             detail: "message count mismatch: expected 10, got 8".to_string(),
+            mismatches: vec!["message count mismatch: expected 10, got 8".to_string()],
         };
         let msg = err.to_string();
         assert!(msg.contains("gemini"));
         assert!(msg.contains("bug in casr"));
         assert!(msg.contains("message count mismatch"));
     }
+
+    #[test]
+    fn strict_budget_display_names_the_flags_to_relax() {
+        let err = CasrError::StrictBudget {
+            reasoning_dropped: 0,
+            tool_outputs_truncated: 3,
+            turns_dropped: 1,
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("truncated 3 tool output(s)"));
+        assert!(msg.contains("dropped 1 turn(s)"));
+        assert!(msg.contains("--max-tool-output 0"));
+    }
 }
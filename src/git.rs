@@ -0,0 +1,258 @@
+//! Repository state snapshots for `--enrich`.
+//!
+//! Runs the `git` CLI in the repository containing the session's workspace
+//! (see [`find_git_root`]) so the target agent knows what the previous agent
+//! already changed on disk: branch, HEAD, commits made during the session,
+//! uncommitted files, and optionally a `git diff --stat`. Every list is
+//! bounded; a missing `git` binary or repository simply yields no snapshot.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use tracing::debug;
+
+use crate::discovery::find_git_root;
+
+/// Commits listed before the rest are only counted.
+pub const MAX_COMMITS: usize = 20;
+/// Changed files listed before the rest are only counted.
+pub const MAX_CHANGED_FILES: usize = 50;
+/// Per-file `--stat` lines kept; the summary line is always kept.
+pub const MAX_DIFF_STAT_LINES: usize = 30;
+
+/// The state of a repository at conversion time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GitSnapshot {
+    /// Repository root.
+    pub root: PathBuf,
+    /// Current branch; `None` on a detached HEAD.
+    pub branch: Option<String>,
+    /// `<short sha> <subject>` of HEAD; `None` before the first commit.
+    pub head: Option<String>,
+    /// Commits since the session started, newest first, as `<short sha> <subject>`.
+    /// Empty when the session has no start time.
+    pub commits_since_start: Vec<String>,
+    /// Commits since the start beyond [`MAX_COMMITS`].
+    pub more_commits: usize,
+    /// `git status --porcelain` lines: status code and path.
+    pub changed_files: Vec<String>,
+    /// Changed files beyond [`MAX_CHANGED_FILES`].
+    pub more_changed_files: usize,
+    /// `git diff HEAD --stat`, when requested and the tree is dirty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_stat: Option<String>,
+}
+
+impl GitSnapshot {
+    /// Snapshot the repository containing `workspace`. `since_ms` (epoch
+    /// milliseconds) bounds the commit list. Returns `None` when `workspace`
+    /// is not inside a repository or `git` can't read it.
+    pub fn capture(workspace: &Path, since_ms: Option<i64>, diff_stat: bool) -> Option<Self> {
+        let root = find_git_root(workspace)?;
+        // Fails outside a work tree, and when git is missing.
+        git(&root, &["rev-parse", "--is-inside-work-tree"])?;
+
+        let branch = git(&root, &["symbolic-ref", "--short", "-q", "HEAD"])
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty());
+        let head = git(&root, &["log", "-1", "--format=%h %s"])
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty());
+
+        let mut snapshot = GitSnapshot {
+            root: root.clone(),
+            branch,
+            ..GitSnapshot::default()
+        };
+        if head.is_some()
+            && let Some(since_ms) = since_ms
+        {
+            let since = format!("--since=@{}", since_ms.div_euclid(1000));
+            let total = git(&root, &["rev-list", "--count", &since, "HEAD"])
+                .and_then(|n| n.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            let limit = format!("-n{MAX_COMMITS}");
+            snapshot.commits_since_start = git(&root, &["log", &since, &limit, "--format=%h %s"])
+                .map(|out| out.lines().map(str::to_string).collect())
+                .unwrap_or_default();
+            snapshot.more_commits = total.saturating_sub(snapshot.commits_since_start.len());
+        }
+
+        let status = git(&root, &["status", "--porcelain", "--untracked-files=all"])?;
+        let mut changed: Vec<String> = status
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        snapshot.more_changed_files = changed.len().saturating_sub(MAX_CHANGED_FILES);
+        changed.truncate(MAX_CHANGED_FILES);
+        snapshot.changed_files = changed;
+
+        if diff_stat && head.is_some() && !snapshot.changed_files.is_empty() {
+            snapshot.diff_stat = git(&root, &["diff", "HEAD", "--stat=100"])
+                .map(|stat| bound_diff_stat(&stat))
+                .filter(|stat| !stat.is_empty());
+        }
+        snapshot.head = head;
+
+        debug!(
+            root = %snapshot.root.display(),
+            commits = snapshot.commits_since_start.len() + snapshot.more_commits,
+            changed = snapshot.changed_files.len() + snapshot.more_changed_files,
+            "captured git snapshot"
+        );
+        Some(snapshot)
+    }
+
+    /// Human-readable lines for the enrichment message.
+    pub fn lines(&self, started_at_known: bool) -> Vec<String> {
+        let mut lines = vec![format!("Repository: {}", self.root.display())];
+        lines.push(format!(
+            "Branch: {}",
+            self.branch.as_deref().unwrap_or("(detached HEAD)")
+        ));
+        lines.push(format!(
+            "HEAD: {}",
+            self.head.as_deref().unwrap_or("(no commits yet)")
+        ));
+
+        if started_at_known {
+            let total = self.commits_since_start.len() + self.more_commits;
+            if total == 0 {
+                lines.push("No commits since the session started.".to_string());
+            } else {
+                lines.push(format!("Commits since the session started ({total}):"));
+                lines.extend(self.commits_since_start.iter().map(|c| format!("- {c}")));
+                if self.more_commits > 0 {
+                    lines.push(format!("- … and {} older", self.more_commits));
+                }
+            }
+        }
+
+        let total = self.changed_files.len() + self.more_changed_files;
+        if total == 0 {
+            lines.push("Working tree clean.".to_string());
+        } else {
+            lines.push(format!("Uncommitted changes ({total} file(s)):"));
+            lines.extend(self.changed_files.iter().map(|f| format!("- {f}")));
+            if self.more_changed_files > 0 {
+                lines.push(format!("- … and {} more", self.more_changed_files));
+            }
+        }
+
+        if let Some(stat) = &self.diff_stat {
+            lines.push("Diff stat against HEAD:".to_string());
+            lines.extend(stat.lines().map(str::to_string));
+        }
+        lines
+    }
+}
+
+/// Run `git -C root <args>`, returning stdout on success.
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        // Don't take the index lock while the user's agent may be running.
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Keep the first [`MAX_DIFF_STAT_LINES`] per-file lines plus the summary.
+fn bound_diff_stat(stat: &str) -> String {
+    let lines: Vec<&str> = stat.lines().filter(|l| !l.trim().is_empty()).collect();
+    let Some((summary, files)) = lines.split_last() else {
+        return String::new();
+    };
+    let mut kept: Vec<String> = files
+        .iter()
+        .take(MAX_DIFF_STAT_LINES)
+        .map(|l| l.to_string())
+        .collect();
+    if files.len() > MAX_DIFF_STAT_LINES {
+        kept.push(format!(
+            " … and {} more file(s)",
+            files.len() - MAX_DIFF_STAT_LINES
+        ));
+    }
+    kept.push(summary.to_string());
+    kept.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["-c", "user.name=casr", "-c", "user.email=casr@example.com"])
+            .args(args)
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn capture_reports_branch_commits_and_changes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path();
+        run(root, &["init", "-q", "-b", "main"]);
+        std::fs::write(root.join("a.txt"), "one\n").unwrap();
+        run(root, &["add", "a.txt"]);
+        run(root, &["commit", "-q", "-m", "add a"]);
+        let started = chrono::Utc::now().timestamp_millis() - 60_000;
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(root.join("b.txt"), "new\n").unwrap();
+        let nested = root.join("src");
+        std::fs::create_dir(&nested).unwrap();
+
+        let snapshot = GitSnapshot::capture(&nested, Some(started), true).expect("snapshot");
+        assert_eq!(snapshot.branch.as_deref(), Some("main"));
+        assert!(snapshot.head.as_deref().unwrap().ends_with(" add a"));
+        assert_eq!(snapshot.commits_since_start.len(), 1);
+        assert_eq!(snapshot.changed_files, vec![" M a.txt", "?? b.txt"]);
+        let stat = snapshot.diff_stat.as_deref().expect("diff stat");
+        assert!(
+            stat.contains("a.txt") && stat.contains("1 file changed"),
+            "{stat}"
+        );
+
+        let lines = snapshot.lines(true).join("\n");
+        assert!(lines.contains("Branch: main"), "{lines}");
+        assert!(
+            lines.contains("Uncommitted changes (2 file(s)):"),
+            "{lines}"
+        );
+
+        let later = chrono::Utc::now().timestamp_millis() + 60_000;
+        let snapshot = GitSnapshot::capture(root, Some(later), false).unwrap();
+        assert!(snapshot.commits_since_start.is_empty());
+        assert_eq!(snapshot.diff_stat, None);
+        assert!(snapshot.lines(true).join("\n").contains("No commits since"));
+    }
+
+    #[test]
+    fn capture_outside_a_repository_is_none() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert_eq!(GitSnapshot::capture(tmp.path(), None, false), None);
+    }
+
+    #[test]
+    fn diff_stat_is_bounded_and_keeps_the_summary() {
+        let mut stat: String = (0..40).map(|i| format!(" f{i}.rs | 1 +\n")).collect();
+        stat.push_str(" 40 files changed, 40 insertions(+)\n");
+        let bounded = bound_diff_stat(&stat);
+        assert_eq!(bounded.lines().count(), MAX_DIFF_STAT_LINES + 2);
+        assert!(bounded.contains("… and 10 more file(s)"));
+        assert!(bounded.ends_with("40 files changed, 40 insertions(+)"));
+    }
+}
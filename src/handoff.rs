@@ -0,0 +1,186 @@
+//! User-defined handoff text for `--enrich-template` and `--continue-prompt`.
+//!
+//! Templates are plain text with `{{placeholder}}` markers, filled from a
+//! [`HandoffContext`] built by the pipeline. Unknown placeholders are
+//! rejected when the template is loaded, so a typo fails before any
+//! conversion runs instead of leaking `{{…}}` into the target session.
+
+use std::path::Path;
+
+use anyhow::Context;
+
+/// Placeholders a template may use.
+pub const PLACEHOLDERS: &[&str] = &[
+    "source_provider",
+    "target_provider",
+    "session_id",
+    "workspace",
+    "message_count",
+    "recent_summary",
+    "files_touched",
+    "open_todos",
+    "git_state",
+];
+
+/// Values substituted into a [`HandoffTemplate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandoffContext {
+    pub source_provider: String,
+    pub target_provider: String,
+    pub session_id: String,
+    pub workspace: Option<String>,
+    pub message_count: usize,
+    /// `- role: text` lines for the last few messages.
+    pub recent_summary: String,
+    /// Files the session's edits touched, in first-touched order.
+    pub files_touched: Vec<String>,
+    /// Unfinished plan items as `(text, status)`.
+    pub open_todos: Vec<(String, String)>,
+    /// Repository state lines; `None` outside a git repository.
+    pub git_state: Option<Vec<String>>,
+}
+
+impl HandoffContext {
+    /// The text for placeholder `name`; empty values read as `(none)`.
+    fn value(&self, name: &str) -> String {
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                "(none)".to_string()
+            } else {
+                items.join("\n")
+            }
+        };
+        match name {
+            "source_provider" => self.source_provider.clone(),
+            "target_provider" => self.target_provider.clone(),
+            "session_id" => self.session_id.clone(),
+            "workspace" => self
+                .workspace
+                .clone()
+                .unwrap_or_else(|| "(unknown)".to_string()),
+            "message_count" => self.message_count.to_string(),
+            "recent_summary" => self.recent_summary.clone(),
+            "files_touched" => list(
+                self.files_touched
+                    .iter()
+                    .map(|f| format!("- {f}"))
+                    .collect(),
+            ),
+            "open_todos" => list(
+                self.open_todos
+                    .iter()
+                    .map(|(text, status)| format!("- [{status}] {text}"))
+                    .collect(),
+            ),
+            "git_state" => match &self.git_state {
+                Some(lines) => lines.join("\n"),
+                None => "(not a git repository)".to_string(),
+            },
+            _ => String::new(),
+        }
+    }
+}
+
+/// A validated handoff template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandoffTemplate {
+    text: String,
+}
+
+impl HandoffTemplate {
+    /// Validate `text`: every `{{…}}` must name a known placeholder.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut rest = text;
+        while let Some(open) = rest.find("{{") {
+            let after = &rest[open + 2..];
+            let Some(close) = after.find("}}") else {
+                anyhow::bail!("unclosed '{{{{' in handoff template");
+            };
+            let name = after[..close].trim();
+            if !PLACEHOLDERS.contains(&name) {
+                anyhow::bail!(
+                    "unknown placeholder '{{{{{name}}}}}' in handoff template (known: {})",
+                    PLACEHOLDERS.join(", ")
+                );
+            }
+            rest = &after[close + 2..];
+        }
+        Ok(Self {
+            text: text.to_string(),
+        })
+    }
+
+    /// Read and validate a template file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read handoff template {}", path.display()))?;
+        Self::parse(&text)
+            .map_err(|e| anyhow::anyhow!("invalid handoff template {}: {e}", path.display()))
+    }
+
+    /// Fill in every placeholder from `ctx`.
+    pub fn render(&self, ctx: &HandoffContext) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut rest = self.text.as_str();
+        while let Some(open) = rest.find("{{") {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 2..];
+            // `parse` guarantees the marker is closed.
+            let close = after.find("}}").unwrap_or(after.len());
+            out.push_str(&ctx.value(after[..close].trim()));
+            rest = after.get(close + 2..).unwrap_or("");
+        }
+        out.push_str(rest);
+        out.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> HandoffContext {
+        HandoffContext {
+            source_provider: "codex".to_string(),
+            target_provider: "claude-code".to_string(),
+            session_id: "sess-1".to_string(),
+            workspace: Some("/work/app".to_string()),
+            message_count: 12,
+            recent_summary: "- user: fix the build".to_string(),
+            files_touched: vec!["src/main.rs".to_string()],
+            open_todos: vec![("Write tests".to_string(), "in_progress".to_string())],
+            git_state: None,
+        }
+    }
+
+    #[test]
+    fn render_fills_placeholders_and_marks_empty_values() {
+        let template = HandoffTemplate::parse(
+            "Handoff from {{source_provider}} ({{ session_id }}, {{message_count}} messages) in {{workspace}}.\n\
+Files:\n{{files_touched}}\nTodos:\n{{open_todos}}\nGit: {{git_state}}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&context()),
+            "Handoff from codex (sess-1, 12 messages) in /work/app.\n\
+Files:\n- src/main.rs\nTodos:\n- [in_progress] Write tests\nGit: (not a git repository)"
+        );
+
+        let empty = HandoffContext::default();
+        let template = HandoffTemplate::parse("{{files_touched}} / {{workspace}}").unwrap();
+        assert_eq!(template.render(&empty), "(none) / (unknown)");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_and_unclosed_placeholders() {
+        let err = HandoffTemplate::parse("Hi {{sesion_id}}").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown placeholder '{{sesion_id}}'"),
+            "{err}"
+        );
+        let err = HandoffTemplate::parse("Hi {{session_id").unwrap_err();
+        assert!(err.to_string().contains("unclosed"), "{err}");
+        assert!(HandoffTemplate::parse("no placeholders { at all }").is_ok());
+    }
+}
@@ -0,0 +1,121 @@
+//! Alternate home directories for `--home` / `--root`.
+//!
+//! Providers locate their storage from the current user's home directory and
+//! per-provider env overrides (`CLAUDE_HOME`, `CODEX_HOME`, …). They look both
+//! up through this module, so a caller can re-base every provider onto another
+//! home tree — an old laptop backup, a devcontainer volume, another account —
+//! by running discovery inside [`scoped`].
+//!
+//! Inside a scope the env overrides are ignored: they describe this machine,
+//! not the tree being scanned. The scope is thread-local; code that fans out
+//! to worker threads must re-enter it there.
+
+use std::cell::RefCell;
+use std::env::VarError;
+use std::path::{Path, PathBuf};
+
+thread_local! {
+    static ACTIVE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// The alternate home the current thread is scanning, if any.
+pub fn active() -> Option<PathBuf> {
+    ACTIVE.with(|active| active.borrow().clone())
+}
+
+/// Run `f` with providers re-based onto `home`; `None` runs it against the
+/// current user's home. Restores the previous scope afterwards, even on panic.
+pub fn scoped<T>(home: Option<&Path>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<PathBuf>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            ACTIVE.with(|active| *active.borrow_mut() = previous);
+        }
+    }
+
+    let previous = ACTIVE.with(|active| active.replace(home.map(Path::to_path_buf)));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// The home directory providers resolve their default storage from.
+pub fn home_dir() -> Option<PathBuf> {
+    active().or_else(dirs::home_dir)
+}
+
+/// The platform config directory (`~/.config`, `~/Library/Application Support`,
+/// `%APPDATA%`) under the active home.
+pub fn config_dir() -> Option<PathBuf> {
+    match active() {
+        Some(home) => Some(home.join(platform_config_dir())),
+        None => dirs::config_dir(),
+    }
+}
+
+/// The platform data directory (`~/.local/share`, `~/Library/Application
+/// Support`, `%APPDATA%`) under the active home.
+pub fn data_dir() -> Option<PathBuf> {
+    match active() {
+        Some(home) => Some(home.join(platform_data_dir())),
+        None => dirs::data_dir(),
+    }
+}
+
+/// A storage-location env var, ignored while scanning an alternate home.
+pub fn env_var(name: &str) -> Result<String, VarError> {
+    if active().is_some() {
+        return Err(VarError::NotPresent);
+    }
+    std::env::var(name)
+}
+
+fn platform_config_dir() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Library/Application Support"
+    } else if cfg!(windows) {
+        "AppData/Roaming"
+    } else {
+        ".config"
+    }
+}
+
+fn platform_data_dir() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Library/Application Support"
+    } else if cfg!(windows) {
+        "AppData/Roaming"
+    } else {
+        ".local/share"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_rebases_home_and_hides_env_overrides() {
+        let home = PathBuf::from("/mnt/backup/home/me");
+        scoped(Some(&home), || {
+            assert_eq!(home_dir(), Some(home.clone()));
+            assert!(config_dir().unwrap().starts_with(&home));
+            assert!(data_dir().unwrap().starts_with(&home));
+            assert_eq!(env_var("PATH"), Err(VarError::NotPresent));
+
+            // Nested scopes restore the outer one.
+            scoped(None, || assert_eq!(active(), None));
+            assert_eq!(active(), Some(home.clone()));
+        });
+        assert_eq!(active(), None);
+        assert_eq!(home_dir(), dirs::home_dir());
+    }
+
+    #[test]
+    fn scoped_restores_after_a_panic() {
+        let home = PathBuf::from("/tmp/other-home");
+        let result = std::panic::catch_unwind(|| scoped(Some(&home), || panic!("boom")));
+        assert!(result.is_err());
+        assert_eq!(active(), None);
+    }
+}
@@ -5,9 +5,24 @@
 //! Library entry point exposing the public API for session conversion.
 //! The binary (`main.rs`) is a thin CLI wrapper around this library.
 
+pub mod archive;
+pub mod backup;
 pub mod discovery;
+pub mod doctor;
+pub mod edits;
 pub mod error;
+pub mod git;
+pub mod handoff;
+pub mod homes;
+pub mod lineage;
+pub mod mcp;
 pub mod model;
 pub mod pipeline;
+pub mod pricing;
 pub mod providers;
 pub mod responses;
+pub mod rewrite;
+pub mod selftest;
+pub mod stats;
+pub mod tools;
+pub mod web;
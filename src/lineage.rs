@@ -0,0 +1,231 @@
+//! Conversion lineage: where a converted session came from.
+//!
+//! Every `casr resume` appends a [`Hop`] — casr version, source provider,
+//! source session ID, a hash of the source path, and the conversion time —
+//! to the session's [`Lineage`] before it is written. Writers store the
+//! lineage under [`METADATA_KEY`] in whatever session-level slot their format
+//! has (a header line, a top-level JSON key, a metadata column), and readers
+//! put it back into [`CanonicalSession::metadata`], so a Codex → Claude Code
+//! → Gemini session still knows it started as a Codex conversation.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::model::CanonicalSession;
+
+/// Key of the lineage in canonical metadata and in provider formats that
+/// store it as a JSON field.
+pub const METADATA_KEY: &str = "casr_lineage";
+
+/// One conversion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hop {
+    pub casr_version: String,
+    pub source_provider: String,
+    pub source_session_id: String,
+    /// sha256 of the source session path: identifies the file without
+    /// storing a path from another machine.
+    pub source_path_sha256: String,
+    /// Epoch milliseconds.
+    pub converted_at: i64,
+    /// Messages the converted session was written with; `casr sync-back`
+    /// treats everything after them as continued work.
+    pub messages: usize,
+}
+
+/// The conversions that produced a session, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lineage {
+    pub hops: Vec<Hop>,
+}
+
+impl Lineage {
+    /// Parse a stored lineage; `None` when absent, empty, or malformed.
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value::<Self>(value.clone())
+            .ok()
+            .filter(|lineage| !lineage.hops.is_empty())
+    }
+
+    /// The lineage recorded in a session's metadata.
+    pub fn of(session: &CanonicalSession) -> Option<Self> {
+        session
+            .metadata
+            .get(METADATA_KEY)
+            .and_then(Self::from_value)
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// The conversation this session was converted from, directly or
+    /// through intermediate copies: `(provider, session_id)`.
+    pub fn origin(&self) -> Option<(&str, &str)> {
+        self.hops
+            .first()
+            .map(|hop| (hop.source_provider.as_str(), hop.source_session_id.as_str()))
+    }
+}
+
+/// The value a writer should store for `session`, if it has a lineage.
+pub fn stored_value(session: &CanonicalSession) -> Option<serde_json::Value> {
+    Lineage::of(session).map(|lineage| lineage.to_value())
+}
+
+/// The `source_path_sha256` recorded for a session at `path`.
+pub fn path_sha256(path: &Path) -> String {
+    Sha256::digest(path.to_string_lossy().as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Reader side: copy a lineage found in the source format into canonical
+/// metadata. Ignores `stored` when it isn't a valid lineage.
+pub fn restore(
+    metadata: &mut serde_json::Map<String, serde_json::Value>,
+    stored: Option<&serde_json::Value>,
+) {
+    if let Some(lineage) = stored.and_then(Lineage::from_value) {
+        metadata.insert(METADATA_KEY.to_string(), lineage.to_value());
+    }
+}
+
+/// Append a hop for converting `session` (as read from its source, ready to
+/// write) to its lineage, and return the result.
+pub fn stamp(session: &mut CanonicalSession) -> Lineage {
+    let mut lineage = Lineage::of(session).unwrap_or_default();
+    lineage.hops.push(Hop {
+        casr_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_provider: session.provider_slug.clone(),
+        source_session_id: session.session_id.clone(),
+        source_path_sha256: path_sha256(&session.source_path),
+        converted_at: chrono::Utc::now().timestamp_millis(),
+        messages: session.messages.len(),
+    });
+    if !session.metadata.is_object() {
+        session.metadata = serde_json::Value::Object(serde_json::Map::new());
+    }
+    session.metadata[METADATA_KEY] = lineage.to_value();
+    lineage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(provider: &str, id: &str, metadata: serde_json::Value) -> CanonicalSession {
+        CanonicalSession {
+            session_id: id.to_string(),
+            provider_slug: provider.to_string(),
+            workspace: None,
+            title: None,
+            started_at: None,
+            ended_at: None,
+            messages: Vec::new(),
+            metadata,
+            source_path: PathBuf::from(format!("/sessions/{id}.jsonl")),
+            model_name: None,
+            usage: None,
+            unrecognized: None,
+        }
+    }
+
+    #[test]
+    fn stamp_appends_hops_and_keeps_the_origin() {
+        let mut codex = session("codex", "cx-1", serde_json::Value::Null);
+        let first = stamp(&mut codex);
+        assert_eq!(first.origin(), Some(("codex", "cx-1")));
+        assert_eq!(first.hops[0].source_path_sha256.len(), 64);
+        assert_eq!(first.hops[0].messages, 0);
+
+        // The Claude Code copy reads the lineage back and is converted again.
+        let mut metadata = serde_json::Map::new();
+        restore(&mut metadata, codex.metadata.get(METADATA_KEY));
+        let mut claude = session("claude-code", "cc-2", serde_json::Value::Object(metadata));
+        let second = stamp(&mut claude);
+        assert_eq!(second.hops.len(), 2);
+        assert_eq!(second.hops[1].source_provider, "claude-code");
+        assert_eq!(second.origin(), Some(("codex", "cx-1")));
+        assert_eq!(Lineage::of(&claude), Some(second));
+    }
+
+    #[test]
+    fn every_writer_round_trips_the_lineage() {
+        use crate::model::{CanonicalMessage, MessageRole};
+        use crate::providers::WriteOptions;
+
+        let message = |idx: usize, role: MessageRole, content: &str| CanonicalMessage {
+            idx,
+            role,
+            content: content.to_string(),
+            parts: Vec::new(),
+            timestamp: Some(1_700_000_000_000 + idx as i64 * 1_000),
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_results: Vec::new(),
+            attachments: Vec::new(),
+            usage: None,
+            extra: serde_json::Value::Null,
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut source = session("codex", "cx-1", serde_json::Value::Null);
+        source.workspace = Some(tmp.path().join("app"));
+        source.started_at = Some(1_700_000_000_000);
+        source.ended_at = Some(1_700_000_001_000);
+        source.messages = vec![
+            message(0, MessageRole::User, "Fix the build"),
+            message(1, MessageRole::Assistant, "Done."),
+        ];
+        let lineage = stamp(&mut source);
+
+        crate::homes::scoped(Some(tmp.path()), || {
+            // Cline only writes into an existing extension storage dir.
+            std::fs::create_dir_all(
+                crate::homes::config_dir()
+                    .unwrap()
+                    .join("Code/User/globalStorage/saoudrizwan.claude-dev"),
+            )
+            .unwrap();
+            let registry = crate::discovery::ProviderRegistry::default_registry();
+            let opts = WriteOptions::default();
+            // Antigravity is read-only; ChatGPT desktop storage only
+            // exists on macOS.
+            for provider in registry.all_providers().into_iter().filter(|p| {
+                p.slug() != "antigravity" && (cfg!(target_os = "macos") || p.slug() != "chatgpt")
+            }) {
+                let written = provider
+                    .write_session(&source, &opts)
+                    .unwrap_or_else(|e| panic!("{} write failed: {e}", provider.slug()));
+                let readback = provider.read_session(&written.paths[0]).unwrap();
+                assert_eq!(
+                    Lineage::of(&readback).as_ref(),
+                    Some(&lineage),
+                    "{} dropped the lineage",
+                    provider.slug()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn malformed_or_empty_lineage_is_ignored() {
+        let mut metadata = serde_json::Map::new();
+        restore(
+            &mut metadata,
+            Some(&serde_json::json!({"not": "a lineage"})),
+        );
+        restore(&mut metadata, Some(&serde_json::json!([])));
+        assert!(metadata.is_empty());
+        assert_eq!(
+            Lineage::of(&session("codex", "x", serde_json::json!({METADATA_KEY: 3}))),
+            None
+        );
+    }
+}
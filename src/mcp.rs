@@ -0,0 +1,766 @@
+//! Model Context Protocol server (`casr mcp`).
+//!
+//! Speaks JSON-RPC 2.0 over stdio, one message per line, and exposes the
+//! sessions every provider can see as MCP tools. A running agent (Claude Code,
+//! Codex, Gemini, Cursor — all MCP clients) can then list, search and read
+//! another agent's history itself, or ask for a handoff brief, instead of the
+//! user converting a session first.
+//!
+//! Everything is read through [`ProviderRegistry`] and the canonical model,
+//! including `--home` trees and archives. The server never writes a session.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tracing::{debug, trace};
+
+use crate::discovery::{ProviderRegistry, SourceHint};
+use crate::git::GitSnapshot;
+use crate::handoff::HandoffTemplate;
+use crate::lineage::Lineage;
+use crate::model::{CanonicalSession, MessageRole};
+use crate::pipeline::{handoff_context, message_role_label};
+use crate::providers::{HistoryMode, Provider, ReadOptions};
+use crate::tools::ToolMap;
+
+/// Protocol revisions this server speaks, newest first.
+pub const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// The text `handoff_brief` renders; the same placeholders as
+/// `--enrich-template`.
+const BRIEF_TEMPLATE: &str = "\
+Handoff brief for {{source_provider}} session {{session_id}} ({{message_count}} messages).
+Workspace: {{workspace}}
+
+Recent conversation:
+{{recent_summary}}
+
+Files changed:
+{{files_touched}}
+
+Open todos:
+{{open_todos}}
+
+Repository state:
+{{git_state}}
+";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Longest message text `get_session_messages` returns unless asked for more.
+const DEFAULT_MAX_CHARS: usize = 2_000;
+/// Most messages one `get_session_messages` call returns.
+const MAX_MESSAGES_PER_CALL: usize = 200;
+
+/// A session as `list_sessions` and `search_sessions` describe it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionItem {
+    pub session_id: String,
+    pub provider: String,
+    pub title: Option<String>,
+    pub workspace: Option<String>,
+    pub started_at: Option<i64>,
+    pub last_active_at: Option<i64>,
+    pub messages: usize,
+    pub path: String,
+    /// `provider:session_id` of the conversation casr converted this from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+/// A message matching a `search_sessions` query.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub idx: usize,
+    pub role: String,
+    pub timestamp: Option<i64>,
+    pub snippet: String,
+}
+
+/// Serves MCP requests against a provider registry.
+pub struct McpServer {
+    registry: ProviderRegistry,
+    tool_map: ToolMap,
+}
+
+impl McpServer {
+    pub fn new(registry: ProviderRegistry, tool_map: ToolMap) -> Self {
+        Self { registry, tool_map }
+    }
+
+    /// Answer requests from `input` on `output` until `input` closes.
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> anyhow::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(&message),
+                Err(e) => Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    &format!("parse error: {e}"),
+                )),
+            };
+            if let Some(response) = response {
+                serde_json::to_writer(&mut output, &response)?;
+                output.write_all(b"\n")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The response to one JSON-RPC message; `None` for notifications and
+    /// for responses the client sends us.
+    pub fn handle(&self, message: &Value) -> Option<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_response(
+                message.get("id").cloned().unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "invalid request: expected a JSON-RPC object with a method",
+            ));
+        };
+        // Notifications (`notifications/initialized`, cancellations) take no reply.
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        debug!(method, "mcp request");
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("method not found: {method}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "tools/call needs a tool name".to_string()))?;
+        let args = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let outcome = match name {
+            "list_sessions" => self.list_sessions(&args),
+            "search_sessions" => self.search_sessions(&args),
+            "get_session_summary" => self.session_summary(&args),
+            "get_session_messages" => self.session_messages(&args),
+            "handoff_brief" => self.handoff_brief(&args),
+            _ => return Err((INVALID_PARAMS, format!("unknown tool: {name}"))),
+        };
+        // Tool failures are results the calling model can read, not
+        // protocol errors.
+        Ok(match outcome {
+            Ok((text, structured)) => json!({
+                "content": [{ "type": "text", "text": text }],
+                "structuredContent": structured,
+                "isError": false,
+            }),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": format!("{e:#}") }],
+                "isError": true,
+            }),
+        })
+    }
+
+    // -- Tools ---------------------------------------------------------------
+
+    fn list_sessions(&self, args: &Value) -> anyhow::Result<(String, Value)> {
+        let limit = usize_arg(args, "limit")?.unwrap_or(20);
+        let mut sessions = self.scan(str_arg(args, "provider"), str_arg(args, "workspace"))?;
+        sessions.truncate(limit);
+        let items: Vec<SessionItem> = sessions.iter().map(|(item, _)| item.clone()).collect();
+        structured(json!({ "sessions": items }))
+    }
+
+    fn search_sessions(&self, args: &Value) -> anyhow::Result<(String, Value)> {
+        let query = required_str(args, "query")?;
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| term.to_ascii_lowercase())
+            .collect();
+        if terms.is_empty() {
+            anyhow::bail!("query must contain at least one word");
+        }
+        let limit = usize_arg(args, "limit")?.unwrap_or(10);
+        let hits_per_session = usize_arg(args, "max_hits_per_session")?.unwrap_or(3);
+
+        let mut results: Vec<(SessionItem, usize, Vec<SearchHit>)> = self
+            .scan(str_arg(args, "provider"), str_arg(args, "workspace"))?
+            .into_iter()
+            .filter_map(|(item, session)| {
+                let matching: Vec<SearchHit> = session
+                    .messages
+                    .iter()
+                    .filter_map(|msg| {
+                        let haystack = msg.content.to_ascii_lowercase();
+                        terms
+                            .iter()
+                            .all(|term| haystack.contains(term))
+                            .then(|| SearchHit {
+                                idx: msg.idx,
+                                role: message_role_label(&msg.role).to_string(),
+                                timestamp: msg.timestamp,
+                                snippet: snippet(&msg.content, &haystack, &terms[0], 160),
+                            })
+                    })
+                    .collect();
+                let total = matching.len();
+                (total > 0).then(|| {
+                    (
+                        item,
+                        total,
+                        matching.into_iter().take(hits_per_session).collect(),
+                    )
+                })
+            })
+            .collect();
+        // Most matches first; `scan` already ordered ties by recency.
+        results.sort_by_key(|(_, total, _)| std::cmp::Reverse(*total));
+        results.truncate(limit);
+
+        let results: Vec<Value> = results
+            .into_iter()
+            .map(|(item, total, hits)| json!({ "session": item, "matches": total, "hits": hits }))
+            .collect();
+        structured(json!({ "query": query, "results": results }))
+    }
+
+    fn session_summary(&self, args: &Value) -> anyhow::Result<(String, Value)> {
+        let (provider, session) = self.read(args, HistoryMode::Live)?;
+        let context = handoff_context(&session, provider.slug(), "", &self.tool_map, None);
+        let count = |role: MessageRole| session.messages.iter().filter(|m| m.role == role).count();
+        let tool_calls: usize = session.messages.iter().map(|m| m.tool_calls.len()).sum();
+        let first_request = session
+            .messages
+            .iter()
+            .find(|m| m.role == MessageRole::User && !m.content.trim().is_empty())
+            .map(|m| truncate_chars(&m.content, 500).0);
+        let lineage = Lineage::of(&session).map(|lineage| lineage.to_value());
+
+        structured(json!({
+            "session_id": session.session_id,
+            "provider": provider.slug(),
+            "title": session.title,
+            "workspace": context.workspace,
+            "model": session.model_name,
+            "started_at": session.started_at,
+            "ended_at": session.ended_at,
+            "path": session.source_path.display().to_string(),
+            "messages": session.messages.len(),
+            "by_role": {
+                "user": count(MessageRole::User),
+                "assistant": count(MessageRole::Assistant),
+                "tool": count(MessageRole::Tool),
+                "system": count(MessageRole::System),
+            },
+            "tool_calls": tool_calls,
+            "first_request": first_request,
+            "recent": context.recent_summary,
+            "files_touched": context.files_touched,
+            "open_todos": context
+                .open_todos
+                .iter()
+                .map(|(text, status)| json!({ "text": text, "status": status }))
+                .collect::<Vec<_>>(),
+            "lineage": lineage,
+        }))
+    }
+
+    fn session_messages(&self, args: &Value) -> anyhow::Result<(String, Value)> {
+        let history = if args
+            .get("full_history")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            HistoryMode::Full
+        } else {
+            HistoryMode::Live
+        };
+        let (provider, session) = self.read(args, history)?;
+        let total = session.messages.len();
+        // A negative start counts from the end: -10 is the last ten messages.
+        let start = match args.get("start") {
+            None | Some(Value::Null) => 0,
+            Some(value) => {
+                let start = value
+                    .as_i64()
+                    .ok_or_else(|| anyhow::anyhow!("start must be an integer"))?;
+                if start < 0 {
+                    total.saturating_sub(start.unsigned_abs() as usize)
+                } else {
+                    (start as usize).min(total)
+                }
+            }
+        };
+        let end = usize_arg(args, "end")?
+            .unwrap_or(total)
+            .clamp(start, total)
+            .min(start + MAX_MESSAGES_PER_CALL);
+        let max_chars = usize_arg(args, "max_chars")?.unwrap_or(DEFAULT_MAX_CHARS);
+
+        let messages: Vec<Value> = session.messages[start..end]
+            .iter()
+            .map(|msg| {
+                let (content, truncated) = truncate_chars(&msg.content, max_chars);
+                json!({
+                    "idx": msg.idx,
+                    "role": message_role_label(&msg.role),
+                    "timestamp": msg.timestamp,
+                    "content": content,
+                    "truncated": truncated,
+                    "tool_calls": msg
+                        .tool_calls
+                        .iter()
+                        .map(|call| json!({ "id": call.id, "name": call.name, "arguments": call.arguments }))
+                        .collect::<Vec<_>>(),
+                    "tool_results": msg
+                        .tool_results
+                        .iter()
+                        .map(|result| json!({
+                            "call_id": result.call_id,
+                            "is_error": result.is_error,
+                            "content": truncate_chars(&result.content, max_chars).0,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        structured(json!({
+            "session_id": session.session_id,
+            "provider": provider.slug(),
+            "total": total,
+            "start": start,
+            "end": end,
+            "messages": messages,
+        }))
+    }
+
+    fn handoff_brief(&self, args: &Value) -> anyhow::Result<(String, Value)> {
+        let (provider, session) = self.read(args, HistoryMode::Live)?;
+        let target = str_arg(args, "target")
+            .map(|alias| {
+                self.registry
+                    .find_by_alias(alias)
+                    .map(|p| p.slug().to_string())
+                    .ok_or_else(|| anyhow::anyhow!("unknown provider '{alias}'"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let git = session
+            .workspace
+            .as_deref()
+            .filter(|workspace| workspace.is_dir())
+            .and_then(|workspace| GitSnapshot::capture(workspace, session.started_at, false));
+        let context = handoff_context(
+            &session,
+            provider.slug(),
+            &target,
+            &self.tool_map,
+            git.as_ref(),
+        );
+        let brief = HandoffTemplate::parse(BRIEF_TEMPLATE)?.render(&context);
+        Ok((
+            brief.clone(),
+            json!({
+                "session_id": session.session_id,
+                "provider": provider.slug(),
+                "brief": brief,
+            }),
+        ))
+    }
+
+    // -- Session access ------------------------------------------------------
+
+    /// Resolve and read the session named by `session_id` (and `provider`).
+    fn read(
+        &self,
+        args: &Value,
+        history: HistoryMode,
+    ) -> anyhow::Result<(&dyn Provider, CanonicalSession)> {
+        let session_id = required_str(args, "session_id")?;
+        let hint = str_arg(args, "provider").map(SourceHint::parse);
+        let resolved = self.registry.resolve_session(session_id, hint.as_ref())?;
+        let session = resolved.read(&ReadOptions { history })?;
+        Ok((resolved.provider, session))
+    }
+
+    /// Every readable session in the current home and the registry's `--home`
+    /// trees, most recently active first.
+    fn scan(
+        &self,
+        provider_filter: Option<&str>,
+        workspace_filter: Option<&str>,
+    ) -> anyhow::Result<Vec<(SessionItem, CanonicalSession)>> {
+        let provider_filter = provider_filter
+            .map(|alias| {
+                self.registry
+                    .find_by_alias(alias)
+                    .map(|p| p.slug())
+                    .ok_or_else(|| anyhow::anyhow!("unknown provider '{alias}'"))
+            })
+            .transpose()?;
+        let in_workspace = |session: &CanonicalSession| {
+            workspace_filter.is_none_or(|filter| {
+                session
+                    .workspace
+                    .as_deref()
+                    .is_some_and(|ws| ws.starts_with(filter))
+            })
+        };
+
+        let mut found: Vec<(SessionItem, CanonicalSession)> = Vec::new();
+        let homes =
+            std::iter::once(None).chain(self.registry.homes().iter().map(|h| Some(h.as_path())));
+        for home in homes {
+            if let Some(archive) = home.filter(|h| crate::archive::is_archive(h)) {
+                crate::archive::scan_sessions(archive, |member, path| {
+                    let Some(provider) = self.registry.infer_provider_for_path(path) else {
+                        return;
+                    };
+                    if provider_filter.is_some_and(|slug| slug != provider.slug()) {
+                        return;
+                    }
+                    if let Ok(mut session) = provider.read_session(path) {
+                        session.source_path = crate::archive::virtual_path(archive, member);
+                        if in_workspace(&session) {
+                            found.push((session_item(provider, &session, None), session));
+                        }
+                    }
+                })?;
+                continue;
+            }
+            crate::homes::scoped(home, || {
+                for provider in self.registry.installed_providers() {
+                    if provider_filter.is_some_and(|slug| slug != provider.slug()) {
+                        continue;
+                    }
+                    let paths = crate::discovery::session_files(provider);
+                    trace!(
+                        provider = provider.slug(),
+                        candidates = paths.len(),
+                        "mcp scan"
+                    );
+                    let sessions: Vec<(SessionItem, CanonicalSession)> = paths
+                        .into_par_iter()
+                        .filter_map(|path| {
+                            let session =
+                                crate::homes::scoped(home, || provider.read_session(&path)).ok()?;
+                            in_workspace(&session)
+                                .then(|| (session_item(provider, &session, Some(&path)), session))
+                        })
+                        .collect();
+                    found.extend(sessions);
+                }
+            });
+        }
+        found.sort_by_key(|(item, _)| {
+            std::cmp::Reverse(item.last_active_at.or(item.started_at).unwrap_or(0))
+        });
+        Ok(found)
+    }
+}
+
+fn session_item(
+    provider: &dyn Provider,
+    session: &CanonicalSession,
+    path: Option<&Path>,
+) -> SessionItem {
+    let mtime = path
+        .and_then(|p| std::fs::metadata(p).ok())
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+    SessionItem {
+        session_id: session.session_id.clone(),
+        provider: provider.slug().to_string(),
+        title: session.title.clone(),
+        workspace: session.workspace.as_ref().map(|w| w.display().to_string()),
+        started_at: session.started_at,
+        last_active_at: session.ended_at.or(mtime),
+        messages: session.messages.len(),
+        path: session.source_path.display().to_string(),
+        origin: Lineage::of(session).and_then(|lineage| {
+            lineage
+                .origin()
+                .map(|(provider, id)| format!("{provider}:{id}"))
+        }),
+    }
+}
+
+// -- Protocol ----------------------------------------------------------------
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "casr", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Read-only access to coding-agent sessions on this machine \
+    (Claude Code, Codex, Gemini, Cursor, and more). Find a session with list_sessions or \
+    search_sessions, then read it with get_session_summary, get_session_messages, or \
+    handoff_brief.",
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tool_definitions() -> Value {
+    let session_args = json!({
+        "session_id": { "type": "string", "description": "Session ID (a unique prefix is enough)." },
+        "provider": { "type": "string", "description": "Provider alias or slug (cc, cod, gmi, …) to search, or a session file path." },
+    });
+    let with = |extra: Value| {
+        let mut properties = session_args.clone();
+        if let (Some(properties), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+            properties.extend(extra.clone());
+        }
+        json!({ "type": "object", "properties": properties, "required": ["session_id"] })
+    };
+    let filters = json!({
+        "provider": { "type": "string", "description": "Only this provider (alias or slug)." },
+        "workspace": { "type": "string", "description": "Only sessions whose workspace is this directory or inside it." },
+    });
+    let with_filters = |extra: Value, required: &[&str]| {
+        let mut properties = filters.clone();
+        if let (Some(properties), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+            properties.extend(extra.clone());
+        }
+        json!({ "type": "object", "properties": properties, "required": required })
+    };
+
+    json!([
+        {
+            "name": "list_sessions",
+            "description": "List coding-agent sessions across providers, most recently active first.",
+            "inputSchema": with_filters(json!({
+                "limit": { "type": "integer", "minimum": 0, "default": 20 },
+            }), &[]),
+        },
+        {
+            "name": "search_sessions",
+            "description": "Find sessions whose messages contain every word of the query (case-insensitive).",
+            "inputSchema": with_filters(json!({
+                "query": { "type": "string" },
+                "limit": { "type": "integer", "minimum": 0, "default": 10 },
+                "max_hits_per_session": { "type": "integer", "minimum": 0, "default": 3 },
+            }), &["query"]),
+        },
+        {
+            "name": "get_session_summary",
+            "description": "Overview of one session: workspace, timing, message counts, first request, recent turns, files changed and open todos.",
+            "inputSchema": with(json!({})),
+        },
+        {
+            "name": "get_session_messages",
+            "description": "Messages of one session in the range [start, end). A negative start counts from the end.",
+            "inputSchema": with(json!({
+                "start": { "type": "integer", "default": 0 },
+                "end": { "type": "integer", "minimum": 0 },
+                "max_chars": { "type": "integer", "minimum": 0, "default": DEFAULT_MAX_CHARS },
+                "full_history": { "type": "boolean", "default": false, "description": "Include turns from before the agent compacted its context." },
+            })),
+        },
+        {
+            "name": "handoff_brief",
+            "description": "A short brief for continuing a session in another agent: recent turns, files changed, open todos and repository state.",
+            "inputSchema": with(json!({
+                "target": { "type": "string", "description": "Provider that will continue the work." },
+            })),
+        },
+    ])
+}
+
+// -- Helpers -----------------------------------------------------------------
+
+fn structured(value: Value) -> anyhow::Result<(String, Value)> {
+    Ok((serde_json::to_string_pretty(&value)?, value))
+}
+
+fn str_arg<'a>(args: &'a Value, name: &str) -> Option<&'a str> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+}
+
+fn required_str<'a>(args: &'a Value, name: &str) -> anyhow::Result<&'a str> {
+    str_arg(args, name).ok_or_else(|| anyhow::anyhow!("missing required argument '{name}'"))
+}
+
+fn usize_arg(args: &Value, name: &str) -> anyhow::Result<Option<usize>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(|n| Some(n as usize))
+            .ok_or_else(|| anyhow::anyhow!("{name} must be a non-negative integer")),
+    }
+}
+
+/// The first `max_chars` characters of `text`, and whether anything was cut.
+fn truncate_chars(text: &str, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => (format!("{}…", &text[..end]), true),
+        None => (text.to_string(), false),
+    }
+}
+
+/// About `width` bytes of `text` around the first occurrence of `term` in
+/// `haystack` (its ASCII-lowercased copy, so byte offsets line up).
+fn snippet(text: &str, haystack: &str, term: &str, width: usize) -> String {
+    let at = haystack.find(term).unwrap_or(0);
+    let mut start = at.saturating_sub(width / 2);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + width).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    let body = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    format!("{prefix}{body}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> McpServer {
+        McpServer::new(ProviderRegistry::new(Vec::new()), ToolMap::builtin())
+    }
+
+    fn request(id: i64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[test]
+    fn handshake_negotiates_the_version_and_ignores_notifications() {
+        let server = server();
+        let init = server
+            .handle(&request(
+                1,
+                "initialize",
+                json!({ "protocolVersion": "2025-03-26" }),
+            ))
+            .unwrap();
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "casr");
+        let unknown = server
+            .handle(&request(
+                2,
+                "initialize",
+                json!({ "protocolVersion": "1999-01-01" }),
+            ))
+            .unwrap();
+        assert_eq!(unknown["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(server.handle(&notification), None);
+        let missing = server
+            .handle(&request(3, "resources/list", Value::Null))
+            .unwrap();
+        assert_eq!(missing["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tools_list_and_call_errors() {
+        let server = server();
+        let listed = server
+            .handle(&request(1, "tools/list", Value::Null))
+            .unwrap();
+        let names: Vec<&str> = listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "list_sessions",
+                "search_sessions",
+                "get_session_summary",
+                "get_session_messages",
+                "handoff_brief"
+            ]
+        );
+
+        let unknown = server
+            .handle(&request(
+                2,
+                "tools/call",
+                json!({ "name": "delete_everything" }),
+            ))
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+
+        // A failing tool is reported to the model, not as a protocol error.
+        let failed = server
+            .handle(&request(
+                3,
+                "tools/call",
+                json!({ "name": "get_session_summary", "arguments": {} }),
+            ))
+            .unwrap();
+        assert_eq!(failed["result"]["isError"], true);
+        assert!(
+            failed["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("session_id")
+        );
+    }
+
+    #[test]
+    fn serve_answers_line_by_line_and_reports_parse_errors() {
+        let input = "{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"ping\"}\n\nnot json\n";
+        let mut output = Vec::new();
+        server().serve(input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], json!({ "jsonrpc": "2.0", "id": 7, "result": {} }));
+        assert_eq!(lines[1]["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn snippets_and_truncation_respect_char_boundaries() {
+        let text = format!("{} needle {}", "é".repeat(100), "ü".repeat(100));
+        let haystack = text.to_ascii_lowercase();
+        let snip = snippet(&text, &haystack, "needle", 40);
+        assert!(snip.contains("needle") && snip.starts_with('…') && snip.ends_with('…'));
+        assert_eq!(truncate_chars("héllo", 2), ("hé…".to_string(), true));
+        assert_eq!(truncate_chars("hi", 5), ("hi".to_string(), false));
+    }
+}
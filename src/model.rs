@@ -14,11 +14,12 @@
 //! [`MessageRole::Assistant`] to bridge this.
 //!
 //! **Deliberately omitted from CASS** (not needed for session conversion):
-//! - `approx_tokens` — per-message token data lives in `extra` if present.
+//! - `approx_tokens` — casr keeps the provider-reported [`Usage`] instead.
 //! - `source_id` / `origin_host` — casr works with local files only.
 //! - `Snippet` type — code snippet extraction is a CASS indexing feature.
 //! - Database `id` fields — casr has no database.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
@@ -50,6 +51,13 @@ pub struct CanonicalSession {
     pub source_path: PathBuf,
     /// Convenience: most common model name in the session.
     pub model_name: Option<String>,
+    /// Token totals for the whole session: the provider's own session counters
+    /// when it keeps them, otherwise the sum of per-message `usage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    /// Source constructs the reader skipped, for spotting format drift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unrecognized: Option<Unrecognized>,
 }
 
 /// A single message in a canonical session.
@@ -61,14 +69,28 @@ pub struct CanonicalMessage {
     pub role: MessageRole,
     /// The textual content of the message.
     pub content: String,
+    /// Ordered body parts, when the source stored a block array. Empty means
+    /// no ordering information beyond `content` + the flat lists below.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
     /// Message timestamp as epoch milliseconds.
     pub timestamp: Option<i64>,
-    /// Model name or `"user"` or `"reasoning"`.
+    /// Model name or `"user"`.
     pub author: Option<String>,
+    /// Model reasoning ("thinking") produced alongside this message, in
+    /// source order. A message may carry reasoning and no visible content.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasoning: Vec<Reasoning>,
     /// Tool invocations made in this message.
     pub tool_calls: Vec<ToolCall>,
     /// Results returned from tool invocations.
     pub tool_results: Vec<ToolResult>,
+    /// Images and files attached to this message, in source order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Tokens consumed by the model response that produced this message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
     /// Provider-specific fields preserved for round-trip fidelity.
     pub extra: serde_json::Value,
 }
@@ -89,6 +111,55 @@ pub struct ToolCall {
     pub id: Option<String>,
     pub name: String,
     pub arguments: serde_json::Value,
+    /// File modifications the call performs, parsed from `arguments` for
+    /// known edit tools (see [`crate::edits`]). Empty for other tools.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<FileEdit>,
+}
+
+impl ToolCall {
+    /// Build a call, parsing `edits` from the tool name and arguments.
+    pub fn new(id: Option<String>, name: String, arguments: serde_json::Value) -> Self {
+        let edits = crate::edits::parse_tool_edits(&name, &arguments);
+        Self {
+            id,
+            name,
+            arguments,
+            edits,
+        }
+    }
+}
+
+/// One file modification, independent of the tool that expressed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEdit {
+    /// Path as the agent wrote it (absolute or workspace-relative).
+    pub path: String,
+    #[serde(flatten)]
+    pub op: EditOp,
+    /// New path when the edit also renames the file (Codex `*** Move to:`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_to: Option<String>,
+}
+
+/// What a [`FileEdit`] does to its file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum EditOp {
+    /// Create the file, or overwrite it, with `content`.
+    Create { content: String },
+    /// Replace `old` with `new` — every occurrence when `replace_all`.
+    Replace {
+        old: String,
+        new: String,
+        #[serde(default)]
+        replace_all: bool,
+    },
+    /// Apply hunks in `apply_patch` form: optional `@@` headers followed by
+    /// ` `/`-`/`+` prefixed lines.
+    Hunks { hunks: String },
+    /// Delete the file.
+    Delete,
 }
 
 /// A tool result within a message.
@@ -99,6 +170,363 @@ pub struct ToolResult {
     pub is_error: bool,
 }
 
+/// A block of model reasoning.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reasoning {
+    /// Plain-text reasoning. Empty when `redacted`.
+    pub text: String,
+    /// Provider token authenticating `text` (Anthropic `signature`), or the
+    /// opaque encrypted payload when `redacted`.
+    pub signature: Option<String>,
+    /// The provider withheld the reasoning text.
+    pub redacted: bool,
+    /// Model that produced the reasoning; signatures are only valid for it.
+    pub model: Option<String>,
+}
+
+/// One ordered piece of a message body.
+///
+/// Reasoning, tool calls, tool results, and attachments are referenced by
+/// position in the message's `reasoning` / `tool_calls` / `tool_results` /
+/// `attachments` lists, so the flat fields stay the single source of truth
+/// for their payloads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    Thinking { index: usize },
+    ToolCall { index: usize },
+    ToolResult { index: usize },
+    Attachment { index: usize },
+    Citation(Citation),
+}
+
+/// A source citation attached to the preceding text part.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub cited_text: Option<String>,
+    /// The provider-native citation object.
+    pub original: serde_json::Value,
+}
+
+/// Token counts for one model response, or totals for a session.
+///
+/// Counts follow the Anthropic convention so they add up without double
+/// counting: `input_tokens` excludes cached input, and `output_tokens`
+/// includes `reasoning_tokens`. [`parse_usage`] converts other conventions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Uncached input tokens.
+    #[serde(default)]
+    pub input_tokens: u64,
+    /// Output tokens, reasoning included.
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Input tokens served from the prompt cache.
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    /// Input tokens written to the prompt cache.
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    /// The part of `output_tokens` spent on reasoning.
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+    /// Cost in USD as recorded by the provider itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl Usage {
+    /// Every token the model processed: input (cached or not) plus output.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_read_tokens + self.cache_write_tokens + self.output_tokens
+    }
+
+    /// Add `other` into `self`. Recorded costs are summed where present.
+    pub fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Sum a set of usage reports. `None` when the set is empty.
+    pub fn sum<'a>(usages: impl IntoIterator<Item = &'a Usage>) -> Option<Usage> {
+        usages.into_iter().fold(None, |total, u| {
+            let mut total = total.unwrap_or_default();
+            total.add(u);
+            Some(total)
+        })
+    }
+}
+
+/// Source-format constructs a reader skipped because it doesn't know them.
+///
+/// Counts are keyed by the construct's name (entry `type`, content block
+/// `type`, or object key). A growing count usually means the provider added
+/// something new that casr is silently discarding.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unrecognized {
+    /// Top-level entry / event types (e.g. a new Codex `event_msg` subtype).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub entry_types: BTreeMap<String, usize>,
+    /// Content block types inside a message body.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub block_types: BTreeMap<String, usize>,
+    /// Object keys the reader neither maps nor preserves.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, usize>,
+}
+
+/// Content block types that at least one reader understands.
+const KNOWN_BLOCK_TYPES: &[&str] = &[
+    "text",
+    "input_text",
+    "output_text",
+    "thinking",
+    "redacted_thinking",
+    "tool_use",
+    "tool_result",
+    "toolCall",
+    "toolUse",
+    "toolResult",
+    "image",
+    "document",
+    "input_image",
+];
+
+impl Unrecognized {
+    pub fn entry_type(&mut self, name: &str) {
+        *self.entry_types.entry(name.to_string()).or_default() += 1;
+    }
+
+    pub fn block_type(&mut self, name: &str) {
+        *self.block_types.entry(name.to_string()).or_default() += 1;
+    }
+
+    /// Record every key of `value` (an object) that isn't in `known`,
+    /// prefixed with `scope` (e.g. `"message."`).
+    pub fn keys(&mut self, scope: &str, value: &serde_json::Value, known: &[&str]) {
+        let Some(obj) = value.as_object() else {
+            return;
+        };
+        for key in obj.keys().filter(|k| !known.contains(&k.as_str())) {
+            *self.keys.entry(format!("{scope}{key}")).or_default() += 1;
+        }
+    }
+
+    /// Record the unknown block types in a content block array. Blocks are
+    /// typed by `type`, or by `kind` for Kiro parts.
+    pub fn blocks(&mut self, content: &serde_json::Value) {
+        for block in content.as_array().into_iter().flatten() {
+            let kind = block
+                .get("type")
+                .or_else(|| block.get("kind"))
+                .and_then(|v| v.as_str());
+            if let Some(kind) = kind
+                && !KNOWN_BLOCK_TYPES.contains(&kind)
+            {
+                self.block_type(kind);
+            }
+        }
+    }
+
+    /// Add `other`'s counts into `self`.
+    pub fn merge(&mut self, other: &Unrecognized) {
+        for (mine, theirs) in [
+            (&mut self.entry_types, &other.entry_types),
+            (&mut self.block_types, &other.block_types),
+            (&mut self.keys, &other.keys),
+        ] {
+            for (name, count) in theirs {
+                *mine.entry(name.clone()).or_default() += count;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entry_types.is_empty() && self.block_types.is_empty() && self.keys.is_empty()
+    }
+
+    /// `None` when nothing was recorded, for [`CanonicalSession::unrecognized`].
+    pub fn into_option(self) -> Option<Unrecognized> {
+        (!self.is_empty()).then_some(self)
+    }
+
+    /// Human-readable `label: name ×count, …` lines, one per non-empty group.
+    pub fn lines(&self) -> Vec<String> {
+        [
+            ("Entry types", &self.entry_types),
+            ("Block types", &self.block_types),
+            ("Keys", &self.keys),
+        ]
+        .into_iter()
+        .filter(|(_, counts)| !counts.is_empty())
+        .map(|(label, counts)| {
+            let names: Vec<String> = counts
+                .iter()
+                .map(|(name, count)| format!("{name} ×{count}"))
+                .collect();
+            format!("{label}: {}", names.join(", "))
+        })
+        .collect()
+    }
+}
+
+impl CanonicalMessage {
+    /// The ordered parts, if they still describe this message exactly: every
+    /// reasoning block, tool call, tool result, and attachment is referenced
+    /// once, and the text parts joined with `text_separator` reproduce
+    /// `content`.
+    ///
+    /// Writers pass the separator their own reader joins text blocks with.
+    /// `None` (no parts, or parts made stale by an edit to the flat fields)
+    /// means the writer should fall back to `content` + the flat lists.
+    pub fn ordered_parts(&self, text_separator: &str) -> Option<&[ContentPart]> {
+        if self.parts.is_empty() {
+            return None;
+        }
+        fn claim(seen: &mut [bool], index: usize) -> Option<()> {
+            let slot = seen.get_mut(index)?;
+            if *slot {
+                return None;
+            }
+            *slot = true;
+            Some(())
+        }
+        let mut reasoning = vec![false; self.reasoning.len()];
+        let mut calls = vec![false; self.tool_calls.len()];
+        let mut results = vec![false; self.tool_results.len()];
+        let mut attachments = vec![false; self.attachments.len()];
+        let mut texts: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            match part {
+                ContentPart::Text { text } => texts.push(text),
+                ContentPart::Thinking { index } => claim(&mut reasoning, *index)?,
+                ContentPart::ToolCall { index } => claim(&mut calls, *index)?,
+                ContentPart::ToolResult { index } => claim(&mut results, *index)?,
+                ContentPart::Attachment { index } => claim(&mut attachments, *index)?,
+                ContentPart::Citation(_) => {}
+            }
+        }
+        let complete = reasoning
+            .iter()
+            .chain(&calls)
+            .chain(&results)
+            .chain(&attachments)
+            .all(|s| *s);
+        (complete && texts.join(text_separator) == self.content).then_some(&self.parts)
+    }
+}
+
+/// An image or file attached to a message (e.g. a pasted screenshot).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// MIME type such as `"image/png"`, if known.
+    pub media_type: Option<String>,
+    /// Where the attachment bytes live.
+    pub source: AttachmentSource,
+    /// Decoded payload size in bytes, if known.
+    pub size_bytes: Option<u64>,
+    /// The provider-native block this attachment was parsed from.
+    pub original: serde_json::Value,
+}
+
+/// Location of an attachment's payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttachmentSource {
+    /// Inline base64-encoded bytes.
+    Base64 { data: String },
+    /// Remote (`http(s)://`) URL.
+    Url { url: String },
+    /// Local file path or `file://` URI.
+    File { path: String },
+}
+
+impl Attachment {
+    /// Build an inline base64 attachment, deriving `size_bytes` from the payload.
+    pub fn inline(
+        media_type: Option<String>,
+        data: impl Into<String>,
+        original: serde_json::Value,
+    ) -> Self {
+        let data = data.into();
+        Self {
+            media_type,
+            size_bytes: Some(base64_decoded_len(&data)),
+            source: AttachmentSource::Base64 { data },
+            original,
+        }
+    }
+
+    /// Whether the attachment is an image (by MIME type).
+    pub fn is_image(&self) -> bool {
+        self.media_type
+            .as_deref()
+            .is_some_and(|m| m.starts_with("image/"))
+    }
+
+    /// Inline payload, if the attachment carries its bytes.
+    pub fn base64_data(&self) -> Option<&str> {
+        match &self.source {
+            AttachmentSource::Base64 { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    /// URL form of the attachment: a `data:` URL for inline payloads, the
+    /// remote URL, or a `file://` URI for local files.
+    pub fn url(&self) -> String {
+        match &self.source {
+            AttachmentSource::Base64 { data } => format!(
+                "data:{};base64,{data}",
+                self.media_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream")
+            ),
+            AttachmentSource::Url { url } => url.clone(),
+            AttachmentSource::File { path } if path.starts_with("file://") => path.clone(),
+            AttachmentSource::File { path } => format!("file://{path}"),
+        }
+    }
+
+    /// Text stand-in for targets that cannot carry attachments,
+    /// e.g. `[Image: image/png, 2048 bytes]`.
+    pub fn placeholder(&self) -> String {
+        let label = if self.is_image() {
+            "Image"
+        } else {
+            "Attachment"
+        };
+        let mut details: Vec<String> = Vec::new();
+        if let Some(ref media_type) = self.media_type {
+            details.push(media_type.clone());
+        }
+        match &self.source {
+            AttachmentSource::Base64 { .. } => {
+                if let Some(size) = self.size_bytes {
+                    details.push(format!("{size} bytes"));
+                }
+            }
+            AttachmentSource::Url { url } => details.push(url.clone()),
+            AttachmentSource::File { path } => details.push(path.clone()),
+        }
+        if details.is_empty() {
+            format!("[{label}]")
+        } else {
+            format!("[{label}: {}]", details.join(", "))
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers — ported/adapted from CASS connectors/mod.rs
 // ---------------------------------------------------------------------------
@@ -178,6 +606,398 @@ pub fn flatten_content(value: &serde_json::Value) -> String {
     }
 }
 
+/// Build ordered [`ContentPart`]s from a provider-native content block array.
+///
+/// `reasoning`, `tool_calls`, `tool_results`, and `attachments` are the
+/// lengths of the message's flat lists as extracted by the reader; references are assigned
+/// in block order, and items that don't appear in the array (e.g. Codex
+/// payload-level function calls) are appended at the end. Recognizes the
+/// Anthropic/Codex `type` vocabulary, Pi-Agent/OpenClaw camelCase blocks, and
+/// Kiro `kind`/`data` parts. Non-array content yields no parts — a plain
+/// string has no interleaving to preserve.
+pub fn content_parts(
+    content: &serde_json::Value,
+    reasoning: usize,
+    tool_calls: usize,
+    tool_results: usize,
+    attachments: usize,
+) -> Vec<ContentPart> {
+    let Some(blocks) = content.as_array() else {
+        return Vec::new();
+    };
+    let (mut thoughts, mut calls, mut results, mut atts) = (0, 0, 0, 0);
+    let mut parts = Vec::new();
+    for block in blocks {
+        if let Some(s) = block.as_str() {
+            parts.push(ContentPart::Text {
+                text: s.to_string(),
+            });
+            continue;
+        }
+        let Some(obj) = block.as_object() else {
+            continue;
+        };
+        if parse_attachment(block).is_some() {
+            if atts < attachments {
+                parts.push(ContentPart::Attachment { index: atts });
+                atts += 1;
+            }
+            continue;
+        }
+        let kind = obj
+            .get("type")
+            .or_else(|| obj.get("kind"))
+            .and_then(|v| v.as_str());
+        match kind {
+            None | Some("text") | Some("input_text") | Some("output_text") => {
+                let text = obj
+                    .get("text")
+                    .or_else(|| obj.get("data"))
+                    .and_then(|v| v.as_str());
+                if let Some(text) = text {
+                    parts.push(ContentPart::Text {
+                        text: text.to_string(),
+                    });
+                }
+                if let Some(citations) = obj.get("citations").and_then(|v| v.as_array()) {
+                    parts.extend(citations.iter().map(|c| ContentPart::Citation(citation(c))));
+                }
+            }
+            Some("thinking") | Some("redacted_thinking")
+                if thoughts < reasoning && parse_reasoning(block).is_some() =>
+            {
+                parts.push(ContentPart::Thinking { index: thoughts });
+                thoughts += 1;
+            }
+            Some("tool_use") | Some("toolCall") | Some("toolUse") if calls < tool_calls => {
+                parts.push(ContentPart::ToolCall { index: calls });
+                calls += 1;
+            }
+            Some("tool_result") | Some("toolResult") if results < tool_results => {
+                parts.push(ContentPart::ToolResult { index: results });
+                results += 1;
+            }
+            _ => {}
+        }
+    }
+    parts.extend((thoughts..reasoning).map(|index| ContentPart::Thinking { index }));
+    parts.extend((calls..tool_calls).map(|index| ContentPart::ToolCall { index }));
+    parts.extend((results..tool_results).map(|index| ContentPart::ToolResult { index }));
+    parts.extend((atts..attachments).map(|index| ContentPart::Attachment { index }));
+    parts
+}
+
+fn citation(value: &serde_json::Value) -> Citation {
+    let field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+    Citation {
+        url: field("url").or_else(|| field("uri")),
+        title: field("title").or_else(|| field("document_title")),
+        cited_text: field("cited_text"),
+        original: value.clone(),
+    }
+}
+
+/// Collect reasoning blocks from a content block array.
+///
+/// Recognizes Anthropic `thinking` / `redacted_thinking`, Pi-Agent and
+/// OpenClaw `thinking` blocks, and Kiro `thinking` parts. Blocks with neither
+/// text nor a redacted payload are skipped.
+pub fn extract_reasoning(content: &serde_json::Value) -> Vec<Reasoning> {
+    content
+        .as_array()
+        .map(|blocks| blocks.iter().filter_map(parse_reasoning).collect())
+        .unwrap_or_default()
+}
+
+fn parse_reasoning(block: &serde_json::Value) -> Option<Reasoning> {
+    let str_at = |v: &serde_json::Value, key: &str| {
+        v.get(key)
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    if block.get("type").and_then(|v| v.as_str()) == Some("redacted_thinking") {
+        return Some(Reasoning {
+            signature: Some(str_at(block, "data")?),
+            redacted: true,
+            ..Reasoning::default()
+        });
+    }
+    if block.get("kind").and_then(|v| v.as_str()) == Some("thinking") {
+        let data = block.get("data")?;
+        if let Some(payload) = str_at(data, "redactedContent") {
+            return Some(Reasoning {
+                signature: Some(payload),
+                redacted: true,
+                model: str_at(data, "modelId"),
+                ..Reasoning::default()
+            });
+        }
+        let text = str_at(data, "text").filter(|t| !t.trim().is_empty())?;
+        return Some(Reasoning {
+            text,
+            signature: str_at(data, "signature"),
+            redacted: false,
+            model: str_at(data, "modelId"),
+        });
+    }
+    if block.get("type").and_then(|v| v.as_str()) != Some("thinking") {
+        return None;
+    }
+    let text = str_at(block, "thinking")
+        .or_else(|| str_at(block, "text"))
+        .filter(|t| !t.trim().is_empty())?;
+    Some(Reasoning {
+        text,
+        signature: str_at(block, "signature").or_else(|| str_at(block, "thinkingSignature")),
+        redacted: false,
+        model: None,
+    })
+}
+
+/// Collect attachments from a content block array.
+///
+/// Non-array values and blocks that aren't attachments are ignored; see
+/// [`parse_attachment`] for the recognized shapes.
+pub fn extract_attachments(content: &serde_json::Value) -> Vec<Attachment> {
+    content
+        .as_array()
+        .map(|blocks| blocks.iter().filter_map(parse_attachment).collect())
+        .unwrap_or_default()
+}
+
+/// Parse a single provider-native content block into an [`Attachment`].
+///
+/// Recognized shapes:
+/// - Anthropic `{type:"image"|"document", source:{type:"base64"|"url"|"file", …}}`
+/// - Pi-Agent / OpenClaw `{type:"image", data:"…", mimeType:"…"}` (or `url`)
+/// - Codex `{type:"input_image", image_url:"data:…"|"https://…"}`
+/// - Gemini parts `{inlineData:{mimeType,data}}` / `{fileData:{mimeType,fileUri}}`
+/// - Kiro `{kind:"image", data:{format, source:{bytes}|{url}}}`
+///
+/// The block is kept verbatim in [`Attachment::original`].
+pub fn parse_attachment(block: &serde_json::Value) -> Option<Attachment> {
+    let obj = block.as_object()?;
+    let str_field = |v: &serde_json::Value, key: &str| {
+        v.get(key).and_then(|x| x.as_str()).map(ToString::to_string)
+    };
+
+    if let Some(inline) = obj.get("inlineData") {
+        let data = str_field(inline, "data")?;
+        return Some(Attachment::inline(
+            str_field(inline, "mimeType"),
+            data,
+            block.clone(),
+        ));
+    }
+    if let Some(file) = obj.get("fileData") {
+        let uri = str_field(file, "fileUri")?;
+        return Some(attachment_from_url(
+            str_field(file, "mimeType"),
+            &uri,
+            block.clone(),
+        ));
+    }
+
+    if obj.get("kind").and_then(|v| v.as_str()) == Some("image") {
+        let data = obj.get("data")?;
+        let media_type = str_field(data, "format").map(|f| format!("image/{f}"));
+        if let Some(bytes) = data.pointer("/source/bytes").and_then(|v| v.as_str()) {
+            return Some(Attachment::inline(media_type, bytes, block.clone()));
+        }
+        let url = data.pointer("/source/url").and_then(|v| v.as_str())?;
+        return Some(attachment_from_url(media_type, url, block.clone()));
+    }
+
+    match obj.get("type").and_then(|v| v.as_str())? {
+        "image" | "document" => {
+            if let Some(source) = obj.get("source") {
+                let media_type = str_field(source, "media_type");
+                return match source.get("type").and_then(|v| v.as_str()) {
+                    Some("base64") => Some(Attachment::inline(
+                        media_type,
+                        str_field(source, "data")?,
+                        block.clone(),
+                    )),
+                    Some("url") => Some(attachment_from_url(
+                        media_type,
+                        &str_field(source, "url")?,
+                        block.clone(),
+                    )),
+                    Some("file") => Some(Attachment {
+                        media_type,
+                        source: AttachmentSource::File {
+                            path: str_field(source, "file_id")?,
+                        },
+                        size_bytes: None,
+                        original: block.clone(),
+                    }),
+                    _ => None,
+                };
+            }
+            let media_type = str_field(block, "mimeType").or_else(|| str_field(block, "mediaType"));
+            if let Some(data) = str_field(block, "data") {
+                return Some(Attachment::inline(media_type, data, block.clone()));
+            }
+            let url = str_field(block, "url").or_else(|| str_field(block, "path"))?;
+            Some(attachment_from_url(media_type, &url, block.clone()))
+        }
+        "input_image" => {
+            let url = obj
+                .get("image_url")
+                .and_then(|v| v.as_str().or_else(|| v.get("url").and_then(|u| u.as_str())))?;
+            Some(attachment_from_url(None, url, block.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Build an attachment from a URL-ish string: `data:` URLs become inline
+/// payloads, `file://` URIs and bare paths become file references.
+pub fn attachment_from_url(
+    media_type: Option<String>,
+    url: &str,
+    original: serde_json::Value,
+) -> Attachment {
+    if let Some((data_media_type, data)) = parse_data_url(url) {
+        return Attachment::inline(media_type.or(data_media_type), data, original);
+    }
+    let source = if url.contains("://") && !url.starts_with("file://") {
+        AttachmentSource::Url {
+            url: url.to_string(),
+        }
+    } else {
+        AttachmentSource::File {
+            path: url.to_string(),
+        }
+    };
+    Attachment {
+        media_type,
+        source,
+        size_bytes: None,
+        original,
+    }
+}
+
+/// Split a `data:<media-type>;base64,<payload>` URL into its parts.
+///
+/// Returns `None` for anything that isn't a base64 data URL.
+pub fn parse_data_url(url: &str) -> Option<(Option<String>, String)> {
+    let rest = url.strip_prefix("data:")?;
+    let (header, payload) = rest.split_once(',')?;
+    let media_type = header.strip_suffix(";base64")?;
+    let media_type = (!media_type.is_empty()).then(|| media_type.to_string());
+    Some((media_type, payload.to_string()))
+}
+
+/// Decoded byte length of a base64 payload (padding- and whitespace-aware).
+fn base64_decoded_len(data: &str) -> u64 {
+    let significant = data
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace() && *b != b'=')
+        .count() as u64;
+    significant * 3 / 4
+}
+
+/// Normalize a provider usage object into [`Usage`].
+///
+/// Recognizes the key spellings used across providers:
+/// - Anthropic / Claude Code: `input_tokens`, `output_tokens`,
+///   `cache_read_input_tokens`, `cache_creation_input_tokens`
+/// - Bedrock / Kiro / Amp: `inputTokens`, `outputTokens`,
+///   `cacheReadInputTokens`, `cacheWriteInputTokens`
+/// - Pi-Agent / OpenClaw: `input`, `output`, `cacheRead`, `cacheWrite`, `cost.total`
+/// - Codex / OpenAI: `cached_input_tokens` and `reasoning_output_tokens`; cached
+///   input is counted inside `input_tokens` there and is subtracted out
+/// - Gemini: `input`, `output`, `cached`, `thoughts`; thoughts are billed as
+///   output but reported separately, so they are added to `output_tokens`
+/// - Cline: `tokensIn`, `tokensOut`, `cacheReads`, `cacheWrites`, `totalCost`
+///
+/// Returns `None` when no count is recognized or every count is zero (writers
+/// often emit zeroed placeholders).
+pub fn parse_usage(value: &serde_json::Value) -> Option<Usage> {
+    let obj = value.as_object()?;
+    let count = |keys: &[&str]| keys.iter().find_map(|k| obj.get(*k).and_then(token_count));
+    let nested = |outer: &str, inner: &str| obj.get(outer)?.get(inner).and_then(token_count);
+
+    // OpenAI-style counters include cached tokens in the input count.
+    let cached_in_input = count(&["cached_input_tokens", "cachedInputTokens", "cached"]);
+    let cache_read = cached_in_input
+        .or_else(|| {
+            count(&[
+                "cache_read_input_tokens",
+                "cacheReadInputTokens",
+                "cacheRead",
+                "cacheReads",
+            ])
+        })
+        .or_else(|| nested("cache", "read"));
+    let cache_write = count(&[
+        "cache_creation_input_tokens",
+        "cacheCreationInputTokens",
+        "cacheWriteInputTokens",
+        "cacheWrite",
+        "cacheWrites",
+    ])
+    .or_else(|| nested("cache", "write"));
+    let input = count(&[
+        "input_tokens",
+        "inputTokens",
+        "input",
+        "prompt_tokens",
+        "promptTokens",
+        "tokensIn",
+    ]);
+    let output = count(&[
+        "output_tokens",
+        "outputTokens",
+        "output",
+        "completion_tokens",
+        "completionTokens",
+        "tokensOut",
+    ]);
+    let thoughts = count(&["thoughts"]);
+    let reasoning = count(&[
+        "reasoning_output_tokens",
+        "reasoning_tokens",
+        "reasoningTokens",
+    ])
+    .or(thoughts);
+    let cost_usd = obj
+        .get("cost")
+        .and_then(|c| c.as_f64().or_else(|| c.get("total")?.as_f64()))
+        .or_else(|| {
+            ["total_cost", "totalCost", "costUSD"]
+                .iter()
+                .find_map(|k| obj.get(*k)?.as_f64())
+        });
+
+    let usage = Usage {
+        input_tokens: match cached_in_input {
+            Some(cached) => input.unwrap_or(0).saturating_sub(cached),
+            None => input.unwrap_or(0),
+        },
+        output_tokens: output.unwrap_or(0) + thoughts.unwrap_or(0),
+        cache_read_tokens: cache_read.unwrap_or(0),
+        cache_write_tokens: cache_write.unwrap_or(0),
+        reasoning_tokens: reasoning.unwrap_or(0),
+        cost_usd: cost_usd.filter(|c| *c > 0.0),
+    };
+    (usage.total_tokens() > 0 || usage.cost_usd.is_some()).then_some(usage)
+}
+
+fn token_count(value: &serde_json::Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64))
+        .or_else(|| value.as_str()?.trim().parse().ok())
+}
+
+/// Sum per-message usage into session totals. `None` when no message has any.
+pub fn sum_message_usage(messages: &[CanonicalMessage]) -> Option<Usage> {
+    Usage::sum(messages.iter().filter_map(|m| m.usage.as_ref()))
+}
+
 /// Parse a timestamp value into epoch milliseconds.
 ///
 /// Accepts:
@@ -248,6 +1068,61 @@ pub fn parse_timestamp(value: &serde_json::Value) -> Option<i64> {
     }
 }
 
+/// Fold reasoning-only assistant messages into the turn that follows them.
+///
+/// Several agents log a turn's thinking as its own entry just before the
+/// visible reply (Claude Code writes one JSONL line per content block; Codex
+/// emits `agent_reasoning` ahead of the response). When
+/// `same_turn(reasoning_msg, next)` holds for the next assistant message, the
+/// reasoning moves onto it and the standalone message is dropped. Call before
+/// [`reindex_messages`].
+pub fn attach_reasoning_to_next_turn(
+    messages: &mut Vec<CanonicalMessage>,
+    same_turn: impl Fn(&CanonicalMessage, &CanonicalMessage) -> bool,
+) {
+    let is_reasoning_only = |m: &CanonicalMessage| {
+        m.role == MessageRole::Assistant
+            && !m.reasoning.is_empty()
+            && m.content.trim().is_empty()
+            && m.tool_calls.is_empty()
+            && m.tool_results.is_empty()
+            && m.attachments.is_empty()
+    };
+    let mut out: Vec<CanonicalMessage> = Vec::with_capacity(messages.len());
+    let mut pending: Option<CanonicalMessage> = None;
+    for mut msg in std::mem::take(messages) {
+        if let Some(prev) = pending.take() {
+            if msg.role == MessageRole::Assistant && same_turn(&prev, &msg) {
+                let moved = prev.reasoning.len();
+                if !msg.parts.is_empty() {
+                    for part in &mut msg.parts {
+                        if let ContentPart::Thinking { index } = part {
+                            *index += moved;
+                        }
+                    }
+                    msg.parts.splice(
+                        0..0,
+                        (0..moved).map(|index| ContentPart::Thinking { index }),
+                    );
+                }
+                msg.reasoning.splice(0..0, prev.reasoning);
+                if let Some(usage) = prev.usage {
+                    msg.usage.get_or_insert_with(Usage::default).add(&usage);
+                }
+            } else {
+                out.push(prev);
+            }
+        }
+        if is_reasoning_only(&msg) {
+            pending = Some(msg);
+        } else {
+            out.push(msg);
+        }
+    }
+    out.extend(pending);
+    *messages = out;
+}
+
 /// Re-assign sequential idx values (0, 1, 2, …) after filtering/sorting.
 pub fn reindex_messages(messages: &mut [CanonicalMessage]) {
     for (i, msg) in messages.iter_mut().enumerate() {
@@ -381,6 +1256,262 @@ mod tests {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // attachments
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn parse_attachment_anthropic_base64_image() {
+        let block = json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}
+        });
+        let att = parse_attachment(&block).unwrap();
+        assert_eq!(att.media_type.as_deref(), Some("image/png"));
+        assert_eq!(att.base64_data(), Some("AAAA"));
+        assert_eq!(att.size_bytes, Some(3));
+        assert_eq!(att.original, block);
+    }
+
+    #[test]
+    fn parse_attachment_codex_data_url() {
+        let block = json!({"type": "input_image", "image_url": "data:image/jpeg;base64,AAA="});
+        let att = parse_attachment(&block).unwrap();
+        assert_eq!(att.media_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(att.base64_data(), Some("AAA="));
+        assert_eq!(att.size_bytes, Some(2));
+        assert_eq!(att.url(), "data:image/jpeg;base64,AAA=");
+    }
+
+    #[test]
+    fn parse_attachment_gemini_parts() {
+        let inline = json!({"inlineData": {"mimeType": "image/webp", "data": "AAAA"}});
+        assert_eq!(
+            parse_attachment(&inline).unwrap().media_type.as_deref(),
+            Some("image/webp")
+        );
+        let file =
+            json!({"fileData": {"mimeType": "application/pdf", "fileUri": "gs://b/spec.pdf"}});
+        let att = parse_attachment(&file).unwrap();
+        assert_eq!(
+            att.source,
+            AttachmentSource::Url {
+                url: "gs://b/spec.pdf".to_string()
+            }
+        );
+        assert!(!att.is_image());
+    }
+
+    #[test]
+    fn parse_attachment_pi_and_kiro_shapes() {
+        let pi = json!({"type": "image", "data": "AAAA", "mimeType": "image/png"});
+        assert_eq!(parse_attachment(&pi).unwrap().base64_data(), Some("AAAA"));
+
+        let kiro = json!({"kind": "image", "data": {"format": "gif", "source": {"bytes": "AAAA"}}});
+        let att = parse_attachment(&kiro).unwrap();
+        assert_eq!(att.media_type.as_deref(), Some("image/gif"));
+        assert_eq!(att.base64_data(), Some("AAAA"));
+    }
+
+    #[test]
+    fn parse_attachment_local_path_is_file_source() {
+        let block = json!({"type": "image", "url": "/tmp/shot.png", "mimeType": "image/png"});
+        let att = parse_attachment(&block).unwrap();
+        assert_eq!(
+            att.source,
+            AttachmentSource::File {
+                path: "/tmp/shot.png".to_string()
+            }
+        );
+        assert_eq!(att.url(), "file:///tmp/shot.png");
+    }
+
+    #[test]
+    fn parse_attachment_ignores_text_and_tool_blocks() {
+        assert!(parse_attachment(&json!({"type": "text", "text": "hi"})).is_none());
+        assert!(parse_attachment(&json!({"type": "tool_use", "name": "Read"})).is_none());
+        assert!(parse_attachment(&json!("plain")).is_none());
+        let blocks = json!([
+            {"type": "text", "text": "see"},
+            {"type": "image", "data": "AAAA", "mimeType": "image/png"}
+        ]);
+        assert_eq!(extract_attachments(&blocks).len(), 1);
+        assert!(extract_attachments(&json!("text only")).is_empty());
+    }
+
+    #[test]
+    fn parse_data_url_requires_base64_marker() {
+        assert_eq!(
+            parse_data_url("data:image/png;base64,AAAA"),
+            Some((Some("image/png".to_string()), "AAAA".to_string()))
+        );
+        assert!(parse_data_url("data:text/plain,hello").is_none());
+        assert!(parse_data_url("https://example.com/a.png").is_none());
+    }
+
+    #[test]
+    fn attachment_placeholder_describes_payload() {
+        let inline = Attachment::inline(Some("image/png".to_string()), "AAAAAAAA", json!(null));
+        assert_eq!(inline.placeholder(), "[Image: image/png, 6 bytes]");
+        let remote = attachment_from_url(None, "https://example.com/spec.pdf", json!(null));
+        assert_eq!(
+            remote.placeholder(),
+            "[Attachment: https://example.com/spec.pdf]"
+        );
+    }
+
+    #[test]
+    fn message_without_attachments_omits_field_and_deserializes_legacy_json() {
+        let legacy = json!({
+            "idx": 0, "role": "User", "content": "hi", "timestamp": null, "author": null,
+            "tool_calls": [], "tool_results": [], "extra": null
+        });
+        let msg: CanonicalMessage = serde_json::from_value(legacy).unwrap();
+        assert!(msg.attachments.is_empty());
+        let serialized = serde_json::to_value(&msg).unwrap();
+        assert!(serialized.get("attachments").is_none());
+    }
+
+    // -----------------------------------------------------------------------
+    // content parts
+    // -----------------------------------------------------------------------
+
+    fn interleaved_message() -> CanonicalMessage {
+        CanonicalMessage {
+            idx: 0,
+            role: MessageRole::Assistant,
+            content: "Let me check.\nDone.".to_string(),
+            parts: vec![
+                ContentPart::Text {
+                    text: "Let me check.".to_string(),
+                },
+                ContentPart::ToolCall { index: 0 },
+                ContentPart::Text {
+                    text: "Done.".to_string(),
+                },
+            ],
+            timestamp: None,
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("t1".to_string()),
+                "Read".to_string(),
+                json!({}),
+            )],
+            tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
+            extra: json!({}),
+        }
+    }
+
+    #[test]
+    fn content_parts_preserves_block_order() {
+        let content = json!([
+            {"type": "thinking", "thinking": "plan"},
+            {"type": "text", "text": "Let me check."},
+            {"type": "tool_use", "id": "t1", "name": "Read", "input": {}},
+            {"type": "image", "source": {"type": "url", "url": "https://x/a.png"}},
+            {"type": "text", "text": "Done.", "citations": [
+                {"type": "web_search_result_location", "url": "https://x", "title": "X", "cited_text": "q"}
+            ]}
+        ]);
+        let parts = content_parts(&content, 1, 1, 0, 1);
+        assert_eq!(parts.len(), 6);
+        assert_eq!(parts[0], ContentPart::Thinking { index: 0 });
+        assert!(matches!(&parts[1], ContentPart::Text { text } if text == "Let me check."));
+        assert_eq!(parts[2], ContentPart::ToolCall { index: 0 });
+        assert_eq!(parts[3], ContentPart::Attachment { index: 0 });
+        assert!(matches!(&parts[4], ContentPart::Text { text } if text == "Done."));
+        let ContentPart::Citation(citation) = &parts[5] else {
+            panic!("expected citation, got {:?}", parts[5]);
+        };
+        assert_eq!(citation.url.as_deref(), Some("https://x"));
+        assert_eq!(citation.title.as_deref(), Some("X"));
+        assert_eq!(citation.cited_text.as_deref(), Some("q"));
+    }
+
+    #[test]
+    fn content_parts_reads_kiro_and_camel_case_blocks() {
+        let kiro = json!([
+            {"kind": "text", "data": "hi"},
+            {"kind": "toolUse", "data": {"toolUseId": "t1", "name": "ls", "input": {}}},
+            {"kind": "toolResult", "data": {"toolUseId": "t1", "content": []}}
+        ]);
+        assert_eq!(
+            content_parts(&kiro, 0, 1, 1, 0)[1..],
+            [
+                ContentPart::ToolCall { index: 0 },
+                ContentPart::ToolResult { index: 0 }
+            ]
+        );
+        let pi = json!([{"type": "toolCall", "id": "c1", "name": "bash"}, {"type": "text", "text": "ok"}]);
+        assert_eq!(
+            content_parts(&pi, 0, 1, 0, 0)[0],
+            ContentPart::ToolCall { index: 0 }
+        );
+    }
+
+    #[test]
+    fn content_parts_appends_unreferenced_items_and_ignores_strings() {
+        let content = json!([{"type": "output_text", "text": "hi"}]);
+        let parts = content_parts(&content, 0, 1, 0, 0);
+        assert_eq!(parts.last(), Some(&ContentPart::ToolCall { index: 0 }));
+        assert!(content_parts(&json!("plain"), 0, 0, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn extract_reasoning_normalizes_provider_blocks() {
+        let content = json!([
+            {"type": "thinking", "thinking": "signed", "signature": "sig-1"},
+            {"type": "redacted_thinking", "data": "opaque"},
+            {"type": "thinking", "text": "openclaw"},
+            {"type": "thinking", "thinking": "pi", "thinkingSignature": "sig-2"},
+            {"kind": "thinking", "data": {"text": "kiro", "signature": "sig-3", "modelId": "m"}},
+            {"kind": "thinking", "data": {"redactedContent": "blob"}},
+            {"type": "thinking", "thinking": "  "},
+            {"type": "text", "text": "visible"}
+        ]);
+        let reasoning = extract_reasoning(&content);
+        assert_eq!(reasoning.len(), 6);
+        assert_eq!(reasoning[0].text, "signed");
+        assert_eq!(reasoning[0].signature.as_deref(), Some("sig-1"));
+        assert!(reasoning[1].redacted);
+        assert_eq!(reasoning[1].signature.as_deref(), Some("opaque"));
+        assert_eq!(reasoning[2].text, "openclaw");
+        assert_eq!(reasoning[3].signature.as_deref(), Some("sig-2"));
+        assert_eq!(reasoning[4].model.as_deref(), Some("m"));
+        assert!(reasoning[5].redacted);
+        assert!(extract_reasoning(&json!("plain")).is_empty());
+    }
+
+    #[test]
+    fn ordered_parts_returns_consistent_parts() {
+        let msg = interleaved_message();
+        assert_eq!(msg.ordered_parts("\n").map(<[_]>::len), Some(3));
+        // A different text separator no longer reproduces `content`.
+        assert!(msg.ordered_parts("\n\n").is_none());
+    }
+
+    #[test]
+    fn ordered_parts_rejects_stale_parts() {
+        let mut edited = interleaved_message();
+        edited.content = "[Tool: Read]".to_string();
+        assert!(edited.ordered_parts("\n").is_none());
+
+        let mut dropped = interleaved_message();
+        dropped.tool_calls.clear();
+        assert!(dropped.ordered_parts("\n").is_none());
+
+        let mut added = interleaved_message();
+        added.tool_calls.push(added.tool_calls[0].clone());
+        assert!(added.ordered_parts("\n").is_none());
+
+        let mut none = interleaved_message();
+        none.parts.clear();
+        assert!(none.ordered_parts("\n").is_none());
+    }
+
     // -----------------------------------------------------------------------
     // parse_timestamp
     // -----------------------------------------------------------------------
@@ -562,20 +1693,28 @@ mod tests {
                 idx: 99,
                 role: MessageRole::User,
                 content: "a".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: json!({}),
             },
             CanonicalMessage {
                 idx: 42,
                 role: MessageRole::Assistant,
                 content: "b".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: json!({}),
             },
         ];
@@ -595,18 +1734,22 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: "Hello".to_string(),
+            parts: Vec::new(),
             timestamp: Some(1_700_000_000_000),
             author: Some("claude-3".to_string()),
-            tool_calls: vec![ToolCall {
-                id: Some("tc1".to_string()),
-                name: "Read".to_string(),
-                arguments: json!({"file_path": "/foo.rs"}),
-            }],
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("tc1".to_string()),
+                "Read".to_string(),
+                json!({"file_path": "/foo.rs"}),
+            )],
             tool_results: vec![ToolResult {
                 call_id: Some("tc1".to_string()),
                 content: "file contents".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: json!({"custom": "field"}),
         };
 
@@ -628,6 +1771,8 @@ mod tests {
             metadata: json!({"source": "claude_code"}),
             source_path: std::path::PathBuf::from("/tmp/test.jsonl"),
             model_name: Some("claude-3".to_string()),
+            usage: None,
+            unrecognized: None,
         };
 
         let serialized = serde_json::to_string(&session).unwrap();
@@ -642,4 +1787,123 @@ mod tests {
         let deserialized: MessageRole = serde_json::from_str(&serialized).unwrap();
         assert_eq!(role, deserialized);
     }
+
+    // -- parse_usage ----------------------------------------------------------
+
+    #[test]
+    fn parse_usage_anthropic_shape() {
+        let usage = parse_usage(&json!({
+            "input_tokens": 12,
+            "cache_creation_input_tokens": 300,
+            "cache_read_input_tokens": 4000,
+            "output_tokens": 50,
+            "service_tier": "standard"
+        }))
+        .unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.cache_write_tokens, 300);
+        assert_eq!(usage.cache_read_tokens, 4000);
+        assert_eq!(usage.output_tokens, 50);
+        assert_eq!(usage.total_tokens(), 4362);
+        assert_eq!(usage.cost_usd, None);
+    }
+
+    #[test]
+    fn parse_usage_codex_subtracts_cached_input() {
+        let usage = parse_usage(&json!({
+            "input_tokens": 150,
+            "cached_input_tokens": 80,
+            "output_tokens": 42,
+            "reasoning_output_tokens": 10,
+            "total_tokens": 192
+        }))
+        .unwrap();
+        assert_eq!(usage.input_tokens, 70);
+        assert_eq!(usage.cache_read_tokens, 80);
+        assert_eq!(usage.output_tokens, 42);
+        assert_eq!(usage.reasoning_tokens, 10);
+        assert_eq!(usage.total_tokens(), 192);
+    }
+
+    #[test]
+    fn parse_usage_gemini_thoughts_count_as_output() {
+        let usage = parse_usage(&json!({
+            "input": 100, "output": 20, "cached": 30, "thoughts": 5, "tool": 0, "total": 125
+        }))
+        .unwrap();
+        assert_eq!(usage.input_tokens, 70);
+        assert_eq!(usage.cache_read_tokens, 30);
+        assert_eq!(usage.output_tokens, 25);
+        assert_eq!(usage.reasoning_tokens, 5);
+    }
+
+    #[test]
+    fn parse_usage_reads_costs() {
+        let pi = parse_usage(&json!({
+            "input": 10, "output": 5, "cacheRead": 0, "cacheWrite": 0,
+            "cost": {"input": 0.001, "output": 0.002, "total": 0.003}
+        }))
+        .unwrap();
+        assert_eq!(pi.cost_usd, Some(0.003));
+
+        let cline = parse_usage(&json!({"tokensIn": 7, "tokensOut": 3, "totalCost": 0.5})).unwrap();
+        assert_eq!((cline.input_tokens, cline.output_tokens), (7, 3));
+        assert_eq!(cline.cost_usd, Some(0.5));
+    }
+
+    #[test]
+    fn parse_usage_empty_is_none() {
+        assert!(parse_usage(&json!({"input_tokens": 0, "output_tokens": 0})).is_none());
+        assert!(parse_usage(&json!({"totalCost": 0.0})).is_none());
+        assert!(parse_usage(&json!("12")).is_none());
+    }
+
+    #[test]
+    fn usage_add_sums_counts_and_costs() {
+        let mut a = Usage {
+            input_tokens: 1,
+            output_tokens: 2,
+            cost_usd: Some(0.25),
+            ..Usage::default()
+        };
+        a.add(&Usage {
+            input_tokens: 10,
+            cache_read_tokens: 5,
+            cost_usd: Some(0.5),
+            ..Usage::default()
+        });
+        assert_eq!(a.input_tokens, 11);
+        assert_eq!(a.cache_read_tokens, 5);
+        assert_eq!(a.cost_usd, Some(0.75));
+        assert!(Usage::sum(std::iter::empty()).is_none());
+    }
+
+    // -----------------------------------------------------------------------
+    // Unrecognized
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn unrecognized_counts_unknown_keys_and_blocks() {
+        let mut u = Unrecognized::default();
+        u.keys("message.", &json!({"role": "user", "mood": 1}), &["role"]);
+        u.blocks(&json!([
+            {"type": "text", "text": "hi"},
+            {"type": "hologram"},
+            {"kind": "hologram"},
+            "plain"
+        ]));
+        assert_eq!(u.keys["message.mood"], 1);
+        assert_eq!(u.block_types["hologram"], 2);
+        assert_eq!(u.block_types.len(), 1);
+
+        let mut total = Unrecognized::default();
+        total.merge(&u);
+        total.merge(&u);
+        assert_eq!(total.block_types["hologram"], 4);
+        assert_eq!(
+            total.lines(),
+            ["Block types: hologram ×4", "Keys: message.mood ×2"]
+        );
+        assert!(Unrecognized::default().into_option().is_none());
+    }
 }
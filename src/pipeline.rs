@@ -5,16 +5,26 @@
 //! trait — concrete providers are wired in via the registry.
 
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, BTreeSet, HashSet},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
+use serde::Serialize;
 use tracing::{debug, info, warn};
 
 use crate::discovery::{ProviderRegistry, SourceHint};
 use crate::error::CasrError;
-use crate::model::{CanonicalMessage, CanonicalSession, MessageRole, reindex_messages};
-use crate::providers::{WriteOptions, WrittenSession};
+use crate::git::GitSnapshot;
+use crate::handoff::{HandoffContext, HandoffTemplate};
+use crate::model::{
+    CanonicalMessage, CanonicalSession, ContentPart, MessageRole, Reasoning, reindex_messages,
+};
+use crate::providers::{
+    HistoryMode, Provider, ReadOptions, VerifyTolerance, WriteOptions, WrittenSession,
+};
+use crate::rewrite::{EditSummary, SessionEdit, apply_edits};
+use crate::tools::ToolMap;
 
 /// Top-level orchestrator for session conversion.
 pub struct ConversionPipeline {
@@ -28,6 +38,15 @@ pub struct ConvertOptions {
     pub force: bool,
     pub verbose: bool,
     pub enrich: bool,
+    /// Include a bounded `git diff --stat` in the enrichment's repository
+    /// snapshot.
+    pub enrich_diff_stat: bool,
+    /// Replace the built-in `--enrich` messages with one rendered from this
+    /// template.
+    pub enrich_template: Option<HandoffTemplate>,
+    /// Append a final user message, rendered from this template, so the
+    /// target agent picks up the work immediately.
+    pub continue_prompt: Option<HandoffTemplate>,
     pub source_hint: Option<String>,
     /// Cap the transferred history at roughly this many tokens (0 = unlimited).
     /// Applied only to cross-provider conversions; mirrors the source agent's
@@ -38,6 +57,17 @@ pub struct ConvertOptions {
     /// Keep source-agent reasoning traces (dropped by default for cross-agent
     /// handoffs since the target agent cannot use another agent's hidden reasoning).
     pub keep_reasoning: bool,
+    /// Read the source agent's live post-compaction context (default) or its
+    /// full on-disk archive. Readers that honor it record `historyMode` in
+    /// session metadata.
+    pub history: HistoryMode,
+    /// Tool vocabularies used to rewrite tool calls for the target agent.
+    pub tool_map: ToolMap,
+    /// Lossy conditions that fail the conversion instead of warning.
+    pub strict: BTreeSet<StrictCheck>,
+    /// Append the messages the target doesn't hold yet to this existing
+    /// target session instead of writing a new one.
+    pub append_to: Option<String>,
 }
 
 impl Default for ConvertOptions {
@@ -48,14 +78,69 @@ impl Default for ConvertOptions {
             force: false,
             verbose: false,
             enrich: false,
+            enrich_diff_stat: false,
+            enrich_template: None,
+            continue_prompt: None,
             source_hint: None,
             max_context_tokens: 0,
             max_tool_output: 0,
             keep_reasoning: true,
+            history: HistoryMode::Live,
+            tool_map: ToolMap::builtin(),
+            strict: BTreeSet::new(),
+            append_to: None,
+        }
+    }
+}
+
+/// A lossy condition that `--strict` turns into an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StrictCheck {
+    /// The context budget truncated tool output or dropped turns.
+    Budget,
+    /// A tool result references a tool call id that isn't in the session.
+    Tools,
+    /// The target stored a message under a different role.
+    Roles,
+    /// The target dropped message metadata (`extra` keys).
+    Metadata,
+}
+
+impl StrictCheck {
+    pub const ALL: [StrictCheck; 4] = [
+        StrictCheck::Budget,
+        StrictCheck::Tools,
+        StrictCheck::Roles,
+        StrictCheck::Metadata,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StrictCheck::Budget => "budget",
+            StrictCheck::Tools => "tools",
+            StrictCheck::Roles => "roles",
+            StrictCheck::Metadata => "metadata",
         }
     }
 }
 
+impl FromStr for StrictCheck {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StrictCheck::ALL
+            .into_iter()
+            .find(|check| check.as_str() == s.trim())
+            .ok_or_else(|| {
+                let known: Vec<&str> = StrictCheck::ALL.iter().map(|c| c.as_str()).collect();
+                format!(
+                    "unknown strict check '{s}' (expected one of: {})",
+                    known.join(", ")
+                )
+            })
+    }
+}
+
 /// Outcome of a successful (or dry-run) conversion.
 #[derive(Debug)]
 pub struct ConversionResult {
@@ -64,6 +149,217 @@ pub struct ConversionResult {
     pub canonical_session: CanonicalSession,
     pub written: Option<WrittenSession>,
     pub warnings: Vec<String>,
+    /// What the conversion lost or transformed. Empty for dry runs and
+    /// same-provider no-ops, which transform nothing.
+    pub fidelity: FidelityReport,
+    /// With [`ConvertOptions::append_to`], what was added to the existing
+    /// target session; `None` for dry runs.
+    pub append: Option<AppendSummary>,
+}
+
+/// What `--append-to` added to an existing target session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AppendSummary {
+    /// Messages appended.
+    pub appended: usize,
+    /// Source messages up to where the target session leaves off, which
+    /// were skipped.
+    pub already_present: usize,
+}
+
+/// Options for [`ConversionPipeline::edit`].
+#[derive(Debug, Clone, Default)]
+pub struct EditOptions {
+    pub dry_run: bool,
+    pub source_hint: Option<String>,
+    /// Rewrite the session under its own ID, setting the original aside as
+    /// `.bak`, instead of writing the edit as a new session.
+    pub in_place: bool,
+}
+
+/// Outcome of a successful (or dry-run) edit.
+#[derive(Debug)]
+pub struct EditResult {
+    pub provider: String,
+    /// ID of the session that was edited.
+    pub source_session_id: String,
+    /// The edited session, as written.
+    pub canonical_session: CanonicalSession,
+    pub summary: EditSummary,
+    /// `None` for dry runs.
+    pub written: Option<WrittenSession>,
+    /// Where the original session was set aside by an in-place edit.
+    pub original_backup: Option<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+/// Options for [`ConversionPipeline::sync_back`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncBackOptions {
+    pub dry_run: bool,
+    pub source_hint: Option<String>,
+    /// Keep the continuing agent's reasoning traces (dropped by default, as
+    /// in a handoff).
+    pub keep_reasoning: bool,
+    /// Tool vocabularies used to rewrite tool calls for the source agent.
+    pub tool_map: ToolMap,
+}
+
+/// Outcome of a successful (or dry-run) sync-back.
+#[derive(Debug)]
+pub struct SyncBackResult {
+    /// Provider of the converted session that was continued.
+    pub provider: String,
+    pub session_id: String,
+    /// The session it was converted from, which receives the new turns.
+    pub source_provider: String,
+    pub source_session_id: String,
+    pub source_path: PathBuf,
+    /// The turns appended to the source (or that would be, for a dry run).
+    pub appended: Vec<CanonicalMessage>,
+    /// New turns that an earlier sync-back already appended.
+    pub already_synced: usize,
+    /// `None` for dry runs and when there was nothing to append.
+    pub written: Option<WrittenSession>,
+    pub warnings: Vec<String>,
+}
+
+/// Everything a conversion lost or transformed on the way to the target.
+///
+/// Collected from the lossy pipeline steps (context budget, tool translation,
+/// reasoning/attachment folding) and from comparing the written session with
+/// its read-back, so a handoff can be judged before resuming it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FidelityReport {
+    /// Reasoning blocks dropped: source traces without `--keep-reasoning`,
+    /// plus redacted blocks the target cannot store.
+    pub reasoning_dropped: usize,
+    /// Reasoning blocks rendered as `[Thinking] …` text.
+    pub reasoning_rendered_as_text: usize,
+    /// Tool results elided to `--max-tool-output`.
+    pub tool_outputs_truncated: usize,
+    /// Older turns dropped to fit `--max-context-tokens`.
+    pub turns_dropped: usize,
+    /// Tool calls renamed into the target's vocabulary.
+    pub tool_calls_translated: usize,
+    /// Tool calls rendered as text because the target has no equivalent,
+    /// by source tool name.
+    pub tool_calls_rendered_as_text: BTreeMap<String, usize>,
+    /// Tool-only messages given synthesized `[Tool: …]` / `[Tool Output]` text.
+    pub tool_text_synthesized: usize,
+    /// Attachments replaced with text placeholders.
+    pub attachments_replaced: usize,
+    /// Roles the target stored as another role, keyed `"from → to"`.
+    pub roles_collapsed: BTreeMap<String, usize>,
+    /// Top-level message `extra` keys missing from the read-back, with the
+    /// number of messages that lost each.
+    pub extra_keys_ignored: BTreeMap<String, usize>,
+    /// Messages without a source timestamp that read back with one.
+    pub timestamps_synthesized: usize,
+}
+
+impl FidelityReport {
+    /// True when nothing was lost or transformed.
+    pub fn is_lossless(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// One human-readable line per non-empty finding.
+    pub fn lines(&self) -> Vec<String> {
+        let tally = |map: &BTreeMap<String, usize>| {
+            map.iter()
+                .map(|(k, n)| format!("{k} ×{n}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = Vec::new();
+        let mut count = |n: usize, what: &str| {
+            if n > 0 {
+                lines.push(format!("{n} {what}"));
+            }
+        };
+        count(self.reasoning_dropped, "reasoning block(s) dropped");
+        count(
+            self.reasoning_rendered_as_text,
+            "reasoning block(s) rendered as text",
+        );
+        count(self.tool_outputs_truncated, "tool output(s) truncated");
+        count(
+            self.turns_dropped,
+            "older turn(s) dropped for the context budget",
+        );
+        count(
+            self.tool_calls_translated,
+            "tool call(s) translated to the target's tools",
+        );
+        count(
+            self.tool_text_synthesized,
+            "tool-only message(s) given synthesized text",
+        );
+        count(
+            self.attachments_replaced,
+            "attachment(s) replaced with placeholders",
+        );
+        count(
+            self.timestamps_synthesized,
+            "timestamp(s) synthesized by the target",
+        );
+        if !self.tool_calls_rendered_as_text.is_empty() {
+            lines.push(format!(
+                "Tool calls rendered as text: {}",
+                tally(&self.tool_calls_rendered_as_text)
+            ));
+        }
+        if !self.roles_collapsed.is_empty() {
+            lines.push(format!("Roles collapsed: {}", tally(&self.roles_collapsed)));
+        }
+        if !self.extra_keys_ignored.is_empty() {
+            lines.push(format!(
+                "Metadata keys ignored by the target: {}",
+                tally(&self.extra_keys_ignored)
+            ));
+        }
+        lines
+    }
+
+    /// Record what the target's reader changed: collapsed roles, dropped
+    /// `extra` keys, and timestamps the writer filled in.
+    fn record_readback(&mut self, written: &CanonicalSession, readback: &CanonicalSession) {
+        for (orig, rb) in written.messages.iter().zip(&readback.messages) {
+            if orig.role != rb.role {
+                *self
+                    .roles_collapsed
+                    .entry(format!(
+                        "{} → {}",
+                        message_role_label(&orig.role),
+                        message_role_label(&rb.role)
+                    ))
+                    .or_default() += 1;
+            }
+            if let Some(keys) = orig.extra.as_object() {
+                for key in keys.keys() {
+                    if !extra_key_carried(&rb.extra, key) {
+                        *self.extra_keys_ignored.entry(key.clone()).or_default() += 1;
+                    }
+                }
+            }
+            if orig.timestamp.is_none() && rb.timestamp.is_some() {
+                self.timestamps_synthesized += 1;
+            }
+        }
+    }
+}
+
+/// Whether `key` survives in the read-back `extra`, either at the top level
+/// or one level down (writers that embed the source record under a key).
+fn extra_key_carried(readback: &serde_json::Value, key: &str) -> bool {
+    let Some(obj) = readback.as_object() else {
+        return false;
+    };
+    obj.contains_key(key)
+        || obj
+            .values()
+            .any(|v| v.as_object().is_some_and(|o| o.contains_key(key)))
 }
 
 // ---------------------------------------------------------------------------
@@ -142,41 +438,52 @@ pub fn validate_session(session: &CanonicalSession) -> ValidationResult {
         );
     }
 
-    let mut known_tool_call_ids: HashSet<&str> = HashSet::new();
-    for msg in &session.messages {
-        for call in &msg.tool_calls {
-            if let Some(call_id) = call.id.as_deref() {
-                known_tool_call_ids.insert(call_id);
-            }
-        }
-    }
-
-    for msg in &session.messages {
-        for tool_result in &msg.tool_results {
-            if let Some(call_id) = tool_result.call_id.as_deref()
-                && !known_tool_call_ids.contains(call_id)
-            {
-                result.info.push(format!(
-                    "Tool result at message index {} references unknown tool call id '{call_id}'.",
-                    msg.idx
-                ));
-                break;
-            }
+    let mut noted: HashSet<usize> = HashSet::new();
+    for (idx, call_id) in orphan_tool_results(session) {
+        if noted.insert(idx) {
+            result.info.push(format!(
+                "Tool result at message index {idx} references unknown tool call id '{call_id}'."
+            ));
         }
     }
 
     result
 }
 
+/// `(message idx, call id)` of every tool result whose call id matches no
+/// tool call in the session.
+pub fn orphan_tool_results(session: &CanonicalSession) -> Vec<(usize, String)> {
+    let known_tool_call_ids: HashSet<&str> = session
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_calls)
+        .filter_map(|call| call.id.as_deref())
+        .collect();
+    session
+        .messages
+        .iter()
+        .flat_map(|msg| {
+            msg.tool_results
+                .iter()
+                .filter_map(|tr| tr.call_id.as_deref())
+                .filter(|id| !known_tool_call_ids.contains(id))
+                .map(|id| (msg.idx, id.to_string()))
+        })
+        .collect()
+}
+
 fn prepend_enrichment_messages(
     session: &mut CanonicalSession,
     source_provider: &str,
     target_provider: &str,
     source_session_id: &str,
+    git: Option<&GitSnapshot>,
 ) -> usize {
+    let inserted = if git.is_some() { 3 } else { 2 };
     let first_timestamp = session.messages.iter().filter_map(|m| m.timestamp).min();
-    let notice_timestamp = first_timestamp.map(|ts| ts.saturating_sub(2));
-    let summary_timestamp = notice_timestamp.map(|ts| ts.saturating_add(1));
+    let notice_timestamp = first_timestamp.map(|ts| ts.saturating_sub(inserted));
+    let git_timestamp = notice_timestamp.map(|ts| ts.saturating_add(1));
+    let summary_timestamp = first_timestamp.map(|ts| ts.saturating_sub(1));
 
     let mut notice_lines = vec![
         "[casr synthetic context]".to_string(),
@@ -200,10 +507,14 @@ fn prepend_enrichment_messages(
         idx: 0,
         role: MessageRole::System,
         content: notice_lines.join("\n"),
+        parts: Vec::new(),
         timestamp: notice_timestamp,
         author: Some("casr-enrichment".to_string()),
+        reasoning: Vec::new(),
         tool_calls: Vec::new(),
         tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
         extra: serde_json::json!({
             "casr_enrichment": true,
             "synthetic": true,
@@ -218,10 +529,14 @@ fn prepend_enrichment_messages(
         idx: 1,
         role: MessageRole::System,
         content: summary_body,
+        parts: Vec::new(),
         timestamp: summary_timestamp,
         author: Some("casr-enrichment".to_string()),
+        reasoning: Vec::new(),
         tool_calls: Vec::new(),
         tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
         extra: serde_json::json!({
             "casr_enrichment": true,
             "synthetic": true,
@@ -233,11 +548,135 @@ fn prepend_enrichment_messages(
         }),
     };
 
-    let inserted = 2;
+    // What the previous agent left on disk, so the target doesn't redo or
+    // clobber it.
+    let git_message = git.map(|snapshot| CanonicalMessage {
+        idx: 1,
+        role: MessageRole::System,
+        content: format!(
+            "[casr synthetic context]\nRepository state at conversion time:\n{}",
+            snapshot.lines(session.started_at.is_some()).join("\n")
+        ),
+        parts: Vec::new(),
+        timestamp: git_timestamp,
+        author: Some("casr-enrichment".to_string()),
+        reasoning: Vec::new(),
+        tool_calls: Vec::new(),
+        tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
+        extra: serde_json::json!({
+            "casr_enrichment": true,
+            "synthetic": true,
+            "enrichment_type": "git_snapshot",
+            "source_provider": source_provider,
+            "target_provider": target_provider,
+            "source_session_id": source_session_id,
+            "git": snapshot,
+        }),
+    });
+
     session.messages.insert(0, summary);
+    if let Some(git_message) = git_message {
+        session.messages.insert(0, git_message);
+    }
     session.messages.insert(0, notice);
     reindex_messages(&mut session.messages);
-    inserted
+    inserted as usize
+}
+
+/// Prepend one synthetic system message holding a rendered
+/// `--enrich-template`. Returns the number of messages inserted.
+fn prepend_template_message(
+    session: &mut CanonicalSession,
+    content: String,
+    context: &HandoffContext,
+) -> usize {
+    let timestamp = session
+        .messages
+        .iter()
+        .filter_map(|m| m.timestamp)
+        .min()
+        .map(|ts| ts.saturating_sub(1));
+    let message = CanonicalMessage {
+        idx: 0,
+        role: MessageRole::System,
+        content,
+        parts: Vec::new(),
+        timestamp,
+        author: Some("casr-enrichment".to_string()),
+        reasoning: Vec::new(),
+        tool_calls: Vec::new(),
+        tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
+        extra: serde_json::json!({
+            "casr_enrichment": true,
+            "synthetic": true,
+            "enrichment_type": "template",
+            "source_provider": context.source_provider,
+            "target_provider": context.target_provider,
+            "source_session_id": context.session_id,
+        }),
+    };
+    session.messages.insert(0, message);
+    reindex_messages(&mut session.messages);
+    1
+}
+
+/// Append the rendered `--continue-prompt` as the final user message.
+fn append_continue_prompt(
+    session: &mut CanonicalSession,
+    content: String,
+    context: &HandoffContext,
+) {
+    let timestamp = session
+        .messages
+        .iter()
+        .filter_map(|m| m.timestamp)
+        .max()
+        .map(|ts| ts.saturating_add(1));
+    session.messages.push(CanonicalMessage {
+        idx: session.messages.len(),
+        role: MessageRole::User,
+        content,
+        parts: Vec::new(),
+        timestamp,
+        author: Some("user".to_string()),
+        reasoning: Vec::new(),
+        tool_calls: Vec::new(),
+        tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
+        extra: serde_json::json!({
+            "casr_continue_prompt": true,
+            "source_provider": context.source_provider,
+            "target_provider": context.target_provider,
+            "source_session_id": context.session_id,
+        }),
+    });
+}
+
+/// The values a handoff of `session` (read from `source_provider`) to
+/// `target_provider` fills into templates and briefs.
+pub fn handoff_context(
+    session: &CanonicalSession,
+    source_provider: &str,
+    target_provider: &str,
+    tool_map: &ToolMap,
+    git: Option<&GitSnapshot>,
+) -> HandoffContext {
+    HandoffContext {
+        source_provider: source_provider.to_string(),
+        target_provider: target_provider.to_string(),
+        session_id: session.session_id.clone(),
+        workspace: session.workspace.as_ref().map(|w| w.display().to_string()),
+        message_count: session.messages.len(),
+        recent_summary: build_recent_summary(session, 4, 180).1,
+        files_touched: crate::edits::changed_files(session),
+        open_todos: tool_map.open_todos(source_provider, session),
+        git_state: git.map(|g| g.lines(session.started_at.is_some())),
+    }
 }
 
 fn build_recent_summary(
@@ -284,13 +723,13 @@ fn compact_summary_text(text: &str, max_chars: usize) -> String {
     truncated
 }
 
-fn message_role_label(role: &MessageRole) -> String {
+pub(crate) fn message_role_label(role: &MessageRole) -> &str {
     match role {
-        MessageRole::User => "user".to_string(),
-        MessageRole::Assistant => "assistant".to_string(),
-        MessageRole::Tool => "tool".to_string(),
-        MessageRole::System => "system".to_string(),
-        MessageRole::Other(other) => other.clone(),
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+        MessageRole::System => "system",
+        MessageRole::Other(other) => other,
     }
 }
 
@@ -319,6 +758,12 @@ impl ConversionPipeline {
             session_id, "starting conversion"
         );
 
+        // 1b. With `--append-to`, the existing target session to extend.
+        let append_target = match opts.append_to.as_deref() {
+            Some(target_id) => Some(self.resolve_append_target(target_provider, target_id)?),
+            None => None,
+        };
+
         let target_detection = target_provider.detect();
         debug!(
             target = target_provider.name(),
@@ -347,11 +792,15 @@ but resume may fail until the CLI is installed.",
         debug!(
             source = resolved.provider.name(),
             path = %resolved.path.display(),
+            home = ?resolved.home,
             "source session resolved"
         );
 
         // 3. Read source session into canonical IR.
-        let mut canonical = resolved.provider.read_session(&resolved.path)?;
+        let read_opts = ReadOptions {
+            history: opts.history,
+        };
+        let mut canonical = resolved.read(&read_opts)?;
         debug!(
             messages = canonical.messages.len(),
             session_id = canonical.session_id,
@@ -374,20 +823,55 @@ but resume may fail until the CLI is installed.",
         for note in &validation.info {
             debug!(note, "validation info");
         }
+        if opts.strict.contains(&StrictCheck::Tools) {
+            let orphans = orphan_tool_results(&canonical);
+            if !orphans.is_empty() {
+                return Err(CasrError::StrictOrphanToolResults {
+                    call_ids: orphans.into_iter().map(|(_, id)| id).collect(),
+                }
+                .into());
+            }
+        }
 
-        // 5. Optional synthetic context enrichment.
-        if opts.enrich {
+        // 5. Optional synthetic context enrichment and continuation prompt.
+        let handoff = opts.enrich || opts.continue_prompt.is_some();
+        if handoff {
             let source_session_id = canonical.session_id.clone();
-            let inserted = prepend_enrichment_messages(
-                &mut canonical,
+            let git = canonical.workspace.as_deref().and_then(|workspace| {
+                GitSnapshot::capture(workspace, canonical.started_at, opts.enrich_diff_stat)
+            });
+            let context = handoff_context(
+                &canonical,
                 resolved.provider.slug(),
                 target_provider.slug(),
-                &source_session_id,
+                &opts.tool_map,
+                git.as_ref(),
             );
-            info!(inserted, "applied casr enrichment");
-            all_warnings.push(format!(
-                "Added {inserted} synthetic context message(s) via --enrich."
-            ));
+            if opts.enrich {
+                let inserted = match &opts.enrich_template {
+                    Some(template) => prepend_template_message(
+                        &mut canonical,
+                        template.render(&context),
+                        &context,
+                    ),
+                    None => prepend_enrichment_messages(
+                        &mut canonical,
+                        resolved.provider.slug(),
+                        target_provider.slug(),
+                        &source_session_id,
+                        git.as_ref(),
+                    ),
+                };
+                info!(inserted, "applied casr enrichment");
+                all_warnings.push(format!(
+                    "Added {inserted} synthetic context message(s) via --enrich."
+                ));
+            }
+            if let Some(prompt) = &opts.continue_prompt {
+                append_continue_prompt(&mut canonical, prompt.render(&context), &context);
+                all_warnings
+                    .push("Appended a continuation prompt via --continue-prompt.".to_string());
+            }
         }
 
         // 6. Dry-run short-circuit.
@@ -399,11 +883,18 @@ but resume may fail until the CLI is installed.",
                 canonical_session: canonical,
                 written: None,
                 warnings: all_warnings,
+                fidelity: FidelityReport::default(),
+                append: None,
             });
         }
 
-        // 7. Same-provider short-circuit.
-        if !opts.enrich && resolved.provider.slug() == target_provider.slug() {
+        // 7. Same-provider short-circuit. A session found under an alternate
+        // home or in an archive still has to be copied into the current home.
+        if !handoff
+            && append_target.is_none()
+            && resolved.origin().is_none()
+            && resolved.provider.slug() == target_provider.slug()
+        {
             info!("source and target provider are the same — skipping write and verify");
             all_warnings.push(
                 "Source and target provider are the same. Skipping conversion write.".to_string(),
@@ -419,6 +910,8 @@ but resume may fail until the CLI is installed.",
                     backup_path: None,
                 }),
                 warnings: all_warnings,
+                fidelity: FidelityReport::default(),
+                append: None,
             });
         }
 
@@ -430,13 +923,58 @@ but resume may fail until the CLI is installed.",
         // truncate oversized tool observations, then drop the oldest turns if
         // still over the token cap — preserving the original task message and
         // the most recent history, and never severing tool_use/tool_result pairs.
+        let mut fidelity = FidelityReport::default();
         let budget_warnings = apply_context_budget(
             &mut canonical,
             opts.max_context_tokens,
             opts.max_tool_output,
             opts.keep_reasoning,
+            &mut fidelity,
         );
         all_warnings.extend(budget_warnings);
+        if opts.strict.contains(&StrictCheck::Budget)
+            && (fidelity.reasoning_dropped > 0
+                || fidelity.tool_outputs_truncated > 0
+                || fidelity.turns_dropped > 0)
+        {
+            return Err(CasrError::StrictBudget {
+                reasoning_dropped: fidelity.reasoning_dropped,
+                tool_outputs_truncated: fidelity.tool_outputs_truncated,
+                turns_dropped: fidelity.turns_dropped,
+            }
+            .into());
+        }
+
+        // 7a3. Translate tool calls into the target's vocabulary (Codex
+        // `shell` → Claude `Bash`, Claude `Edit` → Gemini `replace`, …). Calls
+        // the target has no equivalent for become `[Tool: …]` text, with
+        // their results, so the target never sees tools it wasn't given.
+        let translation = opts.tool_map.translate_session(
+            &mut canonical,
+            resolved.provider.slug(),
+            target_provider.slug(),
+        );
+        fidelity.tool_calls_translated = translation.translated;
+        fidelity.tool_calls_rendered_as_text = translation.rendered.clone();
+        if translation.translated > 0 {
+            info!(
+                translated = translation.translated,
+                "translated tool calls to target vocabulary"
+            );
+        }
+        if !translation.rendered.is_empty() {
+            let names: Vec<String> = translation
+                .rendered
+                .iter()
+                .map(|(name, count)| format!("{name} ×{count}"))
+                .collect();
+            all_warnings.push(format!(
+                "Rendered {} tool call(s) {} has no equivalent for as text: {}.",
+                translation.rendered.values().sum::<usize>(),
+                target_provider.name(),
+                names.join(", ")
+            ));
+        }
 
         // 7b. Normalize tool-only messages with empty content.
         //
@@ -456,61 +994,162 @@ but resume may fail until the CLI is installed.",
         // a synthesized text block there would corrupt the round-trip and cause
         // the Anthropic API to reject the replayed history alongside the
         // matching `tool_result`.
-        let target_preserves_tool_blocks = target_provider.slug() == "claude-code";
-        if !target_preserves_tool_blocks {
-            for msg in &mut canonical.messages {
-                if !msg.content.trim().is_empty() {
-                    continue;
-                }
-
-                let has_tool_calls = !msg.tool_calls.is_empty();
-                let has_tool_results = !msg.tool_results.is_empty();
-
-                if !has_tool_calls && !has_tool_results {
-                    continue;
-                }
-
-                let mut parts: Vec<String> = Vec::new();
-
-                // Synthesize text for tool calls (matches Pi reader's format).
-                for tc in &msg.tool_calls {
-                    parts.push(format!("[Tool: {}]", tc.name));
-                }
-
-                // Synthesize text for tool results.
-                for tr in &msg.tool_results {
-                    if tr.is_error {
-                        parts.push(format!("[Tool Error] {}", tr.content));
-                    } else {
-                        parts.push(format!("[Tool Output] {}", tr.content));
-                    }
-                }
+        fidelity.tool_text_synthesized = synthesize_tool_text(&mut canonical, target_provider);
+
+        // 7c. Render reasoning the target can't store natively as text, so
+        // kept reasoning (`--keep-reasoning`) stays visible and read-back
+        // compares like with like. Redacted reasoning has no text to show.
+        let (rendered, withheld) = fold_reasoning_into_content(&mut canonical, target_provider);
+        fidelity.reasoning_rendered_as_text = rendered;
+        fidelity.reasoning_dropped += withheld;
+        if rendered > 0 {
+            all_warnings.push(format!(
+                "{rendered} reasoning block(s) rendered as text: {} cannot store them natively.",
+                target_provider.name()
+            ));
+        }
+        if withheld > 0 {
+            all_warnings.push(format!(
+                "Dropped {withheld} redacted reasoning block(s) that {} cannot store.",
+                target_provider.name()
+            ));
+        }
 
-                if !parts.is_empty() {
-                    msg.content = parts.join("\n");
-                }
+        // 7d. Fold attachments into text placeholders for targets that can't
+        // carry them, so a pasted screenshot still leaves a visible trace and
+        // read-back verification compares like with like.
+        if !target_provider.supports_attachments() {
+            let folded = fold_attachments_into_content(&mut canonical);
+            fidelity.attachments_replaced = folded;
+            if folded > 0 {
+                all_warnings.push(format!(
+                    "{folded} attachment(s) replaced with text placeholders: {} cannot carry images or files.",
+                    target_provider.name()
+                ));
             }
         }
 
-        // 8. Write to target provider.
-        let write_opts = WriteOptions { force: opts.force };
-        let written = target_provider.write_session(&canonical, &write_opts)?;
+        // 8. Write to target provider: a new session, or (`--append-to`) the
+        // messages the existing target session doesn't hold yet. Appending
+        // keeps the target's own records and metadata, so read-back is
+        // compared with the existing session plus the new messages.
+        let full_history = ReadOptions {
+            history: HistoryMode::Full,
+        };
+        let (written, append, appended_session) = match &append_target {
+            None => {
+                // Record this conversion in the session's lineage; the writer
+                // stores it wherever the target format allows.
+                let lineage = crate::lineage::stamp(&mut canonical);
+                debug!(hops = lineage.hops.len(), "stamped conversion lineage");
+
+                let write_opts = WriteOptions {
+                    force: opts.force,
+                    ..Default::default()
+                };
+                (
+                    target_provider.write_session(&canonical, &write_opts)?,
+                    None,
+                    None,
+                )
+            }
+            Some(target) => {
+                let mut existing = target.read(&full_history)?;
+                let (new, already_present) = new_messages(
+                    &existing.messages,
+                    canonical.messages.clone(),
+                    target_provider.verify_tolerance().timestamp_drift_ms,
+                );
+                let append = AppendSummary {
+                    appended: new.len(),
+                    already_present,
+                };
+                if new.is_empty() {
+                    info!(
+                        target_session_id = existing.session_id,
+                        already_present, "target session already holds every message"
+                    );
+                    all_warnings.push(format!(
+                        "{} session '{}' already holds all {already_present} message(s); nothing appended.",
+                        target_provider.name(),
+                        existing.session_id
+                    ));
+                    return Ok(ConversionResult {
+                        source_provider: resolved.provider.slug().to_string(),
+                        target_provider: target_provider.slug().to_string(),
+                        written: Some(WrittenSession {
+                            paths: Vec::new(),
+                            session_id: existing.session_id.clone(),
+                            resume_command: target_provider.resume_command(&existing.session_id),
+                            backup_path: None,
+                        }),
+                        canonical_session: canonical,
+                        warnings: all_warnings,
+                        fidelity,
+                        append: Some(append),
+                    });
+                }
+                let written = target_provider.append_messages(&target.path, &existing, &new)?;
+                existing.messages.extend(new);
+                (written, Some(append), Some(existing))
+            }
+        };
         info!(
             target_session_id = written.session_id,
             resume_command = written.resume_command,
+            appended = ?append.map(|a| a.appended),
             "session written"
         );
 
         // 9. Read-back verification.
         if let Some(first_path) = written.paths.first() {
-            match target_provider.read_session(first_path) {
+            let (expected, readback) = match &appended_session {
+                Some(appended) => (
+                    appended,
+                    target_provider.read_session_with(first_path, &full_history),
+                ),
+                None => (&canonical, target_provider.read_session(first_path)),
+            };
+            match readback {
                 Ok(readback) => {
                     debug!(
                         readback_messages = readback.messages.len(),
-                        original_messages = canonical.messages.len(),
+                        original_messages = expected.messages.len(),
                         "read-back verification"
                     );
-                    if let Some(detail) = readback_mismatch_detail(&canonical, &readback) {
+                    let mismatches =
+                        verify_readback(expected, &readback, &target_provider.verify_tolerance());
+                    if mismatches.is_empty() {
+                        fidelity.record_readback(expected, &readback);
+                        let collapsed = opts.strict.contains(&StrictCheck::Roles)
+                            && !fidelity.roles_collapsed.is_empty();
+                        let dropped = opts.strict.contains(&StrictCheck::Metadata)
+                            && !fidelity.extra_keys_ignored.is_empty();
+                        if collapsed || dropped {
+                            let provider = target_provider.slug().to_string();
+                            let rollback = match rollback_written_session(&provider, &written) {
+                                Ok(()) => "rollback succeeded".to_string(),
+                                Err(rollback_error) => {
+                                    format!("rollback failed: {rollback_error}")
+                                }
+                            };
+                            let err = if collapsed {
+                                CasrError::StrictRolesCollapsed {
+                                    provider,
+                                    roles: fidelity.roles_collapsed.keys().cloned().collect(),
+                                    rollback,
+                                }
+                            } else {
+                                CasrError::StrictMetadataDropped {
+                                    provider,
+                                    keys: fidelity.extra_keys_ignored.keys().cloned().collect(),
+                                    rollback,
+                                }
+                            };
+                            return Err(err.into());
+                        }
+                    } else {
+                        let detail = summarize_mismatches(&mismatches);
                         warn!(detail, "read-back verification failed");
                         let rollback_detail =
                             match rollback_written_session(target_provider.slug(), &written) {
@@ -523,37 +1162,555 @@ but resume may fail until the CLI is installed.",
                             provider: target_provider.slug().to_string(),
                             written_paths: written.paths.clone(),
                             detail: format!("{detail}; {rollback_detail}"),
+                            mismatches,
                         }
                         .into());
                     }
                 }
                 Err(e) => {
-                    warn!(error = %e, "read-back verification failed");
-                    let rollback_detail =
-                        match rollback_written_session(target_provider.slug(), &written) {
-                            Ok(()) => "rollback succeeded".to_string(),
-                            Err(rollback_error) => {
-                                format!("rollback failed: {rollback_error}")
-                            }
-                        };
+                    warn!(error = %e, "read-back verification failed");
+                    let rollback_detail =
+                        match rollback_written_session(target_provider.slug(), &written) {
+                            Ok(()) => "rollback succeeded".to_string(),
+                            Err(rollback_error) => {
+                                format!("rollback failed: {rollback_error}")
+                            }
+                        };
+                    return Err(CasrError::VerifyFailed {
+                        provider: target_provider.slug().to_string(),
+                        written_paths: written.paths.clone(),
+                        detail: format!("unable to read written session: {e}; {rollback_detail}"),
+                        mismatches: Vec::new(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(ConversionResult {
+            source_provider: resolved.provider.slug().to_string(),
+            target_provider: target_provider.slug().to_string(),
+            canonical_session: canonical,
+            written: Some(written),
+            warnings: all_warnings,
+            fidelity,
+            append,
+        })
+    }
+
+    /// Resolve the `--append-to` session in `target`, which must be able to
+    /// take appended messages in the current home.
+    fn resolve_append_target(
+        &self,
+        target: &dyn Provider,
+        target_session_id: &str,
+    ) -> anyhow::Result<crate::discovery::ResolvedSession<'_>> {
+        let rejected = |reason: String| CasrError::AppendRejected {
+            session_id: target_session_id.to_string(),
+            reason,
+        };
+        if !target.appends_messages() {
+            return Err(rejected(format!(
+                "{} sessions can't be appended to; omit --append-to to write a new session",
+                target.name()
+            ))
+            .into());
+        }
+        let resolved = self.registry.resolve_session(
+            target_session_id,
+            Some(&SourceHint::Alias(target.slug().to_string())),
+        )?;
+        if let Some(origin) = resolved.origin() {
+            return Err(rejected(format!(
+                "it was found in {}, not the current home",
+                origin.display()
+            ))
+            .into());
+        }
+        Ok(resolved)
+    }
+
+    /// Apply `edits` to a session and write it back to the provider it came
+    /// from, as a new session or (with [`EditOptions::in_place`]) under its
+    /// own ID. The full on-disk history is edited, never just the live
+    /// context, and the result is verified by reading it back.
+    pub fn edit(
+        &self,
+        session_id: &str,
+        edits: &[SessionEdit],
+        opts: EditOptions,
+    ) -> anyhow::Result<EditResult> {
+        let rejected = |reason: String| CasrError::EditRejected {
+            session_id: session_id.to_string(),
+            reason,
+        };
+
+        // 1. Resolve and check that the provider can take the edit back.
+        let source_hint = opts.source_hint.as_deref().map(SourceHint::parse);
+        let resolved = self
+            .registry
+            .resolve_session(session_id, source_hint.as_ref())?;
+        let provider = resolved.provider;
+        if !provider.writable() {
+            return Err(CasrError::ProviderUnavailable {
+                provider: provider.slug().to_string(),
+                reason: format!("{} sessions are read-only for casr", provider.name()),
+                evidence: vec![resolved.path.display().to_string()],
+            }
+            .into());
+        }
+        if opts.in_place && !provider.edits_in_place() {
+            return Err(rejected(format!(
+                "{} sessions can't be rewritten in place; omit --in-place to save the edit as a new session",
+                provider.name()
+            ))
+            .into());
+        }
+        if opts.in_place
+            && let Some(origin) = resolved.origin()
+        {
+            return Err(rejected(format!(
+                "it was read from {}; omit --in-place to save the edit as a new session in the current home",
+                origin.display()
+            ))
+            .into());
+        }
+
+        // 2. Read the full archive: an in-place rewrite must not drop
+        // history hidden behind compaction.
+        let read_opts = ReadOptions {
+            history: HistoryMode::Full,
+        };
+        let mut canonical = resolved.read(&read_opts)?;
+        let source_session_id = canonical.session_id.clone();
+        info!(
+            provider = provider.slug(),
+            session_id = source_session_id,
+            edits = edits.len(),
+            "editing session"
+        );
+
+        // 3. Edit and validate.
+        let summary = apply_edits(&mut canonical, edits).map_err(&rejected)?;
+        if summary.is_empty() {
+            return Err(rejected(
+                "the edits matched nothing; the session is unchanged".to_string(),
+            )
+            .into());
+        }
+        let validation = validate_session(&canonical);
+        if validation.has_errors() {
+            return Err(CasrError::ValidationError {
+                errors: validation.errors,
+                warnings: validation.warnings,
+                info: validation.info,
+            }
+            .into());
+        }
+        let mut warnings = validation.warnings;
+
+        if opts.dry_run {
+            return Ok(EditResult {
+                provider: provider.slug().to_string(),
+                source_session_id,
+                canonical_session: canonical,
+                summary,
+                written: None,
+                original_backup: None,
+                warnings,
+            });
+        }
+
+        // 4. Mirror the conversion normalizations so read-back compares like
+        // with like.
+        synthesize_tool_text(&mut canonical, provider);
+        let (rendered, withheld) = fold_reasoning_into_content(&mut canonical, provider);
+        if rendered + withheld > 0 {
+            warnings.push(format!(
+                "{rendered} reasoning block(s) rendered as text and {withheld} redacted block(s) dropped: {} cannot store them natively.",
+                provider.name()
+            ));
+        }
+        if !provider.supports_attachments() {
+            let folded = fold_attachments_into_content(&mut canonical);
+            if folded > 0 {
+                warnings.push(format!(
+                    "{folded} attachment(s) replaced with text placeholders: {} cannot carry images or files.",
+                    provider.name()
+                ));
+            }
+        }
+
+        // 5. Write. Writers that reuse the canonical ID need a fresh one for
+        // a new session; in place, every writer is asked for the original.
+        let write_opts = if opts.in_place {
+            WriteOptions {
+                force: true,
+                session_id: Some(source_session_id.clone()),
+            }
+        } else {
+            canonical.session_id = uuid::Uuid::new_v4().to_string();
+            WriteOptions::default()
+        };
+        let written = provider.write_session(&canonical, &write_opts)?;
+        let rollback =
+            |written: &WrittenSession| match rollback_written_session(provider.slug(), written) {
+                Ok(()) => "rollback succeeded".to_string(),
+                Err(rollback_error) => format!("rollback failed: {rollback_error}"),
+            };
+        if opts.in_place && written.session_id != source_session_id {
+            let rollback_detail = rollback(&written);
+            return Err(rejected(format!(
+                "{} wrote the edit as '{}' instead of rewriting it in place; {rollback_detail}",
+                provider.name(),
+                written.session_id
+            ))
+            .into());
+        }
+
+        // 6. Read-back verification.
+        if let Some(first_path) = written.paths.first() {
+            let mismatches = match provider.read_session(first_path) {
+                Ok(readback) => {
+                    verify_readback(&canonical, &readback, &provider.verify_tolerance())
+                }
+                Err(e) => {
+                    let rollback_detail = rollback(&written);
                     return Err(CasrError::VerifyFailed {
-                        provider: target_provider.slug().to_string(),
+                        provider: provider.slug().to_string(),
                         written_paths: written.paths.clone(),
                         detail: format!("unable to read written session: {e}; {rollback_detail}"),
+                        mismatches: Vec::new(),
                     }
                     .into());
                 }
+            };
+            if !mismatches.is_empty() {
+                let detail = summarize_mismatches(&mismatches);
+                warn!(detail, "read-back verification failed");
+                let rollback_detail = rollback(&written);
+                return Err(CasrError::VerifyFailed {
+                    provider: provider.slug().to_string(),
+                    written_paths: written.paths.clone(),
+                    detail: format!("{detail}; {rollback_detail}"),
+                    mismatches,
+                }
+                .into());
             }
         }
 
-        Ok(ConversionResult {
-            source_provider: resolved.provider.slug().to_string(),
-            target_provider: target_provider.slug().to_string(),
+        // 7. In place, set the original aside unless the writer already
+        // replaced it (and made the `.bak` itself).
+        let mut original_backup = written.backup_path.clone();
+        if opts.in_place && !written.paths.contains(&resolved.path) && resolved.path.exists() {
+            let backup = find_backup_path(&resolved.path);
+            if let Err(error) = std::fs::rename(&resolved.path, &backup) {
+                let rollback_detail = rollback(&written);
+                return Err(CasrError::SessionWriteError {
+                    path: resolved.path.clone(),
+                    provider: provider.slug().to_string(),
+                    detail: format!(
+                        "failed to back up the original session: {error}; {rollback_detail}"
+                    ),
+                }
+                .into());
+            }
+            original_backup = Some(backup);
+        }
+        info!(
+            session_id = written.session_id,
+            backup = ?original_backup,
+            "edited session written"
+        );
+
+        Ok(EditResult {
+            provider: provider.slug().to_string(),
+            source_session_id,
             canonical_session: canonical,
+            summary,
             written: Some(written),
-            warnings: all_warnings,
+            original_backup,
+            warnings,
+        })
+    }
+
+    /// Append the turns a converted session gained since `casr resume`
+    /// wrote it to the session it was converted from (the last hop of its
+    /// lineage), so both agents share the full history.
+    pub fn sync_back(
+        &self,
+        session_id: &str,
+        opts: SyncBackOptions,
+    ) -> anyhow::Result<SyncBackResult> {
+        let rejected = |reason: String| CasrError::SyncBackRejected {
+            session_id: session_id.to_string(),
+            reason,
+        };
+        // The source is appended to and verified as a whole, so compacted
+        // turns must be read too.
+        let read_opts = ReadOptions {
+            history: HistoryMode::Full,
+        };
+
+        // 1. Read the converted session and find where it came from.
+        let source_hint = opts.source_hint.as_deref().map(SourceHint::parse);
+        let resolved = self
+            .registry
+            .resolve_session(session_id, source_hint.as_ref())?;
+        let converted = resolved.read(&read_opts)?;
+        let lineage = crate::lineage::Lineage::of(&converted).ok_or_else(|| {
+            rejected("it has no recorded lineage; only sessions written by `casr resume` can be synced back".to_string())
+        })?;
+        let hop = lineage
+            .hops
+            .last()
+            .expect("a parsed lineage has at least one hop");
+        if converted.messages.len() < hop.messages {
+            return Err(rejected(format!(
+                "it has {} messages, fewer than the {} it was written with",
+                converted.messages.len(),
+                hop.messages
+            ))
+            .into());
+        }
+
+        // 2. Resolve the source session; it must take the new turns in place.
+        let parent = self.registry.resolve_session(
+            &hop.source_session_id,
+            Some(&SourceHint::Alias(hop.source_provider.clone())),
+        )?;
+        let provider = parent.provider;
+        if !provider.appends_messages() {
+            return Err(rejected(format!(
+                "it was converted from {} session '{}', which casr can't append to",
+                provider.name(),
+                hop.source_session_id
+            ))
+            .into());
+        }
+        if let Some(origin) = parent.origin() {
+            return Err(rejected(format!(
+                "its source session '{}' was found in {}, not the current home",
+                hop.source_session_id,
+                origin.display()
+            ))
+            .into());
+        }
+        let mut warnings = Vec::new();
+        if crate::lineage::path_sha256(&parent.path) != hop.source_path_sha256 {
+            warnings.push(format!(
+                "{} session '{}' has moved since it was converted; appending to {}.",
+                provider.name(),
+                hop.source_session_id,
+                parent.path.display()
+            ));
+        }
+        let source = parent.read(&read_opts)?;
+        info!(
+            session_id = converted.session_id,
+            source = provider.slug(),
+            source_session_id = source.session_id,
+            "syncing session back"
+        );
+
+        // 3. The new turns, prepared for the source format as a conversion
+        // would prepare them.
+        // A compaction in the continuing agent adds a summary of turns the
+        // source already has; it isn't a turn of its own.
+        let mut delta = CanonicalSession {
+            messages: converted.messages[hop.messages..]
+                .iter()
+                .filter(|msg| !is_compaction_summary(msg))
+                .cloned()
+                .collect(),
+            ..converted.clone()
+        };
+        let mut fidelity = FidelityReport::default();
+        warnings.extend(apply_context_budget(
+            &mut delta,
+            0,
+            0,
+            opts.keep_reasoning,
+            &mut fidelity,
+        ));
+        opts.tool_map
+            .translate_session(&mut delta, resolved.provider.slug(), provider.slug());
+        synthesize_tool_text(&mut delta, provider);
+        fold_reasoning_into_content(&mut delta, provider);
+        if !provider.supports_attachments() {
+            fold_attachments_into_content(&mut delta);
+        }
+
+        // 4. Skip turns an earlier sync-back already appended.
+        let already_synced = synced_prefix_len(&source.messages, &delta.messages);
+        let mut appended = delta.messages.split_off(already_synced);
+        for (offset, msg) in appended.iter_mut().enumerate() {
+            msg.idx = source.messages.len() + offset;
+        }
+        debug!(
+            new = appended.len(),
+            already_synced, "computed sync-back delta"
+        );
+
+        let mut result = SyncBackResult {
+            provider: resolved.provider.slug().to_string(),
+            session_id: converted.session_id.clone(),
+            source_provider: provider.slug().to_string(),
+            source_session_id: source.session_id.clone(),
+            source_path: parent.path.clone(),
+            appended,
+            already_synced,
+            written: None,
+            warnings,
+        };
+        if opts.dry_run || result.appended.is_empty() {
+            return Ok(result);
+        }
+
+        // 5. Append, then read back and compare with the source plus the
+        // new turns; restore the original on any mismatch.
+        let written = provider.append_messages(&parent.path, &source, &result.appended)?;
+        let mut expected = source;
+        expected.messages.extend(result.appended.iter().cloned());
+        let mismatches = match provider.read_session_with(&parent.path, &read_opts) {
+            Ok(readback) => verify_readback(&expected, &readback, &provider.verify_tolerance()),
+            Err(e) => vec![format!("unable to read appended session: {e}")],
+        };
+        if !mismatches.is_empty() {
+            let detail = summarize_mismatches(&mismatches);
+            warn!(detail, "sync-back verification failed");
+            let rollback_detail = match rollback_written_session(provider.slug(), &written) {
+                Ok(()) => "rollback succeeded".to_string(),
+                Err(rollback_error) => format!("rollback failed: {rollback_error}"),
+            };
+            return Err(CasrError::VerifyFailed {
+                provider: provider.slug().to_string(),
+                written_paths: written.paths.clone(),
+                detail: format!("{detail}; {rollback_detail}"),
+                mismatches,
+            }
+            .into());
+        }
+        info!(
+            appended = result.appended.len(),
+            backup = ?written.backup_path,
+            "synced new turns back to the source session"
+        );
+        result.written = Some(written);
+        Ok(result)
+    }
+}
+
+/// Split `incoming` into the messages after where `existing` leaves off and
+/// a count of those before it. The target's last messages are aligned with
+/// the longest run of `incoming` they repeat, so earlier turns that differ
+/// (an `--enrich` notice, a trimmed history) don't break the match and a
+/// repeated short turn ("continue") only matches in place. Messages match
+/// on role and text and, when both carry one and the format stores them
+/// (`drift_ms`), a timestamp within `drift_ms`.
+fn new_messages(
+    existing: &[CanonicalMessage],
+    mut incoming: Vec<CanonicalMessage>,
+    drift_ms: Option<i64>,
+) -> (Vec<CanonicalMessage>, usize) {
+    let same = |old: &CanonicalMessage, msg: &CanonicalMessage| {
+        readback_role_bucket(&old.role) == readback_role_bucket(&msg.role)
+            && old.content.trim() == msg.content.trim()
+            && match (drift_ms, old.timestamp, msg.timestamp) {
+                (Some(drift), Some(a), Some(b)) => (a - b).abs() <= drift,
+                _ => true,
+            }
+    };
+    // For each end position in `incoming`, how many of `existing`'s last
+    // messages end there; the earliest longest run wins.
+    let mut best = (0, 0);
+    for end in 1..=incoming.len() {
+        let run = existing
+            .iter()
+            .rev()
+            .zip(incoming[..end].iter().rev())
+            .take_while(|(old, msg)| same(old, msg))
+            .count();
+        if run > best.1 {
+            best = (end, run);
+        }
+    }
+    let already_present = best.0;
+    let mut new = incoming.split_off(already_present);
+    for (offset, msg) in new.iter_mut().enumerate() {
+        msg.idx = existing.len() + offset;
+    }
+    (new, already_present)
+}
+
+/// Whether `msg` was written by an agent's context compaction rather than
+/// said in the conversation: a Claude Code `isCompactSummary` entry or a
+/// message from a Codex `compacted` event.
+fn is_compaction_summary(msg: &CanonicalMessage) -> bool {
+    msg.extra.get("isCompactSummary").and_then(|v| v.as_bool()) == Some(true)
+        || msg.extra.get("type").and_then(|v| v.as_str()) == Some("compacted")
+}
+
+/// How many of `new`'s leading messages already end `existing`: the turns a
+/// previous sync-back appended.
+fn synced_prefix_len(existing: &[CanonicalMessage], new: &[CanonicalMessage]) -> usize {
+    let same = |a: &CanonicalMessage, b: &CanonicalMessage| {
+        readback_role_bucket(&a.role) == readback_role_bucket(&b.role)
+            && a.content.trim() == b.content.trim()
+    };
+    (1..=existing.len().min(new.len()))
+        .rev()
+        .find(|&k| {
+            existing[existing.len() - k..]
+                .iter()
+                .zip(&new[..k])
+                .all(|(a, b)| same(a, b))
         })
+        .unwrap_or(0)
+}
+
+/// Materialize `[Tool: …]` / `[Tool Output] …` text into tool-only messages
+/// with empty content (step 7b of [`ConversionPipeline::convert`]). Returns
+/// the number of messages given synthesized text.
+fn synthesize_tool_text(session: &mut CanonicalSession, target: &dyn Provider) -> usize {
+    if target.slug() == "claude-code" {
+        return 0;
+    }
+    let mut synthesized = 0;
+    for msg in &mut session.messages {
+        if !msg.content.trim().is_empty() {
+            continue;
+        }
+
+        let has_tool_calls = !msg.tool_calls.is_empty();
+        let has_tool_results = !msg.tool_results.is_empty();
+
+        if !has_tool_calls && !has_tool_results {
+            continue;
+        }
+
+        let mut parts: Vec<String> = Vec::new();
+
+        // Synthesize text for tool calls (matches Pi reader's format).
+        for tc in &msg.tool_calls {
+            parts.push(format!("[Tool: {}]", tc.name));
+        }
+
+        // Synthesize text for tool results.
+        for tr in &msg.tool_results {
+            if tr.is_error {
+                parts.push(format!("[Tool Error] {}", tr.content));
+            } else {
+                parts.push(format!("[Tool Output] {}", tr.content));
+            }
+        }
+
+        if !parts.is_empty() {
+            msg.content = parts.join("\n");
+            synthesized += 1;
+        }
     }
+    synthesized
 }
 
 // ---------------------------------------------------------------------------
@@ -563,6 +1720,9 @@ but resume may fail until the CLI is installed.",
 /// Rough token estimate (~4 chars/token) for one message, including tool I/O.
 fn estimate_message_tokens(m: &CanonicalMessage) -> usize {
     let mut chars = m.content.len();
+    for r in &m.reasoning {
+        chars += r.text.len();
+    }
     for tc in &m.tool_calls {
         chars += tc.name.len() + tc.arguments.to_string().len();
     }
@@ -591,12 +1751,13 @@ fn elide_middle(s: &str, max: usize) -> Option<String> {
 }
 
 /// Remove `tool_use` blocks that lack a matching `tool_result` (and vice
-/// versa), then drop messages left with no content and no tool payloads.
+/// versa), then drop messages left with no content, reasoning, or tool
+/// payloads.
 ///
 /// The Anthropic API requires paired tool calls/results. After older turns are
 /// dropped by the token budget, previously-paired tool_use/tool_result entries
 /// can become orphaned; this function restores validity.
-fn repair_tool_pairing(session: &mut CanonicalSession) {
+pub(crate) fn repair_tool_pairing(session: &mut CanonicalSession) {
     let result_ids: std::collections::HashSet<String> = session
         .messages
         .iter()
@@ -620,7 +1781,11 @@ fn repair_tool_pairing(session: &mut CanonicalSession) {
         });
     }
     session.messages.retain(|m| {
-        !(m.content.trim().is_empty() && m.tool_calls.is_empty() && m.tool_results.is_empty())
+        !(m.content.trim().is_empty()
+            && m.reasoning.is_empty()
+            && m.tool_calls.is_empty()
+            && m.tool_results.is_empty()
+            && m.attachments.is_empty())
     });
 }
 
@@ -637,16 +1802,21 @@ fn apply_context_budget(
     max_tokens: usize,
     max_tool_output: usize,
     keep_reasoning: bool,
+    report: &mut FidelityReport,
 ) -> Vec<String> {
     let mut warnings = Vec::new();
 
     // 1. Drop source-agent reasoning traces (unusable by another agent).
+    //    Messages left empty are removed with the tool-pairing repair below.
     if !keep_reasoning {
-        let before = canonical.messages.len();
-        canonical
-            .messages
-            .retain(|m| m.author.as_deref() != Some("reasoning"));
-        let dropped = before - canonical.messages.len();
+        let mut dropped = 0usize;
+        for m in &mut canonical.messages {
+            dropped += m.reasoning.len();
+            m.reasoning.clear();
+            m.parts
+                .retain(|p| !matches!(p, ContentPart::Thinking { .. }));
+        }
+        report.reasoning_dropped += dropped;
         if dropped > 0 {
             warnings.push(format!(
                 "Dropped {dropped} source reasoning trace(s); pass --keep-reasoning to retain."
@@ -665,6 +1835,7 @@ fn apply_context_budget(
                 }
             }
         }
+        report.tool_outputs_truncated = truncated;
         if truncated > 0 {
             warnings.push(format!(
                 "Truncated {truncated} oversized tool result(s) to ~{max_tool_output} chars each."
@@ -690,6 +1861,7 @@ fn apply_context_budget(
             }
             if keep_from > 1 {
                 let dropped = keep_from - 1;
+                report.turns_dropped = dropped;
                 let tail = canonical.messages.split_off(keep_from);
                 canonical.messages.truncate(1);
                 canonical.messages.extend(tail);
@@ -708,6 +1880,77 @@ turn(s) between the task and the most recent history."
     warnings
 }
 
+/// Move reasoning that `target` can't carry into the message text as
+/// `[Thinking] …` lines ahead of the existing content; redacted reasoning is
+/// dropped. Returns `(rendered, dropped)` counts.
+fn fold_reasoning_into_content(
+    session: &mut CanonicalSession,
+    target: &dyn Provider,
+) -> (usize, usize) {
+    let (mut rendered, mut dropped) = (0, 0);
+    for msg in &mut session.messages {
+        let carried: Vec<bool> = msg
+            .reasoning
+            .iter()
+            .map(|r| target.carries_reasoning(msg, r))
+            .collect();
+        if carried.iter().all(|c| *c) {
+            continue;
+        }
+        let mut kept: Vec<Reasoning> = Vec::new();
+        let mut lines: Vec<String> = Vec::new();
+        for (r, carried) in std::mem::take(&mut msg.reasoning).into_iter().zip(carried) {
+            if carried {
+                kept.push(r);
+            } else if r.redacted {
+                dropped += 1;
+            } else {
+                lines.push(format!("[Thinking] {}", r.text));
+                rendered += 1;
+            }
+        }
+        if !msg.content.is_empty() {
+            lines.push(std::mem::take(&mut msg.content));
+        }
+        msg.content = lines.join("\n");
+        msg.reasoning = kept;
+        // Part indexes no longer line up with the flat fields.
+        msg.parts.clear();
+    }
+    session.messages.retain(|m| {
+        !(m.content.is_empty()
+            && m.reasoning.is_empty()
+            && m.tool_calls.is_empty()
+            && m.tool_results.is_empty()
+            && m.attachments.is_empty())
+    });
+    reindex_messages(&mut session.messages);
+    (rendered, dropped)
+}
+
+/// Replace every attachment with its [`Attachment::placeholder`] text,
+/// appended to the message content. Returns the number of attachments folded.
+///
+/// [`Attachment::placeholder`]: crate::model::Attachment::placeholder
+fn fold_attachments_into_content(session: &mut CanonicalSession) -> usize {
+    let mut folded = 0;
+    for msg in &mut session.messages {
+        if msg.attachments.is_empty() {
+            continue;
+        }
+        let mut parts: Vec<String> = Vec::new();
+        if !msg.content.is_empty() {
+            parts.push(std::mem::take(&mut msg.content));
+        }
+        for attachment in msg.attachments.drain(..) {
+            parts.push(attachment.placeholder());
+            folded += 1;
+        }
+        msg.content = parts.join("\n");
+    }
+    folded
+}
+
 /// Coarse role bucket used for read-back verification.
 ///
 /// Some target formats (notably Claude Code JSONL) don't distinguish between
@@ -729,40 +1972,190 @@ fn readback_role_bucket(role: &MessageRole) -> &'static str {
     }
 }
 
-fn readback_mismatch_detail(
-    canonical: &CanonicalSession,
+/// Compare a written session with what the target's reader returns.
+///
+/// Message count, roles (by [`readback_role_bucket`]), content, reasoning
+/// and attachment counts must always match; tool calls, tool results,
+/// timestamps, workspace, and title are checked as far as `tolerance`
+/// declares the format preserves them. Returns every mismatch found.
+pub fn verify_readback(
+    written: &CanonicalSession,
     readback: &CanonicalSession,
-) -> Option<String> {
-    if readback.messages.len() != canonical.messages.len() {
-        return Some(format!(
+    tolerance: &VerifyTolerance,
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if readback.messages.len() != written.messages.len() {
+        mismatches.push(format!(
             "message count mismatch: wrote {} messages, read back {}",
-            canonical.messages.len(),
+            written.messages.len(),
             readback.messages.len()
         ));
+        return mismatches;
+    }
+
+    if tolerance.workspace && written.workspace.is_some() && readback.workspace != written.workspace
+    {
+        mismatches.push(format!(
+            "workspace mismatch: wrote {}, read back {}",
+            display_path(written.workspace.as_deref()),
+            display_path(readback.workspace.as_deref())
+        ));
+    }
+    if tolerance.title && written.title.is_some() && readback.title != written.title {
+        mismatches.push(format!(
+            "title mismatch: wrote {:?}, read back {:?}",
+            written.title.as_deref().unwrap_or_default(),
+            readback.title.as_deref().unwrap_or_default()
+        ));
     }
 
-    for (i, (orig, rb)) in canonical
+    let written_call_ids = tool_call_ids(written);
+    let readback_call_ids = tool_call_ids(readback);
+    for (i, (orig, rb)) in written
         .messages
         .iter()
         .zip(readback.messages.iter())
         .enumerate()
     {
         if readback_role_bucket(&orig.role) != readback_role_bucket(&rb.role) {
-            return Some(format!(
+            mismatches.push(format!(
                 "message role mismatch at idx {i}: wrote {:?}, read back {:?}",
                 orig.role, rb.role
             ));
         }
         if orig.content != rb.content {
-            return Some(format!(
+            mismatches.push(format!(
                 "message content mismatch at idx {i}: wrote {} bytes, read back {} bytes",
                 orig.content.len(),
                 rb.content.len()
             ));
         }
+        if orig.reasoning.len() != rb.reasoning.len() {
+            mismatches.push(format!(
+                "reasoning count mismatch at idx {i}: wrote {}, read back {}",
+                orig.reasoning.len(),
+                rb.reasoning.len()
+            ));
+        }
+        if orig.attachments.len() != rb.attachments.len() {
+            mismatches.push(format!(
+                "attachment count mismatch at idx {i}: wrote {}, read back {}",
+                orig.attachments.len(),
+                rb.attachments.len()
+            ));
+        }
+
+        if let Some(drift) = tolerance.timestamp_drift_ms
+            && let Some(wrote) = orig.timestamp
+        {
+            match rb.timestamp {
+                Some(read) if (read - wrote).abs() <= drift => {}
+                Some(read) => mismatches.push(format!(
+                    "timestamp mismatch at idx {i}: read back {}ms off (tolerance {drift}ms)",
+                    read - wrote
+                )),
+                None => mismatches.push(format!("timestamp missing at idx {i}")),
+            }
+        }
+
+        if tolerance.tool_calls {
+            if orig.tool_calls.len() != rb.tool_calls.len() {
+                mismatches.push(format!(
+                    "tool call count mismatch at idx {i}: wrote {}, read back {}",
+                    orig.tool_calls.len(),
+                    rb.tool_calls.len()
+                ));
+            } else {
+                for (j, (oc, rc)) in orig.tool_calls.iter().zip(&rb.tool_calls).enumerate() {
+                    if oc.name != rc.name {
+                        mismatches.push(format!(
+                            "tool call {j} at idx {i}: wrote name {:?}, read back {:?}",
+                            oc.name, rc.name
+                        ));
+                    }
+                    if oc.arguments != rc.arguments {
+                        mismatches.push(format!("tool call {j} at idx {i}: arguments differ"));
+                    }
+                    if tolerance.tool_call_ids && oc.id != rc.id {
+                        mismatches.push(format!(
+                            "tool call {j} at idx {i}: wrote id {:?}, read back {:?}",
+                            oc.id.as_deref().unwrap_or_default(),
+                            rc.id.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+        }
+
+        if tolerance.tool_results {
+            if orig.tool_results.len() != rb.tool_results.len() {
+                mismatches.push(format!(
+                    "tool result count mismatch at idx {i}: wrote {}, read back {}",
+                    orig.tool_results.len(),
+                    rb.tool_results.len()
+                ));
+            } else {
+                for (j, (or, rr)) in orig.tool_results.iter().zip(&rb.tool_results).enumerate() {
+                    if or.is_error != rr.is_error {
+                        mismatches.push(format!(
+                            "tool result {j} at idx {i}: wrote is_error={}, read back {}",
+                            or.is_error, rr.is_error
+                        ));
+                    }
+                    let paired = |call_id: &Option<String>, ids: &HashSet<&str>| {
+                        call_id.as_deref().is_some_and(|id| ids.contains(id))
+                    };
+                    if paired(&or.call_id, &written_call_ids)
+                        && !paired(&rr.call_id, &readback_call_ids)
+                    {
+                        mismatches.push(format!(
+                            "tool result {j} at idx {i}: no longer paired with its tool call"
+                        ));
+                    } else if tolerance.tool_call_ids && or.call_id != rr.call_id {
+                        mismatches.push(format!(
+                            "tool result {j} at idx {i}: wrote call_id {:?}, read back {:?}",
+                            or.call_id.as_deref().unwrap_or_default(),
+                            rr.call_id.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+        }
     }
 
-    None
+    mismatches
+}
+
+fn tool_call_ids(session: &CanonicalSession) -> HashSet<&str> {
+    session
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_calls)
+        .filter_map(|tc| tc.id.as_deref())
+        .collect()
+}
+
+fn display_path(path: Option<&Path>) -> String {
+    path.map_or_else(|| "none".to_string(), |p| p.display().to_string())
+}
+
+/// One-line summary of read-back mismatches for `VerifyFailed.detail`.
+fn summarize_mismatches(mismatches: &[String]) -> String {
+    const SHOWN: usize = 5;
+    match mismatches {
+        [only] => only.clone(),
+        _ => {
+            let mut detail = format!(
+                "{} read-back mismatches: {}",
+                mismatches.len(),
+                mismatches[..mismatches.len().min(SHOWN)].join("; ")
+            );
+            if mismatches.len() > SHOWN {
+                detail.push_str(&format!("; … and {} more", mismatches.len() - SHOWN));
+            }
+            detail
+        }
+    }
 }
 
 fn rollback_written_session(
@@ -1047,7 +2440,7 @@ pub fn restore_backup(outcome: &AtomicWriteOutcome, provider_slug: &str) -> Resu
 }
 
 /// Find an available backup path, deduplicating with `.bak`, `.bak.1`, `.bak.2`, etc.
-fn find_backup_path(target: &Path) -> PathBuf {
+pub(crate) fn find_backup_path(target: &Path) -> PathBuf {
     let mut filename = target.file_name().unwrap_or_default().to_os_string();
     filename.push(".bak");
     let bak = target.with_file_name(&filename);
@@ -1081,10 +2474,14 @@ mod tests {
             idx,
             role,
             content: content.to_string(),
+            parts: Vec::new(),
             timestamp: Some(1_700_000_000_000 + idx as i64),
             author: None,
+            reasoning: Vec::new(),
             tool_calls: Vec::new(),
             tool_results: Vec::new(),
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }
     }
@@ -1117,6 +2514,8 @@ mod tests {
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: Some("gpt-5-codex".to_string()),
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -1125,7 +2524,8 @@ mod tests {
         let mut session = sample_session();
         let original_len = session.messages.len();
 
-        let inserted = prepend_enrichment_messages(&mut session, "codex", "claude-code", "src-123");
+        let inserted =
+            prepend_enrichment_messages(&mut session, "codex", "claude-code", "src-123", None);
 
         assert_eq!(inserted, 2);
         assert_eq!(session.messages.len(), original_len + 2);
@@ -1161,6 +2561,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn enrich_inserts_git_snapshot_between_notice_and_summary() {
+        let mut session = sample_session();
+        let git = GitSnapshot {
+            root: PathBuf::from("/work/repo"),
+            branch: Some("feature/auth".to_string()),
+            head: Some("abc1234 Add login".to_string()),
+            changed_files: vec![" M src/auth.rs".to_string()],
+            ..GitSnapshot::default()
+        };
+
+        let inserted = prepend_enrichment_messages(
+            &mut session,
+            "codex",
+            "claude-code",
+            "src-123",
+            Some(&git),
+        );
+
+        assert_eq!(inserted, 3);
+        let snapshot = &session.messages[1];
+        assert_eq!(snapshot.extra["enrichment_type"], "git_snapshot");
+        assert_eq!(snapshot.extra["git"]["branch"], "feature/auth");
+        assert!(snapshot.content.contains("Branch: feature/auth"));
+        assert!(snapshot.content.contains("-  M src/auth.rs"));
+        assert_eq!(
+            session.messages[2].extra["enrichment_type"],
+            "recent_summary"
+        );
+        let timestamps: Vec<_> = session.messages[..4].iter().map(|m| m.timestamp).collect();
+        assert!(timestamps.windows(2).all(|w| w[0] < w[1]), "{timestamps:?}");
+    }
+
     #[test]
     fn recent_summary_is_deterministic_and_compact() {
         let mut session = sample_session();
@@ -1359,10 +2792,14 @@ mod tests {
             idx: 0,
             role,
             content: content.to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }
     }
@@ -1379,6 +2816,8 @@ mod tests {
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/x"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -1393,17 +2832,20 @@ mod tests {
 
     #[test]
     fn budget_drops_reasoning_and_truncates_tool_output() {
-        use crate::model::{ToolCall, ToolResult};
+        use crate::model::{Reasoning, ToolCall, ToolResult};
 
-        let mut reasoning = budget_msg(MessageRole::Assistant, "secret thoughts");
-        reasoning.author = Some("reasoning".into());
+        let mut reasoning = budget_msg(MessageRole::Assistant, "done");
+        reasoning.reasoning.push(Reasoning {
+            text: "secret thoughts".into(),
+            ..Reasoning::default()
+        });
 
         let mut call = budget_msg(MessageRole::Assistant, "run it");
-        call.tool_calls.push(ToolCall {
-            id: Some("c1".into()),
-            name: "Bash".into(),
-            arguments: serde_json::json!({"cmd": "ls"}),
-        });
+        call.tool_calls.push(ToolCall::new(
+            Some("c1".into()),
+            "Bash".into(),
+            serde_json::json!({"cmd": "ls"}),
+        ));
 
         let mut tool = budget_msg(MessageRole::Tool, "");
         tool.tool_results.push(ToolResult {
@@ -1415,13 +2857,11 @@ mod tests {
         let task = budget_msg(MessageRole::User, "task");
         let mut s = budget_session(vec![task, call, tool, reasoning]);
 
-        let warns = apply_context_budget(&mut s, 0, 4000, false);
+        let warns = apply_context_budget(&mut s, 0, 4000, false, &mut FidelityReport::default());
 
         // Reasoning was dropped.
         assert!(
-            !s.messages
-                .iter()
-                .any(|m| m.author.as_deref() == Some("reasoning")),
+            s.messages.iter().all(|m| m.reasoning.is_empty()),
             "reasoning trace should be gone"
         );
 
@@ -1464,7 +2904,7 @@ mod tests {
         let before = msgs.len();
         let mut s = budget_session(msgs);
 
-        let warns = apply_context_budget(&mut s, 2000, 0, true);
+        let warns = apply_context_budget(&mut s, 2000, 0, true, &mut FidelityReport::default());
 
         assert!(s.messages.len() < before, "older turns should be dropped");
         assert_eq!(
@@ -1488,15 +2928,15 @@ mod tests {
         use crate::model::ToolCall;
 
         let mut call = budget_msg(MessageRole::Assistant, "");
-        call.tool_calls.push(ToolCall {
-            id: Some("orphan".into()),
-            name: "X".into(),
-            arguments: serde_json::Value::Null,
-        });
+        call.tool_calls.push(ToolCall::new(
+            Some("orphan".into()),
+            "X".into(),
+            serde_json::Value::Null,
+        ));
         // No matching tool_result — the tool call is already orphaned.
         let mut s = budget_session(vec![budget_msg(MessageRole::User, "hi"), call]);
 
-        apply_context_budget(&mut s, 0, 0, true);
+        apply_context_budget(&mut s, 0, 0, true, &mut FidelityReport::default());
 
         // Orphaned tool_use removed; now-empty assistant turn also dropped.
         assert!(
@@ -1509,4 +2949,298 @@ mod tests {
             "empty assistant turn should be dropped"
         );
     }
+
+    #[test]
+    fn fold_reasoning_keeps_native_blocks_and_renders_the_rest() {
+        use crate::model::Reasoning;
+        use crate::providers::claude_code::ClaudeCode;
+        use crate::providers::gemini::Gemini;
+
+        let session = || {
+            let mut answer = budget_msg(MessageRole::Assistant, "Answer");
+            answer.reasoning = vec![
+                Reasoning {
+                    text: "plan".into(),
+                    signature: Some("sig".into()),
+                    ..Reasoning::default()
+                },
+                Reasoning {
+                    signature: Some("opaque".into()),
+                    redacted: true,
+                    ..Reasoning::default()
+                },
+            ];
+            let mut unsigned = budget_msg(MessageRole::Assistant, "");
+            unsigned.reasoning.push(Reasoning {
+                text: "loose thought".into(),
+                ..Reasoning::default()
+            });
+            budget_session(vec![budget_msg(MessageRole::User, "Q"), answer, unsigned])
+        };
+
+        // Claude Code stores signed and redacted blocks; unsigned ones become text.
+        let mut s = session();
+        assert_eq!(fold_reasoning_into_content(&mut s, &ClaudeCode), (1, 0));
+        assert_eq!(s.messages[1].reasoning.len(), 2);
+        assert_eq!(s.messages[2].content, "[Thinking] loose thought");
+
+        // Gemini stores none: visible text is rendered, redacted data dropped.
+        let mut s = session();
+        assert_eq!(fold_reasoning_into_content(&mut s, &Gemini), (2, 1));
+        assert!(s.messages.iter().all(|m| m.reasoning.is_empty()));
+        assert_eq!(s.messages[1].content, "[Thinking] plan\nAnswer");
+        assert_eq!(s.messages[2].idx, 2);
+    }
+
+    fn tool_session() -> CanonicalSession {
+        let mut session = sample_session();
+        session.messages[1].tool_calls = vec![crate::model::ToolCall::new(
+            Some("call-1".to_string()),
+            "Bash".to_string(),
+            serde_json::json!({"command": "cargo test"}),
+        )];
+        session.messages[2].tool_results = vec![crate::model::ToolResult {
+            call_id: Some("call-1".to_string()),
+            content: "1 failed".to_string(),
+            is_error: true,
+        }];
+        session
+    }
+
+    #[test]
+    fn verify_readback_reports_every_mismatch() {
+        let written = tool_session();
+        assert!(verify_readback(&written, &written, &VerifyTolerance::STRICT).is_empty());
+
+        let mut readback = written.clone();
+        readback.title = Some("Investigate parser behavior".to_string());
+        readback.messages[1].tool_calls.clear();
+        readback.messages[2].tool_results[0].is_error = false;
+        readback.messages[0].timestamp = Some(1_700_000_000_000 + 3 * 3_600_000);
+        readback.messages[1].timestamp = Some(1_700_000_000_500);
+
+        let mismatches = verify_readback(&written, &readback, &VerifyTolerance::STRICT);
+        assert_eq!(mismatches.len(), 5, "{mismatches:?}");
+        assert!(mismatches[0].starts_with("title mismatch"));
+        assert!(mismatches[1].contains("timestamp mismatch at idx 0"));
+        assert!(mismatches[2].contains("tool call count mismatch at idx 1"));
+        assert!(mismatches[3].contains("is_error=true"));
+        assert!(mismatches[4].contains("no longer paired"));
+
+        let summary = summarize_mismatches(&mismatches);
+        assert!(summary.starts_with("5 read-back mismatches: title mismatch"));
+    }
+
+    #[test]
+    fn verify_readback_honors_declared_tolerances() {
+        let written = tool_session();
+        let mut readback = written.clone();
+        readback.workspace = None;
+        readback.messages[1].tool_calls[0].id = Some("regenerated".to_string());
+        readback.messages[2].tool_results[0].call_id = Some("regenerated".to_string());
+        for msg in &mut readback.messages {
+            msg.timestamp = None;
+        }
+
+        let strict = verify_readback(&written, &readback, &VerifyTolerance::STRICT);
+        assert_eq!(strict.len(), 6, "{strict:?}");
+
+        let tolerance = VerifyTolerance {
+            tool_call_ids: false,
+            timestamp_drift_ms: None,
+            workspace: false,
+            ..VerifyTolerance::STRICT
+        };
+        assert!(verify_readback(&written, &readback, &tolerance).is_empty());
+
+        readback.messages[0].content.push('!');
+        let content_only = verify_readback(&written, &readback, &VerifyTolerance::CONTENT_ONLY);
+        assert_eq!(
+            content_only,
+            ["message content mismatch at idx 0: wrote 49 bytes, read back 50 bytes"]
+        );
+    }
+
+    #[test]
+    fn strict_check_parses_names() {
+        assert_eq!("budget".parse::<StrictCheck>(), Ok(StrictCheck::Budget));
+        assert_eq!(
+            " metadata".parse::<StrictCheck>(),
+            Ok(StrictCheck::Metadata)
+        );
+        let err = "everything".parse::<StrictCheck>().unwrap_err();
+        assert!(err.contains("budget, tools, roles, metadata"), "{err}");
+    }
+
+    #[test]
+    fn fidelity_report_records_readback_losses() {
+        let mut written = tool_session();
+        written.messages[0].role = MessageRole::System;
+        written.messages[0].extra = serde_json::json!({"uuid": "u-1", "cwd": "/repo"});
+        written.messages[2].timestamp = None;
+        let mut readback = written.clone();
+        readback.messages[0].role = MessageRole::User;
+        readback.messages[0].extra = serde_json::json!({"source": {"uuid": "u-1"}});
+        readback.messages[2].timestamp = Some(1_700_000_060_000);
+
+        let mut report = FidelityReport::default();
+        assert!(report.is_lossless());
+        report.record_readback(&written, &readback);
+        assert_eq!(report.roles_collapsed["system → user"], 1);
+        assert_eq!(
+            report.extra_keys_ignored.keys().collect::<Vec<_>>(),
+            ["cwd"]
+        );
+        assert_eq!(report.timestamps_synthesized, 1);
+
+        report
+            .tool_calls_rendered_as_text
+            .insert("TodoWrite".to_string(), 2);
+        assert_eq!(
+            report.lines(),
+            [
+                "1 timestamp(s) synthesized by the target",
+                "Tool calls rendered as text: TodoWrite ×2",
+                "Roles collapsed: system → user ×1",
+                "Metadata keys ignored by the target: cwd ×1",
+            ]
+        );
+    }
+
+    #[test]
+    fn synced_prefix_len_finds_turns_an_earlier_sync_appended() {
+        let existing = sample_session().messages;
+        let new = vec![
+            sample_message(
+                0,
+                MessageRole::User,
+                "Please also verify resume command compatibility.",
+            ),
+            sample_message(1, MessageRole::Assistant, "Verified."),
+        ];
+        assert_eq!(synced_prefix_len(&existing, &new), 1);
+        assert_eq!(synced_prefix_len(&existing[..2], &new), 0);
+        assert_eq!(synced_prefix_len(&[], &new), 0);
+    }
+
+    #[test]
+    fn appending_providers_keep_existing_records_and_read_back_new_turns() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        crate::homes::scoped(Some(tmp.path()), || {
+            let registry = ProviderRegistry::default_registry();
+            let mut session = sample_session();
+            session.workspace = Some(tmp.path().join("workspace"));
+            let new = vec![
+                sample_message(
+                    3,
+                    MessageRole::Assistant,
+                    "Resume works with `codex resume`.",
+                ),
+                sample_message(4, MessageRole::User, "Thanks, ship it."),
+            ];
+            let providers: Vec<_> = registry
+                .all_providers()
+                .into_iter()
+                .filter(|p| p.appends_messages())
+                .collect();
+            assert_eq!(providers.len(), 6);
+            for provider in providers {
+                let opts = WriteOptions::default();
+                let written = provider.write_session(&session, &opts).unwrap();
+                let path = &written.paths[0];
+                let before = provider.read_session(path).unwrap();
+
+                let appended = provider
+                    .append_messages(path, &before, &new)
+                    .unwrap_or_else(|e| panic!("{} append failed: {e}", provider.slug()));
+                let backup = appended.backup_path.as_ref().expect("append keeps a .bak");
+                assert!(
+                    fs::read_to_string(&appended.paths[0])
+                        .unwrap()
+                        .starts_with(&fs::read_to_string(backup).unwrap()),
+                    "{} rewrote existing records",
+                    provider.slug()
+                );
+
+                // A second append replaces the `.bak` instead of adding `.bak.1`.
+                let middle = fs::read(&appended.paths[0]).unwrap();
+                let again = provider
+                    .append_messages(path, &before, &new[..0])
+                    .unwrap_or_else(|e| panic!("{} append failed: {e}", provider.slug()));
+                assert_eq!(again.backup_path.as_ref(), Some(backup));
+                assert_eq!(fs::read(backup).unwrap(), middle);
+                let mut numbered = backup.as_os_str().to_os_string();
+                numbered.push(".1");
+                assert!(!Path::new(&numbered).exists(), "{}", provider.slug());
+
+                let mut expected = before;
+                expected.messages.extend(new.iter().cloned());
+                let readback = provider.read_session(path).unwrap();
+                assert_eq!(
+                    verify_readback(&expected, &readback, &provider.verify_tolerance()),
+                    Vec::<String>::new(),
+                    "{}",
+                    provider.slug()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn new_messages_skips_what_the_target_holds_by_text_and_time() {
+        let existing = sample_session().messages;
+        let mut incoming = sample_session().messages;
+        incoming.push(sample_message(3, MessageRole::Assistant, "Resume works."));
+
+        let (new, already_present) = new_messages(&existing, incoming.clone(), Some(1_000));
+        assert_eq!(already_present, 3);
+        assert_eq!(
+            new.iter()
+                .map(|m| (m.idx, m.content.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(3, "Resume works.")]
+        );
+
+        // The target's first message was a notice the source doesn't repeat.
+        let (new, already_present) = new_messages(&existing[1..], incoming.clone(), Some(1_000));
+        assert_eq!((new.len(), already_present), (1, 3));
+
+        // Same text, but written an hour later: the target doesn't hold it.
+        incoming[2].timestamp = incoming[2].timestamp.map(|ts| ts + 3_600_000);
+        let (new, already_present) = new_messages(&existing, incoming.clone(), Some(1_000));
+        assert_eq!((new.len(), already_present), (4, 0));
+        // A format without timestamps matches on text alone.
+        let (new, already_present) = new_messages(&existing, incoming, None);
+        assert_eq!((new.len(), already_present), (1, 3));
+    }
+
+    #[test]
+    fn new_messages_keeps_repeated_turns() {
+        let turn = |idx: usize, role: MessageRole, text: &str| {
+            let mut msg = sample_message(idx, role, text);
+            msg.timestamp = Some(1_700_000_000_000 + idx as i64 * 60_000);
+            msg
+        };
+        let existing = vec![
+            turn(0, MessageRole::User, "continue"),
+            turn(1, MessageRole::Assistant, "Step one done."),
+        ];
+        let mut incoming = existing.clone();
+        incoming.extend([
+            turn(2, MessageRole::User, "continue"),
+            turn(3, MessageRole::Assistant, "Step one done."),
+            turn(4, MessageRole::User, "yes"),
+        ]);
+
+        for drift in [Some(1_000), None] {
+            let (new, already_present) = new_messages(&existing, incoming.clone(), drift);
+            assert_eq!(already_present, 2);
+            assert_eq!(
+                new.iter()
+                    .map(|m| (m.idx, m.content.as_str()))
+                    .collect::<Vec<_>>(),
+                vec![(2, "continue"), (3, "Step one done."), (4, "yes")]
+            );
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Model price table for session cost estimates.
+//!
+//! Prices are USD per million tokens. casr ships a small built-in table of
+//! common models; a JSON price file adds entries or overrides built-in ones:
+//!
+//! ```json
+//! {
+//!   "claude-sonnet-4-5": { "input": 3.0, "output": 15.0, "cache_read": 0.3, "cache_write": 3.75 },
+//!   "my-local-model": { "input": 0.0, "output": 0.0 }
+//! }
+//! ```
+//!
+//! A model name matches an entry when it starts with the entry's key, after
+//! lowercasing, dropping any `provider/` prefix, and treating `.` as `-`. The
+//! longest matching key wins, so `claude-sonnet-4-5-20250929` and
+//! `anthropic/claude-sonnet-4.5` are both priced by `claude-sonnet-4-5`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{CanonicalSession, Usage, sum_message_usage};
+
+/// Per-million-token prices for one model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// Cached input reads; defaults to `input`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+    /// Cache writes; defaults to `input`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64, cache_read: f64, cache_write: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_read: Some(cache_read),
+            cache_write: Some(cache_write),
+        }
+    }
+
+    /// Cost of `usage` in USD.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let per_token = |count: u64, price: f64| count as f64 * price / 1_000_000.0;
+        per_token(usage.input_tokens, self.input)
+            + per_token(usage.output_tokens, self.output)
+            + per_token(
+                usage.cache_read_tokens,
+                self.cache_read.unwrap_or(self.input),
+            )
+            + per_token(
+                usage.cache_write_tokens,
+                self.cache_write.unwrap_or(self.input),
+            )
+    }
+}
+
+/// Built-in list prices (USD per million tokens). Approximate; override with
+/// a price file when they drift.
+const BUILTIN_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4-5", ModelPrice::new(5.0, 25.0, 0.5, 6.25)),
+    ("claude-opus-4-1", ModelPrice::new(15.0, 75.0, 1.5, 18.75)),
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0, 1.5, 18.75)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0, 0.3, 3.75)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0, 0.3, 3.75)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0, 0.3, 3.75)),
+    ("claude-haiku-4-5", ModelPrice::new(1.0, 5.0, 0.1, 1.25)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0, 0.08, 1.0)),
+    ("gpt-5", ModelPrice::new(1.25, 10.0, 0.125, 1.25)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0, 0.025, 0.25)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.4, 0.005, 0.05)),
+    ("gpt-4-1", ModelPrice::new(2.0, 8.0, 0.5, 2.0)),
+    ("gpt-4o", ModelPrice::new(2.5, 10.0, 1.25, 2.5)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.6, 0.075, 0.15)),
+    ("o3", ModelPrice::new(2.0, 8.0, 0.5, 2.0)),
+    ("o3-mini", ModelPrice::new(1.1, 4.4, 0.55, 1.1)),
+    ("o4-mini", ModelPrice::new(1.1, 4.4, 0.275, 1.1)),
+    ("gemini-3-pro", ModelPrice::new(2.0, 12.0, 0.2, 2.0)),
+    ("gemini-2-5-pro", ModelPrice::new(1.25, 10.0, 0.31, 1.25)),
+    ("gemini-2-5-flash", ModelPrice::new(0.3, 2.5, 0.075, 0.3)),
+];
+
+/// A set of model prices keyed by model-name prefix.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceTable {
+    prices: BTreeMap<String, ModelPrice>,
+}
+
+/// Estimated cost of a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CostEstimate {
+    /// USD for the tokens whose model has a price.
+    pub usd: f64,
+    /// Tokens included in `usd`.
+    pub priced_tokens: u64,
+    /// Models with usage but no price; their tokens are not counted in `usd`.
+    /// A session with no recorded model is listed as `unknown`.
+    pub unpriced_models: Vec<String>,
+}
+
+impl PriceTable {
+    /// The built-in table.
+    pub fn builtin() -> Self {
+        let mut table = Self::default();
+        for (model, price) in BUILTIN_PRICES {
+            table.insert(model, price.clone());
+        }
+        table
+    }
+
+    /// The built-in table extended by the price file at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read price table {}", path.display()))?;
+        let overrides: BTreeMap<String, ModelPrice> = serde_json::from_str(&text)
+            .with_context(|| format!("invalid price table {}", path.display()))?;
+        let mut table = Self::builtin();
+        for (model, price) in overrides {
+            table.insert(&model, price);
+        }
+        Ok(table)
+    }
+
+    /// Add or replace the price for models starting with `model`.
+    pub fn insert(&mut self, model: &str, price: ModelPrice) {
+        self.prices.insert(normalize_model(model), price);
+    }
+
+    /// The price for `model`, by longest matching prefix.
+    pub fn lookup(&self, model: &str) -> Option<&ModelPrice> {
+        let model = normalize_model(model);
+        self.prices
+            .iter()
+            .filter(|(key, _)| model.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, price)| price)
+    }
+
+    /// Estimate what `session` cost. `None` when it carries no usage.
+    ///
+    /// Each message's usage is priced by its own model (`author`), falling
+    /// back to the session model. Session totals not attributed to any
+    /// message (provider-level counters, compacted-away turns) are priced by
+    /// the session model.
+    pub fn estimate(&self, session: &CanonicalSession) -> Option<CostEstimate> {
+        let total = session.usage.as_ref()?;
+        let mut estimate = CostEstimate::default();
+        let mut price =
+            |model: Option<&str>, usage: &Usage| match model.and_then(|m| self.lookup(m)) {
+                Some(p) => {
+                    estimate.usd += p.cost(usage);
+                    estimate.priced_tokens += usage.total_tokens();
+                }
+                None => {
+                    let name = model.unwrap_or("unknown").to_string();
+                    if !estimate.unpriced_models.contains(&name) {
+                        estimate.unpriced_models.push(name);
+                    }
+                }
+            };
+
+        let session_model = session.model_name.as_deref();
+        for msg in &session.messages {
+            if let Some(ref usage) = msg.usage {
+                let model = msg
+                    .author
+                    .as_deref()
+                    .filter(|a| self.lookup(a).is_some())
+                    .or(session_model);
+                price(model, usage);
+            }
+        }
+        let attributed = sum_message_usage(&session.messages).unwrap_or_default();
+        let unattributed = Usage {
+            input_tokens: total.input_tokens.saturating_sub(attributed.input_tokens),
+            output_tokens: total.output_tokens.saturating_sub(attributed.output_tokens),
+            cache_read_tokens: total
+                .cache_read_tokens
+                .saturating_sub(attributed.cache_read_tokens),
+            cache_write_tokens: total
+                .cache_write_tokens
+                .saturating_sub(attributed.cache_write_tokens),
+            ..Usage::default()
+        };
+        if unattributed.total_tokens() > 0 {
+            price(session_model, &unattributed);
+        }
+        Some(estimate)
+    }
+}
+
+/// Lowercase, drop a `provider/` prefix, and spell version dots as dashes.
+fn normalize_model(model: &str) -> String {
+    let model = model.rsplit('/').next().unwrap_or(model);
+    model.trim().to_ascii_lowercase().replace('.', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CanonicalMessage, MessageRole};
+    use std::path::PathBuf;
+
+    fn usage(input: u64, output: u64) -> Usage {
+        Usage {
+            input_tokens: input,
+            output_tokens: output,
+            ..Usage::default()
+        }
+    }
+
+    fn session(messages: Vec<CanonicalMessage>, total: Option<Usage>) -> CanonicalSession {
+        CanonicalSession {
+            session_id: "s".to_string(),
+            provider_slug: "test".to_string(),
+            workspace: None,
+            title: None,
+            started_at: None,
+            ended_at: None,
+            messages,
+            metadata: serde_json::Value::Null,
+            source_path: PathBuf::from("/tmp/s.jsonl"),
+            model_name: Some("claude-sonnet-4-5-20250929".to_string()),
+            usage: total,
+            unrecognized: None,
+        }
+    }
+
+    fn assistant(author: Option<&str>, u: Usage) -> CanonicalMessage {
+        CanonicalMessage {
+            idx: 0,
+            role: MessageRole::Assistant,
+            content: "ok".to_string(),
+            parts: Vec::new(),
+            timestamp: None,
+            author: author.map(String::from),
+            reasoning: Vec::new(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            attachments: Vec::new(),
+            usage: Some(u),
+            extra: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn lookup_uses_longest_normalized_prefix() {
+        let table = PriceTable::builtin();
+        let opus = table.lookup("claude-opus-4-5-20251101").unwrap();
+        assert_eq!(opus.input, 5.0);
+        let opus_4 = table.lookup("claude-opus-4-20250514").unwrap();
+        assert_eq!(opus_4.input, 15.0);
+        assert_eq!(
+            table.lookup("anthropic/Claude-Sonnet-4.5"),
+            table.lookup("claude-sonnet-4-5")
+        );
+        assert_eq!(table.lookup("gpt-4o-mini").unwrap().input, 0.15);
+        assert!(table.lookup("llama-3").is_none());
+    }
+
+    #[test]
+    fn cost_prices_cache_tokens_separately() {
+        let price = ModelPrice::new(3.0, 15.0, 0.3, 3.75);
+        let u = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cache_read_tokens: 1_000_000,
+            cache_write_tokens: 1_000_000,
+            ..Usage::default()
+        };
+        assert!((price.cost(&u) - 22.05).abs() < 1e-9);
+
+        let uncached = ModelPrice {
+            cache_read: None,
+            cache_write: None,
+            ..price
+        };
+        assert!((uncached.cost(&u) - 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimate_prices_messages_and_unattributed_totals() {
+        let table = PriceTable::builtin();
+        let messages = vec![
+            assistant(Some("claude-opus-4-5"), usage(1_000_000, 0)),
+            assistant(None, usage(1_000_000, 0)),
+        ];
+        // 1M more input tokens than the messages account for.
+        let s = session(messages, Some(usage(3_000_000, 0)));
+        let estimate = table.estimate(&s).unwrap();
+        // Opus input ($5) + two Sonnet inputs ($3 each).
+        assert!((estimate.usd - 11.0).abs() < 1e-9);
+        assert_eq!(estimate.priced_tokens, 3_000_000);
+        assert!(estimate.unpriced_models.is_empty());
+    }
+
+    #[test]
+    fn estimate_reports_unpriced_models() {
+        let table = PriceTable::builtin();
+        let mut s = session(
+            vec![assistant(Some("mystery-model"), usage(10, 10))],
+            Some(usage(10, 10)),
+        );
+        s.model_name = Some("mystery-model".to_string());
+        let estimate = table.estimate(&s).unwrap();
+        assert_eq!(estimate.usd, 0.0);
+        assert_eq!(estimate.priced_tokens, 0);
+        assert_eq!(estimate.unpriced_models, vec!["mystery-model".to_string()]);
+
+        s.usage = None;
+        assert!(table.estimate(&s).is_none());
+    }
+
+    #[test]
+    fn load_merges_price_file_over_builtins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("prices.json");
+        std::fs::write(
+            &path,
+            r#"{"mystery-model": {"input": 1.0, "output": 2.0}, "gpt-5": {"input": 9.0, "output": 9.0}}"#,
+        )
+        .unwrap();
+        let table = PriceTable::load(&path).unwrap();
+        assert_eq!(table.lookup("mystery-model-v2").unwrap().output, 2.0);
+        assert_eq!(table.lookup("gpt-5-codex").unwrap().input, 9.0);
+        assert_eq!(table.lookup("gpt-5-mini").unwrap().input, 0.25);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(PriceTable::load(&path).is_err());
+    }
+}
@@ -33,7 +33,7 @@ use crate::discovery::DetectionResult;
 use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Aider provider implementation.
 pub struct Aider;
@@ -52,7 +52,7 @@ impl Aider {
     /// Root directory for Aider data.
     /// Respects `AIDER_HOME` env var override.
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("AIDER_HOME") {
+        if let Ok(home) = crate::homes::env_var("AIDER_HOME") {
             return Some(PathBuf::from(home));
         }
         None
@@ -68,7 +68,7 @@ impl Aider {
         }
 
         // 2. Check explicit AIDER_CHAT_HISTORY_FILE.
-        if let Ok(path) = std::env::var("AIDER_CHAT_HISTORY_FILE") {
+        if let Ok(path) = crate::homes::env_var("AIDER_CHAT_HISTORY_FILE") {
             let p = PathBuf::from(path);
             if p.is_file() && !files.contains(&p) {
                 files.push(p);
@@ -182,6 +182,7 @@ impl Aider {
         let mut tool_lines: Vec<String> = Vec::new();
         let mut model_name: Option<String> = None;
         let mut workspace: Option<PathBuf> = None;
+        let mut lineage: Option<serde_json::Value> = None;
 
         // Flush accumulated lines into a message.
         let flush_user = |lines: &mut Vec<String>, msgs: &mut Vec<CanonicalMessage>| {
@@ -197,10 +198,14 @@ impl Aider {
                 idx: 0,
                 role: MessageRole::User,
                 content,
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             });
         };
@@ -218,10 +223,14 @@ impl Aider {
                 idx: 0,
                 role: MessageRole::Assistant,
                 content,
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             });
         };
@@ -239,10 +248,14 @@ impl Aider {
                 idx: 0,
                 role: MessageRole::Tool,
                 content,
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             });
         };
@@ -262,7 +275,10 @@ impl Aider {
                 let stripped = rest.trim_end().trim_end_matches("  ");
                 let parsed_model = extract_model_from_tool_line(stripped);
                 let parsed_workspace = extract_workspace_from_tool_line(stripped);
-                let is_metadata_only_line = parsed_model.is_some() || parsed_workspace.is_some();
+                let parsed_lineage = extract_lineage_from_tool_line(stripped);
+                let is_metadata_only_line = parsed_model.is_some()
+                    || parsed_workspace.is_some()
+                    || parsed_lineage.is_some();
 
                 // Extract metadata from tool output lines.
                 if model_name.is_none()
@@ -275,6 +291,9 @@ impl Aider {
                 {
                     workspace = Some(ws);
                 }
+                if lineage.is_none() {
+                    lineage = parsed_lineage;
+                }
                 if !is_metadata_only_line {
                     tool_lines.push(stripped.to_string());
                 }
@@ -330,6 +349,7 @@ impl Aider {
             "start_timestamp_raw".into(),
             serde_json::Value::String(session.start_timestamp.clone()),
         );
+        crate::lineage::restore(&mut metadata, lineage.as_ref());
 
         let source_path = Self::virtual_session_path(path, &session.session_id);
 
@@ -350,6 +370,8 @@ impl Aider {
             metadata: serde_json::Value::Object(metadata),
             source_path,
             model_name,
+            usage: None,
+            unrecognized: None,
         })
     }
 }
@@ -523,6 +545,11 @@ impl Provider for Aider {
         if let Some(ref model) = session.model_name {
             output.push_str(&format!("> Model: {model}  \n"));
         }
+        // `--restore-chat-history` skips tool output, so the lineage stays
+        // out of the model's context.
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            output.push_str(&format!("> {LINEAGE_PREFIX}{lineage}  \n"));
+        }
 
         // Write messages.
         for msg in &session.messages {
@@ -571,10 +598,21 @@ impl Provider for Aider {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // A Markdown transcript: no tool calls, per-message timestamps, title, or workspace.
+        VerifyTolerance::CONTENT_ONLY
+    }
+
     fn resume_command(&self, _session_id: &str) -> String {
         "aider --restore-chat-history".to_string()
     }
 
+    fn edits_in_place(&self) -> bool {
+        // Session IDs are write timestamps and one history file holds many
+        // sessions, so a rewrite can't replace just the original.
+        false
+    }
+
     fn list_sessions(&self) -> Option<Vec<(String, PathBuf)>> {
         let history_files = Self::find_history_files();
         if history_files.is_empty() {
@@ -654,6 +692,15 @@ fn extract_model_from_tool_line(line: &str) -> Option<String> {
     None
 }
 
+/// Prefix of the tool output line casr writes its conversion lineage on.
+const LINEAGE_PREFIX: &str = "casr-lineage: ";
+
+/// Extract casr's conversion lineage from an Aider tool output line.
+fn extract_lineage_from_tool_line(line: &str) -> Option<serde_json::Value> {
+    let json = line.trim().strip_prefix(LINEAGE_PREFIX)?;
+    serde_json::from_str(json).ok()
+}
+
 /// Extract workspace path from an Aider tool output line.
 ///
 /// Looks for patterns like:
@@ -1173,30 +1220,40 @@ Response three
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix the bug".to_string(),
+                    parts: Vec::new(),
                     timestamp: None,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "I'll fix it now.".to_string(),
+                    parts: Vec::new(),
                     timestamp: None,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({"source": "claude-code"}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: Some("claude-3".to_string()),
+            usage: None,
+            unrecognized: None,
         };
 
         let provider = Aider;
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let result = provider
             .write_session(&session, &opts)
             .expect("write should succeed");
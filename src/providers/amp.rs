@@ -19,6 +19,8 @@
 //! The thread JSON format is Amp-internal but resembles Anthropic-style message
 //! blocks: messages have a `role` and an array `content` with blocks like
 //! `{type:"text", text:"..."}`, `{type:"tool_use", ...}`, `{type:"tool_result", ...}`.
+//! Assistant messages may carry `usage` (`model`, `inputTokens`, `outputTokens`,
+//! `cacheCreationInputTokens`, `cacheReadInputTokens`).
 
 use std::path::{Path, PathBuf};
 
@@ -27,8 +29,9 @@ use tracing::{debug, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, flatten_content,
-    normalize_role, reindex_messages, truncate_title,
+    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, content_parts,
+    extract_reasoning, flatten_content, normalize_role, parse_usage, reindex_messages,
+    sum_message_usage, truncate_title,
 };
 use crate::providers::{Provider, WriteOptions, WrittenSession};
 
@@ -45,20 +48,20 @@ pub struct Amp;
 
 impl Amp {
     fn amp_home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("AMP_HOME") {
+        if let Ok(home) = crate::homes::env_var("AMP_HOME") {
             return Some(PathBuf::from(home));
         }
 
         // Match Amp's own centralized storage behavior: XDG_DATA_HOME or ~/.local/share.
-        if let Ok(data_home) = std::env::var("AMP_DATA_HOME") {
+        if let Ok(data_home) = crate::homes::env_var("AMP_DATA_HOME") {
             return Some(PathBuf::from(data_home).join("amp"));
         }
 
-        if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+        if let Ok(xdg) = crate::homes::env_var("XDG_DATA_HOME") {
             return Some(PathBuf::from(xdg).join("amp"));
         }
 
-        dirs::home_dir().map(|h| h.join(".local").join("share").join("amp"))
+        crate::homes::home_dir().map(|h| h.join(".local").join("share").join("amp"))
     }
 
     fn centralized_threads_root() -> Option<PathBuf> {
@@ -69,14 +72,14 @@ impl Amp {
         // Editor config roots that can host VS Code-style `User/globalStorage`.
         // Probe both config_dir and data_dir to cover Linux/Windows vs macOS.
         let mut host_roots: Vec<PathBuf> = Vec::new();
-        if let Some(cfg) = dirs::config_dir() {
+        if let Some(cfg) = crate::homes::config_dir() {
             host_roots.push(cfg.join("Code"));
             host_roots.push(cfg.join("Code - Insiders"));
             host_roots.push(cfg.join("VSCodium"));
             host_roots.push(cfg.join("Cursor"));
             host_roots.push(cfg.join("Windsurf"));
         }
-        if let Some(data) = dirs::data_dir() {
+        if let Some(data) = crate::homes::data_dir() {
             host_roots.push(data.join("Code"));
             host_roots.push(data.join("Code - Insiders"));
             host_roots.push(data.join("VSCodium"));
@@ -188,15 +191,14 @@ impl Amp {
                 if obj.get("type")?.as_str()? != "tool_use" {
                     return None;
                 }
-                Some(ToolCall {
-                    id: obj.get("id").and_then(|v| v.as_str()).map(String::from),
-                    name: obj
-                        .get("name")
+                Some(ToolCall::new(
+                    obj.get("id").and_then(|v| v.as_str()).map(String::from),
+                    obj.get("name")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
-                })
+                    obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
+                ))
             })
             .collect()
     }
@@ -457,6 +459,10 @@ impl Amp {
             "messages".to_string(),
             serde_json::Value::Array(amp_messages),
         );
+        // The reader keeps the whole thread as metadata, lineage included.
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            thread_obj.insert(crate::lineage::METADATA_KEY.to_string(), lineage);
+        }
 
         serde_json::Value::Object(thread_obj)
     }
@@ -479,7 +485,7 @@ impl Provider for Amp {
         let mut evidence = Vec::new();
         let mut installed = false;
 
-        if let Ok(home) = std::env::var("AMP_HOME") {
+        if let Ok(home) = crate::homes::env_var("AMP_HOME") {
             evidence.push(format!("AMP_HOME={home}"));
             let p = PathBuf::from(&home);
             if p.is_dir() {
@@ -488,7 +494,7 @@ impl Provider for Amp {
                 evidence.push(format!("{} missing", p.display()));
             }
         }
-        if let Ok(data_home) = std::env::var("AMP_DATA_HOME") {
+        if let Ok(data_home) = crate::homes::env_var("AMP_DATA_HOME") {
             evidence.push(format!("AMP_DATA_HOME={data_home}"));
         }
 
@@ -529,6 +535,12 @@ impl Provider for Amp {
         roots
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        let mut locations: Vec<PathBuf> = Self::centralized_threads_root().into_iter().collect();
+        locations.extend(Self::legacy_threads_roots());
+        locations
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let roots = self.session_roots();
         Self::owns_session_in_roots(session_id, &roots)
@@ -568,7 +580,13 @@ impl Provider for Amp {
             anyhow::bail!("Amp thread messages must be an array");
         };
 
+        let mut model_counts: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+
         for (idx, msg) in msgs.iter().enumerate() {
+            if let Some(model) = msg.pointer("/usage/model").and_then(|v| v.as_str()) {
+                *model_counts.entry(model.to_string()).or_insert(0) += 1;
+            }
             let role_str = msg.get("role").and_then(|v| v.as_str()).unwrap_or("other");
             let role = normalize_role(role_str);
             let content_val = msg
@@ -586,8 +604,20 @@ impl Provider for Amp {
             let tool_calls = Self::extract_tool_calls(&content_val);
             let tool_results = Self::extract_tool_results(&content_val);
             let timestamp = Self::extract_message_timestamp(msg);
+            let reasoning = extract_reasoning(&content_val);
+            let parts = content_parts(
+                &content_val,
+                reasoning.len(),
+                tool_calls.len(),
+                tool_results.len(),
+                0,
+            );
 
-            if content.trim().is_empty() && tool_calls.is_empty() && tool_results.is_empty() {
+            if content.trim().is_empty()
+                && reasoning.is_empty()
+                && tool_calls.is_empty()
+                && tool_results.is_empty()
+            {
                 continue;
             }
 
@@ -595,15 +625,24 @@ impl Provider for Amp {
                 idx,
                 role,
                 content,
+                parts,
                 timestamp,
                 author: None,
+                reasoning,
                 tool_calls,
                 tool_results,
+                attachments: Vec::new(),
+                usage: msg.get("usage").and_then(parse_usage),
                 extra: msg.clone(),
             });
         }
 
         reindex_messages(&mut messages);
+        let model_name = model_counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(name, _)| name);
+        let usage = sum_message_usage(&messages);
 
         let started_at = created;
         let ended_at = messages
@@ -638,7 +677,9 @@ impl Provider for Amp {
             messages,
             metadata: thread.clone(),
             source_path: path.to_path_buf(),
-            model_name: None,
+            model_name,
+            usage,
+            unrecognized: None,
         })
     }
 
@@ -648,7 +689,10 @@ impl Provider for Amp {
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
         let threads_root = Self::pick_threads_root_for_write()?;
-        let thread_id = Self::generate_thread_id();
+        let thread_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(Self::generate_thread_id);
 
         let created = session
             .started_at
@@ -912,18 +956,22 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: "Hello".to_string(),
+            parts: Vec::new(),
             timestamp: Some(1_700_000_000_000_i64),
             author: None,
-            tool_calls: vec![ToolCall {
-                id: Some("tool-1".to_string()),
-                name: "Read".to_string(),
-                arguments: serde_json::json!({"path":"src/main.rs"}),
-            }],
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("tool-1".to_string()),
+                "Read".to_string(),
+                serde_json::json!({"path":"src/main.rs"}),
+            )],
             tool_results: vec![ToolResult {
                 call_id: Some("tool-1".to_string()),
                 content: "ok".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
 
@@ -961,34 +1009,44 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Hello".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(created),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Hi".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(created + 1),
                     author: None,
-                    tool_calls: vec![ToolCall {
-                        id: Some("tool-1".to_string()),
-                        name: "Read".to_string(),
-                        arguments: serde_json::json!({"path":"src/lib.rs"}),
-                    }],
+                    reasoning: Vec::new(),
+                    tool_calls: vec![ToolCall::new(
+                        Some("tool-1".to_string()),
+                        "Read".to_string(),
+                        serde_json::json!({"path":"src/lib.rs"}),
+                    )],
                     tool_results: vec![ToolResult {
                         call_id: Some("tool-1".to_string()),
                         content: "ok".to_string(),
                         is_error: false,
                     }],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
             ],
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let thread_json = Amp::build_thread_json(&session, thread_id, created);
@@ -55,7 +55,7 @@ use tracing::{debug, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, parse_timestamp, reindex_messages,
+    CanonicalMessage, CanonicalSession, MessageRole, Reasoning, parse_timestamp, reindex_messages,
     truncate_title,
 };
 use crate::providers::{Provider, WriteOptions, WrittenSession};
@@ -74,10 +74,10 @@ impl Antigravity {
     /// Respects the `GEMINI_HOME` env var override (shared with the legacy
     /// Gemini CLI provider so a single override relocates both).
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("GEMINI_HOME") {
+        if let Ok(home) = crate::homes::env_var("GEMINI_HOME") {
             return Some(PathBuf::from(home));
         }
-        dirs::home_dir().map(|h| h.join(".gemini"))
+        crate::homes::home_dir().map(|h| h.join(".gemini"))
     }
 
     /// The Antigravity CLI data directory: `<home>/antigravity-cli`.
@@ -198,6 +198,17 @@ impl Provider for Antigravity {
         Some(Self::list_conversations())
     }
 
+    fn database_files(&self) -> Vec<PathBuf> {
+        Self::list_conversations()
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect()
+    }
+
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::conversations_dir().into_iter().collect()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let conv_dir = Self::conversations_dir()?;
         if !conv_dir.is_dir() {
@@ -318,6 +329,8 @@ impl Provider for Antigravity {
             metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
             model_name: Some(AGY_REQUIRED_MODEL.to_string()),
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -338,6 +351,10 @@ impl Provider for Antigravity {
         ))
     }
 
+    fn writable(&self) -> bool {
+        false
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("agy --conversation {session_id} --model \"{AGY_REQUIRED_MODEL}\"")
     }
@@ -392,18 +409,16 @@ fn extract_tool_calls(step: &serde_json::Value) -> Vec<crate::model::ToolCall> {
     arr.iter()
         .filter_map(|call| {
             let obj = call.as_object()?;
-            Some(crate::model::ToolCall {
-                id: obj
-                    .get("id")
+            Some(crate::model::ToolCall::new(
+                obj.get("id")
                     .and_then(|v| v.as_str())
                     .map(ToString::to_string),
-                name: obj
-                    .get("name")
+                obj.get("name")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown")
                     .to_string(),
-                arguments: obj.get("args").cloned().unwrap_or(serde_json::Value::Null),
-            })
+                obj.get("args").cloned().unwrap_or(serde_json::Value::Null),
+            ))
         })
         .collect()
 }
@@ -426,9 +441,9 @@ fn step_to_message(step: &serde_json::Value) -> Option<CanonicalMessage> {
         raw_content.trim().to_string()
     };
 
-    // The model's internal reasoning is preserved as a fallback when the
-    // visible content is empty (tool-only planner steps), mirroring the Gemini
-    // provider's `thoughts` handling.
+    // The model's internal reasoning stands in for empty visible content
+    // (tool-only planner steps), mirroring the Gemini provider's `thoughts`
+    // handling; otherwise it is kept as canonical reasoning.
     let thinking = step
         .get("thinking")
         .and_then(|v| v.as_str())
@@ -438,10 +453,16 @@ fn step_to_message(step: &serde_json::Value) -> Option<CanonicalMessage> {
 
     let tool_calls = extract_tool_calls(step);
 
-    let effective_content = if content.is_empty() && tool_calls.is_empty() {
-        thinking.clone()
+    let (effective_content, reasoning) = if content.is_empty() && tool_calls.is_empty() {
+        (thinking, Vec::new())
+    } else if thinking.is_empty() {
+        (content, Vec::new())
     } else {
-        content
+        let reasoning = vec![Reasoning {
+            text: thinking,
+            ..Reasoning::default()
+        }];
+        (content, reasoning)
     };
 
     // Skip steps that carry no content and no tool activity at all.
@@ -455,10 +476,14 @@ fn step_to_message(step: &serde_json::Value) -> Option<CanonicalMessage> {
         idx: 0,
         role,
         content: effective_content,
+        parts: Vec::new(),
         timestamp,
         author: None,
+        reasoning,
         tool_calls,
         tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
         extra: step.clone(),
     })
 }
@@ -631,8 +656,10 @@ mod tests {
             metadata: serde_json::Value::Null,
             source_path: std::path::PathBuf::from("/tmp/x"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
-        let opts = crate::providers::WriteOptions { force: false };
+        let opts = crate::providers::WriteOptions::default();
         let err = p
             .write_session(&session, &opts)
             .expect_err("agy must refuse writes");
@@ -33,7 +33,7 @@ use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, flatten_content, normalize_role,
     parse_timestamp, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// ChatGPT desktop app provider implementation.
 pub struct ChatGpt;
@@ -42,13 +42,13 @@ impl ChatGpt {
     /// Root directory for ChatGPT app data.
     /// Respects `CHATGPT_HOME` env var override.
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("CHATGPT_HOME") {
+        if let Ok(home) = crate::homes::env_var("CHATGPT_HOME") {
             return Some(PathBuf::from(home));
         }
         // ChatGPT desktop is macOS only.
         #[cfg(target_os = "macos")]
         {
-            dirs::home_dir().map(|h| h.join("Library/Application Support/com.openai.chat"))
+            crate::homes::home_dir().map(|h| h.join("Library/Application Support/com.openai.chat"))
         }
         #[cfg(not(target_os = "macos"))]
         {
@@ -153,6 +153,10 @@ impl Provider for ChatGpt {
             .collect()
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::home_dir().into_iter().collect()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let home = Self::home_dir()?;
         if !home.is_dir() {
@@ -320,10 +324,14 @@ impl Provider for ChatGpt {
                     idx: 0,
                     role,
                     content: text,
+                    parts: Vec::new(),
                     timestamp: ts,
                     author: msg_model,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: msg.clone(),
                 });
             }
@@ -364,10 +372,14 @@ impl Provider for ChatGpt {
                     idx: 0,
                     role,
                     content: text,
+                    parts: Vec::new(),
                     timestamp: ts,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: msg.clone(),
                 });
             }
@@ -392,6 +404,7 @@ impl Provider for ChatGpt {
         if let Some(ref m) = model_name {
             metadata.insert("model".into(), serde_json::Value::String(m.clone()));
         }
+        crate::lineage::restore(&mut metadata, root.get(crate::lineage::METADATA_KEY));
 
         debug!(
             session_id,
@@ -410,6 +423,8 @@ impl Provider for ChatGpt {
             metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
             model_name,
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -418,7 +433,10 @@ impl Provider for ChatGpt {
         session: &CanonicalSession,
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
-        let target_session_id = uuid::Uuid::new_v4().to_string();
+        let target_session_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
         // Determine target directory.
         let home = Self::home_dir()
@@ -492,13 +510,16 @@ impl Provider for ChatGpt {
             prev_node_id = Some(node_id);
         }
 
-        let root = serde_json::json!({
+        let mut root = serde_json::json!({
             "id": target_session_id,
             "title": session.title.as_deref().unwrap_or("Imported conversation"),
             "create_time": create_time,
             "update_time": update_time,
             "mapping": mapping,
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            root[crate::lineage::METADATA_KEY] = lineage;
+        }
 
         let content_bytes = serde_json::to_string_pretty(&root)?.into_bytes();
 
@@ -520,6 +541,17 @@ impl Provider for ChatGpt {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Conversations carry text only and have no workspace.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            workspace: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("open \"https://chatgpt.com/c/{session_id}\"")
     }
@@ -1129,26 +1161,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Hello".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_001_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Hi there".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_002_000),
                     author: Some("gpt-4".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
             ],
             metadata: json!({"source": "test"}),
             source_path: std::path::PathBuf::from("/tmp/test.json"),
             model_name: Some("gpt-4".to_string()),
+            usage: None,
+            unrecognized: None,
         };
 
         // Set CHATGPT_HOME to temp dir so writer has a target.
@@ -1229,26 +1271,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "User says hello".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_001_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Assistant responds".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_002_000),
                     author: Some("gpt-4o".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
             ],
             metadata: json!({"source": "chatgpt"}),
             source_path: std::path::PathBuf::from("/tmp/test.json"),
             model_name: Some("gpt-4o".to_string()),
+            usage: None,
+            unrecognized: None,
         };
 
         // Build the ChatGPT JSON manually (writer logic).
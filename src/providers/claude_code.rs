@@ -7,7 +7,20 @@
 //!
 //! Each line is a JSON object with a `type` field:
 //! - `"user"` / `"assistant"` — conversational messages (extracted).
-//! - `"file-history-snapshot"` / `"summary"` — non-conversational (skipped).
+//! - `"system"` with `subtype: "compact_boundary"` — written by `/compact`;
+//!   everything before it has been replaced by the compact summary that follows.
+//! - `"summary"` — legacy conversation summaries (kept as metadata / title fallback).
+//! - `"file-history-snapshot"`, `"queue-operation"`, `"progress"`,
+//!   `"last-prompt"`, `"mode"`, `"cost-state"` — client bookkeeping (skipped).
+//!
+//! Any other entry type, and unknown keys or content block types on
+//! conversational entries, are counted in [`CanonicalSession::unrecognized`].
+//!
+//! The user entry right after a compact boundary carries `isCompactSummary: true`
+//! and holds the condensed history. With [`HistoryMode::Live`] (the default) the
+//! reader mirrors the agent's live context — the latest summary plus everything
+//! after it — just like the Codex reader does for `compacted` events.
+//! [`HistoryMode::Full`] replays the whole on-disk archive instead.
 //!
 //! Conversational entries carry:
 //! - `message.role` / `message.content` / `message.model`
@@ -22,10 +35,14 @@ use tracing::{debug, info, trace, warn};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, normalize_role,
-    parse_timestamp, reindex_messages, truncate_title,
+    Attachment, AttachmentSource, CanonicalMessage, CanonicalSession, ContentPart, MessageRole,
+    Reasoning, ToolCall, ToolResult, Unrecognized, Usage, attach_reasoning_to_next_turn,
+    content_parts, extract_attachments, extract_reasoning, normalize_role, parse_timestamp,
+    parse_usage, reindex_messages, truncate_title,
+};
+use crate::providers::{
+    HistoryMode, Provider, ReadOptions, VerifyTolerance, WriteOptions, WrittenSession,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
 
 /// Claude Code provider implementation.
 pub struct ClaudeCode;
@@ -51,10 +68,10 @@ impl ClaudeCode {
     /// Root directory for Claude Code sessions.
     /// Respects `CLAUDE_HOME` env var override.
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("CLAUDE_HOME") {
+        if let Ok(home) = crate::homes::env_var("CLAUDE_HOME") {
             return Some(PathBuf::from(home));
         }
-        dirs::home_dir().map(|h| h.join(".claude"))
+        crate::homes::home_dir().map(|h| h.join(".claude"))
     }
 
     /// Projects directory where session files live.
@@ -155,6 +172,10 @@ impl Provider for ClaudeCode {
         Some(sessions)
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::projects_dir().into_iter().collect()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let projects_dir = Self::projects_dir()?;
         if !projects_dir.is_dir() {
@@ -175,7 +196,15 @@ impl Provider for ClaudeCode {
     }
 
     fn read_session(&self, path: &Path) -> anyhow::Result<CanonicalSession> {
-        debug!(path = %path.display(), "reading Claude Code session");
+        self.read_session_with(path, &ReadOptions::default())
+    }
+
+    fn read_session_with(
+        &self,
+        path: &Path,
+        opts: &ReadOptions,
+    ) -> anyhow::Result<CanonicalSession> {
+        debug!(path = %path.display(), history = opts.history.as_str(), "reading Claude Code session");
 
         let file = std::fs::File::open(path)
             .with_context(|| format!("failed to open {}", path.display()))?;
@@ -195,6 +224,22 @@ impl Provider for ClaudeCode {
         let mut line_num: usize = 0;
         let mut skipped: usize = 0;
 
+        // Every line of a streamed API message repeats its `usage`, so session
+        // totals keep the last report per `message.id`. Totals also cover turns
+        // dropped by compaction: those tokens were still spent.
+        let mut usage_by_message: std::collections::HashMap<String, Usage> =
+            std::collections::HashMap::new();
+        let mut unkeyed_usage: Option<Usage> = None;
+
+        // Compaction bookkeeping. The title is tracked across the whole file so
+        // it stays stable regardless of which history mode was requested.
+        let mut compact_boundaries: usize = 0;
+        let mut dropped_pre_compaction: usize = 0;
+        let mut legacy_summary: Option<String> = None;
+        let mut lineage: Option<serde_json::Value> = None;
+        let mut first_user_title: Option<String> = None;
+        let mut unrecognized = Unrecognized::default();
+
         for line_result in reader.lines() {
             line_num += 1;
 
@@ -243,11 +288,47 @@ impl Provider for ClaudeCode {
             {
                 version = Some(v.to_string());
             }
+            if lineage.is_none() {
+                lineage = entry.get(crate::lineage::METADATA_KEY).cloned();
+            }
 
-            // Filter: only extract user/assistant conversational messages.
             let entry_type = entry.get("type").and_then(|v| v.as_str());
+
+            // `/compact` boundary: the pre-compaction transcript is no longer in
+            // the agent's context. In live mode, drop it; the compact summary
+            // entry that follows replaces it.
+            if entry_type == Some("system")
+                && entry.get("subtype").and_then(|v| v.as_str()) == Some("compact_boundary")
+            {
+                compact_boundaries += 1;
+                if opts.history == HistoryMode::Live {
+                    debug!(
+                        line = line_num,
+                        dropped = messages.len(),
+                        "claude compaction: resetting history at compact_boundary"
+                    );
+                    dropped_pre_compaction += messages.len();
+                    messages.clear();
+                }
+                continue;
+            }
+
+            if entry_type == Some("summary") {
+                if let Some(text) = entry.get("summary").and_then(|v| v.as_str())
+                    && !text.trim().is_empty()
+                {
+                    legacy_summary = Some(text.to_string());
+                }
+                continue;
+            }
+
+            // Filter: only extract user/assistant conversational messages.
             let is_conversational = matches!(entry_type, Some("user") | Some("assistant"));
             if !is_conversational {
+                match entry_type {
+                    Some(t) if !SKIPPED_ENTRY_TYPES.contains(&t) => unrecognized.entry_type(t),
+                    _ => {}
+                }
                 trace!(
                     line = line_num,
                     ?entry_type,
@@ -256,6 +337,21 @@ impl Provider for ClaudeCode {
                 continue;
             }
 
+            unrecognized.keys("", &entry, ENTRY_KEYS);
+            if let Some(message) = entry.get("message") {
+                unrecognized.keys("message.", message, MESSAGE_KEYS);
+            }
+
+            let usage = entry.pointer("/message/usage").and_then(parse_usage);
+            if let Some(ref u) = usage {
+                match entry.pointer("/message/id").and_then(|v| v.as_str()) {
+                    Some(id) => {
+                        usage_by_message.insert(id.to_string(), u.clone());
+                    }
+                    None => unkeyed_usage.get_or_insert_with(Usage::default).add(u),
+                }
+            }
+
             // Extract role from message.role → top-level type.
             let role_str = entry
                 .pointer("/message/role")
@@ -268,12 +364,33 @@ impl Provider for ClaudeCode {
             let content_value = entry
                 .pointer("/message/content")
                 .or_else(|| entry.get("content"));
+            if let Some(v) = content_value {
+                unrecognized.blocks(v);
+            }
             let content = claude_extract_text_content(content_value);
             let tool_calls = extract_tool_calls(content_value);
             let tool_results = extract_tool_results(content_value);
-
-            // Skip messages that have neither text nor tool payloads.
-            if content.trim().is_empty() && tool_calls.is_empty() && tool_results.is_empty() {
+            let attachments = content_value.map(extract_attachments).unwrap_or_default();
+            let mut reasoning = content_value.map(extract_reasoning).unwrap_or_default();
+            let parts = content_value
+                .map(|v| {
+                    content_parts(
+                        v,
+                        reasoning.len(),
+                        tool_calls.len(),
+                        tool_results.len(),
+                        attachments.len(),
+                    )
+                })
+                .unwrap_or_default();
+
+            // Skip messages that have neither text nor reasoning/tool/attachment payloads.
+            if content.trim().is_empty()
+                && reasoning.is_empty()
+                && tool_calls.is_empty()
+                && tool_results.is_empty()
+                && attachments.is_empty()
+            {
                 trace!(line = line_num, "skipping empty content message");
                 continue;
             }
@@ -298,19 +415,56 @@ impl Provider for ClaudeCode {
             if let Some(ref m) = model {
                 *model_counts.entry(m.clone()).or_insert(0) += 1;
             }
+            // Thinking signatures are bound to the model that produced them.
+            for r in &mut reasoning {
+                r.model = model.clone();
+            }
+
+            let is_compact_summary = entry
+                .get("isCompactSummary")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if first_user_title.is_none() && role == MessageRole::User && !is_compact_summary {
+                let title = truncate_title(&content, 100);
+                if !title.is_empty() {
+                    first_user_title = Some(title);
+                }
+            }
 
             messages.push(CanonicalMessage {
                 idx: 0, // Re-indexed below.
                 role,
                 content,
+                parts,
                 timestamp,
                 author: model,
+                reasoning,
                 tool_calls,
                 tool_results,
+                attachments,
+                usage,
                 extra: entry,
             });
         }
 
+        // Keep each API message's usage on its last line only.
+        let mut credited: std::collections::HashSet<serde_json::Value> =
+            std::collections::HashSet::new();
+        for msg in messages.iter_mut().rev() {
+            if msg.usage.is_some()
+                && let Some(id) = msg.extra.pointer("/message/id")
+                && !credited.insert(id.clone())
+            {
+                msg.usage = None;
+            }
+        }
+
+        // A `thinking` block is logged as its own line ahead of the reply
+        // blocks of the same API message; keep it on that turn.
+        attach_reasoning_to_next_turn(&mut messages, |thinking, next| {
+            let id = |m: &CanonicalMessage| m.extra.pointer("/message/id").cloned();
+            id(thinking).is_some() && id(thinking) == id(next)
+        });
         reindex_messages(&mut messages);
 
         // Derive session ID from filename if not found in content.
@@ -321,11 +475,16 @@ impl Provider for ClaudeCode {
                 .to_string()
         });
 
-        // Derive title from first user message.
-        let title = messages
-            .iter()
-            .find(|m| m.role == MessageRole::User)
-            .map(|m| truncate_title(&m.content, 100));
+        // Derive title from the first genuine user message, falling back to a
+        // legacy summary entry and finally to whatever user text survived.
+        let title = first_user_title
+            .or_else(|| legacy_summary.as_deref().map(|s| truncate_title(s, 100)))
+            .or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == MessageRole::User)
+                    .map(|m| truncate_title(&m.content, 100))
+            });
 
         // Most common model name.
         let model_name = model_counts
@@ -333,6 +492,8 @@ impl Provider for ClaudeCode {
             .max_by_key(|(_, count)| *count)
             .map(|(name, _)| name);
 
+        let usage = Usage::sum(usage_by_message.values().chain(&unkeyed_usage));
+
         // Build metadata.
         let mut metadata = serde_json::Map::new();
         metadata.insert(
@@ -345,6 +506,18 @@ impl Provider for ClaudeCode {
         if let Some(ref v) = version {
             metadata.insert("claudeVersion".into(), serde_json::Value::String(v.clone()));
         }
+        metadata.insert(
+            "historyMode".into(),
+            serde_json::Value::String(opts.history.as_str().to_string()),
+        );
+        if compact_boundaries > 0 {
+            metadata.insert("compactBoundaries".into(), compact_boundaries.into());
+            metadata.insert("droppedPreCompaction".into(), dropped_pre_compaction.into());
+        }
+        if let Some(ref summary) = legacy_summary {
+            metadata.insert("summary".into(), serde_json::Value::String(summary.clone()));
+        }
+        crate::lineage::restore(&mut metadata, lineage.as_ref());
 
         debug!(
             session_id,
@@ -364,6 +537,8 @@ impl Provider for ClaudeCode {
             metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
             model_name,
+            usage,
+            unrecognized: unrecognized.into_option(),
         })
     }
 
@@ -372,7 +547,10 @@ impl Provider for ClaudeCode {
         session: &CanonicalSession,
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
-        let target_session_id = uuid::Uuid::new_v4().to_string();
+        let target_session_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         let now = chrono::Utc::now();
         let now_iso = now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
 
@@ -397,39 +575,22 @@ impl Provider for ClaudeCode {
         // Build JSONL content: one line per message.
         let mut lines: Vec<String> = Vec::with_capacity(session.messages.len());
         let mut prev_uuid: Option<String> = None;
+        // Claude Code ignores unknown entry fields, so the lineage rides on
+        // the first entry.
+        let mut lineage = crate::lineage::stored_value(session);
 
         for msg in &session.messages {
-            let entry_uuid = uuid::Uuid::new_v4().to_string();
-            let msg_ts = msg
-                .timestamp
-                .map(|ts| {
-                    chrono::DateTime::from_timestamp_millis(ts)
-                        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
-                        .unwrap_or_else(|| now_iso.clone())
-                })
-                .unwrap_or_else(|| now_iso.clone());
-
-            let entry_type = claude_entry_type(&msg.role);
-            let inner_msg = build_inner_message(msg, session.model_name.as_deref(), entry_type);
-
-            // Build the full JSONL entry.
-            let parent_uuid_val = match &prev_uuid {
-                Some(u) => serde_json::Value::String(u.clone()),
-                None => serde_json::Value::Null,
-            };
-            let entry = serde_json::json!({
-                "parentUuid": parent_uuid_val,
-                "isSidechain": false,
-                "userType": "external",
-                "cwd": workspace_str.to_string_lossy(),
-                "sessionId": target_session_id,
-                "version": "casr",
-                "gitBranch": "main",
-                "type": entry_type,
-                "message": inner_msg,
-                "uuid": entry_uuid,
-                "timestamp": msg_ts,
-            });
+            let (mut entry, entry_uuid) = build_entry(
+                msg,
+                &target_session_id,
+                workspace_str,
+                session.model_name.as_deref(),
+                prev_uuid.as_deref(),
+                &now_iso,
+            );
+            if let Some(lineage) = lineage.take() {
+                entry[crate::lineage::METADATA_KEY] = lineage;
+            }
 
             lines.push(serde_json::to_string(&entry)?);
             prev_uuid = Some(entry_uuid);
@@ -463,11 +624,152 @@ impl Provider for ClaudeCode {
         })
     }
 
+    fn appends_messages(&self) -> bool {
+        true
+    }
+
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        let now_iso = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let workspace = session
+            .workspace
+            .as_deref()
+            .unwrap_or(std::path::Path::new("/tmp"));
+        // Chain the new entries onto the last one in the file.
+        let existing = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut prev_uuid = existing.lines().rev().find_map(|line| {
+            serde_json::from_str::<serde_json::Value>(line)
+                .ok()?
+                .get("uuid")?
+                .as_str()
+                .map(str::to_string)
+        });
+
+        let mut lines = Vec::with_capacity(messages.len());
+        for msg in messages {
+            let (entry, entry_uuid) = build_entry(
+                msg,
+                &session.session_id,
+                workspace,
+                session.model_name.as_deref(),
+                prev_uuid.as_deref(),
+                &now_iso,
+            );
+            lines.push(serde_json::to_string(&entry)?);
+            prev_uuid = Some(entry_uuid);
+        }
+        let outcome = super::append_jsonl_lines(path, &lines, self.slug())?;
+        info!(
+            session_id = session.session_id,
+            path = %outcome.target_path.display(),
+            messages = messages.len(),
+            "Claude Code session appended"
+        );
+
+        Ok(WrittenSession {
+            paths: vec![outcome.target_path],
+            session_id: session.session_id.clone(),
+            resume_command: self.resume_command(&session.session_id),
+            backup_path: outcome.backup_path,
+        })
+    }
+
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Titles are re-derived from the first user message on read.
+        VerifyTolerance {
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("claude --resume {session_id}")
     }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
+    fn carries_reasoning(&self, _msg: &CanonicalMessage, reasoning: &Reasoning) -> bool {
+        reasoning.signature.is_some()
+    }
 }
 
+// ---------------------------------------------------------------------------
+// Helpers — format vocabulary
+// ---------------------------------------------------------------------------
+
+/// Non-conversational entry types that are deliberately skipped.
+const SKIPPED_ENTRY_TYPES: &[&str] = &[
+    "system",
+    "summary",
+    "file-history-snapshot",
+    "queue-operation",
+    "progress",
+    "last-prompt",
+    "mode",
+    "cost-state",
+];
+
+/// Top-level keys of `user` / `assistant` entries. They ride along in
+/// `extra` either way; anything else is reported as unrecognized.
+const ENTRY_KEYS: &[&str] = &[
+    "parentUuid",
+    "logicalParentUuid",
+    "isSidechain",
+    "userType",
+    "cwd",
+    "sessionId",
+    "version",
+    "gitBranch",
+    "slug",
+    "type",
+    "message",
+    "uuid",
+    "timestamp",
+    "entrypoint",
+    "requestId",
+    "promptId",
+    "promptSource",
+    "permissionMode",
+    "effort",
+    "perTurnEffort",
+    "apiBlockIndex",
+    "turnOrigin",
+    "toolUseResult",
+    "sourceToolAssistantUUID",
+    "isApiErrorMessage",
+    "apiErrorStatus",
+    "error",
+    "isMeta",
+    "isCompactSummary",
+    "isVisibleInTranscriptOnly",
+    "thinkingMetadata",
+    "todos",
+    "content",
+];
+
+/// Keys of the Anthropic API `message` object.
+const MESSAGE_KEYS: &[&str] = &[
+    "id",
+    "type",
+    "role",
+    "model",
+    "content",
+    "usage",
+    "stop_reason",
+    "stop_sequence",
+    "stop_details",
+    "container",
+    "context_management",
+    "diagnostics",
+];
+
 // ---------------------------------------------------------------------------
 // Helpers — tool call/result extraction from content blocks
 // ---------------------------------------------------------------------------
@@ -484,15 +786,14 @@ fn extract_tool_calls(content: Option<&serde_json::Value>) -> Vec<ToolCall> {
             if obj.get("type")?.as_str()? != "tool_use" {
                 return None;
             }
-            Some(ToolCall {
-                id: obj.get("id").and_then(|v| v.as_str()).map(String::from),
-                name: obj
-                    .get("name")
+            Some(ToolCall::new(
+                obj.get("id").and_then(|v| v.as_str()).map(String::from),
+                obj.get("name")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown")
                     .to_string(),
-                arguments: obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
-            })
+                obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
+            ))
         })
         .collect()
 }
@@ -558,46 +859,38 @@ fn coerce_tool_input(arguments: &serde_json::Value) -> serde_json::Value {
 }
 
 fn build_message_content(msg: &CanonicalMessage) -> serde_json::Value {
+    if let Some(blocks) = ordered_content_blocks(msg) {
+        return serde_json::Value::Array(blocks);
+    }
     match msg.role {
         MessageRole::Assistant => {
-            let mut blocks: Vec<serde_json::Value> = Vec::new();
+            let mut blocks: Vec<serde_json::Value> =
+                msg.reasoning.iter().filter_map(reasoning_block).collect();
             if !msg.content.is_empty() {
                 blocks.push(serde_json::json!({ "type": "text", "text": msg.content }));
             }
-            for tc in &msg.tool_calls {
-                blocks.push(serde_json::json!({
-                    "type": "tool_use",
-                    "id": tc.id.as_deref().unwrap_or(""),
-                    "name": tc.name,
-                    "input": coerce_tool_input(&tc.arguments),
-                }));
-            }
+            blocks.extend(msg.tool_calls.iter().map(tool_use_block));
             // Some source agents (e.g. Gemini) attach tool results directly to
             // the assistant message. Preserve them so multi-hop conversions stay
             // lossless. For the common Codex→Claude path, tool output is
             // reclassified as Tool role in the Codex reader, so codex assistant
             // messages never reach here with results.
-            for tr in &msg.tool_results {
-                blocks.push(serde_json::json!({
-                    "type": "tool_result",
-                    "tool_use_id": tr.call_id.as_deref().unwrap_or(""),
-                    "content": tr.content,
-                    "is_error": tr.is_error,
-                }));
-            }
+            blocks.extend(msg.tool_results.iter().map(tool_result_block));
+            blocks.extend(msg.attachments.iter().map(attachment_block));
             serde_json::Value::Array(blocks)
         }
         _ => {
             if !msg.tool_results.is_empty() {
+                let mut blocks: Vec<serde_json::Value> =
+                    msg.tool_results.iter().map(tool_result_block).collect();
+                blocks.extend(msg.attachments.iter().map(attachment_block));
+                serde_json::Value::Array(blocks)
+            } else if !msg.attachments.is_empty() {
                 let mut blocks: Vec<serde_json::Value> = Vec::new();
-                for tr in &msg.tool_results {
-                    blocks.push(serde_json::json!({
-                        "type": "tool_result",
-                        "tool_use_id": tr.call_id.as_deref().unwrap_or(""),
-                        "content": tr.content,
-                        "is_error": tr.is_error,
-                    }));
+                if !msg.content.is_empty() {
+                    blocks.push(serde_json::json!({ "type": "text", "text": msg.content }));
                 }
+                blocks.extend(msg.attachments.iter().map(attachment_block));
                 serde_json::Value::Array(blocks)
             } else {
                 serde_json::Value::String(msg.content.clone())
@@ -606,6 +899,151 @@ fn build_message_content(msg: &CanonicalMessage) -> serde_json::Value {
     }
 }
 
+/// Replay the source's block order when the message carries ordered parts
+/// that interleave text with tool calls, results, or attachments.
+///
+/// Citations are re-attached to the text block they followed.
+fn ordered_content_blocks(msg: &CanonicalMessage) -> Option<Vec<serde_json::Value>> {
+    let parts = msg.ordered_parts("\n")?;
+    if parts
+        .iter()
+        .all(|p| matches!(p, ContentPart::Text { .. } | ContentPart::Citation(_)))
+    {
+        return None;
+    }
+    let mut blocks: Vec<serde_json::Value> = Vec::new();
+    for part in parts {
+        match part {
+            ContentPart::Text { text } => {
+                blocks.push(serde_json::json!({ "type": "text", "text": text }));
+            }
+            ContentPart::Thinking { index } => {
+                blocks.extend(reasoning_block(&msg.reasoning[*index]));
+            }
+            ContentPart::ToolCall { index } => blocks.push(tool_use_block(&msg.tool_calls[*index])),
+            ContentPart::ToolResult { index } => {
+                blocks.push(tool_result_block(&msg.tool_results[*index]));
+            }
+            ContentPart::Attachment { index } => {
+                blocks.push(attachment_block(&msg.attachments[*index]));
+            }
+            ContentPart::Citation(citation) => {
+                if let Some(last) = blocks.last_mut().and_then(|b| b.as_object_mut())
+                    && last.get("type").and_then(|v| v.as_str()) == Some("text")
+                    && let serde_json::Value::Array(list) = last
+                        .entry("citations")
+                        .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+                {
+                    list.push(citation.original.clone());
+                }
+            }
+        }
+    }
+    Some(blocks)
+}
+
+/// Render reasoning as a `thinking` / `redacted_thinking` block.
+///
+/// Unsigned reasoning is omitted: Claude Code rejects thinking blocks without
+/// a valid signature on resume (the pipeline folds it into text beforehand).
+fn reasoning_block(reasoning: &Reasoning) -> Option<serde_json::Value> {
+    let signature = reasoning.signature.as_deref()?;
+    Some(if reasoning.redacted {
+        serde_json::json!({ "type": "redacted_thinking", "data": signature })
+    } else {
+        serde_json::json!({
+            "type": "thinking",
+            "thinking": reasoning.text,
+            "signature": signature,
+        })
+    })
+}
+
+fn tool_use_block(tc: &ToolCall) -> serde_json::Value {
+    serde_json::json!({
+        "type": "tool_use",
+        "id": tc.id.as_deref().unwrap_or(""),
+        "name": tc.name,
+        "input": coerce_tool_input(&tc.arguments),
+    })
+}
+
+fn tool_result_block(tr: &ToolResult) -> serde_json::Value {
+    serde_json::json!({
+        "type": "tool_result",
+        "tool_use_id": tr.call_id.as_deref().unwrap_or(""),
+        "content": tr.content,
+        "is_error": tr.is_error,
+    })
+}
+
+/// Render an attachment as an Anthropic `image` / `document` content block.
+///
+/// Blocks that already came from an Anthropic-format source are replayed
+/// verbatim.
+fn attachment_block(attachment: &Attachment) -> serde_json::Value {
+    let original = &attachment.original;
+    if matches!(
+        original.get("type").and_then(|v| v.as_str()),
+        Some("image") | Some("document")
+    ) && original.get("source").is_some_and(|s| s.is_object())
+    {
+        return original.clone();
+    }
+    let block_type = if attachment.is_image() {
+        "image"
+    } else {
+        "document"
+    };
+    let source = match &attachment.source {
+        AttachmentSource::Base64 { data } => serde_json::json!({
+            "type": "base64",
+            "media_type": attachment.media_type.as_deref().unwrap_or("application/octet-stream"),
+            "data": data,
+        }),
+        AttachmentSource::Url { url } => serde_json::json!({ "type": "url", "url": url }),
+        AttachmentSource::File { .. } => {
+            serde_json::json!({ "type": "url", "url": attachment.url() })
+        }
+    };
+    serde_json::json!({ "type": block_type, "source": source })
+}
+
+/// One JSONL entry for `msg`, chained to `parent_uuid`. Returns the entry and
+/// its `uuid`.
+fn build_entry(
+    msg: &CanonicalMessage,
+    session_id: &str,
+    workspace: &Path,
+    model_name: Option<&str>,
+    parent_uuid: Option<&str>,
+    now_iso: &str,
+) -> (serde_json::Value, String) {
+    let entry_uuid = uuid::Uuid::new_v4().to_string();
+    let msg_ts = msg
+        .timestamp
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_else(|| now_iso.to_string());
+
+    let entry_type = claude_entry_type(&msg.role);
+    let inner_msg = build_inner_message(msg, model_name, entry_type);
+    let entry = serde_json::json!({
+        "parentUuid": parent_uuid,
+        "isSidechain": false,
+        "userType": "external",
+        "cwd": workspace.to_string_lossy(),
+        "sessionId": session_id,
+        "version": "casr",
+        "gitBranch": "main",
+        "type": entry_type,
+        "message": inner_msg,
+        "uuid": entry_uuid,
+        "timestamp": msg_ts,
+    });
+    (entry, entry_uuid)
+}
+
 fn build_inner_message(
     msg: &CanonicalMessage,
     session_model_name: Option<&str>,
@@ -637,10 +1075,18 @@ fn build_inner_message(
         }
         inner_msg["stop_reason"] = serde_json::Value::String("end_turn".to_string());
         inner_msg["stop_sequence"] = serde_json::Value::Null;
-        inner_msg["usage"] = serde_json::json!({
-            "input_tokens": 0,
-            "output_tokens": 0,
-        });
+        inner_msg["usage"] = match msg.usage {
+            Some(ref u) => serde_json::json!({
+                "input_tokens": u.input_tokens,
+                "cache_creation_input_tokens": u.cache_write_tokens,
+                "cache_read_input_tokens": u.cache_read_tokens,
+                "output_tokens": u.output_tokens,
+            }),
+            None => serde_json::json!({
+                "input_tokens": 0,
+                "output_tokens": 0,
+            }),
+        };
     }
     inner_msg
 }
@@ -702,7 +1148,9 @@ fn claude_extract_text_content(content: Option<&serde_json::Value>) -> String {
 #[cfg(test)]
 mod tests {
     use super::{build_inner_message, build_message_content, claude_entry_type, project_dir_key};
-    use crate::model::{CanonicalMessage, MessageRole, ToolCall, ToolResult};
+    use crate::model::{
+        CanonicalMessage, ContentPart, MessageRole, Reasoning, ToolCall, ToolResult,
+    };
     use std::path::Path;
 
     #[test]
@@ -728,10 +1176,14 @@ mod tests {
             idx: 0,
             role,
             content: content.to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: Vec::new(),
             tool_results: Vec::new(),
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }
     }
@@ -739,11 +1191,11 @@ mod tests {
     #[test]
     fn writer_assistant_content_serializes_text_and_tool_use_blocks() {
         let mut msg = sample_message(MessageRole::Assistant, "Plan generated.");
-        msg.tool_calls.push(ToolCall {
-            id: Some("tool-1".to_string()),
-            name: "Read".to_string(),
-            arguments: serde_json::json!({"file_path": "src/main.rs"}),
-        });
+        msg.tool_calls.push(ToolCall::new(
+            Some("tool-1".to_string()),
+            "Read".to_string(),
+            serde_json::json!({"file_path": "src/main.rs"}),
+        ));
 
         let content = build_message_content(&msg);
         let blocks = content
@@ -815,6 +1267,24 @@ mod tests {
             .unwrap_or_else(|e| panic!("read_session failed: {e}"))
     }
 
+    #[test]
+    fn reader_records_block_order_as_parts() {
+        let entry = serde_json::json!({
+            "type": "assistant",
+            "sessionId": "s",
+            "message": {"role": "assistant", "content": [
+                {"type": "text", "text": "Before."},
+                {"type": "tool_use", "id": "t1", "name": "Read", "input": {}},
+                {"type": "text", "text": "After."}
+            ]}
+        });
+        let session = read_cc_jsonl(&format!("{entry}\n"));
+        let msg = &session.messages[0];
+        assert_eq!(msg.content, "Before.\nAfter.");
+        assert_eq!(msg.parts[1], ContentPart::ToolCall { index: 0 });
+        assert!(msg.ordered_parts("\n").is_some());
+    }
+
     #[test]
     fn reader_basic_user_assistant_exchange() {
         let session = read_cc_jsonl(
@@ -834,6 +1304,27 @@ mod tests {
         assert_eq!(session.model_name.as_deref(), Some("claude-3"));
     }
 
+    #[test]
+    fn reader_counts_streamed_usage_once_per_message() {
+        // Streamed assistant turns repeat message.id and usage on every line.
+        let session = read_cc_jsonl(
+            r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":"Hello"},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}
+{"type":"assistant","sessionId":"s1","message":{"id":"msg_1","role":"assistant","content":[{"type":"thinking","thinking":"hm"}],"usage":{"input_tokens":10,"cache_read_input_tokens":500,"output_tokens":1}},"uuid":"u2","timestamp":"2026-01-01T00:00:01Z"}
+{"type":"assistant","sessionId":"s1","message":{"id":"msg_1","role":"assistant","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":10,"cache_read_input_tokens":500,"output_tokens":40}},"uuid":"u3","timestamp":"2026-01-01T00:00:02Z"}
+{"type":"assistant","sessionId":"s1","message":{"id":"msg_2","role":"assistant","content":[{"type":"text","text":"More"}],"usage":{"input_tokens":5,"output_tokens":7}},"uuid":"u4","timestamp":"2026-01-01T00:00:03Z"}"#,
+        );
+        let with_usage: Vec<_> = session
+            .messages
+            .iter()
+            .filter_map(|m| m.usage.as_ref())
+            .collect();
+        assert_eq!(with_usage.len(), 2);
+        let total = session.usage.as_ref().unwrap();
+        assert_eq!(total.input_tokens, 15);
+        assert_eq!(total.cache_read_tokens, 500);
+        assert_eq!(total.output_tokens, 47);
+    }
+
     #[test]
     fn reader_string_content_for_assistant() {
         let session = read_cc_jsonl(
@@ -922,6 +1413,29 @@ not json at all
         assert_eq!(session.metadata["gitBranch"].as_str(), Some("feature/foo"));
     }
 
+    #[test]
+    fn reader_reports_unrecognized_entries_keys_and_blocks() {
+        let session = read_cc_jsonl(
+            r#"{"type":"user","sessionId":"s9","message":{"role":"user","content":"Hi"},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}
+{"type":"queue-operation","operation":"enqueue"}
+{"type":"hologram","sessionId":"s9"}
+{"type":"assistant","sessionId":"s9","vibe":"calm","message":{"role":"assistant","content":[{"type":"text","text":"Hello"},{"type":"sparkle","data":"x"}]},"uuid":"u2","timestamp":"2026-01-01T00:00:01Z"}"#,
+        );
+        assert_eq!(session.messages.len(), 2);
+        let unrecognized = session.unrecognized.expect("unrecognized report");
+        assert_eq!(
+            unrecognized.entry_types.keys().collect::<Vec<_>>(),
+            ["hologram"]
+        );
+        assert_eq!(unrecognized.keys.keys().collect::<Vec<_>>(), ["vibe"]);
+        assert_eq!(unrecognized.block_types["sparkle"], 1);
+
+        let clean = read_cc_jsonl(
+            r#"{"type":"user","sessionId":"s10","message":{"role":"user","content":"Hi"},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}"#,
+        );
+        assert!(clean.unrecognized.is_none());
+    }
+
     #[test]
     fn reader_preserves_version_in_metadata() {
         let session = read_cc_jsonl(
@@ -977,14 +1491,116 @@ not json at all
         assert_eq!(content.as_str().unwrap(), "Just text");
     }
 
+    #[test]
+    fn writer_user_attachments_serialize_as_image_blocks() {
+        let mut msg = sample_message(MessageRole::User, "See screenshot");
+        msg.attachments = vec![
+            crate::model::Attachment::inline(
+                Some("image/png".to_string()),
+                "AAAA",
+                serde_json::Value::Null,
+            ),
+            crate::model::attachment_from_url(
+                Some("application/pdf".to_string()),
+                "https://example.com/spec.pdf",
+                serde_json::Value::Null,
+            ),
+        ];
+        let content = build_message_content(&msg);
+        let blocks = content.as_array().expect("attachments force block array");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["text"], "See screenshot");
+        assert_eq!(blocks[1]["type"], "image");
+        assert_eq!(blocks[1]["source"]["type"], "base64");
+        assert_eq!(blocks[1]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[2]["type"], "document");
+        assert_eq!(blocks[2]["source"]["url"], "https://example.com/spec.pdf");
+    }
+
+    #[test]
+    fn writer_replays_interleaved_parts_in_source_order() {
+        let mut msg = sample_message(MessageRole::Assistant, "Checking.\nAll good.");
+        msg.tool_calls.push(ToolCall::new(
+            Some("t1".to_string()),
+            "Bash".to_string(),
+            serde_json::json!({"command": "ls"}),
+        ));
+        msg.reasoning.push(Reasoning {
+            text: "plan".to_string(),
+            ..Reasoning::default()
+        });
+        msg.parts = vec![
+            ContentPart::Thinking { index: 0 },
+            ContentPart::Text {
+                text: "Checking.".to_string(),
+            },
+            ContentPart::ToolCall { index: 0 },
+            ContentPart::Text {
+                text: "All good.".to_string(),
+            },
+        ];
+        let content = build_message_content(&msg);
+        let types: Vec<&str> = content
+            .as_array()
+            .expect("assistant content should be array")
+            .iter()
+            .map(|b| b["type"].as_str().unwrap_or_default())
+            .collect();
+        // Unsigned thinking is dropped; the rest keeps its original order.
+        assert_eq!(types, ["text", "tool_use", "text"]);
+
+        msg.reasoning[0].signature = Some("sig".to_string());
+        let content = build_message_content(&msg);
+        assert_eq!(content[0]["type"], "thinking");
+        assert_eq!(content[0]["signature"], "sig");
+        assert_eq!(content[1]["text"], "Checking.");
+
+        // Parts that no longer match the flat fields fall back to the flat layout.
+        msg.content = "[Tool: Bash]".to_string();
+        let content = build_message_content(&msg);
+        assert_eq!(content[0]["type"], "thinking");
+        assert_eq!(content[1]["text"], "[Tool: Bash]");
+        assert_eq!(content[2]["type"], "tool_use");
+    }
+
+    #[test]
+    fn writer_replays_signed_and_redacted_reasoning() {
+        let mut msg = sample_message(MessageRole::Assistant, "Answer");
+        msg.reasoning = vec![
+            Reasoning {
+                text: "step by step".to_string(),
+                signature: Some("sig".to_string()),
+                ..Reasoning::default()
+            },
+            Reasoning {
+                signature: Some("opaque".to_string()),
+                redacted: true,
+                ..Reasoning::default()
+            },
+            Reasoning {
+                text: "unsigned".to_string(),
+                ..Reasoning::default()
+            },
+        ];
+        let content = build_message_content(&msg);
+        let blocks = content
+            .as_array()
+            .expect("assistant content should be array");
+        assert_eq!(blocks.len(), 3, "unsigned reasoning is not replayed");
+        assert_eq!(blocks[0]["thinking"], "step by step");
+        assert_eq!(blocks[1]["type"], "redacted_thinking");
+        assert_eq!(blocks[1]["data"], "opaque");
+        assert_eq!(blocks[2]["text"], "Answer");
+    }
+
     #[test]
     fn writer_assistant_empty_content_only_tool_calls() {
         let mut msg = sample_message(MessageRole::Assistant, "");
-        msg.tool_calls.push(ToolCall {
-            id: Some("t1".to_string()),
-            name: "Bash".to_string(),
-            arguments: serde_json::json!({"command": "ls"}),
-        });
+        msg.tool_calls.push(ToolCall::new(
+            Some("t1".to_string()),
+            "Bash".to_string(),
+            serde_json::json!({"command": "ls"}),
+        ));
         let content = build_message_content(&msg);
         let blocks = content
             .as_array()
@@ -998,16 +1614,16 @@ not json at all
     #[test]
     fn writer_multiple_tool_calls_all_serialized() {
         let mut msg = sample_message(MessageRole::Assistant, "Running two tools.");
-        msg.tool_calls.push(ToolCall {
-            id: Some("t1".to_string()),
-            name: "Read".to_string(),
-            arguments: serde_json::json!({"file_path": "a.rs"}),
-        });
-        msg.tool_calls.push(ToolCall {
-            id: Some("t2".to_string()),
-            name: "Write".to_string(),
-            arguments: serde_json::json!({"file_path": "b.rs"}),
-        });
+        msg.tool_calls.push(ToolCall::new(
+            Some("t1".to_string()),
+            "Read".to_string(),
+            serde_json::json!({"file_path": "a.rs"}),
+        ));
+        msg.tool_calls.push(ToolCall::new(
+            Some("t2".to_string()),
+            "Write".to_string(),
+            serde_json::json!({"file_path": "b.rs"}),
+        ));
         let content = build_message_content(&msg);
         let blocks = content.as_array().unwrap();
         assert_eq!(blocks.len(), 3, "text + 2 tool_use blocks");
@@ -1049,4 +1665,100 @@ not json at all
         // Explicit author on message should override session model name.
         assert_eq!(inner["model"], "claude-4-opus");
     }
+
+    // -----------------------------------------------------------------------
+    // Compaction handling
+    // -----------------------------------------------------------------------
+
+    const COMPACTED_SESSION: &str = r#"{"type":"user","sessionId":"sc","cwd":"/tmp/proj","message":{"role":"user","content":"Build the parser"},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}
+{"type":"assistant","sessionId":"sc","message":{"role":"assistant","content":[{"type":"text","text":"Parser drafted."}],"model":"m1"},"uuid":"u2","timestamp":"2026-01-01T00:00:01Z"}
+{"type":"system","subtype":"compact_boundary","sessionId":"sc","content":"Conversation compacted","compactMetadata":{"trigger":"manual","preTokens":90000},"uuid":"u3","timestamp":"2026-01-01T00:00:02Z"}
+{"type":"user","sessionId":"sc","isCompactSummary":true,"message":{"role":"user","content":"This session is being continued from a previous conversation. Summary: parser drafted."},"uuid":"u4","timestamp":"2026-01-01T00:00:03Z"}
+{"type":"user","sessionId":"sc","message":{"role":"user","content":"Now add tests"},"uuid":"u5","timestamp":"2026-01-01T00:00:04Z"}
+{"type":"assistant","sessionId":"sc","message":{"role":"assistant","content":"Tests added."},"uuid":"u6","timestamp":"2026-01-01T00:00:05Z"}"#;
+
+    fn read_cc_jsonl_with(
+        content: &str,
+        opts: &super::ReadOptions,
+    ) -> crate::model::CanonicalSession {
+        let mut tmp = tempfile::NamedTempFile::with_suffix(".jsonl").unwrap();
+        tmp.write_all(content.as_bytes()).unwrap();
+        tmp.flush().unwrap();
+        ClaudeCode
+            .read_session_with(tmp.path(), opts)
+            .unwrap_or_else(|e| panic!("read_session_with failed: {e}"))
+    }
+
+    #[test]
+    fn reader_live_mode_starts_at_latest_compact_boundary() {
+        let session = read_cc_jsonl(COMPACTED_SESSION);
+        assert_eq!(session.messages.len(), 3);
+        assert!(
+            session.messages[0]
+                .content
+                .starts_with("This session is being continued")
+        );
+        assert_eq!(session.messages[0].extra["isCompactSummary"], true);
+        assert_eq!(session.messages[1].content, "Now add tests");
+        assert_eq!(session.metadata["historyMode"], "live");
+        assert_eq!(session.metadata["compactBoundaries"], 1);
+        assert_eq!(session.metadata["droppedPreCompaction"], 2);
+        // Title still comes from the original task, not the compact summary.
+        assert_eq!(session.title.as_deref(), Some("Build the parser"));
+        // Session start reflects the whole file.
+        assert_eq!(session.started_at, session_ts("2026-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn reader_full_mode_replays_entire_archive() {
+        let opts = super::ReadOptions {
+            history: super::HistoryMode::Full,
+        };
+        let session = read_cc_jsonl_with(COMPACTED_SESSION, &opts);
+        assert_eq!(session.messages.len(), 5);
+        assert_eq!(session.messages[0].content, "Build the parser");
+        assert_eq!(session.metadata["historyMode"], "full");
+        assert_eq!(session.metadata["compactBoundaries"], 1);
+        assert_eq!(session.metadata["droppedPreCompaction"], 0);
+    }
+
+    #[test]
+    fn reader_multiple_boundaries_keep_only_last_segment() {
+        let twice = format!(
+            "{COMPACTED_SESSION}\n{}\n{}\n{}",
+            r#"{"type":"system","subtype":"compact_boundary","sessionId":"sc","uuid":"u7","timestamp":"2026-01-01T00:00:06Z"}"#,
+            r#"{"type":"user","sessionId":"sc","isCompactSummary":true,"message":{"role":"user","content":"Second summary."},"uuid":"u8","timestamp":"2026-01-01T00:00:07Z"}"#,
+            r#"{"type":"assistant","sessionId":"sc","message":{"role":"assistant","content":"Continuing."},"uuid":"u9","timestamp":"2026-01-01T00:00:08Z"}"#,
+        );
+        let session = read_cc_jsonl(&twice);
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session.messages[0].content, "Second summary.");
+        assert_eq!(session.metadata["compactBoundaries"], 2);
+        assert_eq!(session.metadata["droppedPreCompaction"], 5);
+    }
+
+    #[test]
+    fn reader_uncompacted_session_records_live_mode_only() {
+        let session = read_cc_jsonl(
+            r#"{"type":"user","sessionId":"sn","message":{"role":"user","content":"Hi"},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}
+{"type":"assistant","sessionId":"sn","message":{"role":"assistant","content":"Hello"},"uuid":"u2","timestamp":"2026-01-01T00:00:01Z"}"#,
+        );
+        assert_eq!(session.metadata["historyMode"], "live");
+        assert!(session.metadata.get("compactBoundaries").is_none());
+    }
+
+    #[test]
+    fn reader_legacy_summary_entry_is_metadata_and_title_fallback() {
+        let session = read_cc_jsonl(
+            r#"{"type":"summary","summary":"Refactor auth module","leafUuid":"x"}
+{"type":"assistant","sessionId":"sl","message":{"role":"assistant","content":"Picking up the refactor."},"uuid":"u1","timestamp":"2026-01-01T00:00:00Z"}"#,
+        );
+        assert_eq!(session.messages.len(), 1);
+        assert_eq!(session.metadata["summary"], "Refactor auth module");
+        assert_eq!(session.title.as_deref(), Some("Refactor auth module"));
+    }
+
+    fn session_ts(iso: &str) -> Option<i64> {
+        crate::model::parse_timestamp(&serde_json::json!(iso))
+    }
 }
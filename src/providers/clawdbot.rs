@@ -29,7 +29,7 @@ use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, flatten_content, normalize_role,
     parse_timestamp, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// ClawdBot provider implementation.
 pub struct ClawdBot;
@@ -38,10 +38,10 @@ impl ClawdBot {
     /// Root directory for ClawdBot session storage.
     /// Respects `CLAWDBOT_HOME` env var override.
     fn home_dir() -> PathBuf {
-        if let Ok(home) = std::env::var("CLAWDBOT_HOME") {
+        if let Ok(home) = crate::homes::env_var("CLAWDBOT_HOME") {
             return PathBuf::from(home);
         }
-        dirs::home_dir()
+        crate::homes::home_dir()
             .unwrap_or_default()
             .join(".clawdbot")
             .join("sessions")
@@ -82,6 +82,10 @@ impl Provider for ClawdBot {
         if root.is_dir() { vec![root] } else { vec![] }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        vec![Self::home_dir()]
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let root = Self::home_dir();
         if !root.is_dir() {
@@ -138,6 +142,7 @@ impl Provider for ClawdBot {
         let mut messages: Vec<CanonicalMessage> = Vec::new();
         let mut started_at: Option<i64> = None;
         let mut ended_at: Option<i64> = None;
+        let mut lineage: Option<serde_json::Value> = None;
 
         for line_result in reader.lines() {
             let line = match line_result {
@@ -148,10 +153,16 @@ impl Provider for ClawdBot {
                 continue;
             }
 
-            let val: serde_json::Value = match serde_json::from_str(&line) {
+            let mut val: serde_json::Value = match serde_json::from_str(&line) {
                 Ok(v) => v,
                 Err(_) => continue,
             };
+            if let Some(stored) = val
+                .as_object_mut()
+                .and_then(|obj| obj.remove(crate::lineage::METADATA_KEY))
+            {
+                lineage.get_or_insert(stored);
+            }
 
             let role_str = val
                 .get("role")
@@ -177,10 +188,14 @@ impl Provider for ClawdBot {
                 idx: 0,
                 role,
                 content,
+                parts: Vec::new(),
                 timestamp: ts,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: val,
             });
         }
@@ -198,7 +213,9 @@ impl Provider for ClawdBot {
             .find(|m| m.role == MessageRole::User)
             .map(|m| truncate_title(&m.content, 100));
 
-        let metadata = serde_json::json!({ "source": "clawdbot" });
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("source".into(), "clawdbot".into());
+        crate::lineage::restore(&mut metadata, lineage.as_ref());
 
         info!(
             session_id,
@@ -214,9 +231,11 @@ impl Provider for ClawdBot {
             started_at,
             ended_at,
             messages,
-            metadata,
+            metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -242,6 +261,9 @@ impl Provider for ClawdBot {
         );
 
         let mut lines: Vec<String> = Vec::with_capacity(session.messages.len());
+        // No session header in this format: the lineage rides on the first
+        // message and the reader lifts it back off.
+        let mut lineage = crate::lineage::stored_value(session);
         for msg in &session.messages {
             let role_str = match &msg.role {
                 MessageRole::User => "user",
@@ -268,6 +290,9 @@ impl Provider for ClawdBot {
                     serde_json::Value::String(dt.to_rfc3339()),
                 );
             }
+            if let Some(lineage) = lineage.take() {
+                obj.insert(crate::lineage::METADATA_KEY.into(), lineage);
+            }
 
             lines.push(serde_json::to_string(&serde_json::Value::Object(obj))?);
         }
@@ -295,6 +320,18 @@ impl Provider for ClawdBot {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Transcripts carry text only, with no workspace or title.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            workspace: false,
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("clawdbot --resume {session_id}")
     }
@@ -532,26 +569,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix the bug".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "I'll fix it now.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_500_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({"source": "claude-code"}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let paths = write_clawdbot_session(tmp.path(), &original);
@@ -583,15 +630,21 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hi".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: json!({}),
             }],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let paths = write_clawdbot_session(tmp.path(), &session);
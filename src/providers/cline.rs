@@ -22,9 +22,9 @@ use tracing::{debug, trace};
 use crate::discovery::DetectionResult;
 use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, flatten_content,
-    normalize_role, reindex_messages, truncate_title,
+    normalize_role, parse_usage, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// VS Code Marketplace extension identifier.
 const CLINE_EXTENSION_ID: &str = "saoudrizwan.claude-dev";
@@ -44,20 +44,20 @@ impl Cline {
     /// The value is expected to be the extension's globalStorage directory, i.e.
     /// the directory that contains `tasks/` and `state/`.
     fn storage_roots() -> Vec<PathBuf> {
-        if let Ok(home) = std::env::var("CLINE_HOME") {
+        if let Ok(home) = crate::homes::env_var("CLINE_HOME") {
             return vec![PathBuf::from(home)];
         }
 
         // Editor config roots that can host VS Code-style `User/globalStorage`.
         // We probe both config_dir and data_dir to cover Linux/Windows vs macOS.
         let mut host_roots: Vec<PathBuf> = Vec::new();
-        if let Some(cfg) = dirs::config_dir() {
+        if let Some(cfg) = crate::homes::config_dir() {
             host_roots.push(cfg.join("Code"));
             host_roots.push(cfg.join("Code - Insiders"));
             host_roots.push(cfg.join("VSCodium"));
             host_roots.push(cfg.join("Cursor"));
         }
-        if let Some(data) = dirs::data_dir() {
+        if let Some(data) = crate::homes::data_dir() {
             host_roots.push(data.join("Code"));
             host_roots.push(data.join("Code - Insiders"));
             host_roots.push(data.join("VSCodium"));
@@ -154,15 +154,14 @@ impl Cline {
                 if obj.get("type")?.as_str()? != "tool_use" {
                     return None;
                 }
-                Some(ToolCall {
-                    id: obj.get("id").and_then(|v| v.as_str()).map(String::from),
-                    name: obj
-                        .get("name")
+                Some(ToolCall::new(
+                    obj.get("id").and_then(|v| v.as_str()).map(String::from),
+                    obj.get("name")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
-                })
+                    obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
+                ))
             })
             .collect()
     }
@@ -354,12 +353,18 @@ impl Cline {
         obj.insert("id".into(), serde_json::Value::String(task_id.to_string()));
         obj.insert("ts".into(), serde_json::Value::Number(ts.into()));
         obj.insert("task".into(), serde_json::Value::String(title));
-        obj.insert("tokensIn".into(), serde_json::Value::Number(0.into()));
-        obj.insert("tokensOut".into(), serde_json::Value::Number(0.into()));
+        let usage = session.usage.clone().unwrap_or_default();
+        obj.insert("tokensIn".into(), usage.input_tokens.into());
+        obj.insert("tokensOut".into(), usage.output_tokens.into());
+        if usage.cache_read_tokens > 0 || usage.cache_write_tokens > 0 {
+            obj.insert("cacheReads".into(), usage.cache_read_tokens.into());
+            obj.insert("cacheWrites".into(), usage.cache_write_tokens.into());
+        }
         obj.insert(
             "totalCost".into(),
             serde_json::Value::Number(
-                serde_json::Number::from_f64(0.0).unwrap_or_else(|| 0.into()),
+                serde_json::Number::from_f64(usage.cost_usd.unwrap_or(0.0))
+                    .unwrap_or_else(|| 0.into()),
             ),
         );
         if let Some(ws) = session.workspace.as_ref() {
@@ -411,7 +416,7 @@ impl Provider for Cline {
         let mut evidence = Vec::new();
         let mut installed = false;
 
-        if let Ok(home) = std::env::var("CLINE_HOME") {
+        if let Ok(home) = crate::homes::env_var("CLINE_HOME") {
             evidence.push(format!("CLINE_HOME={home}"));
             let p = PathBuf::from(&home);
             if p.is_dir() {
@@ -545,10 +550,14 @@ impl Provider for Cline {
                     idx: 0,
                     role,
                     content,
+                    parts: Vec::new(),
                     timestamp: None,
                     author,
+                    reasoning: Vec::new(),
                     tool_calls,
                     tool_results,
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Object(obj.clone()),
                 });
             }
@@ -581,10 +590,14 @@ impl Provider for Cline {
                     idx: 0,
                     role,
                     content: text.to_string(),
+                    parts: Vec::new(),
                     timestamp: ts,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Object(obj.clone()),
                 });
             }
@@ -627,6 +640,11 @@ impl Provider for Cline {
                     .map(String::from)
             });
 
+        // Cline keeps token and cost totals per task in `taskHistory.json`.
+        let usage = history_item
+            .as_ref()
+            .and_then(|h| parse_usage(&serde_json::Value::Object(h.clone())));
+
         let mut metadata = serde_json::Map::new();
         metadata.insert(
             "source".into(),
@@ -635,6 +653,19 @@ impl Provider for Cline {
         if let Some(h) = history_item {
             metadata.insert("taskHistoryItem".into(), serde_json::Value::Object(h));
         }
+        let task_metadata = std::fs::read(
+            Self::tasks_root(&storage_root)
+                .join(&task_id)
+                .join(FILE_TASK_METADATA),
+        )
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok());
+        crate::lineage::restore(
+            &mut metadata,
+            task_metadata
+                .as_ref()
+                .and_then(|m| m.get(crate::lineage::METADATA_KEY)),
+        );
 
         debug!(task_id, messages = messages.len(), "Cline session parsed");
 
@@ -649,6 +680,8 @@ impl Provider for Cline {
             metadata: serde_json::Value::Object(metadata),
             source_path: api_source_path,
             model_name,
+            usage,
+            unrecognized: None,
         })
     }
 
@@ -659,7 +692,10 @@ impl Provider for Cline {
     ) -> anyhow::Result<WrittenSession> {
         let storage_root = Self::pick_storage_root_for_write()?;
 
-        let target_task_id = Self::generate_task_id(&storage_root);
+        let target_task_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| Self::generate_task_id(&storage_root));
         let task_dir = Self::tasks_root(&storage_root).join(&target_task_id);
         std::fs::create_dir_all(&task_dir)
             .with_context(|| format!("failed to create {}", task_dir.display()))?;
@@ -678,14 +714,18 @@ impl Provider for Cline {
         let ui_path = task_dir.join(FILE_UI_MESSAGES);
         let _ = crate::pipeline::atomic_write(&ui_path, &ui_bytes, opts.force, self.slug())?;
 
-        // 3) task_metadata.json (minimal)
+        // 3) task_metadata.json (minimal, plus casr's conversion lineage)
         let metadata_path = task_dir.join(FILE_TASK_METADATA);
-        let metadata_bytes = serde_json::to_vec_pretty(&serde_json::json!({
+        let mut task_metadata = serde_json::json!({
             "files_in_context": [],
             "model_usage": [],
             "environment_history": [],
-        }))
-        .context("failed to serialize task metadata")?;
+        });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            task_metadata[crate::lineage::METADATA_KEY] = lineage;
+        }
+        let metadata_bytes = serde_json::to_vec_pretty(&task_metadata)
+            .context("failed to serialize task metadata")?;
         let _ = crate::pipeline::atomic_write(
             &metadata_path,
             &metadata_bytes,
@@ -711,6 +751,14 @@ impl Provider for Cline {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Task history carries no per-message timestamps.
+        VerifyTolerance {
+            timestamp_drift_ms: None,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, _session_id: &str) -> String {
         // Cline has no CLI resume flag. Best effort: open the workspace in VS Code.
         "code .".to_string()
@@ -786,6 +834,8 @@ mod tests {
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -1050,24 +1100,32 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Hi".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
-                tool_calls: vec![ToolCall {
-                    id: Some("tc-1".to_string()),
-                    name: "Read".to_string(),
-                    arguments: json!({"path": "main.rs"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    Some("tc-1".to_string()),
+                    "Read".to_string(),
+                    json!({"path": "main.rs"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1101,14 +1159,18 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![ToolResult {
                 call_id: Some("tc-1".to_string()),
                 content: "file contents".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }]);
 
@@ -1138,20 +1200,28 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Fix the bug".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "On it".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1170,30 +1240,42 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Initial task".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "OK".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 2,
                 role: MessageRole::User,
                 content: "Follow up".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_002),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1213,30 +1295,42 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "  ".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 2,
                 role: MessageRole::Assistant,
                 content: "Real reply".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_002),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1287,24 +1381,32 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello roundtrip".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Hi roundtrip".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
-                tool_calls: vec![ToolCall {
-                    id: Some("tc-rt".to_string()),
-                    name: "Read".to_string(),
-                    arguments: json!({"path": "lib.rs"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    Some("tc-rt".to_string()),
+                    "Read".to_string(),
+                    json!({"path": "lib.rs"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1345,40 +1447,56 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Hi".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 2,
                 role: MessageRole::System,
                 content: "System msg".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 3,
                 role: MessageRole::Tool,
                 content: "Tool output".to_string(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1400,20 +1518,28 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Task text".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Reply".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1444,20 +1570,28 @@ mod tests {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::System,
                 content: "System note".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_001),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ]);
@@ -1477,10 +1611,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "My task".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }]);
 
@@ -1515,10 +1653,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "New task".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }]);
         session.started_at = Some(1_700_000_000_000);
@@ -1555,10 +1697,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "New version".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         }]);
 
@@ -38,7 +38,7 @@ use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, flatten_content, normalize_role,
     parse_timestamp, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Cursor AI provider implementation.
 pub struct Cursor;
@@ -55,20 +55,20 @@ const BUBBLE_TYPE_ASSISTANT: i64 = 2;
 impl Cursor {
     /// Config directory for Cursor. Respects `CURSOR_HOME` env var override.
     fn config_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("CURSOR_HOME") {
+        if let Ok(home) = crate::homes::env_var("CURSOR_HOME") {
             return Some(PathBuf::from(home));
         }
         #[cfg(target_os = "linux")]
         {
-            dirs::config_dir().map(|c| c.join("Cursor"))
+            crate::homes::config_dir().map(|c| c.join("Cursor"))
         }
         #[cfg(target_os = "macos")]
         {
-            dirs::data_dir().map(|d| d.join("Cursor"))
+            crate::homes::data_dir().map(|d| d.join("Cursor"))
         }
         #[cfg(not(any(target_os = "linux", target_os = "macos")))]
         {
-            dirs::config_dir().map(|c| c.join("Cursor"))
+            crate::homes::config_dir().map(|c| c.join("Cursor"))
         }
     }
 
@@ -337,10 +337,14 @@ impl Cursor {
                 idx: 0,
                 role: MessageRole::User,
                 content,
+                parts: Vec::new(),
                 timestamp: started_at,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: Vec::new(),
                 tool_results: Vec::new(),
+                attachments: Vec::new(),
+                usage: None,
                 extra: composer.clone(),
             });
         }
@@ -381,6 +385,7 @@ impl Cursor {
                 serde_json::Value::String(mode.to_string()),
             );
         }
+        crate::lineage::restore(&mut metadata, composer.get(crate::lineage::METADATA_KEY));
 
         // Unique source path: db_path/composer_id for dedup.
         let source = Self::virtual_session_path(source_path, composer_id);
@@ -402,6 +407,8 @@ impl Cursor {
             metadata: serde_json::Value::Object(metadata),
             source_path: source,
             model_name,
+            usage: None,
+            unrecognized: None,
         })
     }
 }
@@ -455,6 +462,16 @@ impl Provider for Cursor {
         Self::find_db_files()
     }
 
+    fn database_files(&self) -> Vec<PathBuf> {
+        Self::find_db_files()
+    }
+
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::config_dir()
+            .map(|dir| vec![dir.join("User")])
+            .unwrap_or_default()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         for db_path in Self::find_db_files() {
             if let Ok(conn) = Self::open_db(&db_path) {
@@ -520,7 +537,10 @@ impl Provider for Cursor {
         session: &CanonicalSession,
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
-        let target_composer_id = uuid::Uuid::new_v4().to_string();
+        let target_composer_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         let now_millis = chrono::Utc::now().timestamp_millis();
 
         // Determine target DB path.
@@ -592,7 +612,7 @@ impl Provider for Cursor {
         }
 
         // Build composerData entry.
-        let composer_data = serde_json::json!({
+        let mut composer_data = serde_json::json!({
             "fullConversationHeadersOnly": headers,
             "createdAt": session.started_at.unwrap_or(now_millis),
             "lastUpdatedAt": session.ended_at.unwrap_or(now_millis),
@@ -604,6 +624,9 @@ impl Provider for Cursor {
             "casr_source_provider": session.provider_slug,
             "casr_source_session_id": session.session_id,
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            composer_data[crate::lineage::METADATA_KEY] = lineage;
+        }
 
         let composer_key = format!("composerData:{target_composer_id}");
         let composer_json = serde_json::to_string(&composer_data)?;
@@ -632,6 +655,17 @@ impl Provider for Cursor {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Composer bubbles carry text only, and the workspace lives in a separate database.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            workspace: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, _session_id: &str) -> String {
         // Cursor has no session-specific resume mechanism.
         // Best we can do is open Cursor.
@@ -723,10 +757,14 @@ fn parse_bubble(
         idx: 0, // Re-indexed by caller.
         role,
         content,
+        parts: Vec::new(),
         timestamp,
         author,
+        reasoning: Vec::new(),
         tool_calls: Vec::new(),
         tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
         extra: bubble.clone(),
     })
 }
@@ -1346,26 +1384,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Hello".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: Vec::new(),
                     tool_results: Vec::new(),
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Hi there!".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_005_000),
                     author: Some("gpt-4".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: Vec::new(),
                     tool_results: Vec::new(),
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/original.jsonl"),
             model_name: Some("gpt-4".to_string()),
+            usage: None,
+            unrecognized: None,
         };
 
         // Write using internal method.
@@ -28,7 +28,7 @@ use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, flatten_content,
     normalize_role, parse_timestamp, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Factory provider implementation.
 pub struct Factory;
@@ -37,10 +37,10 @@ impl Factory {
     /// Root directory for Factory session storage.
     /// Respects `FACTORY_HOME` env var override.
     fn home_dir() -> PathBuf {
-        if let Ok(home) = std::env::var("FACTORY_HOME") {
+        if let Ok(home) = crate::homes::env_var("FACTORY_HOME") {
             return PathBuf::from(home);
         }
-        dirs::home_dir()
+        crate::homes::home_dir()
             .unwrap_or_default()
             .join(".factory")
             .join("sessions")
@@ -66,6 +66,45 @@ impl Factory {
         s.replace('/', "-")
     }
 
+    /// The `message` entry for one canonical message.
+    fn message_entry(msg: &CanonicalMessage) -> serde_json::Value {
+        let role_str = match &msg.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+            MessageRole::Tool => "tool",
+            MessageRole::Other(r) => r.as_str(),
+        };
+
+        let mut message_obj = serde_json::Map::new();
+        message_obj.insert(
+            "role".into(),
+            serde_json::Value::String(role_str.to_string()),
+        );
+        message_obj.insert(
+            "content".into(),
+            serde_json::Value::String(msg.content.clone()),
+        );
+        if let Some(ref author) = msg.author {
+            message_obj.insert("model".into(), serde_json::Value::String(author.clone()));
+        }
+
+        let mut entry = serde_json::Map::new();
+        entry.insert(
+            "type".into(),
+            serde_json::Value::String("message".to_string()),
+        );
+        if let Some(ts) = msg.timestamp {
+            let dt = chrono::DateTime::from_timestamp_millis(ts).unwrap_or_else(chrono::Utc::now);
+            entry.insert(
+                "timestamp".into(),
+                serde_json::Value::String(dt.to_rfc3339()),
+            );
+        }
+        entry.insert("message".into(), serde_json::Value::Object(message_obj));
+        serde_json::Value::Object(entry)
+    }
+
     fn extract_tool_calls(
         message_obj: Option<&serde_json::Value>,
         content_value: Option<&serde_json::Value>,
@@ -92,21 +131,19 @@ impl Factory {
                     .or_else(|| obj.get("args"))
                     .cloned()
                     .unwrap_or(serde_json::Value::Null);
-                calls.push(ToolCall {
-                    id: obj
-                        .get("id")
+                calls.push(ToolCall::new(
+                    obj.get("id")
                         .or_else(|| obj.get("call_id"))
                         .or_else(|| obj.get("tool_use_id"))
                         .and_then(|v| v.as_str())
                         .map(String::from),
-                    name: obj
-                        .get("name")
+                    obj.get("name")
                         .or_else(|| obj.get("function").and_then(|v| v.get("name")))
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
                     arguments,
-                });
+                ));
             }
         }
 
@@ -118,25 +155,22 @@ impl Factory {
                 let Some(obj) = call.as_object() else {
                     continue;
                 };
-                calls.push(ToolCall {
-                    id: obj
-                        .get("id")
+                calls.push(ToolCall::new(
+                    obj.get("id")
                         .or_else(|| obj.get("call_id"))
                         .and_then(|v| v.as_str())
                         .map(String::from),
-                    name: obj
-                        .get("name")
+                    obj.get("name")
                         .or_else(|| obj.get("function").and_then(|v| v.get("name")))
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: obj
-                        .get("input")
+                    obj.get("input")
                         .or_else(|| obj.get("arguments"))
                         .or_else(|| obj.get("args"))
                         .cloned()
                         .unwrap_or(serde_json::Value::Null),
-                });
+                ));
             }
         }
 
@@ -263,6 +297,10 @@ impl Provider for Factory {
         if root.is_dir() { vec![root] } else { vec![] }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        vec![Self::home_dir()]
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let root = Self::home_dir();
         if !root.is_dir() {
@@ -302,6 +340,7 @@ impl Provider for Factory {
         let mut title_from_header: Option<String> = None;
         let mut workspace: Option<PathBuf> = None;
         let mut owner: Option<String> = None;
+        let mut lineage: Option<serde_json::Value> = None;
         let mut started_at: Option<i64> = None;
         let mut ended_at: Option<i64> = None;
         let mut model_from_settings: Option<String> = None;
@@ -334,6 +373,7 @@ impl Provider for Factory {
                         val.get("id").and_then(|v| v.as_str()).map(String::from);
                     title_from_header = val.get("title").and_then(|v| v.as_str()).map(String::from);
                     owner = val.get("owner").and_then(|v| v.as_str()).map(String::from);
+                    lineage = val.get(crate::lineage::METADATA_KEY).cloned();
                     workspace = val
                         .get("cwd")
                         .and_then(|v| v.as_str())
@@ -376,10 +416,14 @@ impl Provider for Factory {
                         idx: 0,
                         role,
                         content,
+                        parts: Vec::new(),
                         timestamp: ts,
                         author,
+                        reasoning: Vec::new(),
                         tool_calls,
                         tool_results,
+                        attachments: Vec::new(),
+                        usage: None,
                         extra: val,
                     });
                 }
@@ -419,12 +463,15 @@ impl Provider for Factory {
             model_from_settings = val.get("model").and_then(|m| m.as_str()).map(String::from);
         }
 
-        let metadata = serde_json::json!({
+        let mut metadata = serde_json::json!({
             "source": "factory",
             "sessionId": session_id,
             "owner": owner,
             "model": model_from_settings,
         });
+        if let Some(metadata) = metadata.as_object_mut() {
+            crate::lineage::restore(metadata, lineage.as_ref());
+        }
 
         info!(
             session_id,
@@ -443,6 +490,8 @@ impl Provider for Factory {
             metadata,
             source_path: path.to_path_buf(),
             model_name: model_from_settings,
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -476,53 +525,20 @@ impl Provider for Factory {
         let mut lines: Vec<String> = Vec::with_capacity(session.messages.len() + 1);
 
         // Write session_start header.
-        let header = serde_json::json!({
+        let mut header = serde_json::json!({
             "type": "session_start",
             "id": session_id,
             "title": session.title,
             "cwd": session.workspace.as_ref().map(|p| p.to_string_lossy().to_string()),
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            header[crate::lineage::METADATA_KEY] = lineage;
+        }
         lines.push(serde_json::to_string(&header)?);
 
         // Write message entries.
         for msg in &session.messages {
-            let role_str = match &msg.role {
-                MessageRole::User => "user",
-                MessageRole::Assistant => "assistant",
-                MessageRole::System => "system",
-                MessageRole::Tool => "tool",
-                MessageRole::Other(r) => r.as_str(),
-            };
-
-            let mut message_obj = serde_json::Map::new();
-            message_obj.insert(
-                "role".into(),
-                serde_json::Value::String(role_str.to_string()),
-            );
-            message_obj.insert(
-                "content".into(),
-                serde_json::Value::String(msg.content.clone()),
-            );
-            if let Some(ref author) = msg.author {
-                message_obj.insert("model".into(), serde_json::Value::String(author.clone()));
-            }
-
-            let mut entry = serde_json::Map::new();
-            entry.insert(
-                "type".into(),
-                serde_json::Value::String("message".to_string()),
-            );
-            if let Some(ts) = msg.timestamp {
-                let dt =
-                    chrono::DateTime::from_timestamp_millis(ts).unwrap_or_else(chrono::Utc::now);
-                entry.insert(
-                    "timestamp".into(),
-                    serde_json::Value::String(dt.to_rfc3339()),
-                );
-            }
-            entry.insert("message".into(), serde_json::Value::Object(message_obj));
-
-            lines.push(serde_json::to_string(&serde_json::Value::Object(entry))?);
+            lines.push(serde_json::to_string(&Self::message_entry(msg))?);
         }
 
         let content = lines.join("\n") + "\n";
@@ -548,6 +564,46 @@ impl Provider for Factory {
         })
     }
 
+    fn appends_messages(&self) -> bool {
+        true
+    }
+
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        let lines = messages
+            .iter()
+            .map(|msg| serde_json::to_string(&Self::message_entry(msg)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outcome = super::append_jsonl_lines(path, &lines, self.slug())?;
+        info!(
+            session_id = session.session_id,
+            path = %outcome.target_path.display(),
+            messages = messages.len(),
+            "Factory session appended"
+        );
+
+        Ok(WrittenSession {
+            paths: vec![outcome.target_path],
+            session_id: session.session_id.clone(),
+            resume_command: self.resume_command(&session.session_id),
+            backup_path: outcome.backup_path,
+        })
+    }
+
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Tool calls and results are flattened into message text.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("factory --resume {session_id}")
     }
@@ -865,26 +921,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix it".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Done.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_500_000),
                     author: Some("claude-3".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         // Write directly to validate structure.
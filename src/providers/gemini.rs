@@ -12,12 +12,16 @@
 //!   "startTime": "…",
 //!   "lastUpdated": "…",
 //!   "messages": [
-//!     { "type": "user"|"gemini"|"model", "content": "…"|[…], "timestamp": "…" }
+//!     { "type": "user"|"gemini"|"model", "content": "…"|[…], "timestamp": "…",
+//!       "model": "…", "tokens": { "input", "output", "cached", "thoughts", … } }
 //!   ]
 //! }
 //! ```
 //!
 //! Note: Gemini may use `"gemini"` or `"model"` for assistant responses.
+//!
+//! Top-level keys, message keys and message types outside this vocabulary
+//! are counted in [`CanonicalSession::unrecognized`].
 
 use std::path::{Path, PathBuf};
 
@@ -27,14 +31,43 @@ use walkdir::WalkDir;
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, flatten_content,
-    normalize_role, parse_timestamp, reindex_messages, truncate_title,
+    Attachment, CanonicalMessage, CanonicalSession, MessageRole, Reasoning, ToolCall, ToolResult,
+    Unrecognized, Usage, extract_attachments, flatten_content, normalize_role, parse_timestamp,
+    parse_usage, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Gemini CLI provider implementation.
 pub struct Gemini;
 
+/// Keys of the session file's root object.
+const ROOT_KEYS: &[&str] = &[
+    "sessionId",
+    "projectHash",
+    "startTime",
+    "lastUpdated",
+    "messages",
+    "summary",
+];
+
+/// Keys of one entry in `messages`.
+const MESSAGE_KEYS: &[&str] = &[
+    "id",
+    "type",
+    "role",
+    "content",
+    "timestamp",
+    "model",
+    "tokens",
+    "thoughts",
+    "toolCalls",
+    "groundingMetadata",
+    "citations",
+];
+
+/// Message `type`s: conversation turns plus the CLI's own notices.
+const MESSAGE_TYPES: &[&str] = &["user", "gemini", "model", "info", "error", "warning"];
+
 /// Compute the Gemini project hash directory name from a workspace path.
 ///
 /// Algorithm: `SHA256(absolute_workspace_path)` as lowercase hex.
@@ -61,10 +94,10 @@ impl Gemini {
     /// Root directory for Gemini data.
     /// Respects `GEMINI_HOME` env var override.
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("GEMINI_HOME") {
+        if let Ok(home) = crate::homes::env_var("GEMINI_HOME") {
             return Some(PathBuf::from(home));
         }
-        dirs::home_dir().map(|h| h.join(".gemini"))
+        crate::homes::home_dir().map(|h| h.join(".gemini"))
     }
 
     /// Tmp directory where session hashes live.
@@ -172,6 +205,10 @@ impl Provider for Gemini {
         Some(sessions)
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::tmp_dir().into_iter().collect()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let tmp = Self::tmp_dir()?;
         if !tmp.is_dir() {
@@ -252,6 +289,9 @@ impl Provider for Gemini {
         let started_at = root.get("startTime").and_then(parse_timestamp);
         let mut ended_at = root.get("lastUpdated").and_then(parse_timestamp);
 
+        let mut unrecognized = Unrecognized::default();
+        unrecognized.keys("", &root, ROOT_KEYS);
+
         // Parse messages array.
         let msg_array = root
             .get("messages")
@@ -260,6 +300,10 @@ impl Provider for Gemini {
             .unwrap_or_default();
 
         let mut messages: Vec<CanonicalMessage> = Vec::new();
+        let mut model_counts: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+        // Totals include messages skipped below; their tokens were still spent.
+        let mut usage_total: Option<Usage> = None;
 
         for (i, msg) in msg_array.iter().enumerate() {
             // Role: Gemini uses "type" field with "user" or "model".
@@ -269,14 +313,46 @@ impl Provider for Gemini {
                 .and_then(|v| v.as_str())
                 .unwrap_or("user");
             let role = normalize_role(role_str);
+            if let Some(kind) = msg.get("type").and_then(|v| v.as_str())
+                && !MESSAGE_TYPES.contains(&kind)
+            {
+                unrecognized.entry_type(kind);
+            }
+            unrecognized.keys("message.", msg, MESSAGE_KEYS);
 
             // Content: string or array of content parts.
             let content_val = msg.get("content");
+            if let Some(v) = content_val {
+                unrecognized.blocks(v);
+            }
             let text = gemini_extract_text_content(msg, content_val);
             let tool_calls = gemini_extract_tool_calls(msg, content_val);
             let tool_results = gemini_extract_tool_results(msg, content_val);
+            let attachments = content_val.map(extract_attachments).unwrap_or_default();
+            // `thoughts` are reasoning unless they stood in for empty content.
+            let thoughts = msg
+                .get("thoughts")
+                .map(gemini_extract_thoughts_text)
+                .unwrap_or_default();
+            let reasoning = if thoughts.trim().is_empty() || thoughts == text {
+                Vec::new()
+            } else {
+                vec![Reasoning {
+                    text: thoughts,
+                    ..Reasoning::default()
+                }]
+            };
+
+            let usage = msg.get("tokens").and_then(parse_usage);
+            if let Some(ref u) = usage {
+                usage_total.get_or_insert_with(Usage::default).add(u);
+            }
 
-            if text.trim().is_empty() && tool_calls.is_empty() && tool_results.is_empty() {
+            if text.trim().is_empty()
+                && tool_calls.is_empty()
+                && tool_results.is_empty()
+                && attachments.is_empty()
+            {
                 trace!(index = i, "skipping empty Gemini message");
                 continue;
             }
@@ -287,14 +363,22 @@ impl Provider for Gemini {
                 ended_at = Some(ended_at.map_or(t, |e: i64| e.max(t)));
             }
 
+            if let Some(model) = msg.get("model").and_then(|v| v.as_str()) {
+                *model_counts.entry(model.to_string()).or_insert(0) += 1;
+            }
+
             messages.push(CanonicalMessage {
                 idx: 0,
                 role,
                 content: text,
+                parts: Vec::new(),
                 timestamp: ts,
                 author: None,
+                reasoning,
                 tool_calls,
                 tool_results,
+                attachments,
+                usage,
                 extra: msg.clone(),
             });
         }
@@ -310,6 +394,11 @@ impl Provider for Gemini {
         // Workspace: try to extract from message content (project paths).
         let workspace = extract_workspace_from_messages(&messages);
 
+        let model_name = model_counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(name, _)| name);
+
         // Metadata.
         let mut metadata = serde_json::Map::new();
         metadata.insert(
@@ -319,6 +408,7 @@ impl Provider for Gemini {
         if let Some(ref ph) = project_hash {
             metadata.insert("project_hash".into(), serde_json::Value::String(ph.clone()));
         }
+        crate::lineage::restore(&mut metadata, root.get(crate::lineage::METADATA_KEY));
 
         debug!(
             session_id,
@@ -336,7 +426,9 @@ impl Provider for Gemini {
             messages,
             metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
-            model_name: None,
+            model_name,
+            usage: usage_total,
+            unrecognized: unrecognized.into_option(),
         })
     }
 
@@ -345,7 +437,10 @@ impl Provider for Gemini {
         session: &CanonicalSession,
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
-        let target_session_id = uuid::Uuid::new_v4().to_string();
+        let target_session_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         let now = chrono::Utc::now();
 
         // Determine target path.
@@ -409,13 +504,16 @@ impl Provider for Gemini {
             json_messages.push(entry);
         }
 
-        let root = serde_json::json!({
+        let mut root = serde_json::json!({
             "sessionId": target_session_id,
             "projectHash": hash,
             "startTime": start_time,
             "lastUpdated": last_updated,
             "messages": json_messages,
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            root[crate::lineage::METADATA_KEY] = lineage;
+        }
 
         let content_bytes = serde_json::to_string_pretty(&root)?.into_bytes();
 
@@ -437,9 +535,23 @@ impl Provider for Gemini {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Sessions are keyed by a project hash, so the workspace path isn't
+        // recoverable; titles are re-derived on read.
+        VerifyTolerance {
+            workspace: false,
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("gemini --resume {session_id}")
     }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -579,18 +691,16 @@ fn gemini_extract_tool_calls(
                 continue;
             }
 
-            calls.push(ToolCall {
-                id: obj
-                    .get("id")
+            calls.push(ToolCall::new(
+                obj.get("id")
                     .and_then(|v| v.as_str())
                     .map(ToString::to_string),
-                name: obj
-                    .get("name")
+                obj.get("name")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown")
                     .to_string(),
-                arguments: obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
-            });
+                obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
+            ));
         }
     }
 
@@ -599,18 +709,16 @@ fn gemini_extract_tool_calls(
             let Some(obj) = call.as_object() else {
                 continue;
             };
-            calls.push(ToolCall {
-                id: obj
-                    .get("id")
+            calls.push(ToolCall::new(
+                obj.get("id")
                     .and_then(|v| v.as_str())
                     .map(ToString::to_string),
-                name: obj
-                    .get("name")
+                obj.get("name")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown")
                     .to_string(),
-                arguments: obj.get("args").cloned().unwrap_or(serde_json::Value::Null),
-            });
+                obj.get("args").cloned().unwrap_or(serde_json::Value::Null),
+            ));
         }
     }
 
@@ -721,7 +829,7 @@ fn gemini_message_content(msg: &CanonicalMessage) -> serde_json::Value {
         return content.clone();
     }
 
-    if msg.tool_calls.is_empty() && msg.tool_results.is_empty() {
+    if msg.tool_calls.is_empty() && msg.tool_results.is_empty() && msg.attachments.is_empty() {
         return serde_json::Value::String(msg.content.clone());
     }
 
@@ -748,6 +856,9 @@ fn gemini_message_content(msg: &CanonicalMessage) -> serde_json::Value {
             "is_error": tr.is_error,
         }));
     }
+    for attachment in &msg.attachments {
+        blocks.push(gemini_attachment_part(attachment));
+    }
 
     if blocks.is_empty() {
         serde_json::Value::String(msg.content.clone())
@@ -756,6 +867,22 @@ fn gemini_message_content(msg: &CanonicalMessage) -> serde_json::Value {
     }
 }
 
+/// Render an attachment as a Gemini `inlineData` / `fileData` part.
+fn gemini_attachment_part(attachment: &Attachment) -> serde_json::Value {
+    let mime_type = attachment
+        .media_type
+        .as_deref()
+        .unwrap_or("application/octet-stream");
+    match attachment.base64_data() {
+        Some(data) => serde_json::json!({
+            "inlineData": { "mimeType": mime_type, "data": data },
+        }),
+        None => serde_json::json!({
+            "fileData": { "mimeType": mime_type, "fileUri": attachment.url() },
+        }),
+    }
+}
+
 fn merge_gemini_extra_fields(entry: &mut serde_json::Value, extra: &serde_json::Value) {
     let Some(entry_obj) = entry.as_object_mut() else {
         return;
@@ -905,10 +1032,14 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: "fallback".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: json!({
                 "content": [
                     {"type": "text", "text": "primary"},
@@ -933,18 +1064,22 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: "".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
-            tool_calls: vec![ToolCall {
-                id: Some("call-7".to_string()),
-                name: "read_file".to_string(),
-                arguments: json!({"path":"README.md"}),
-            }],
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("call-7".to_string()),
+                "read_file".to_string(),
+                json!({"path":"README.md"}),
+            )],
             tool_results: vec![ToolResult {
                 call_id: Some("call-7".to_string()),
                 content: "ok".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
 
@@ -983,10 +1118,14 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: String::new(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
         let tool = CanonicalMessage {
@@ -1340,10 +1479,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "Simple text".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
         let content = gemini_message_content(&msg);
@@ -1360,10 +1503,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: String::new(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
         assert_eq!(gemini_message_type(&msg), "user");
@@ -1375,10 +1522,14 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: String::new(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
         assert_eq!(gemini_message_type(&msg), "model");
@@ -33,17 +33,27 @@
 //! Each line is a versioned envelope `{"version":"v1","kind":<Kind>,"data":{…}}`
 //! where `kind` is one of `Prompt` (user), `AssistantMessage` (assistant), or
 //! `ToolResults` (tool). The `data.content` array carries typed parts whose
-//! own `kind` is `text` | `thinking` | `toolUse` | `toolResult`:
+//! own `kind` is `text` | `thinking` | `toolUse` | `toolResult` | `image`:
 //!
 //! - `text`     → `data` is a plain string.
-//! - `thinking` → `data` is `{ modelId, text, signature, redactedContent }`.
+//! - `thinking` → `data` is `{ modelId, text, signature, redactedContent }`;
+//!   collected into the message's `reasoning`, not its text.
 //! - `toolUse`  → `data` is `{ toolUseId, name, input }`.
 //! - `toolResult` → `data` is `{ toolUseId, content: [...], status }`.
+//! - `image`    → `data` is `{ format, source: { bytes } }` (base64) or
+//!   `{ format, source: { url } }`.
+//!
+//! `AssistantMessage` lines may report Bedrock-style token usage under
+//! `data.usage` or `data.meta.usage` (`inputTokens`, `outputTokens`,
+//! `cacheReadInputTokens`, `cacheWriteInputTokens`) → message `usage`.
 //!
 //! A `ToolResults` line additionally carries `data.results`, a map keyed by
 //! tool-use id with the rich tool invocation/outcome. We preserve it verbatim
 //! in the message `extra` so it survives a round-trip.
 //!
+//! Envelope and part `kind`s outside this list are counted in
+//! [`CanonicalSession::unrecognized`].
+//!
 //! ## Resume
 //!
 //! ```bash
@@ -57,10 +67,11 @@ use tracing::{debug, info, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, parse_timestamp,
-    reindex_messages, truncate_title,
+    Attachment, CanonicalMessage, CanonicalSession, ContentPart, MessageRole, Reasoning, ToolCall,
+    ToolResult, Unrecognized, content_parts, extract_reasoning, parse_attachment, parse_timestamp,
+    parse_usage, reindex_messages, sum_message_usage, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Provider slug used in canonical metadata.
 const SLUG: &str = "kiro";
@@ -72,13 +83,13 @@ impl Kiro {
     /// Root directory for Kiro data. Respects the `KIRO_HOME` env override,
     /// otherwise defaults to `~/.kiro`.
     fn home_dir() -> Option<PathBuf> {
-        if let Ok(home) = std::env::var("KIRO_HOME") {
+        if let Ok(home) = crate::homes::env_var("KIRO_HOME") {
             let trimmed = home.trim();
             if !trimmed.is_empty() {
                 return Some(PathBuf::from(trimmed));
             }
         }
-        dirs::home_dir().map(|h| h.join(".kiro"))
+        crate::homes::home_dir().map(|h| h.join(".kiro"))
     }
 
     /// Directory holding the CLI session triplets.
@@ -139,6 +150,10 @@ impl Provider for Kiro {
         }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::sessions_dir().into_iter().collect()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let dir = Self::sessions_dir()?;
         // The metadata `.json` file is the canonical session anchor.
@@ -230,6 +245,7 @@ impl Provider for Kiro {
 
         // --- Conversation journal (.jsonl) --------------------------------
         let mut messages: Vec<CanonicalMessage> = Vec::new();
+        let mut unrecognized = Unrecognized::default();
         if jsonl_path.is_file() {
             let text = std::fs::read_to_string(&jsonl_path)
                 .with_context(|| format!("failed to read {}", jsonl_path.display()))?;
@@ -246,6 +262,14 @@ impl Provider for Kiro {
                         continue;
                     }
                 };
+                if let Some(kind) = envelope.get("kind").and_then(|v| v.as_str())
+                    && !ENVELOPE_KINDS.contains(&kind)
+                {
+                    unrecognized.entry_type(kind);
+                }
+                if let Some(content) = envelope.pointer("/data/content") {
+                    unrecognized.blocks(content);
+                }
                 if let Some(msg) = parse_envelope(&envelope, &mut ended_at) {
                     messages.push(msg);
                 }
@@ -253,6 +277,7 @@ impl Provider for Kiro {
         }
 
         reindex_messages(&mut messages);
+        let usage = sum_message_usage(&messages);
 
         // --- Title --------------------------------------------------------
         let title = meta
@@ -273,7 +298,7 @@ impl Provider for Kiro {
         let model_name = messages
             .iter()
             .filter_map(|m| m.author.as_deref())
-            .find(|a| !a.is_empty() && *a != "user" && *a != "reasoning")
+            .find(|a| !a.is_empty() && *a != "user")
             .map(String::from)
             .or_else(|| {
                 meta.pointer("/session_state/rts_model_state/model_info")
@@ -303,6 +328,7 @@ impl Provider for Kiro {
         if let Some(h) = &history {
             metadata.insert("history".into(), serde_json::Value::String(h.clone()));
         }
+        crate::lineage::restore(&mut metadata, meta.get(crate::lineage::METADATA_KEY));
 
         debug!(session_id, messages = messages.len(), "Kiro session parsed");
 
@@ -321,6 +347,8 @@ impl Provider for Kiro {
                 jsonl_path
             },
             model_name,
+            usage,
+            unrecognized: unrecognized.into_option(),
         })
     }
 
@@ -329,7 +357,10 @@ impl Provider for Kiro {
         session: &CanonicalSession,
         opts: &WriteOptions,
     ) -> anyhow::Result<WrittenSession> {
-        let target_session_id = uuid::Uuid::new_v4().to_string();
+        let target_session_id = opts
+            .session_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         let now = chrono::Utc::now();
 
         let dir = Self::sessions_dir()
@@ -405,6 +436,9 @@ impl Provider for Kiro {
             .cloned()
             .unwrap_or_else(|| default_session_state(&target_session_id, session));
         meta.insert("session_state".into(), session_state);
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            meta.insert(crate::lineage::METADATA_KEY.into(), lineage);
+        }
 
         let json_bytes =
             serde_json::to_string_pretty(&serde_json::Value::Object(meta))?.into_bytes();
@@ -456,15 +490,69 @@ impl Provider for Kiro {
         })
     }
 
+    fn appends_messages(&self) -> bool {
+        true
+    }
+
+    /// New turns go to the `.jsonl` conversation journal; the `.json`
+    /// metadata is left alone.
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        let journal = Self::sibling(path, "jsonl");
+        let lines = messages
+            .iter()
+            .filter_map(message_to_envelope)
+            .map(|envelope| serde_json::to_string(&envelope))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outcome = super::append_jsonl_lines(&journal, &lines, self.slug())?;
+        info!(
+            session_id = session.session_id,
+            path = %outcome.target_path.display(),
+            messages = messages.len(),
+            "Kiro session appended"
+        );
+
+        Ok(WrittenSession {
+            paths: vec![outcome.target_path],
+            session_id: session.session_id.clone(),
+            resume_command: self.resume_command(&session.session_id),
+            backup_path: outcome.backup_path,
+        })
+    }
+
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Only user prompts carry timestamps in the journal.
+        VerifyTolerance {
+            timestamp_drift_ms: None,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("kiro-cli --resume-id {session_id}")
     }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
+    fn carries_reasoning(&self, _msg: &CanonicalMessage, reasoning: &Reasoning) -> bool {
+        !reasoning.redacted || reasoning.signature.is_some()
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Reader helpers
 // ---------------------------------------------------------------------------
 
+/// Envelope `kind`s with a dedicated role. Others still become
+/// [`MessageRole::Other`] messages, but are reported as unrecognized.
+const ENVELOPE_KINDS: &[&str] = &["Prompt", "AssistantMessage", "ToolResults"];
+
 /// Extract the session id from a `<id>.{json,jsonl,history}` path's file stem.
 fn session_id_from_path(path: &Path) -> Option<String> {
     path.file_stem()
@@ -499,7 +587,7 @@ fn parse_envelope(
         *ended_at = Some(ended_at.map_or(t, |e: i64| e.max(t)));
     }
 
-    let content_parts = data
+    let raw_parts = data
         .get("content")
         .and_then(|v| v.as_array())
         .cloned()
@@ -508,9 +596,10 @@ fn parse_envelope(
     let mut text_chunks: Vec<String> = Vec::new();
     let mut tool_calls: Vec<ToolCall> = Vec::new();
     let mut tool_results: Vec<ToolResult> = Vec::new();
+    let mut attachments: Vec<Attachment> = Vec::new();
     let mut author: Option<String> = None;
 
-    for part in &content_parts {
+    for part in &raw_parts {
         let Some(part_kind) = part.get("kind").and_then(|v| v.as_str()) else {
             continue;
         };
@@ -532,31 +621,24 @@ fn parse_envelope(
                 {
                     author = Some(m.to_string());
                 }
-                // Reasoning text is preserved (kept distinct from prose by the
-                // round-trip via the `extra` bag below).
-                if let Some(s) = pdata.get("text").and_then(|v| v.as_str())
-                    && !s.trim().is_empty()
-                {
-                    text_chunks.push(s.to_string());
-                }
             }
             // `toolUse` → `{ toolUseId, name, input }`.
             "toolUse" => {
-                tool_calls.push(ToolCall {
-                    id: pdata
+                tool_calls.push(ToolCall::new(
+                    pdata
                         .get("toolUseId")
                         .and_then(|v| v.as_str())
                         .map(ToString::to_string),
-                    name: pdata
+                    pdata
                         .get("name")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: pdata
+                    pdata
                         .get("input")
                         .cloned()
                         .unwrap_or(serde_json::Value::Null),
-                });
+                ));
             }
             // `toolResult` → `{ toolUseId, content: [...], status }`.
             "toolResult" => {
@@ -573,25 +655,58 @@ fn parse_envelope(
                         .unwrap_or(false),
                 });
             }
+            // `image` → `{ format, source: { bytes | url } }`.
+            "image" => attachments.extend(parse_attachment(part)),
             _ => {}
         }
     }
 
-    if text_chunks.is_empty() && tool_calls.is_empty() && tool_results.is_empty() {
+    // Reasoning (text, signature, redacted payload) is collected separately.
+    let reasoning = data
+        .get("content")
+        .map(extract_reasoning)
+        .unwrap_or_default();
+
+    if text_chunks.is_empty()
+        && reasoning.is_empty()
+        && tool_calls.is_empty()
+        && tool_results.is_empty()
+        && attachments.is_empty()
+    {
         return None;
     }
 
+    let parts = data
+        .get("content")
+        .map(|v| {
+            content_parts(
+                v,
+                reasoning.len(),
+                tool_calls.len(),
+                tool_results.len(),
+                attachments.len(),
+            )
+        })
+        .unwrap_or_default();
+
     Some(CanonicalMessage {
         idx: 0,
         role,
         content: text_chunks.join("\n\n"),
+        parts,
         timestamp,
         author: author.or_else(|| match kind {
             "Prompt" => Some("user".to_string()),
             _ => None,
         }),
+        reasoning,
         tool_calls,
         tool_results,
+        attachments,
+        usage: data
+            .get("usage")
+            .or_else(|| data.pointer("/meta/usage"))
+            .and_then(parse_usage),
         // Preserve the full envelope for high-fidelity round-trip (the nested
         // `results` map on ToolResults can't be reconstructed from the
         // canonical fields alone).
@@ -730,29 +845,57 @@ fn message_to_envelope(msg: &CanonicalMessage) -> Option<serde_json::Value> {
         MessageRole::Other(_) => "AssistantMessage",
     };
 
-    let mut content: Vec<serde_json::Value> = Vec::new();
-    if !msg.content.is_empty() {
-        content.push(serde_json::json!({ "kind": "text", "data": msg.content }));
-    }
-    for tc in &msg.tool_calls {
-        content.push(serde_json::json!({
+    let text_part = |text: &str| serde_json::json!({ "kind": "text", "data": text });
+    let tool_use_part = |tc: &ToolCall| {
+        serde_json::json!({
             "kind": "toolUse",
             "data": {
                 "toolUseId": tc.id.clone().unwrap_or_default(),
                 "name": tc.name,
                 "input": tc.arguments,
             }
-        }));
-    }
-    for tr in &msg.tool_results {
-        content.push(serde_json::json!({
+        })
+    };
+    let tool_result_part = |tr: &ToolResult| {
+        serde_json::json!({
             "kind": "toolResult",
             "data": {
                 "toolUseId": tr.call_id.clone().unwrap_or_default(),
                 "content": [{ "kind": "text", "data": tr.content }],
                 "status": if tr.is_error { "error" } else { "success" },
             }
-        }));
+        })
+    };
+
+    let mut content: Vec<serde_json::Value> = Vec::new();
+    if let Some(parts) = msg.ordered_parts("\n\n") {
+        // Keep the source's interleaving.
+        for part in parts {
+            match part {
+                ContentPart::Text { text } if !text.is_empty() => content.push(text_part(text)),
+                ContentPart::Thinking { index } => {
+                    content.push(thinking_part(&msg.reasoning[*index]));
+                }
+                ContentPart::ToolCall { index } => {
+                    content.push(tool_use_part(&msg.tool_calls[*index]));
+                }
+                ContentPart::ToolResult { index } => {
+                    content.push(tool_result_part(&msg.tool_results[*index]));
+                }
+                ContentPart::Attachment { index } => {
+                    content.push(image_part(&msg.attachments[*index]));
+                }
+                _ => {}
+            }
+        }
+    } else {
+        content.extend(msg.reasoning.iter().map(thinking_part));
+        if !msg.content.is_empty() {
+            content.push(text_part(&msg.content));
+        }
+        content.extend(msg.tool_calls.iter().map(tool_use_part));
+        content.extend(msg.tool_results.iter().map(tool_result_part));
+        content.extend(msg.attachments.iter().map(image_part));
     }
 
     if content.is_empty() {
@@ -776,6 +919,41 @@ fn message_to_envelope(msg: &CanonicalMessage) -> Option<serde_json::Value> {
     }))
 }
 
+/// Render reasoning as a Kiro `thinking` part.
+fn thinking_part(reasoning: &Reasoning) -> serde_json::Value {
+    let mut data = serde_json::Map::new();
+    if reasoning.redacted {
+        data.insert("redactedContent".into(), reasoning.signature.clone().into());
+    } else {
+        data.insert("text".into(), reasoning.text.clone().into());
+        if let Some(signature) = &reasoning.signature {
+            data.insert("signature".into(), signature.clone().into());
+        }
+    }
+    if let Some(model) = &reasoning.model {
+        data.insert("modelId".into(), model.clone().into());
+    }
+    serde_json::json!({ "kind": "thinking", "data": data })
+}
+
+/// Render an attachment as a Kiro `image` part: inline bytes when available,
+/// otherwise a `source.url` reference.
+fn image_part(attachment: &Attachment) -> serde_json::Value {
+    let format = attachment
+        .media_type
+        .as_deref()
+        .and_then(|m| m.strip_prefix("image/"))
+        .unwrap_or("png");
+    let source = match attachment.base64_data() {
+        Some(data) => serde_json::json!({ "bytes": data }),
+        None => serde_json::json!({ "url": attachment.url() }),
+    };
+    serde_json::json!({
+        "kind": "image",
+        "data": { "format": format, "source": source },
+    })
+}
+
 #[cfg(test)]
 mod tests {
     // NOTE: `src/lib.rs` declares `#![forbid(unsafe_code)]`, so these in-crate
@@ -938,28 +1116,36 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "Hi there".into(),
+            parts: Vec::new(),
             timestamp: Some(1_700_000_000_000),
             author: Some("user".into()),
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
         let assistant = CanonicalMessage {
             idx: 1,
             role: MessageRole::Assistant,
             content: "Hello back".into(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
-            tool_calls: vec![ToolCall {
-                id: Some("t1".into()),
-                name: "shell".into(),
-                arguments: serde_json::json!({"command": "ls"}),
-            }],
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("t1".into()),
+                "shell".into(),
+                serde_json::json!({"command": "ls"}),
+            )],
             tool_results: vec![ToolResult {
                 call_id: Some("t1".into()),
                 content: "file.txt".into(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::Value::Null,
         };
 
@@ -982,6 +1168,45 @@ mod tests {
         assert_eq!(ra.tool_results[0].content, "file.txt");
     }
 
+    #[test]
+    fn synthesized_envelopes_carry_image_parts() {
+        let user = CanonicalMessage {
+            idx: 0,
+            role: MessageRole::User,
+            content: String::new(),
+            parts: Vec::new(),
+            timestamp: None,
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            attachments: vec![
+                crate::model::Attachment::inline(
+                    Some("image/jpeg".into()),
+                    "AAAA",
+                    serde_json::Value::Null,
+                ),
+                crate::model::attachment_from_url(
+                    None,
+                    "https://example.com/a.png",
+                    serde_json::Value::Null,
+                ),
+            ],
+            usage: None,
+            extra: serde_json::Value::Null,
+        };
+
+        let env = message_to_envelope(&user).expect("image-only message still emits");
+        assert_eq!(env["data"]["content"][0]["kind"], "image");
+        assert_eq!(env["data"]["content"][0]["data"]["format"], "jpeg");
+
+        let mut ended = None;
+        let parsed = parse_envelope(&env, &mut ended).expect("re-parse image envelope");
+        assert_eq!(parsed.attachments.len(), 2);
+        assert_eq!(parsed.attachments[0].base64_data(), Some("AAAA"));
+        assert_eq!(parsed.attachments[1].url(), "https://example.com/a.png");
+    }
+
     // -----------------------------------------------------------------------
     // Robustness
     // -----------------------------------------------------------------------
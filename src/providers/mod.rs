@@ -25,13 +25,84 @@ pub mod vibe;
 use std::path::{Path, PathBuf};
 
 use crate::discovery::DetectionResult;
-use crate::model::CanonicalSession;
+use crate::model::{CanonicalMessage, CanonicalSession, Reasoning};
+
+/// Which slice of a session's on-disk history a reader should return.
+///
+/// Agents that compact their context (Codex `compacted`, Claude Code
+/// `/compact`) keep the pre-compaction transcript on disk even though it is
+/// no longer in the live context window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryMode {
+    /// Only what the source agent still has in context: the latest compaction
+    /// summary plus everything after it. Default for cross-agent handoff.
+    #[default]
+    Live,
+    /// The full on-disk archive, including turns before every compaction.
+    Full,
+}
+
+impl HistoryMode {
+    /// Stable label recorded in session metadata (`"live"` / `"full"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryMode::Live => "live",
+            HistoryMode::Full => "full",
+        }
+    }
+}
+
+/// Options controlling how a session is read from disk.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Live post-compaction context or the full archive.
+    pub history: HistoryMode,
+}
 
 /// Options controlling how a session is written to disk.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct WriteOptions {
     /// Overwrite existing session file (creates `.bak` backup).
     pub force: bool,
+    /// Write under this session ID instead of a fresh one (in-place edits).
+    /// Writers that can't choose their IDs ignore it; callers compare it with
+    /// [`WrittenSession::session_id`].
+    pub session_id: Option<String>,
+}
+
+/// Append JSONL `lines` to the file at `path` for
+/// [`Provider::append_messages`]. The existing bytes are kept as they are (a
+/// missing final newline is added) and the file is replaced atomically, with
+/// the original set aside as `.bak`. The `.bak` left by an earlier append is
+/// replaced rather than joined by `.bak.1`, `.bak.2`, …, so repeated
+/// `sync-back` and `--append-to` runs keep only the state before the latest.
+pub(crate) fn append_jsonl_lines(
+    path: &Path,
+    lines: &[String],
+    provider_slug: &str,
+) -> anyhow::Result<crate::pipeline::AtomicWriteOutcome> {
+    let mut bak = path.as_os_str().to_os_string();
+    bak.push(".bak");
+    match std::fs::remove_file(&bak) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => anyhow::bail!("failed to replace {}: {e}", Path::new(&bak).display()),
+    }
+    let mut content = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+    if !content.is_empty() && !content.ends_with(b"\n") {
+        content.push(b'\n');
+    }
+    for line in lines {
+        content.extend_from_slice(line.as_bytes());
+        content.push(b'\n');
+    }
+    Ok(crate::pipeline::atomic_write(
+        path,
+        &content,
+        true,
+        provider_slug,
+    )?)
 }
 
 /// Describes the files produced by a successful write operation.
@@ -47,6 +118,58 @@ pub struct WrittenSession {
     pub backup_path: Option<PathBuf>,
 }
 
+/// What read-back verification may expect a provider's format to preserve.
+///
+/// Every writer must round-trip message count, roles, and content exactly.
+/// Beyond that, formats differ: some flatten tool calls into text, regenerate
+/// call ids, store timestamps at second resolution, or have nowhere to keep a
+/// title. Providers declare those losses so verification can hold them to
+/// everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyTolerance {
+    /// Tool call names and arguments read back unchanged.
+    pub tool_calls: bool,
+    /// Tool call ids, and the `call_id` of results, read back unchanged.
+    pub tool_call_ids: bool,
+    /// Tool results read back with their call pairing and `is_error` flag.
+    pub tool_results: bool,
+    /// Largest accepted timestamp drift in milliseconds; `None` when the
+    /// format does not store per-message timestamps.
+    pub timestamp_drift_ms: Option<i64>,
+    /// The workspace path reads back unchanged.
+    pub workspace: bool,
+    /// The title reads back unchanged.
+    pub title: bool,
+}
+
+impl VerifyTolerance {
+    /// Everything round-trips; timestamps may drift by a second.
+    pub const STRICT: Self = Self {
+        tool_calls: true,
+        tool_call_ids: true,
+        tool_results: true,
+        timestamp_drift_ms: Some(1_000),
+        workspace: true,
+        title: true,
+    };
+
+    /// Only the always-checked message fields round-trip.
+    pub const CONTENT_ONLY: Self = Self {
+        tool_calls: false,
+        tool_call_ids: false,
+        tool_results: false,
+        timestamp_drift_ms: None,
+        workspace: false,
+        title: false,
+    };
+}
+
+impl Default for VerifyTolerance {
+    fn default() -> Self {
+        Self::STRICT
+    }
+}
+
 /// The core abstraction each provider implements.
 ///
 /// Object-safe so we can store `Box<dyn Provider>` in the registry.
@@ -72,6 +195,19 @@ pub trait Provider: Send + Sync {
     /// Read a session from its native format into canonical IR.
     fn read_session(&self, path: &Path) -> anyhow::Result<CanonicalSession>;
 
+    /// Read a session honoring [`ReadOptions`].
+    ///
+    /// Providers without compaction semantics ignore the options; the default
+    /// simply delegates to [`Provider::read_session`].
+    fn read_session_with(
+        &self,
+        path: &Path,
+        opts: &ReadOptions,
+    ) -> anyhow::Result<CanonicalSession> {
+        let _ = opts;
+        self.read_session(path)
+    }
+
     /// Write a canonical session into this provider's native format.
     fn write_session(
         &self,
@@ -82,6 +218,71 @@ pub trait Provider: Send + Sync {
     /// Build the shell command to resume a session with this provider.
     fn resume_command(&self, session_id: &str) -> String;
 
+    /// Whether [`Provider::write_session`] can create sessions at all.
+    /// Read-only providers are skipped as conversion targets by `selftest`.
+    fn writable(&self) -> bool {
+        true
+    }
+
+    /// Whether `casr edit --in-place` can rewrite a session under its own
+    /// ID: the writer honors [`WriteOptions::session_id`] and the session
+    /// lives in its own file, so the original can be set aside as `.bak`.
+    /// Database-backed providers default to `false`.
+    fn edits_in_place(&self) -> bool {
+        self.writable() && self.database_files().is_empty()
+    }
+
+    /// Whether [`Provider::append_messages`] can add turns to an existing
+    /// session: its format is an append-only log of records.
+    fn appends_messages(&self) -> bool {
+        false
+    }
+
+    /// Append `messages` to the session at `path` (read as `session`) for
+    /// `casr sync-back`, leaving the records already there byte-for-byte
+    /// intact. The file is replaced atomically and the original is kept as
+    /// the returned `.bak`.
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        let _ = (path, session, messages);
+        anyhow::bail!("{} sessions can't be appended to", self.name())
+    }
+
+    /// Whether the native format can carry message attachments (images,
+    /// files). When `false`, the pipeline folds each attachment into a text
+    /// placeholder before writing.
+    fn supports_attachments(&self) -> bool {
+        false
+    }
+
+    /// Whether the native format can store `reasoning` on `msg` so that it
+    /// reads back unchanged. When `false` and reasoning is kept, the pipeline
+    /// folds it into the message text before writing.
+    fn carries_reasoning(&self, msg: &CanonicalMessage, reasoning: &Reasoning) -> bool {
+        let _ = (msg, reasoning);
+        false
+    }
+
+    /// SQLite databases that back this provider's sessions, checked by
+    /// `casr doctor`. Empty for file-based providers.
+    fn database_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Every directory or database file this provider keeps sessions in,
+    /// including ones that don't exist yet. `casr restore` refuses to write
+    /// anywhere else. Defaults to the existing [`Provider::session_roots`]
+    /// and [`Provider::database_files`].
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        let mut locations = self.session_roots();
+        locations.extend(self.database_files());
+        locations
+    }
+
     /// Enumerate all discoverable sessions for this provider.
     ///
     /// Returns `Some(vec)` of `(session_id, path)` pairs when the provider
@@ -91,4 +292,10 @@ pub trait Provider: Send + Sync {
     fn list_sessions(&self) -> Option<Vec<(String, PathBuf)>> {
         None
     }
+
+    /// What read-back verification may expect this provider's writer and
+    /// reader to preserve. Defaults to [`VerifyTolerance::STRICT`].
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        VerifyTolerance::STRICT
+    }
 }
@@ -16,7 +16,10 @@
 //! Content is an **array of blocks**:
 //! - `{"type":"text","text":"..."}` — text content
 //! - `{"type":"toolCall","name":"...","arguments":{...}}` — tool invocations
-//! - `{"type":"thinking","text":"..."}` — chain-of-thought
+//! - `{"type":"thinking","text":"..."}` — chain-of-thought (→ reasoning)
+//! - `{"type":"image","data":"<base64>","mimeType":"..."}` — images (→ attachments)
+//!
+//! Assistant messages may carry a Pi-style `usage` object (→ usage).
 //!
 //! ## Session ID scheme
 //!
@@ -29,10 +32,11 @@ use tracing::{debug, info, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, normalize_role, parse_timestamp,
-    reindex_messages, truncate_title,
+    Attachment, CanonicalMessage, CanonicalSession, MessageRole, Reasoning, ToolCall,
+    content_parts, extract_attachments, extract_reasoning, normalize_role, parse_timestamp,
+    parse_usage, reindex_messages, sum_message_usage, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// OpenClaw provider implementation.
 pub struct OpenClaw;
@@ -41,10 +45,10 @@ impl OpenClaw {
     /// Root directory for OpenClaw session storage.
     /// Respects `OPENCLAW_HOME` env var override.
     fn home_dir() -> PathBuf {
-        if let Ok(home) = std::env::var("OPENCLAW_HOME") {
+        if let Ok(home) = crate::homes::env_var("OPENCLAW_HOME") {
             return PathBuf::from(home);
         }
-        dirs::home_dir()
+        crate::homes::home_dir()
             .unwrap_or_default()
             .join(".openclaw")
             .join("agents")
@@ -55,7 +59,7 @@ impl OpenClaw {
     /// Flatten OpenClaw content blocks into a single string.
     ///
     /// Content can be a plain string or an array of typed blocks (text,
-    /// toolCall, thinking).
+    /// toolCall). Thinking blocks are collected separately as reasoning.
     fn flatten_content(content: &serde_json::Value) -> String {
         match content {
             serde_json::Value::String(s) => s.clone(),
@@ -73,9 +77,7 @@ impl OpenClaw {
                                     .unwrap_or("tool_call");
                                 Some(format!("[tool: {name}]"))
                             }
-                            "thinking" => {
-                                block.get("text").and_then(|t| t.as_str()).map(String::from)
-                            }
+                            "thinking" => None,
                             _ => block.get("text").and_then(|t| t.as_str()).map(String::from),
                         }
                     })
@@ -86,7 +88,98 @@ impl OpenClaw {
         }
     }
 
+    /// Render reasoning as an OpenClaw `thinking` block.
+    fn thinking_block(reasoning: &Reasoning) -> serde_json::Value {
+        let mut block = serde_json::json!({ "type": "thinking", "text": reasoning.text });
+        if let Some(signature) = &reasoning.signature {
+            block["signature"] = serde_json::Value::String(signature.clone());
+        }
+        block
+    }
+
+    /// Render an attachment as an OpenClaw `image` block.
+    fn image_block(attachment: &Attachment) -> serde_json::Value {
+        match attachment.base64_data() {
+            Some(data) => serde_json::json!({
+                "type": "image",
+                "data": data,
+                "mimeType": attachment.media_type.as_deref().unwrap_or("image/png"),
+            }),
+            None => serde_json::json!({
+                "type": "image",
+                "url": attachment.url(),
+                "mimeType": attachment.media_type,
+            }),
+        }
+    }
+
     /// Extract tool calls from an OpenClaw content block array.
+    /// The `message` entry for one canonical message, with id `m{number}`.
+    fn message_entry(msg: &CanonicalMessage, number: usize) -> serde_json::Value {
+        let role_str = match &msg.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+            MessageRole::Tool => "tool",
+            MessageRole::Other(r) => r.as_str(),
+        };
+
+        // Build content blocks array, reasoning first.
+        let mut blocks: Vec<serde_json::Value> =
+            msg.reasoning.iter().map(Self::thinking_block).collect();
+
+        // Main text content.
+        if !msg.content.is_empty() {
+            blocks.push(serde_json::json!({
+                "type": "text",
+                "text": msg.content,
+            }));
+        }
+
+        // Tool call blocks.
+        for tc in &msg.tool_calls {
+            blocks.push(serde_json::json!({
+                "type": "toolCall",
+                "id": tc.id.as_deref().unwrap_or(""),
+                "name": tc.name,
+                "arguments": tc.arguments,
+            }));
+        }
+
+        // Image blocks.
+        blocks.extend(msg.attachments.iter().map(Self::image_block));
+
+        let content: serde_json::Value = if blocks.len() == 1
+            && blocks[0].get("type").and_then(|t| t.as_str()) == Some("text")
+        {
+            // Single text block — use plain string for compactness.
+            serde_json::Value::String(msg.content.clone())
+        } else {
+            serde_json::Value::Array(blocks)
+        };
+
+        let mut inner = serde_json::json!({
+            "role": role_str,
+            "content": content,
+        });
+        if let Some(ref author) = msg.author {
+            inner["model"] = serde_json::Value::String(author.clone());
+        }
+
+        let ts_str = msg
+            .timestamp
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+        serde_json::json!({
+            "type": "message",
+            "id": format!("m{number}"),
+            "timestamp": ts_str,
+            "message": inner,
+        })
+    }
+
     fn extract_tool_calls(content: &serde_json::Value) -> Vec<ToolCall> {
         let Some(arr) = content.as_array() else {
             return vec![];
@@ -96,18 +189,18 @@ impl OpenClaw {
                 if block.get("type").and_then(|t| t.as_str()) != Some("toolCall") {
                     return None;
                 }
-                Some(ToolCall {
-                    id: block.get("id").and_then(|v| v.as_str()).map(String::from),
-                    name: block
+                Some(ToolCall::new(
+                    block.get("id").and_then(|v| v.as_str()).map(String::from),
+                    block
                         .get("name")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: block
+                    block
                         .get("arguments")
                         .cloned()
                         .unwrap_or(serde_json::Value::Null),
-                })
+                ))
             })
             .collect()
     }
@@ -162,6 +255,10 @@ impl Provider for OpenClaw {
         if root.is_dir() { vec![root] } else { vec![] }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        vec![Self::home_dir()]
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let root = Self::home_dir();
         if !root.is_dir() {
@@ -219,6 +316,7 @@ impl Provider for OpenClaw {
         let mut started_at: Option<i64> = None;
         let mut ended_at: Option<i64> = None;
         let mut session_cwd: Option<String> = None;
+        let mut lineage: Option<serde_json::Value> = None;
         let mut model_name: Option<String> = None;
 
         for line_result in reader.lines() {
@@ -240,6 +338,7 @@ impl Provider for OpenClaw {
             match line_type {
                 "session" => {
                     session_cwd = val.get("cwd").and_then(|v| v.as_str()).map(String::from);
+                    lineage = val.get(crate::lineage::METADATA_KEY).cloned();
                     if let Some(ts) = val.get("timestamp").and_then(parse_timestamp) {
                         started_at = Some(ts);
                     }
@@ -258,14 +357,27 @@ impl Provider for OpenClaw {
 
                     let content_val = msg.get("content");
                     let content = content_val.map(Self::flatten_content).unwrap_or_default();
+                    let attachments = content_val.map(extract_attachments).unwrap_or_default();
+                    let mut reasoning = content_val.map(extract_reasoning).unwrap_or_default();
 
-                    if content.trim().is_empty() {
+                    if content.trim().is_empty() && attachments.is_empty() && reasoning.is_empty() {
                         continue;
                     }
 
                     let tool_calls = content_val
                         .map(Self::extract_tool_calls)
                         .unwrap_or_default();
+                    let parts = content_val
+                        .map(|v| {
+                            content_parts(
+                                v,
+                                reasoning.len(),
+                                tool_calls.len(),
+                                0,
+                                attachments.len(),
+                            )
+                        })
+                        .unwrap_or_default();
 
                     // Timestamps on wrapper or inner message.
                     let ts = val
@@ -286,14 +398,22 @@ impl Provider for OpenClaw {
                         model_name = author.clone();
                     }
 
+                    for r in &mut reasoning {
+                        r.model = author.clone();
+                    }
+
                     messages.push(CanonicalMessage {
                         idx: 0,
                         role,
                         content,
+                        parts,
                         timestamp: ts,
                         author,
+                        reasoning,
                         tool_calls,
                         tool_results: vec![],
+                        attachments,
+                        usage: msg.get("usage").and_then(parse_usage),
                         extra: val,
                     });
                 }
@@ -308,6 +428,7 @@ impl Provider for OpenClaw {
         }
 
         reindex_messages(&mut messages);
+        let usage = sum_message_usage(&messages);
 
         let session_id = path
             .file_stem()
@@ -322,10 +443,13 @@ impl Provider for OpenClaw {
 
         let workspace = session_cwd.as_ref().map(PathBuf::from);
 
-        let metadata = serde_json::json!({
+        let mut metadata = serde_json::json!({
             "source": "openclaw",
             "cwd": session_cwd,
         });
+        if let Some(metadata) = metadata.as_object_mut() {
+            crate::lineage::restore(metadata, lineage.as_ref());
+        }
 
         info!(
             session_id,
@@ -344,6 +468,8 @@ impl Provider for OpenClaw {
             metadata,
             source_path: path.to_path_buf(),
             model_name,
+            usage,
+            unrecognized: None,
         })
     }
 
@@ -376,7 +502,7 @@ impl Provider for OpenClaw {
             .as_ref()
             .and_then(|w| w.to_str())
             .unwrap_or("/tmp");
-        let header = serde_json::json!({
+        let mut header = serde_json::json!({
             "type": "session",
             "id": session_id,
             "timestamp": session.started_at
@@ -386,69 +512,14 @@ impl Provider for OpenClaw {
             "cwd": workspace,
             "version": "0.1.0",
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            header[crate::lineage::METADATA_KEY] = lineage;
+        }
         lines.push(serde_json::to_string(&header)?);
 
         // Messages.
         for (i, msg) in session.messages.iter().enumerate() {
-            let role_str = match &msg.role {
-                MessageRole::User => "user",
-                MessageRole::Assistant => "assistant",
-                MessageRole::System => "system",
-                MessageRole::Tool => "tool",
-                MessageRole::Other(r) => r.as_str(),
-            };
-
-            // Build content blocks array.
-            let mut blocks = Vec::new();
-
-            // Main text content.
-            if !msg.content.is_empty() {
-                blocks.push(serde_json::json!({
-                    "type": "text",
-                    "text": msg.content,
-                }));
-            }
-
-            // Tool call blocks.
-            for tc in &msg.tool_calls {
-                blocks.push(serde_json::json!({
-                    "type": "toolCall",
-                    "id": tc.id.as_deref().unwrap_or(""),
-                    "name": tc.name,
-                    "arguments": tc.arguments,
-                }));
-            }
-
-            let content: serde_json::Value = if blocks.len() == 1
-                && blocks[0].get("type").and_then(|t| t.as_str()) == Some("text")
-            {
-                // Single text block — use plain string for compactness.
-                serde_json::Value::String(msg.content.clone())
-            } else {
-                serde_json::Value::Array(blocks)
-            };
-
-            let mut inner = serde_json::json!({
-                "role": role_str,
-                "content": content,
-            });
-            if let Some(ref author) = msg.author {
-                inner["model"] = serde_json::Value::String(author.clone());
-            }
-
-            let ts_str = msg
-                .timestamp
-                .and_then(chrono::DateTime::from_timestamp_millis)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
-
-            let entry = serde_json::json!({
-                "type": "message",
-                "id": format!("m{}", i + 1),
-                "timestamp": ts_str,
-                "message": inner,
-            });
-            lines.push(serde_json::to_string(&entry)?);
+            lines.push(serde_json::to_string(&Self::message_entry(msg, i + 1))?);
         }
 
         let file_content = lines.join("\n") + "\n";
@@ -474,9 +545,65 @@ impl Provider for OpenClaw {
         })
     }
 
+    fn appends_messages(&self) -> bool {
+        true
+    }
+
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        // Continue the `m1`, `m2`, … numbering of the entries already there.
+        let existing = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+        let numbered = existing
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|entry| entry.get("type").and_then(|t| t.as_str()) == Some("message"))
+            .count();
+        let lines = messages
+            .iter()
+            .enumerate()
+            .map(|(i, msg)| serde_json::to_string(&Self::message_entry(msg, numbered + i + 1)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outcome = super::append_jsonl_lines(path, &lines, self.slug())?;
+        info!(
+            session_id = session.session_id,
+            path = %outcome.target_path.display(),
+            messages = messages.len(),
+            "OpenClaw session appended"
+        );
+
+        Ok(WrittenSession {
+            paths: vec![outcome.target_path],
+            session_id: session.session_id.clone(),
+            resume_command: self.resume_command(&session.session_id),
+            backup_path: outcome.backup_path,
+        })
+    }
+
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Tool results are folded into text, and titles are re-derived on read.
+        VerifyTolerance {
+            tool_results: false,
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("openclaw --resume {session_id}")
     }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
+    fn carries_reasoning(&self, _msg: &CanonicalMessage, reasoning: &Reasoning) -> bool {
+        !reasoning.redacted
+    }
 }
 
 #[cfg(test)]
@@ -577,8 +704,9 @@ mod tests {
         ]);
 
         assert_eq!(session.messages.len(), 1);
-        assert!(session.messages[0].content.contains("Let me reason..."));
-        assert!(session.messages[0].content.contains("Here's my answer."));
+        assert_eq!(session.messages[0].content, "Here's my answer.");
+        assert_eq!(session.messages[0].reasoning.len(), 1);
+        assert_eq!(session.messages[0].reasoning[0].text, "Let me reason...");
     }
 
     #[test]
@@ -768,26 +896,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix the bug".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "I'll fix it now.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_500_000),
                     author: Some("claude-3-opus".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({"source": "claude-code"}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let readback = write_and_read_back(&original);
@@ -819,19 +957,25 @@ mod tests {
                 idx: 0,
                 role: MessageRole::Assistant,
                 content: "Let me check.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
-                tool_calls: vec![ToolCall {
-                    id: Some("tc1".to_string()),
-                    name: "read_file".to_string(),
-                    arguments: json!({"path": "/test.rs"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    Some("tc1".to_string()),
+                    "read_file".to_string(),
+                    json!({"path": "/test.rs"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: json!({}),
             }],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let readback = write_and_read_back(&original);
@@ -2,7 +2,7 @@
 //!
 //! OpenCode stores session state in a SQLite database named `opencode.db`.
 //! The canonical schema includes:
-//! - `sessions` table
+//! - `sessions` table (also holds the session's token and cost totals)
 //! - `messages` table
 //! - `files` table
 //!
@@ -19,16 +19,20 @@ use tracing::{debug, info, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult, flatten_content,
-    normalize_role, parse_timestamp, reindex_messages, truncate_title,
+    CanonicalMessage, CanonicalSession, MessageRole, Reasoning, ToolCall, ToolResult,
+    flatten_content, normalize_role, parse_timestamp, parse_usage, reindex_messages,
+    truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// OpenCode provider implementation.
 pub struct OpenCode;
 
 const DB_FILENAME: &str = "opencode.db";
 const DATA_DIRNAME: &str = ".opencode";
+/// Side table for casr's conversion lineage: OpenCode's own schema has no
+/// per-session metadata slot, and it ignores tables it doesn't know.
+const LINEAGE_TABLE: &str = "casr_lineage";
 
 impl OpenCode {
     /// Parse OPENCODE environment overrides into a target DB path.
@@ -37,13 +41,13 @@ impl OpenCode {
     /// - `OPENCODE_DB_PATH` (direct file path)
     /// - `OPENCODE_HOME` (directory containing `opencode.db`, or a direct `.db` path)
     fn env_db_path() -> Option<PathBuf> {
-        if let Ok(path) = std::env::var("OPENCODE_DB_PATH")
+        if let Ok(path) = crate::homes::env_var("OPENCODE_DB_PATH")
             && !path.trim().is_empty()
         {
             return Some(PathBuf::from(path));
         }
 
-        if let Ok(home) = std::env::var("OPENCODE_HOME")
+        if let Ok(home) = crate::homes::env_var("OPENCODE_HOME")
             && !home.trim().is_empty()
         {
             let home_path = PathBuf::from(home);
@@ -59,11 +63,11 @@ impl OpenCode {
     /// Candidate global config files that may contain `data.directory`.
     fn config_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::homes::home_dir() {
             paths.push(home.join(".opencode.json"));
             paths.push(home.join(".config/opencode/.opencode.json"));
         }
-        if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME")
+        if let Ok(xdg) = crate::homes::env_var("XDG_CONFIG_HOME")
             && !xdg.trim().is_empty()
         {
             paths.push(PathBuf::from(xdg).join("opencode/.opencode.json"));
@@ -116,24 +120,24 @@ impl OpenCode {
     ///
     /// If env override is set, discovery is constrained to that location.
     fn find_db_files() -> Vec<PathBuf> {
+        dedup_existing_files(Self::db_candidates())
+    }
+
+    /// Where a database may live, whether or not it exists.
+    fn db_candidates() -> Vec<PathBuf> {
         if let Some(env_db) = Self::env_db_path() {
-            return if env_db.is_file() {
-                vec![env_db]
-            } else {
-                Vec::new()
-            };
+            return vec![env_db];
         }
 
         let mut candidates = Vec::new();
         candidates.extend(Self::cwd_ancestor_db_paths());
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::homes::home_dir() {
             candidates.push(home.join(DATA_DIRNAME).join(DB_FILENAME));
         }
         for data_dir in Self::configured_data_dirs() {
             candidates.push(data_dir.join(DB_FILENAME));
         }
-
-        dedup_existing_files(candidates)
+        candidates
     }
 
     /// Resolve target DB path for writes.
@@ -289,6 +293,21 @@ CREATE INDEX IF NOT EXISTS idx_files_session_id ON files (session_id);
         .ok()
     }
 
+    /// casr's conversion lineage for `session_id`, if recorded.
+    fn read_lineage(conn: &Connection, session_id: &str) -> Option<serde_json::Value> {
+        if !Self::table_exists(conn, LINEAGE_TABLE) {
+            return None;
+        }
+        let raw: String = conn
+            .query_row(
+                &format!("SELECT lineage FROM {LINEAGE_TABLE} WHERE session_id = ?1"),
+                rusqlite::params![session_id],
+                |row| row.get(0),
+            )
+            .ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
     fn workspace_from_db_path(db_path: &Path) -> Option<PathBuf> {
         let data_dir = db_path.parent()?;
         if data_dir.file_name().and_then(|n| n.to_str()) == Some(DATA_DIRNAME) {
@@ -391,6 +410,7 @@ CREATE INDEX IF NOT EXISTS idx_files_session_id ON files (session_id);
             let raw_parts = serde_json::from_str::<serde_json::Value>(&parts_json)
                 .unwrap_or_else(|_| serde_json::json!([]));
             let (content, tool_calls, tool_results) = parse_parts(&raw_parts);
+            let reasoning = parse_reasoning_parts(&raw_parts, &content, model.as_deref());
 
             if let Some(model_name) = model.as_deref().filter(|m| !m.is_empty()) {
                 *model_counts.entry(model_name.to_string()).or_insert(0) += 1;
@@ -400,10 +420,14 @@ CREATE INDEX IF NOT EXISTS idx_files_session_id ON files (session_id);
                 idx: 0,
                 role: normalize_role(&role_raw),
                 content,
+                parts: Vec::new(),
                 timestamp,
                 author: model.clone(),
+                reasoning,
                 tool_calls,
                 tool_results,
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::json!({
                     "opencode_message_id": message_id,
                     "opencode_parts": raw_parts,
@@ -429,6 +453,16 @@ CREATE INDEX IF NOT EXISTS idx_files_session_id ON files (session_id);
             .map(|(name, _)| name);
 
         let source = Self::virtual_session_path(db_path, session_id);
+        let mut metadata = serde_json::json!({
+            "opencode_db": db_path.display().to_string(),
+            "parent_session_id": parent_session_id,
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "cost": cost,
+        });
+        if let Some(metadata) = metadata.as_object_mut() {
+            crate::lineage::restore(metadata, Self::read_lineage(conn, session_id).as_ref());
+        }
 
         Ok(CanonicalSession {
             session_id: session_id.to_string(),
@@ -438,15 +472,15 @@ CREATE INDEX IF NOT EXISTS idx_files_session_id ON files (session_id);
             started_at,
             ended_at,
             messages,
-            metadata: serde_json::json!({
-                "opencode_db": db_path.display().to_string(),
-                "parent_session_id": parent_session_id,
+            metadata,
+            source_path: source,
+            model_name,
+            usage: parse_usage(&serde_json::json!({
                 "prompt_tokens": prompt_tokens,
                 "completion_tokens": completion_tokens,
                 "cost": cost,
-            }),
-            source_path: source,
-            model_name,
+            })),
+            unrecognized: None,
         })
     }
 }
@@ -495,6 +529,14 @@ impl Provider for OpenCode {
         Self::find_db_files()
     }
 
+    fn database_files(&self) -> Vec<PathBuf> {
+        Self::find_db_files()
+    }
+
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        Self::db_candidates()
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         for db_path in Self::find_db_files() {
             let Ok(conn) = Self::open_db(&db_path) else {
@@ -573,6 +615,12 @@ impl Provider for OpenCode {
                     "DELETE FROM messages WHERE session_id = ?1",
                     rusqlite::params![target_session_id],
                 );
+                if Self::table_exists(&conn, LINEAGE_TABLE) {
+                    let _ = conn.execute(
+                        &format!("DELETE FROM {LINEAGE_TABLE} WHERE session_id = ?1"),
+                        rusqlite::params![target_session_id],
+                    );
+                }
                 conn.execute(
                     "DELETE FROM sessions WHERE id = ?1",
                     rusqlite::params![target_session_id],
@@ -601,13 +649,16 @@ impl Provider for OpenCode {
         });
         let title = title.unwrap_or_else(|| "Converted session".to_string());
 
+        let usage = session.usage.clone().unwrap_or_default();
+        let prompt_tokens = usage.input_tokens + usage.cache_read_tokens + usage.cache_write_tokens;
+
         let tx = conn.transaction().context("failed to begin transaction")?;
 
         tx.execute(
             "INSERT INTO sessions (
                 id, parent_session_id, title, message_count, prompt_tokens, completion_tokens, cost,
                 summary_message_id, updated_at, created_at
-             ) VALUES (?1, NULL, ?2, ?3, 0, 0, 0.0, NULL, ?4, ?5)",
+             ) VALUES (?1, NULL, ?2, ?3, ?4, ?5, ?6, NULL, ?7, ?8)",
             rusqlite::params![
                 target_session_id,
                 title,
@@ -616,6 +667,9 @@ impl Provider for OpenCode {
                 } else {
                     i64::try_from(session.messages.len()).unwrap_or(i64::MAX)
                 },
+                i64::try_from(prompt_tokens).unwrap_or(i64::MAX),
+                i64::try_from(usage.output_tokens).unwrap_or(i64::MAX),
+                usage.cost_usd.unwrap_or(0.0),
                 updated_at,
                 created_at,
             ],
@@ -660,6 +714,23 @@ impl Provider for OpenCode {
             .context("failed to update OpenCode session message_count")?;
         }
 
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            tx.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {LINEAGE_TABLE} (
+                    session_id TEXT PRIMARY KEY,
+                    lineage TEXT NOT NULL
+                 );"
+            ))
+            .context("failed to create casr lineage table")?;
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {LINEAGE_TABLE} (session_id, lineage) VALUES (?1, ?2)"
+                ),
+                rusqlite::params![target_session_id, lineage.to_string()],
+            )
+            .context("failed to record conversion lineage")?;
+        }
+
         tx.commit().context("failed to commit transaction")?;
 
         let virtual_path = Self::virtual_session_path(&db_path, &target_session_id);
@@ -678,6 +749,14 @@ impl Provider for OpenCode {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // The workspace is implied by the project directory, not stored per session.
+        VerifyTolerance {
+            workspace: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, _session_id: &str) -> String {
         // OpenCode has no session-id-specific resume flag.
         "opencode".to_string()
@@ -784,11 +863,7 @@ fn parse_parts(parts: &serde_json::Value) -> (String, Vec<ToolCall>, Vec<ToolRes
                     .and_then(serde_json::Value::as_str)
                     .unwrap_or_default();
 
-                tool_calls.push(ToolCall {
-                    id,
-                    name,
-                    arguments: parse_tool_call_arguments(input),
-                });
+                tool_calls.push(ToolCall::new(id, name, parse_tool_call_arguments(input)));
             }
             "tool_result" => {
                 let content = data
@@ -837,6 +912,37 @@ fn parse_parts(parts: &serde_json::Value) -> (String, Vec<ToolCall>, Vec<ToolRes
     (content, tool_calls, tool_results)
 }
 
+/// Collect `reasoning` parts as canonical reasoning, unless [`parse_parts`]
+/// already promoted them to the visible `content` (reasoning-only turns).
+fn parse_reasoning_parts(
+    parts: &serde_json::Value,
+    content: &str,
+    model: Option<&str>,
+) -> Vec<Reasoning> {
+    let chunks: Vec<&str> = parts
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("type").and_then(serde_json::Value::as_str) == Some("reasoning"))
+        .filter_map(|item| {
+            item.pointer("/data/thinking")
+                .and_then(serde_json::Value::as_str)
+        })
+        .filter(|thinking| !thinking.trim().is_empty())
+        .collect();
+    if chunks.is_empty() || chunks.join("\n") == content {
+        return Vec::new();
+    }
+    chunks
+        .into_iter()
+        .map(|text| Reasoning {
+            text: text.to_string(),
+            model: model.map(String::from),
+            ..Reasoning::default()
+        })
+        .collect()
+}
+
 fn build_parts(message: &CanonicalMessage) -> serde_json::Value {
     let mut parts = Vec::new();
 
@@ -931,34 +1037,44 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Please inspect src/main.rs".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Inspecting now.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_005_000),
                     author: Some("gpt-5".to_string()),
-                    tool_calls: vec![ToolCall {
-                        id: Some("call-1".to_string()),
-                        name: "Read".to_string(),
-                        arguments: serde_json::json!({"path":"src/main.rs"}),
-                    }],
+                    reasoning: Vec::new(),
+                    tool_calls: vec![ToolCall::new(
+                        Some("call-1".to_string()),
+                        "Read".to_string(),
+                        serde_json::json!({"path":"src/main.rs"}),
+                    )],
                     tool_results: vec![ToolResult {
                         call_id: Some("call-1".to_string()),
                         content: "Read complete".to_string(),
                         is_error: false,
                     }],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::json!({}),
                 },
             ],
             metadata: serde_json::json!({}),
             source_path: workspace.join("source.jsonl"),
             model_name: Some("gpt-5".to_string()),
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -999,7 +1115,7 @@ mod tests {
 
         let source = sample_session(&workspace);
         let written = OpenCode
-            .write_session(&source, &WriteOptions { force: false })
+            .write_session(&source, &WriteOptions::default())
             .expect("write should succeed");
 
         assert_eq!(written.resume_command, "opencode");
@@ -1043,14 +1159,14 @@ mod tests {
 
         // First write succeeds.
         let first = OpenCode
-            .write_session(&source, &WriteOptions { force: false })
+            .write_session(&source, &WriteOptions::default())
             .expect("first write should succeed");
         let db_path = first.paths[0].parent().expect("db parent").to_path_buf();
 
         // Second write WITHOUT force must be a clean conflict, not a panic or a
         // raw "failed to insert OpenCode session" error.
         let conflict = OpenCode
-            .write_session(&source, &WriteOptions { force: false })
+            .write_session(&source, &WriteOptions::default())
             .expect_err("second write without --force should conflict");
         match conflict.downcast_ref::<crate::error::CasrError>() {
             Some(crate::error::CasrError::SessionConflict { session_id, .. }) => {
@@ -1061,7 +1177,13 @@ mod tests {
 
         // Second write WITH force succeeds and overwrites in place.
         let second = OpenCode
-            .write_session(&source, &WriteOptions { force: true })
+            .write_session(
+                &source,
+                &WriteOptions {
+                    force: true,
+                    ..Default::default()
+                },
+            )
             .expect("force write should succeed");
 
         // Same stable target id both times.
@@ -1101,7 +1223,7 @@ mod tests {
 
         let source = sample_session(&workspace);
         let written = OpenCode
-            .write_session(&source, &WriteOptions { force: false })
+            .write_session(&source, &WriteOptions::default())
             .expect("write should succeed");
         let found = OpenCode.owns_session(&written.session_id);
 
@@ -1123,7 +1245,7 @@ mod tests {
         first.title = Some("Older Session".to_string());
         first.started_at = Some(1_700_000_000_000);
         let _first_written = OpenCode
-            .write_session(&first, &WriteOptions { force: false })
+            .write_session(&first, &WriteOptions::default())
             .expect("first write");
 
         let mut second = sample_session(&workspace);
@@ -1131,7 +1253,7 @@ mod tests {
         second.title = Some("Newer Session".to_string());
         second.started_at = Some(1_800_000_000_000);
         let second_written = OpenCode
-            .write_session(&second, &WriteOptions { force: false })
+            .write_session(&second, &WriteOptions::default())
             .expect("second write");
 
         let db_path = second_written
@@ -1157,7 +1279,7 @@ mod tests {
 
         let source = sample_session(&workspace);
         OpenCode
-            .write_session(&source, &WriteOptions { force: false })
+            .write_session(&source, &WriteOptions::default())
             .expect("write should succeed");
 
         let detection = OpenCode.detect();
@@ -1343,10 +1465,14 @@ mod tests {
             idx: 0,
             role: MessageRole::User,
             content: "Hello world".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::json!({}),
         };
         let parts = build_parts(&msg);
@@ -1362,18 +1488,22 @@ mod tests {
             idx: 0,
             role: MessageRole::Assistant,
             content: "Let me check.".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
-            tool_calls: vec![ToolCall {
-                id: Some("tc-1".to_string()),
-                name: "Bash".to_string(),
-                arguments: serde_json::json!({"cmd": "ls"}),
-            }],
+            reasoning: Vec::new(),
+            tool_calls: vec![ToolCall::new(
+                Some("tc-1".to_string()),
+                "Bash".to_string(),
+                serde_json::json!({"cmd": "ls"}),
+            )],
             tool_results: vec![ToolResult {
                 call_id: Some("tc-1".to_string()),
                 content: "file1.rs\nfile2.rs".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::json!({}),
         };
         let parts = build_parts(&msg);
@@ -1392,14 +1522,18 @@ mod tests {
             idx: 0,
             role: MessageRole::Tool,
             content: "  ".to_string(),
+            parts: Vec::new(),
             timestamp: None,
             author: None,
+            reasoning: Vec::new(),
             tool_calls: vec![],
             tool_results: vec![ToolResult {
                 call_id: Some("c1".to_string()),
                 content: "result".to_string(),
                 is_error: false,
             }],
+            attachments: Vec::new(),
+            usage: None,
             extra: serde_json::json!({}),
         };
         let parts = build_parts(&msg);
@@ -1460,7 +1594,7 @@ mod tests {
         session.title = None;
 
         let written = OpenCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
         let readback = OpenCode.read_session(&written.paths[0]).expect("readback");
 
@@ -1486,7 +1620,7 @@ mod tests {
         }
 
         let written = OpenCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
         let readback = OpenCode.read_session(&written.paths[0]).expect("readback");
 
@@ -1504,7 +1638,7 @@ mod tests {
 
         let session = sample_session(&workspace);
         let written = OpenCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
         let readback = OpenCode.read_session(&written.paths[0]).expect("readback");
 
@@ -1524,7 +1658,7 @@ mod tests {
 
         let session = sample_session(&workspace);
         let written = OpenCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
         let readback = OpenCode.read_session(&written.paths[0]).expect("readback");
 
@@ -1545,7 +1679,7 @@ mod tests {
 
         let session = sample_session(&workspace);
         let written = OpenCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
         let readback = OpenCode.read_session(&written.paths[0]).expect("readback");
 
@@ -1605,7 +1739,7 @@ mod tests {
         first.title = Some("First Session".to_string());
         first.started_at = Some(1_700_000_000_000);
         let first_written = OpenCode
-            .write_session(&first, &WriteOptions { force: false })
+            .write_session(&first, &WriteOptions::default())
             .expect("first write");
 
         let mut second = sample_session(&workspace);
@@ -1613,7 +1747,7 @@ mod tests {
         second.title = Some("Second Session".to_string());
         second.started_at = Some(1_800_000_000_000);
         let second_written = OpenCode
-            .write_session(&second, &WriteOptions { force: false })
+            .write_session(&second, &WriteOptions::default())
             .expect("second write");
 
         let listed = OpenCode.list_sessions().expect("should return Some");
@@ -7,7 +7,8 @@
 //!
 //! Each line has a `type` discriminator:
 //! - `"session"` — header with `id`, `timestamp`, `cwd`, `provider`, `modelId`
-//! - `"message"` — conversation message with nested `message` object
+//! - `"message"` — conversation message with nested `message` object; assistant
+//!   messages carry `usage` (`input`, `output`, `cacheRead`, `cacheWrite`, `cost`)
 //! - `"model_change"` — records model/provider switches
 //! - `"thinking_level_change"` — records thinking level changes (skipped)
 //!
@@ -19,8 +20,8 @@
 //! Content can be a plain string or an array of typed blocks:
 //! - `{"type":"text","text":"..."}` — text content
 //! - `{"type":"toolCall","name":"...","arguments":{...}}` — tool invocations
-//! - `{"type":"thinking","thinking":"..."}` — chain-of-thought
-//! - `{"type":"image",...}` — images (skipped)
+//! - `{"type":"thinking","thinking":"...","thinkingSignature":"..."}` — chain-of-thought (→ reasoning)
+//! - `{"type":"image","data":"<base64>","mimeType":"..."}` — images (→ attachments)
 //!
 //! ## Session ID scheme
 //!
@@ -34,10 +35,11 @@ use tracing::{debug, info, trace};
 
 use crate::discovery::DetectionResult;
 use crate::model::{
-    CanonicalMessage, CanonicalSession, MessageRole, ToolCall, normalize_role, parse_timestamp,
-    reindex_messages, truncate_title,
+    Attachment, CanonicalMessage, CanonicalSession, MessageRole, Reasoning, ToolCall, Usage,
+    content_parts, extract_attachments, extract_reasoning, normalize_role, parse_timestamp,
+    parse_usage, reindex_messages, sum_message_usage, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Pi-Agent provider implementation.
 pub struct PiAgent;
@@ -46,10 +48,10 @@ impl PiAgent {
     /// Root directory for Pi-Agent session storage.
     /// Respects `PI_AGENT_HOME` env var override.
     fn home_dir() -> PathBuf {
-        if let Ok(home) = std::env::var("PI_AGENT_HOME") {
+        if let Ok(home) = crate::homes::env_var("PI_AGENT_HOME") {
             return PathBuf::from(home);
         }
-        dirs::home_dir()
+        crate::homes::home_dir()
             .unwrap_or_default()
             .join(".pi")
             .join("agent")
@@ -67,8 +69,9 @@ impl PiAgent {
 
     /// Flatten Pi-Agent message content to a string.
     ///
-    /// Handles plain string content and arrays of typed blocks:
-    /// text, thinking, toolCall (image is skipped).
+    /// Handles plain string content and arrays of typed blocks: text and
+    /// toolCall. Thinking and image blocks carry no visible text; they are
+    /// collected separately as reasoning and attachments.
     fn flatten_content(content: &serde_json::Value) -> String {
         if let Some(s) = content.as_str() {
             return s.to_string();
@@ -82,10 +85,6 @@ impl PiAgent {
                         Some("text") => {
                             block.get("text").and_then(|t| t.as_str()).map(String::from)
                         }
-                        Some("thinking") => block
-                            .get("thinking")
-                            .and_then(|t| t.as_str())
-                            .map(|t| format!("[Thinking] {t}")),
                         Some("toolCall") => {
                             let name = block
                                 .get("name")
@@ -93,7 +92,6 @@ impl PiAgent {
                                 .unwrap_or("unknown");
                             Some(format!("[Tool: {name}]"))
                         }
-                        Some("image") => None,
                         _ => None,
                     }
                 })
@@ -103,7 +101,192 @@ impl PiAgent {
         String::new()
     }
 
+    /// Render reasoning as a Pi `thinking` block.
+    fn thinking_block(reasoning: &Reasoning) -> serde_json::Value {
+        let mut block = serde_json::json!({ "type": "thinking", "thinking": reasoning.text });
+        if let Some(signature) = &reasoning.signature {
+            block["thinkingSignature"] = serde_json::Value::String(signature.clone());
+        }
+        block
+    }
+
+    /// Render canonical usage as a Pi `usage` object. Only the total cost is
+    /// known, so the per-category cost breakdown is zeroed.
+    fn usage_object(usage: &Usage) -> serde_json::Value {
+        serde_json::json!({
+            "input": usage.input_tokens,
+            "output": usage.output_tokens,
+            "cacheRead": usage.cache_read_tokens,
+            "cacheWrite": usage.cache_write_tokens,
+            "totalTokens": usage.total_tokens(),
+            "cost": {
+                "input": 0,
+                "output": 0,
+                "cacheRead": 0,
+                "cacheWrite": 0,
+                "total": usage.cost_usd.unwrap_or(0.0)
+            }
+        })
+    }
+
+    /// Render an attachment as a Pi `image` block.
+    fn image_block(attachment: &Attachment) -> serde_json::Value {
+        match attachment.base64_data() {
+            Some(data) => serde_json::json!({
+                "type": "image",
+                "data": data,
+                "mimeType": attachment.media_type.as_deref().unwrap_or("image/png"),
+            }),
+            None => serde_json::json!({
+                "type": "image",
+                "url": attachment.url(),
+                "mimeType": attachment.media_type,
+            }),
+        }
+    }
+
     /// Extract tool calls from a content block array.
+    /// The `message` entry for one canonical message; `None` for messages
+    /// that would read back empty.
+    fn message_entry(msg: &CanonicalMessage) -> Option<serde_json::Value> {
+        // Skip messages that would produce empty content on read-back.
+        // Pi reader skips entries where content.trim().is_empty(), so
+        // we must ensure every written message survives the round-trip.
+        // Tool-result-only messages (empty content, no tool_calls, but
+        // with tool_results) get their content synthesized below.
+        let has_tool_data = !msg.tool_calls.is_empty() || !msg.tool_results.is_empty();
+        if msg.content.trim().is_empty()
+            && !has_tool_data
+            && msg.attachments.is_empty()
+            && msg.reasoning.is_empty()
+        {
+            return None;
+        }
+
+        let role_str = match &msg.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+            MessageRole::Tool => "toolResult",
+            MessageRole::Other(r) => r.as_str(),
+        };
+
+        // For tool-result-only messages (empty content, no tool_calls),
+        // synthesize readable content from the tool results so the Pi
+        // reader won't skip the message on read-back.
+        let effective_content = if msg.content.trim().is_empty()
+            && msg.tool_calls.is_empty()
+            && !msg.tool_results.is_empty()
+        {
+            msg.tool_results
+                .iter()
+                .map(|tr| {
+                    if tr.is_error {
+                        format!("[Tool Error] {}", tr.content)
+                    } else {
+                        format!("[Tool Output] {}", tr.content)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            msg.content.clone()
+        };
+
+        // Build content: always an array of typed blocks so Pi's JS
+        // `message.content.some(...)` never receives a plain string.
+        //
+        // We intentionally emit only a text block here — no toolCall
+        // blocks.  Pi's reader (`flatten_content`) extracts text from
+        // both "text" AND "toolCall" blocks, so emitting both would
+        // cause the read-back content to double up (e.g. "[Tool: shell]"
+        // appearing in both the text block and the toolCall block).
+        // Since the pipeline already normalises tool-call / tool-result
+        // info into `effective_content`, a single text block is both
+        // sufficient and round-trip-safe.
+        //
+        // Reasoning leads as `thinking` blocks. Image- and reasoning-only
+        // messages skip the (empty) text block.
+        let mut blocks: Vec<serde_json::Value> =
+            msg.reasoning.iter().map(Self::thinking_block).collect();
+        if !effective_content.is_empty() || (msg.attachments.is_empty() && msg.reasoning.is_empty())
+        {
+            blocks.push(serde_json::json!({
+                "type": "text",
+                "text": effective_content,
+            }));
+        }
+        blocks.extend(msg.attachments.iter().map(Self::image_block));
+        let content = serde_json::Value::Array(blocks);
+
+        let mut inner = serde_json::json!({
+            "role": role_str,
+            "content": content,
+        });
+        if let Some(ref author) = msg.author {
+            inner["model"] = serde_json::Value::String(author.clone());
+        }
+
+        // Add usage field with the full structure Pi expects.
+        // Pi's footer.js sums: usage.input, usage.output, usage.cacheRead,
+        // usage.cacheWrite, and usage.cost.total — all must be present to
+        // avoid TypeError crashes.
+        let usage = msg
+            .extra
+            .get("message")
+            .and_then(|m| m.get("usage"))
+            .or_else(|| msg.extra.get("usage"))
+            .cloned()
+            .map(|mut u| {
+                // Ensure all required fields exist even if the source
+                // usage object is incomplete.
+                let obj = u.as_object_mut();
+                if let Some(map) = obj {
+                    for key in &["input", "output", "cacheRead", "cacheWrite", "totalTokens"] {
+                        map.entry((*key).to_string())
+                            .or_insert(serde_json::Value::Number(0.into()));
+                    }
+                    map.entry("cost".to_string()).or_insert_with(|| {
+                        serde_json::json!({
+                            "input": 0, "output": 0,
+                            "cacheRead": 0, "cacheWrite": 0, "total": 0
+                        })
+                    });
+                }
+                u
+            })
+            .or_else(|| msg.usage.as_ref().map(Self::usage_object))
+            .unwrap_or_else(|| {
+                serde_json::json!({
+                    "input": 0,
+                    "output": 0,
+                    "cacheRead": 0,
+                    "cacheWrite": 0,
+                    "totalTokens": 0,
+                    "cost": {
+                        "input": 0,
+                        "output": 0,
+                        "cacheRead": 0,
+                        "cacheWrite": 0,
+                        "total": 0
+                    }
+                })
+            });
+        inner["usage"] = usage;
+
+        let ts_str = msg
+            .timestamp
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+        Some(serde_json::json!({
+            "type": "message",
+            "timestamp": ts_str,
+            "message": inner,
+        }))
+    }
+
     fn extract_tool_calls(content: &serde_json::Value) -> Vec<ToolCall> {
         let Some(arr) = content.as_array() else {
             return vec![];
@@ -113,18 +296,18 @@ impl PiAgent {
                 if block.get("type").and_then(|t| t.as_str()) != Some("toolCall") {
                     return None;
                 }
-                Some(ToolCall {
-                    id: block.get("id").and_then(|v| v.as_str()).map(String::from),
-                    name: block
+                Some(ToolCall::new(
+                    block.get("id").and_then(|v| v.as_str()).map(String::from),
+                    block
                         .get("name")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown")
                         .to_string(),
-                    arguments: block
+                    block
                         .get("arguments")
                         .cloned()
                         .unwrap_or(serde_json::Value::Null),
-                })
+                ))
             })
             .collect()
     }
@@ -169,6 +352,10 @@ impl Provider for PiAgent {
         }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        vec![Self::home_dir()]
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let home = Self::home_dir();
         let sessions = Self::sessions_dir(&home);
@@ -217,6 +404,7 @@ impl Provider for PiAgent {
         let mut started_at: Option<i64> = None;
         let mut ended_at: Option<i64> = None;
         let mut session_cwd: Option<String> = None;
+        let mut lineage: Option<serde_json::Value> = None;
         let mut session_id_from_header: Option<String> = None;
         let mut model_id: Option<String> = None;
         let mut provider_name: Option<String> = None;
@@ -242,6 +430,7 @@ impl Provider for PiAgent {
                     session_id_from_header =
                         val.get("id").and_then(|v| v.as_str()).map(String::from);
                     session_cwd = val.get("cwd").and_then(|v| v.as_str()).map(String::from);
+                    lineage = val.get(crate::lineage::METADATA_KEY).cloned();
                     provider_name = val
                         .get("provider")
                         .and_then(|v| v.as_str())
@@ -273,14 +462,27 @@ impl Provider for PiAgent {
 
                     let content_val = msg.get("content");
                     let content = content_val.map(Self::flatten_content).unwrap_or_default();
+                    let attachments = content_val.map(extract_attachments).unwrap_or_default();
+                    let mut reasoning = content_val.map(extract_reasoning).unwrap_or_default();
 
-                    if content.trim().is_empty() {
+                    if content.trim().is_empty() && attachments.is_empty() && reasoning.is_empty() {
                         continue;
                     }
 
                     let tool_calls = content_val
                         .map(Self::extract_tool_calls)
                         .unwrap_or_default();
+                    let parts = content_val
+                        .map(|v| {
+                            content_parts(
+                                v,
+                                reasoning.len(),
+                                tool_calls.len(),
+                                0,
+                                attachments.len(),
+                            )
+                        })
+                        .unwrap_or_default();
 
                     let ts = val.get("timestamp").and_then(parse_timestamp);
 
@@ -301,14 +503,22 @@ impl Provider for PiAgent {
                         None
                     };
 
+                    for r in &mut reasoning {
+                        r.model = author.clone();
+                    }
+
                     messages.push(CanonicalMessage {
                         idx: 0,
                         role,
                         content,
+                        parts,
                         timestamp: ts,
                         author,
+                        reasoning,
                         tool_calls,
                         tool_results: vec![],
+                        attachments,
+                        usage: msg.get("usage").and_then(parse_usage),
                         extra: val,
                     });
                 }
@@ -328,6 +538,7 @@ impl Provider for PiAgent {
         }
 
         reindex_messages(&mut messages);
+        let usage = sum_message_usage(&messages);
 
         // Session ID: prefer header id, then filename stem.
         let session_id = session_id_from_header.unwrap_or_else(|| {
@@ -344,12 +555,15 @@ impl Provider for PiAgent {
 
         let workspace = session_cwd.as_ref().map(PathBuf::from);
 
-        let metadata = serde_json::json!({
+        let mut metadata = serde_json::json!({
             "source": "pi_agent",
             "session_id": session_id,
             "provider": provider_name,
             "model_id": model_id,
         });
+        if let Some(metadata) = metadata.as_object_mut() {
+            crate::lineage::restore(metadata, lineage.as_ref());
+        }
 
         info!(
             session_id,
@@ -368,6 +582,8 @@ impl Provider for PiAgent {
             metadata,
             source_path: path.to_path_buf(),
             model_name: model_id,
+            usage,
+            unrecognized: None,
         })
     }
 
@@ -412,7 +628,7 @@ impl Provider for PiAgent {
             .as_ref()
             .and_then(|w| w.to_str())
             .unwrap_or("/tmp");
-        let header = serde_json::json!({
+        let mut header = serde_json::json!({
             "type": "session",
             "id": session_id,
             "timestamp": session.started_at
@@ -425,133 +641,16 @@ impl Provider for PiAgent {
                 .unwrap_or(session.provider_slug.as_str()),
             "modelId": session.model_name.as_deref().unwrap_or("unknown"),
         });
+        if let Some(lineage) = crate::lineage::stored_value(session) {
+            header[crate::lineage::METADATA_KEY] = lineage;
+        }
         lines.push(serde_json::to_string(&header)?);
 
         // Messages.
         for msg in &session.messages {
-            // Skip messages that would produce empty content on read-back.
-            // Pi reader skips entries where content.trim().is_empty(), so
-            // we must ensure every written message survives the round-trip.
-            // Tool-result-only messages (empty content, no tool_calls, but
-            // with tool_results) get their content synthesized below.
-            let has_tool_data = !msg.tool_calls.is_empty() || !msg.tool_results.is_empty();
-            if msg.content.trim().is_empty() && !has_tool_data {
-                continue;
+            if let Some(entry) = Self::message_entry(msg) {
+                lines.push(serde_json::to_string(&entry)?);
             }
-
-            let role_str = match &msg.role {
-                MessageRole::User => "user",
-                MessageRole::Assistant => "assistant",
-                MessageRole::System => "system",
-                MessageRole::Tool => "toolResult",
-                MessageRole::Other(r) => r.as_str(),
-            };
-
-            // For tool-result-only messages (empty content, no tool_calls),
-            // synthesize readable content from the tool results so the Pi
-            // reader won't skip the message on read-back.
-            let effective_content = if msg.content.trim().is_empty()
-                && msg.tool_calls.is_empty()
-                && !msg.tool_results.is_empty()
-            {
-                msg.tool_results
-                    .iter()
-                    .map(|tr| {
-                        if tr.is_error {
-                            format!("[Tool Error] {}", tr.content)
-                        } else {
-                            format!("[Tool Output] {}", tr.content)
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            } else {
-                msg.content.clone()
-            };
-
-            // Build content: always an array of typed blocks so Pi's JS
-            // `message.content.some(...)` never receives a plain string.
-            //
-            // We intentionally emit only a text block here — no toolCall
-            // blocks.  Pi's reader (`flatten_content`) extracts text from
-            // both "text" AND "toolCall" blocks, so emitting both would
-            // cause the read-back content to double up (e.g. "[Tool: shell]"
-            // appearing in both the text block and the toolCall block).
-            // Since the pipeline already normalises tool-call / tool-result
-            // info into `effective_content`, a single text block is both
-            // sufficient and round-trip-safe.
-            let blocks = vec![serde_json::json!({
-                "type": "text",
-                "text": effective_content,
-            })];
-            let content = serde_json::Value::Array(blocks);
-
-            let mut inner = serde_json::json!({
-                "role": role_str,
-                "content": content,
-            });
-            if let Some(ref author) = msg.author {
-                inner["model"] = serde_json::Value::String(author.clone());
-            }
-
-            // Add usage field with the full structure Pi expects.
-            // Pi's footer.js sums: usage.input, usage.output, usage.cacheRead,
-            // usage.cacheWrite, and usage.cost.total — all must be present to
-            // avoid TypeError crashes.
-            let usage = msg
-                .extra
-                .get("message")
-                .and_then(|m| m.get("usage"))
-                .or_else(|| msg.extra.get("usage"))
-                .cloned()
-                .map(|mut u| {
-                    // Ensure all required fields exist even if the source
-                    // usage object is incomplete.
-                    let obj = u.as_object_mut();
-                    if let Some(map) = obj {
-                        for key in &["input", "output", "cacheRead", "cacheWrite", "totalTokens"] {
-                            map.entry((*key).to_string())
-                                .or_insert(serde_json::Value::Number(0.into()));
-                        }
-                        map.entry("cost".to_string()).or_insert_with(|| {
-                            serde_json::json!({
-                                "input": 0, "output": 0,
-                                "cacheRead": 0, "cacheWrite": 0, "total": 0
-                            })
-                        });
-                    }
-                    u
-                })
-                .unwrap_or_else(|| {
-                    serde_json::json!({
-                        "input": 0,
-                        "output": 0,
-                        "cacheRead": 0,
-                        "cacheWrite": 0,
-                        "totalTokens": 0,
-                        "cost": {
-                            "input": 0,
-                            "output": 0,
-                            "cacheRead": 0,
-                            "cacheWrite": 0,
-                            "total": 0
-                        }
-                    })
-                });
-            inner["usage"] = usage;
-
-            let ts_str = msg
-                .timestamp
-                .and_then(chrono::DateTime::from_timestamp_millis)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
-
-            let entry = serde_json::json!({
-                "type": "message",
-                "timestamp": ts_str,
-                "message": inner,
-            });
-            lines.push(serde_json::to_string(&entry)?);
         }
 
         let file_content = lines.join("\n") + "\n";
@@ -577,12 +676,63 @@ impl Provider for PiAgent {
         })
     }
 
+    fn appends_messages(&self) -> bool {
+        true
+    }
+
+    fn append_messages(
+        &self,
+        path: &Path,
+        session: &CanonicalSession,
+        messages: &[CanonicalMessage],
+    ) -> anyhow::Result<WrittenSession> {
+        let lines = messages
+            .iter()
+            .filter_map(Self::message_entry)
+            .map(|entry| serde_json::to_string(&entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outcome = super::append_jsonl_lines(path, &lines, self.slug())?;
+        info!(
+            session_id = session.session_id,
+            path = %outcome.target_path.display(),
+            messages = messages.len(),
+            "Pi-Agent session appended"
+        );
+
+        Ok(WrittenSession {
+            paths: vec![outcome.target_path],
+            session_id: session.session_id.clone(),
+            resume_command: self.resume_command(&session.session_id),
+            backup_path: outcome.backup_path,
+        })
+    }
+
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Tool calls and results are flattened into message text, and titles
+        // are re-derived on read.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         let home = Self::home_dir();
         let sessions_dir = home.join("sessions");
         let session_path = sessions_dir.join(format!("{session_id}.jsonl"));
         format!("pi --session {}", session_path.display())
     }
+
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
+    fn carries_reasoning(&self, _msg: &CanonicalMessage, reasoning: &Reasoning) -> bool {
+        !reasoning.redacted
+    }
 }
 
 #[cfg(test)]
@@ -670,12 +820,9 @@ mod tests {
         );
         let session = read_piagent(&[&line]);
 
-        assert!(
-            session.messages[0]
-                .content
-                .contains("[Thinking] Let me analyze...")
-        );
-        assert!(session.messages[0].content.contains("Here's my answer."));
+        assert_eq!(session.messages[0].content, "Here's my answer.");
+        assert_eq!(session.messages[0].reasoning.len(), 1);
+        assert_eq!(session.messages[0].reasoning[0].text, "Let me analyze...");
     }
 
     #[test]
@@ -696,10 +843,10 @@ mod tests {
     }
 
     #[test]
-    fn reader_skips_image_blocks() {
+    fn reader_collects_image_blocks_as_attachments() {
         let content = json!([
             {"type": "text", "text": "Before image"},
-            {"type": "image", "url": "data:image/png;base64,..."},
+            {"type": "image", "url": "data:image/png;base64,AAAA"},
             {"type": "text", "text": "After image"}
         ]);
         let line = format!(
@@ -711,6 +858,27 @@ mod tests {
         assert!(session.messages[0].content.contains("Before image"));
         assert!(session.messages[0].content.contains("After image"));
         assert!(!session.messages[0].content.contains("data:image"));
+        let attachments = &session.messages[0].attachments;
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].media_type.as_deref(), Some("image/png"));
+        assert_eq!(attachments[0].base64_data(), Some("AAAA"));
+    }
+
+    #[test]
+    fn reader_keeps_image_only_messages() {
+        let content = json!([{"type": "image", "data": "AAAA", "mimeType": "image/jpeg"}]);
+        let line = format!(
+            r#"{{"type":"message","timestamp":"2025-12-01T10:00:00Z","message":{{"role":"user","content":{}}}}}"#,
+            content
+        );
+        let session = read_piagent(&[&line]);
+
+        assert_eq!(session.messages.len(), 1);
+        assert!(session.messages[0].content.is_empty());
+        assert_eq!(
+            session.messages[0].attachments[0].media_type.as_deref(),
+            Some("image/jpeg")
+        );
     }
 
     #[test]
@@ -929,26 +1097,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix the bug".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "I'll fix it now.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_500_000),
                     author: Some("claude-3-opus".to_string()),
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({"source": "claude-code"}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let readback = write_and_read_back(&original);
@@ -976,19 +1154,25 @@ mod tests {
                 idx: 0,
                 role: MessageRole::Assistant,
                 content: "Let me check.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
-                tool_calls: vec![ToolCall {
-                    id: None,
-                    name: "bash".to_string(),
-                    arguments: json!({"command": "ls"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    None,
+                    "bash".to_string(),
+                    json!({"command": "ls"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: json!({}),
             }],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         let readback = write_and_read_back(&original);
@@ -1023,36 +1207,50 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Hello from Codex".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Hi there".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_001_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 2,
                     role: MessageRole::System,
                     content: "You are a helpful assistant".to_string(),
+                    parts: Vec::new(),
                     timestamp: None,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({}),
             source_path: std::path::PathBuf::from("/tmp/codex.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         // Write using the real write_session path.
@@ -27,7 +27,7 @@ use crate::model::{
     CanonicalMessage, CanonicalSession, MessageRole, flatten_content, normalize_role,
     parse_timestamp, reindex_messages, truncate_title,
 };
-use crate::providers::{Provider, WriteOptions, WrittenSession};
+use crate::providers::{Provider, VerifyTolerance, WriteOptions, WrittenSession};
 
 /// Vibe provider implementation.
 pub struct Vibe;
@@ -36,10 +36,10 @@ impl Vibe {
     /// Root directory for Vibe session storage.
     /// Respects `VIBE_HOME` env var override.
     fn home_dir() -> PathBuf {
-        if let Ok(home) = std::env::var("VIBE_HOME") {
+        if let Ok(home) = crate::homes::env_var("VIBE_HOME") {
             return PathBuf::from(home);
         }
-        dirs::home_dir()
+        crate::homes::home_dir()
             .unwrap_or_default()
             .join(".vibe")
             .join("logs")
@@ -130,6 +130,10 @@ impl Provider for Vibe {
         if root.is_dir() { vec![root] } else { vec![] }
     }
 
+    fn storage_locations(&self) -> Vec<PathBuf> {
+        vec![Self::home_dir()]
+    }
+
     fn owns_session(&self, session_id: &str) -> Option<PathBuf> {
         let root = Self::home_dir();
         if !root.is_dir() {
@@ -183,6 +187,7 @@ impl Provider for Vibe {
         let mut messages: Vec<CanonicalMessage> = Vec::new();
         let mut started_at: Option<i64> = None;
         let mut ended_at: Option<i64> = None;
+        let mut lineage: Option<serde_json::Value> = None;
 
         for line_result in reader.lines() {
             let line = match line_result {
@@ -193,10 +198,16 @@ impl Provider for Vibe {
                 continue;
             }
 
-            let val: serde_json::Value = match serde_json::from_str(&line) {
+            let mut val: serde_json::Value = match serde_json::from_str(&line) {
                 Ok(v) => v,
                 Err(_) => continue,
             };
+            if let Some(stored) = val
+                .as_object_mut()
+                .and_then(|obj| obj.remove(crate::lineage::METADATA_KEY))
+            {
+                lineage.get_or_insert(stored);
+            }
 
             let role_str = Self::extract_role(&val);
             let role = normalize_role(&role_str);
@@ -218,10 +229,14 @@ impl Provider for Vibe {
                 idx: 0,
                 role,
                 content,
+                parts: Vec::new(),
                 timestamp: ts,
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: val,
             });
         }
@@ -245,7 +260,9 @@ impl Provider for Vibe {
             .find(|m| m.role == MessageRole::User)
             .map(|m| truncate_title(&m.content, 100));
 
-        let metadata = serde_json::json!({ "source": "vibe" });
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("source".into(), "vibe".into());
+        crate::lineage::restore(&mut metadata, lineage.as_ref());
 
         info!(session_id, messages = messages.len(), "Vibe session parsed");
 
@@ -257,9 +274,11 @@ impl Provider for Vibe {
             started_at,
             ended_at,
             messages,
-            metadata,
+            metadata: serde_json::Value::Object(metadata),
             source_path: path.to_path_buf(),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -285,6 +304,9 @@ impl Provider for Vibe {
         );
 
         let mut lines: Vec<String> = Vec::with_capacity(session.messages.len());
+        // No session header in this format: the lineage rides on the first
+        // message and the reader lifts it back off.
+        let mut lineage = crate::lineage::stored_value(session);
         for msg in &session.messages {
             let role_str = match &msg.role {
                 MessageRole::User => "user",
@@ -311,6 +333,9 @@ impl Provider for Vibe {
                     serde_json::Value::String(dt.to_rfc3339()),
                 );
             }
+            if let Some(lineage) = lineage.take() {
+                obj.insert(crate::lineage::METADATA_KEY.into(), lineage);
+            }
 
             lines.push(serde_json::to_string(&serde_json::Value::Object(obj))?);
         }
@@ -338,6 +363,18 @@ impl Provider for Vibe {
         })
     }
 
+    fn verify_tolerance(&self) -> VerifyTolerance {
+        // Transcripts carry text only, with no workspace or title.
+        VerifyTolerance {
+            tool_calls: false,
+            tool_call_ids: false,
+            tool_results: false,
+            workspace: false,
+            title: false,
+            ..VerifyTolerance::STRICT
+        }
+    }
+
     fn resume_command(&self, session_id: &str) -> String {
         format!("vibe --resume {session_id}")
     }
@@ -532,26 +569,36 @@ mod tests {
                     idx: 0,
                     role: MessageRole::User,
                     content: "Fix the bug".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "Done.".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_500_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: json!({}),
                 },
             ],
             metadata: json!({}),
             source_path: PathBuf::from("/tmp/test.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         };
 
         // Write directly to the session dir.
@@ -9,6 +9,15 @@ use std::path::PathBuf;
 
 use serde::Serialize;
 
+use crate::backup::RestoredFile;
+use crate::doctor::ProviderDiagnosis;
+use crate::lineage::Lineage;
+use crate::model::{CanonicalMessage, Unrecognized, Usage};
+use crate::pipeline::{AppendSummary, FidelityReport};
+use crate::pricing::CostEstimate;
+use crate::rewrite::EditSummary;
+use crate::selftest::RoundTrip;
+
 /// Current schema version for all JSON envelopes and per-record outputs.
 ///
 /// Bump this when adding/removing/renaming fields in any response struct.
@@ -59,6 +68,21 @@ pub struct ListItem {
     /// Repository name from filesystem git root (only when `--enrich-fs` is set).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repo_name: Option<String>,
+    /// Alternate home (`--home`) the session was found under; absent for the
+    /// current user's home.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
+    /// `provider:session_id` of the conversation casr converted this session
+    /// from (through any intermediate copies); absent for originals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Converted copies of this conversation hidden by `--dedupe`.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub copies_hidden: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 // ---------------------------------------------------------------------------
@@ -86,6 +110,113 @@ pub struct InfoResponse {
     /// Repository name from filesystem git root (only when `--enrich-fs` is set).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repo_name: Option<String>,
+    /// Alternate home (`--home`) the session was found under; absent for the
+    /// current user's home.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
+    /// Session token totals (only when the provider records usage).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    /// Cost estimate from the model price table (only when `usage` is present).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<CostEstimate>,
+    /// Files the session's edits touched, in first-touched order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files_changed: Vec<String>,
+    /// The casr conversions that produced this session, oldest first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<Lineage>,
+    /// Source constructs the reader skipped (only with `--verbose`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unrecognized: Option<Unrecognized>,
+}
+
+// ---------------------------------------------------------------------------
+// `serve`: GET /api/sessions/{id}/messages
+// ---------------------------------------------------------------------------
+
+/// A session's canonical messages, for the web UI's transcript viewer.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptResponse {
+    pub schema_version: u32,
+    pub session_id: String,
+    pub provider: String,
+    pub messages: Vec<CanonicalMessage>,
+}
+
+// ---------------------------------------------------------------------------
+// `stats --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for `stats --json`.
+///
+/// Every key is always present so dashboards can rely on the shape; lists are
+/// sorted busiest-first (weeks chronologically).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsResponse {
+    pub schema_version: u32,
+    /// Lower time bound applied (epoch millis), if any.
+    pub since: Option<i64>,
+    /// Upper time bound applied (epoch millis), if any.
+    pub until: Option<i64>,
+    pub sessions: usize,
+    pub messages: usize,
+    /// Median messages per session.
+    pub median_messages: Option<f64>,
+    /// Median first-to-last-activity span, over sessions with both timestamps.
+    pub median_duration_secs: Option<f64>,
+    pub tool_calls: usize,
+    pub tool_results: usize,
+    pub tool_errors: usize,
+    /// `tool_errors / tool_results`; `null` when there are no results.
+    pub tool_error_rate: Option<f64>,
+    pub providers: Vec<ProviderStats>,
+    pub weeks: Vec<WeekStats>,
+    pub tools: Vec<ToolStats>,
+    pub workspaces: Vec<WorkspaceStats>,
+    pub models: Vec<ModelStats>,
+}
+
+/// Per-provider totals in `stats --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStats {
+    pub provider: String,
+    pub sessions: usize,
+    pub messages: usize,
+    pub median_messages: Option<f64>,
+    pub tool_calls: usize,
+    pub tool_errors: usize,
+}
+
+/// Sessions started in one ISO week (e.g. `2026-W03`), split by provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekStats {
+    pub week: String,
+    pub sessions: usize,
+    pub providers: std::collections::BTreeMap<String, usize>,
+}
+
+/// Call and error counts for one tool name.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStats {
+    pub name: String,
+    pub calls: usize,
+    pub errors: usize,
+}
+
+/// Activity in one workspace (`null` for sessions without one).
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceStats {
+    pub workspace: Option<String>,
+    pub sessions: usize,
+    pub messages: usize,
+}
+
+/// Sessions per model (`null` for sessions that record no model).
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStats {
+    pub model: Option<String>,
+    pub sessions: usize,
 }
 
 // ---------------------------------------------------------------------------
@@ -101,6 +232,10 @@ pub struct ProviderInfo {
     pub installed: bool,
     pub version: Option<String>,
     pub evidence: Vec<String>,
+    /// Alternate home (`--home`) the provider was detected under; absent for
+    /// the current user's home.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -119,6 +254,117 @@ pub struct ResumeSuccess {
     pub resume_command: Option<String>,
     pub dry_run: bool,
     pub warnings: Vec<String>,
+    /// What the conversion lost or transformed (empty for dry runs).
+    pub fidelity: FidelityReport,
+    /// With `--append-to`, what was added to the existing target session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append: Option<AppendSummary>,
+}
+
+// ---------------------------------------------------------------------------
+// `edit --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for a successful `edit --json` (including dry-run).
+#[derive(Debug, Clone, Serialize)]
+pub struct EditSuccess {
+    pub ok: bool,
+    pub provider: String,
+    pub source_session_id: String,
+    /// The original ID for in-place edits; `None` for dry runs.
+    pub target_session_id: Option<String>,
+    pub written_paths: Option<Vec<String>>,
+    /// Where an in-place edit set the original session aside.
+    pub backup_path: Option<String>,
+    pub resume_command: Option<String>,
+    pub in_place: bool,
+    pub dry_run: bool,
+    pub messages: usize,
+    pub changes: EditSummary,
+    pub warnings: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// `sync-back --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for a successful `sync-back --json` (including dry-run).
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncBackSuccess {
+    pub ok: bool,
+    /// The converted session that was continued.
+    pub provider: String,
+    pub session_id: String,
+    /// The session it was converted from, which received the new turns.
+    pub source_provider: String,
+    pub source_session_id: String,
+    pub source_path: String,
+    /// Messages appended (or that would be, for a dry run).
+    pub appended: usize,
+    /// New messages an earlier sync-back already appended.
+    pub already_synced: usize,
+    pub backup_path: Option<String>,
+    pub resume_command: Option<String>,
+    pub dry_run: bool,
+    pub warnings: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// `doctor --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for `doctor --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorResponse {
+    /// False when any provider has problems (status `problems`).
+    pub ok: bool,
+    pub providers: Vec<ProviderDiagnosis>,
+}
+
+// ---------------------------------------------------------------------------
+// `selftest --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for `selftest --json`: one round trip per provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelftestResponse {
+    /// True when every round trip passed.
+    pub ok: bool,
+    /// `None` for the built-in synthetic session.
+    pub session_id: Option<String>,
+    pub origin_provider: String,
+    pub messages: usize,
+    pub results: Vec<RoundTrip>,
+}
+
+// ---------------------------------------------------------------------------
+// `backup --json` / `restore --json`
+// ---------------------------------------------------------------------------
+
+/// Response struct for `backup --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupResponse {
+    pub ok: bool,
+    pub output: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+    pub providers: Vec<BackupProviderTotal>,
+}
+
+/// Per-provider totals in `backup --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupProviderTotal {
+    pub provider: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Response struct for `restore --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResponse {
+    pub ok: bool,
+    pub dry_run: bool,
+    pub files: Vec<RestoredFile>,
 }
 
 // ---------------------------------------------------------------------------
@@ -218,6 +464,9 @@ mod tests {
             workspace_name: Some("test".to_string()),
             workspace_name_source: Some("session_workspace_path".to_string()),
             repo_name: None,
+            home: None,
+            origin: None,
+            copies_hidden: 0,
         };
         let envelope = ListEnvelope::new(vec![item]);
         let json = serde_json::to_value(&envelope).unwrap();
@@ -253,6 +502,12 @@ mod tests {
             workspace_name: None,
             workspace_name_source: Some("none".to_string()),
             repo_name: None,
+            home: None,
+            usage: None,
+            cost_estimate: None,
+            files_changed: Vec::new(),
+            lineage: None,
+            unrecognized: None,
         };
         let json = serde_json::to_value(&info).unwrap();
         assert_eq!(json["schema_version"], 2);
@@ -264,6 +519,9 @@ mod tests {
         assert_eq!(json["model_name"], "gpt-4");
         assert!(json["workspace_name"].is_null());
         assert_eq!(json["workspace_name_source"], "none");
+        assert!(json.get("usage").is_none());
+        assert!(json.get("cost_estimate").is_none());
+        assert!(json.get("files_changed").is_none());
     }
 
     // -----------------------------------------------------------------------
@@ -279,6 +537,7 @@ mod tests {
             installed: true,
             version: Some("1.0".to_string()),
             evidence: vec!["found binary".to_string()],
+            home: None,
         };
         let json = serde_json::to_value(&pi).unwrap();
         assert_eq!(json["name"], "Claude Code");
@@ -305,6 +564,8 @@ mod tests {
             resume_command: None,
             dry_run: true,
             warnings: vec![],
+            fidelity: FidelityReport::default(),
+            append: None,
         };
         let json = serde_json::to_value(&rs).unwrap();
         assert_eq!(json["ok"], true);
@@ -312,6 +573,7 @@ mod tests {
         assert!(json["target_session_id"].is_null());
         assert!(json["written_paths"].is_null());
         assert!(json["resume_command"].is_null());
+        assert!(json.get("append").is_none());
     }
 
     #[test]
@@ -326,6 +588,14 @@ mod tests {
             resume_command: Some("claude --resume sid-tgt".to_string()),
             dry_run: false,
             warnings: vec!["missing workspace".to_string()],
+            fidelity: FidelityReport {
+                turns_dropped: 3,
+                ..FidelityReport::default()
+            },
+            append: Some(AppendSummary {
+                appended: 2,
+                already_present: 5,
+            }),
         };
         let json = serde_json::to_value(&rs).unwrap();
         assert_eq!(json["ok"], true);
@@ -334,6 +604,10 @@ mod tests {
         assert_eq!(json["written_paths"][0], "/tmp/written.jsonl");
         assert_eq!(json["resume_command"], "claude --resume sid-tgt");
         assert_eq!(json["warnings"][0], "missing workspace");
+        assert_eq!(json["fidelity"]["turns_dropped"], 3);
+        assert_eq!(json["fidelity"]["reasoning_dropped"], 0);
+        assert_eq!(json["append"]["appended"], 2);
+        assert_eq!(json["append"]["already_present"], 5);
     }
 
     // -----------------------------------------------------------------------
@@ -361,6 +635,9 @@ mod tests {
             workspace_name: None,
             workspace_name_source: Some("none".to_string()),
             repo_name: None,
+            home: None,
+            origin: None,
+            copies_hidden: 0,
         };
         let json = serde_json::to_value(&item).unwrap();
         assert!(
@@ -390,6 +667,9 @@ mod tests {
             workspace_name: Some("my_repo".to_string()),
             workspace_name_source: Some("session_workspace_path".to_string()),
             repo_name: Some("my_repo".to_string()),
+            home: None,
+            origin: None,
+            copies_hidden: 0,
         };
         let json = serde_json::to_value(&item).unwrap();
         assert_eq!(json["repo_name"], "my_repo");
@@ -412,6 +692,12 @@ mod tests {
             workspace_name: None,
             workspace_name_source: Some("none".to_string()),
             repo_name: None,
+            home: None,
+            usage: None,
+            cost_estimate: None,
+            files_changed: Vec::new(),
+            lineage: None,
+            unrecognized: None,
         };
         let json = serde_json::to_value(&info).unwrap();
         assert!(
@@ -420,6 +706,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn info_response_includes_usage_and_cost_estimate() {
+        let info = InfoResponse {
+            schema_version: SCHEMA_VERSION,
+            session_id: "sid".to_string(),
+            provider: "claude-code".to_string(),
+            title: None,
+            workspace: None,
+            messages: 2,
+            started_at: None,
+            ended_at: None,
+            model_name: Some("claude-sonnet-4-5".to_string()),
+            source_path: "/tmp/x".to_string(),
+            metadata: serde_json::json!(null),
+            workspace_name: None,
+            workspace_name_source: Some("none".to_string()),
+            repo_name: None,
+            home: None,
+            usage: Some(Usage {
+                input_tokens: 100,
+                output_tokens: 20,
+                ..Usage::default()
+            }),
+            cost_estimate: Some(CostEstimate {
+                usd: 0.0006,
+                priced_tokens: 120,
+                unpriced_models: Vec::new(),
+            }),
+            files_changed: vec!["src/main.rs".to_string()],
+            lineage: None,
+            unrecognized: Some(Unrecognized {
+                entry_types: [("hologram".to_string(), 2)].into(),
+                ..Unrecognized::default()
+            }),
+        };
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["usage"]["input_tokens"], 100);
+        assert_eq!(json["usage"]["output_tokens"], 20);
+        assert!(json["usage"].get("cost_usd").is_none());
+        assert_eq!(json["cost_estimate"]["usd"], 0.0006);
+        assert_eq!(json["files_changed"], serde_json::json!(["src/main.rs"]));
+        assert_eq!(
+            json["unrecognized"],
+            serde_json::json!({"entry_types": {"hologram": 2}})
+        );
+    }
+
     #[test]
     fn error_envelope_serializes() {
         let ee = ErrorEnvelope::new("SessionNotFound", "not found".to_string());
@@ -0,0 +1,443 @@
+//! Same-provider session edits.
+//!
+//! `casr edit` reads a session, applies [`SessionEdit`]s to the canonical IR
+//! and writes it back to the provider it came from, either as a new session
+//! or in place. This module holds the transforms; writing and read-back
+//! verification live in
+//! [`ConversionPipeline::edit`](crate::pipeline::ConversionPipeline::edit).
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::model::{CanonicalMessage, CanonicalSession, ContentPart, reindex_messages};
+
+/// Replacement text for redacted strings.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Replacement content for dropped tool outputs.
+pub const TOOL_OUTPUT_REMOVED: &str = "[tool output removed]";
+
+/// Inclusive range of zero-based message indexes (`7` or `12-30`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl FromStr for MessageRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let index = |part: &str| {
+            part.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid message range '{s}' (expected N or N-M)"))
+        };
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (index(start)?, index(end)?),
+            None => {
+                let idx = index(s)?;
+                (idx, idx)
+            }
+        };
+        if start > end {
+            return Err(format!("invalid message range '{s}': start is after end"));
+        }
+        Ok(MessageRange { start, end })
+    }
+}
+
+impl std::fmt::Display for MessageRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// One transformation applied by `casr edit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEdit {
+    /// Remove messages by index. Indexes refer to the session as read, so
+    /// several ranges never shift each other.
+    DropMessages(MessageRange),
+    /// Replace every tool result with [`TOOL_OUTPUT_REMOVED`], keeping the
+    /// call/result pairing intact.
+    DropToolOutputs,
+    /// Replace every occurrence of a literal string with [`REDACTED`] in
+    /// text, reasoning, tool I/O, the title and provider metadata.
+    Redact(String),
+    /// Set the session title.
+    Retitle(String),
+    /// Remove reasoning ("thinking") blocks.
+    StripReasoning,
+}
+
+/// What [`apply_edits`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EditSummary {
+    /// Messages removed, including ones left empty by other edits.
+    pub messages_dropped: usize,
+    pub tool_outputs_dropped: usize,
+    /// Messages in which at least one redaction was made.
+    pub messages_redacted: usize,
+    /// The title or session metadata contained redacted text.
+    pub session_redacted: bool,
+    pub reasoning_stripped: usize,
+    pub retitled: bool,
+}
+
+impl EditSummary {
+    /// True when the edits matched nothing.
+    pub fn is_empty(&self) -> bool {
+        *self == EditSummary::default()
+    }
+
+    /// Human-readable lines, one per kind of change.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.messages_dropped > 0 {
+            lines.push(format!("Dropped {} message(s)", self.messages_dropped));
+        }
+        if self.tool_outputs_dropped > 0 {
+            lines.push(format!(
+                "Removed {} tool output(s)",
+                self.tool_outputs_dropped
+            ));
+        }
+        if self.messages_redacted > 0 {
+            lines.push(format!(
+                "Redacted text in {} message(s)",
+                self.messages_redacted
+            ));
+        }
+        if self.session_redacted {
+            lines.push("Redacted text in the title or session metadata".to_string());
+        }
+        if self.reasoning_stripped > 0 {
+            lines.push(format!(
+                "Stripped {} reasoning block(s)",
+                self.reasoning_stripped
+            ));
+        }
+        if self.retitled {
+            lines.push("Retitled session".to_string());
+        }
+        lines
+    }
+}
+
+/// Apply `edits` to `session`: message drops first, then the other edits in
+/// order. Fails when a range lies past the end of the session.
+///
+/// Messages whose body changes lose their provider `extra`: writers that
+/// replay stored native records (Kiro envelopes, Gemini content) would
+/// otherwise bring the removed data back. Redaction rewrites `extra` instead.
+pub fn apply_edits(
+    session: &mut CanonicalSession,
+    edits: &[SessionEdit],
+) -> Result<EditSummary, String> {
+    let mut summary = EditSummary::default();
+
+    let ranges: Vec<MessageRange> = edits
+        .iter()
+        .filter_map(|edit| match edit {
+            SessionEdit::DropMessages(range) => Some(*range),
+            _ => None,
+        })
+        .collect();
+    if !ranges.is_empty() {
+        let total = session.messages.len();
+        if let Some(range) = ranges.iter().find(|r| r.end >= total) {
+            return Err(format!(
+                "message range {range} is out of bounds (the session has {total} message(s), 0-{})",
+                total.saturating_sub(1)
+            ));
+        }
+        let mut idx = 0;
+        session.messages.retain(|_| {
+            let keep = !ranges.iter().any(|r| (r.start..=r.end).contains(&idx));
+            idx += 1;
+            keep
+        });
+        crate::pipeline::repair_tool_pairing(session);
+        summary.messages_dropped = total - session.messages.len();
+    }
+
+    for edit in edits {
+        match edit {
+            SessionEdit::DropMessages(_) => {}
+            SessionEdit::DropToolOutputs => {
+                for msg in &mut session.messages {
+                    let mut dropped = 0;
+                    for tr in &mut msg.tool_results {
+                        if tr.content != TOOL_OUTPUT_REMOVED {
+                            tr.content = TOOL_OUTPUT_REMOVED.to_string();
+                            dropped += 1;
+                        }
+                    }
+                    if dropped > 0 {
+                        msg.extra = Value::Object(Default::default());
+                        summary.tool_outputs_dropped += dropped;
+                    }
+                }
+            }
+            SessionEdit::Redact(needle) => {
+                if needle.is_empty() {
+                    continue;
+                }
+                for msg in &mut session.messages {
+                    if redact_message(msg, needle) {
+                        summary.messages_redacted += 1;
+                    }
+                }
+                let mut hits = redact_value(&mut session.metadata, needle);
+                if let Some(title) = &mut session.title {
+                    hits += redact_string(title, needle);
+                }
+                summary.session_redacted |= hits > 0;
+            }
+            SessionEdit::Retitle(title) => {
+                if session.title.as_deref() != Some(title.as_str()) {
+                    session.title = Some(title.clone());
+                    summary.retitled = true;
+                }
+            }
+            SessionEdit::StripReasoning => {
+                for msg in &mut session.messages {
+                    if msg.reasoning.is_empty() {
+                        continue;
+                    }
+                    summary.reasoning_stripped += msg.reasoning.len();
+                    msg.reasoning.clear();
+                    msg.parts
+                        .retain(|p| !matches!(p, ContentPart::Thinking { .. }));
+                    msg.extra = Value::Object(Default::default());
+                }
+            }
+        }
+    }
+
+    // Reasoning-only messages are left empty by stripping.
+    let before = session.messages.len();
+    session.messages.retain(|m| {
+        !(m.content.trim().is_empty()
+            && m.reasoning.is_empty()
+            && m.tool_calls.is_empty()
+            && m.tool_results.is_empty()
+            && m.attachments.is_empty())
+    });
+    summary.messages_dropped += before - session.messages.len();
+    reindex_messages(&mut session.messages);
+
+    Ok(summary)
+}
+
+/// Redact `needle` everywhere in `msg`. Returns whether anything changed.
+fn redact_message(msg: &mut CanonicalMessage, needle: &str) -> bool {
+    let mut hits = redact_string(&mut msg.content, needle);
+    for part in &mut msg.parts {
+        match part {
+            ContentPart::Text { text } => hits += redact_string(text, needle),
+            ContentPart::Citation(citation) => {
+                for field in [
+                    &mut citation.title,
+                    &mut citation.cited_text,
+                    &mut citation.url,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    hits += redact_string(field, needle);
+                }
+                hits += redact_value(&mut citation.original, needle);
+            }
+            _ => {}
+        }
+    }
+    for reasoning in &mut msg.reasoning {
+        if redact_string(&mut reasoning.text, needle) > 0 {
+            // The signature authenticated the original text.
+            reasoning.signature = None;
+            hits += 1;
+        }
+    }
+    for tc in &mut msg.tool_calls {
+        if redact_value(&mut tc.arguments, needle) > 0 {
+            tc.edits = crate::edits::parse_tool_edits(&tc.name, &tc.arguments);
+            hits += 1;
+        }
+    }
+    for tr in &mut msg.tool_results {
+        hits += redact_string(&mut tr.content, needle);
+    }
+    hits += redact_value(&mut msg.extra, needle);
+    hits > 0
+}
+
+fn redact_string(s: &mut String, needle: &str) -> usize {
+    let hits = s.matches(needle).count();
+    if hits > 0 {
+        *s = s.replace(needle, REDACTED);
+    }
+    hits
+}
+
+/// Redact string values (not keys) anywhere inside `value`.
+fn redact_value(value: &mut Value, needle: &str) -> usize {
+    match value {
+        Value::String(s) => redact_string(s, needle),
+        Value::Array(items) => items.iter_mut().map(|v| redact_value(v, needle)).sum(),
+        Value::Object(map) => map.values_mut().map(|v| redact_value(v, needle)).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{MessageRole, Reasoning, ToolCall, ToolResult};
+    use serde_json::json;
+
+    fn msg(role: MessageRole, content: &str) -> CanonicalMessage {
+        CanonicalMessage {
+            idx: 0,
+            role,
+            content: content.to_string(),
+            parts: Vec::new(),
+            timestamp: None,
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_results: Vec::new(),
+            attachments: Vec::new(),
+            usage: None,
+            extra: json!({}),
+        }
+    }
+
+    fn session() -> CanonicalSession {
+        let mut call = msg(MessageRole::Assistant, "Reading the config.");
+        call.reasoning.push(Reasoning {
+            text: "The token is sk-secret.".to_string(),
+            signature: Some("sig".to_string()),
+            ..Reasoning::default()
+        });
+        call.tool_calls.push(ToolCall::new(
+            Some("call-1".to_string()),
+            "Read".to_string(),
+            json!({"file_path": "/tmp/sk-secret.env"}),
+        ));
+        let mut result = msg(MessageRole::User, "");
+        result.tool_results.push(ToolResult {
+            call_id: Some("call-1".to_string()),
+            content: "API_KEY=sk-secret".to_string(),
+            is_error: false,
+        });
+        result.extra = json!({"toolUseResult": {"stdout": "API_KEY=sk-secret"}});
+        let mut messages = vec![
+            msg(MessageRole::User, "Fix the config"),
+            call,
+            result,
+            msg(MessageRole::Assistant, "Done."),
+        ];
+        reindex_messages(&mut messages);
+        CanonicalSession {
+            session_id: "s-1".to_string(),
+            provider_slug: "claude-code".to_string(),
+            workspace: None,
+            title: Some("Fix the config".to_string()),
+            started_at: None,
+            ended_at: None,
+            messages,
+            metadata: json!({"note": "uses sk-secret"}),
+            source_path: "/tmp/s-1.jsonl".into(),
+            model_name: None,
+            usage: None,
+            unrecognized: None,
+        }
+    }
+
+    #[test]
+    fn message_range_parses_single_and_inclusive_ranges() {
+        assert_eq!(
+            "7".parse::<MessageRange>(),
+            Ok(MessageRange { start: 7, end: 7 })
+        );
+        assert_eq!(
+            "12-30".parse::<MessageRange>(),
+            Ok(MessageRange { start: 12, end: 30 })
+        );
+        assert!("30-12".parse::<MessageRange>().is_err());
+        assert!("a-b".parse::<MessageRange>().is_err());
+    }
+
+    #[test]
+    fn drop_messages_repairs_tool_pairing_and_rejects_out_of_bounds() {
+        let mut s = session();
+        let summary = apply_edits(
+            &mut s,
+            &[SessionEdit::DropMessages(MessageRange { start: 1, end: 1 })],
+        )
+        .unwrap();
+        // The tool result lost its call, leaving its message empty.
+        assert_eq!(summary.messages_dropped, 2);
+        assert_eq!(s.messages.len(), 2);
+        assert_eq!(s.messages[1].idx, 1);
+        assert_eq!(s.messages[1].content, "Done.");
+
+        let err = apply_edits(
+            &mut session(),
+            &[SessionEdit::DropMessages(MessageRange { start: 2, end: 9 })],
+        )
+        .unwrap_err();
+        assert!(err.contains("out of bounds"), "{err}");
+    }
+
+    #[test]
+    fn redact_reaches_reasoning_tool_io_extra_and_metadata() {
+        let mut s = session();
+        let summary = apply_edits(&mut s, &[SessionEdit::Redact("sk-secret".to_string())]).unwrap();
+        assert_eq!(summary.messages_redacted, 2);
+        assert!(summary.session_redacted);
+        let dump = serde_json::to_string(&s.messages).unwrap() + &s.metadata.to_string();
+        assert!(!dump.contains("sk-secret"), "{dump}");
+        assert_eq!(s.messages[1].reasoning[0].signature, None);
+        assert_eq!(
+            s.messages[1].tool_calls[0].arguments["file_path"],
+            "/tmp/[REDACTED].env"
+        );
+    }
+
+    #[test]
+    fn structural_edits_count_changes_and_reset_extra() {
+        let mut s = session();
+        let summary = apply_edits(
+            &mut s,
+            &[
+                SessionEdit::DropToolOutputs,
+                SessionEdit::StripReasoning,
+                SessionEdit::Retitle("Config fix".to_string()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(summary.tool_outputs_dropped, 1);
+        assert_eq!(summary.reasoning_stripped, 1);
+        assert!(summary.retitled);
+        assert_eq!(s.messages[2].tool_results[0].content, TOOL_OUTPUT_REMOVED);
+        assert_eq!(s.messages[2].extra, json!({}));
+        assert_eq!(s.title.as_deref(), Some("Config fix"));
+
+        // Applying the same edits again changes nothing.
+        let again = apply_edits(
+            &mut s,
+            &[SessionEdit::DropToolOutputs, SessionEdit::StripReasoning],
+        )
+        .unwrap();
+        assert!(again.is_empty());
+    }
+}
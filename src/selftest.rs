@@ -0,0 +1,243 @@
+//! Cross-provider round-trip self-test (`casr selftest`).
+//!
+//! Seeds a session into its origin provider, then converts it through every
+//! other registered provider and back with the real [`ConversionPipeline`],
+//! so each leg gets the pipeline's own read-back verification. Everything
+//! here writes to the provider homes in the environment, so callers must
+//! point them at a throwaway sandbox first — see [`sandbox_env`].
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::discovery::ProviderRegistry;
+use crate::error::CasrError;
+use crate::model::{CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult};
+use crate::pipeline::{ConversionPipeline, ConvertOptions};
+use crate::providers::WriteOptions;
+
+/// Provider home overrides, each pointed at its own sandbox subdirectory.
+/// `HOME` and the XDG roots catch providers that fall back to them.
+pub const SANDBOX_HOMES: &[&str] = &[
+    "HOME",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "CLAUDE_HOME",
+    "CODEX_HOME",
+    "GEMINI_HOME",
+    "CURSOR_HOME",
+    "CLINE_HOME",
+    "AIDER_HOME",
+    "AMP_HOME",
+    "AMP_DATA_HOME",
+    "OPENCODE_HOME",
+    "CHATGPT_HOME",
+    "CLAWDBOT_HOME",
+    "VIBE_HOME",
+    "FACTORY_HOME",
+    "OPENCLAW_HOME",
+    "PI_AGENT_HOME",
+    "KIRO_HOME",
+];
+
+/// File-level overrides that would escape the sandbox and must be unset.
+pub const SANDBOX_UNSET: &[&str] = &["OPENCODE_DB_PATH", "AIDER_CHAT_HISTORY_FILE"];
+
+/// Environment that confines every provider to `root`: one directory per
+/// variable in [`SANDBOX_HOMES`]. Also unset [`SANDBOX_UNSET`].
+pub fn sandbox_env(root: &Path) -> Vec<(&'static str, PathBuf)> {
+    SANDBOX_HOMES
+        .iter()
+        .map(|var| (*var, root.join(var.to_ascii_lowercase())))
+        .collect()
+}
+
+/// A small Claude Code session exercising text, a tool call and its result.
+pub fn synthetic_session(workspace: &Path) -> CanonicalSession {
+    let start = 1_767_225_600_000; // 2026-01-01T00:00:00Z
+    let message = |idx: usize, role: MessageRole, content: &str| CanonicalMessage {
+        idx,
+        role,
+        content: content.to_string(),
+        parts: Vec::new(),
+        timestamp: Some(start + idx as i64 * 15_000),
+        author: None,
+        reasoning: Vec::new(),
+        tool_calls: Vec::new(),
+        tool_results: Vec::new(),
+        attachments: Vec::new(),
+        usage: None,
+        extra: serde_json::Value::Null,
+    };
+    let mut messages = vec![
+        message(0, MessageRole::User, "Why does `cargo test` fail on main?"),
+        message(1, MessageRole::Assistant, "Let me run the tests."),
+        message(2, MessageRole::User, ""),
+        message(
+            3,
+            MessageRole::Assistant,
+            "`parse_header` rejects empty input; the fix is to return `None` early.",
+        ),
+    ];
+    messages[1].tool_calls.push(ToolCall::new(
+        Some("toolu_selftest_1".to_string()),
+        "Bash".to_string(),
+        serde_json::json!({"command": "cargo test"}),
+    ));
+    messages[2].tool_results.push(ToolResult {
+        call_id: Some("toolu_selftest_1".to_string()),
+        content: "test parse::empty_header ... FAILED".to_string(),
+        is_error: true,
+    });
+    CanonicalSession {
+        session_id: "casr-selftest".to_string(),
+        provider_slug: "claude-code".to_string(),
+        workspace: Some(workspace.to_path_buf()),
+        title: Some("Why does `cargo test` fail on main?".to_string()),
+        started_at: Some(start),
+        ended_at: Some(start + 45_000),
+        messages,
+        metadata: serde_json::Value::Null,
+        source_path: PathBuf::new(),
+        model_name: None,
+        usage: None,
+        unrecognized: None,
+    }
+}
+
+/// Outcome of one conversion leg.
+#[derive(Debug, Clone, Serialize)]
+pub struct LegOutcome {
+    pub ok: bool,
+    /// Session ID written by this leg.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Read-back mismatches, when verification failed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<String>,
+}
+
+/// Origin → provider → origin for one provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundTrip {
+    pub provider: String,
+    pub outbound: LegOutcome,
+    /// `None` when the outbound leg failed.
+    pub inbound: Option<LegOutcome>,
+}
+
+/// Every round trip of one `selftest` run.
+#[derive(Debug, Clone)]
+pub struct SelftestRun {
+    /// Provider the session was seeded into and returns to.
+    pub origin_provider: String,
+    pub results: Vec<RoundTrip>,
+}
+
+impl RoundTrip {
+    pub fn passed(&self) -> bool {
+        self.outbound.ok && self.inbound.as_ref().is_some_and(|leg| leg.ok)
+    }
+}
+
+/// Seed `session` into its origin provider (`session.provider_slug`, or
+/// Claude Code when that provider is read-only) and round-trip it through
+/// every other writable provider.
+///
+/// Writes into the provider homes of the current environment; only call
+/// this inside a sandbox built from [`sandbox_env`].
+pub fn run_round_trips(session: &CanonicalSession) -> anyhow::Result<SelftestRun> {
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::default_registry(),
+    };
+    let origin = pipeline
+        .registry
+        .find_by_slug(&session.provider_slug)
+        .filter(|p| p.writable())
+        .or_else(|| pipeline.registry.find_by_slug("claude-code"))
+        .ok_or_else(|| CasrError::UnknownProviderAlias {
+            alias: session.provider_slug.clone(),
+            known_aliases: pipeline.registry.known_aliases(),
+        })?;
+    let seed = origin.write_session(
+        session,
+        &WriteOptions {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+
+    let leg = |target: &str, session_id: &str, source: &str| {
+        let opts = ConvertOptions {
+            force: true,
+            source_hint: Some(source.to_string()),
+            ..ConvertOptions::default()
+        };
+        match pipeline.convert(target, session_id, opts) {
+            Ok(result) => LegOutcome {
+                ok: true,
+                session_id: result.written.map(|w| w.session_id),
+                error: None,
+                mismatches: Vec::new(),
+            },
+            Err(e) => LegOutcome {
+                ok: false,
+                session_id: None,
+                mismatches: match e.downcast_ref::<CasrError>() {
+                    Some(CasrError::VerifyFailed { mismatches, .. }) => mismatches.clone(),
+                    _ => Vec::new(),
+                },
+                error: Some(e.to_string()),
+            },
+        }
+    };
+
+    let origin_slug = origin.slug();
+    let results = pipeline
+        .registry
+        .all_providers()
+        .into_iter()
+        .filter(|p| p.writable() && p.slug() != origin_slug)
+        .map(|p| {
+            let outbound = leg(p.slug(), &seed.session_id, origin_slug);
+            let inbound = outbound
+                .session_id
+                .as_deref()
+                .map(|id| leg(origin_slug, id, p.slug()));
+            RoundTrip {
+                provider: p.slug().to_string(),
+                outbound,
+                inbound,
+            }
+        })
+        .collect();
+    Ok(SelftestRun {
+        origin_provider: origin_slug.to_string(),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_env_stays_under_root() {
+        let root = Path::new("/tmp/casr-selftest-x");
+        let env = sandbox_env(root);
+        assert_eq!(env.len(), SANDBOX_HOMES.len());
+        assert!(env.iter().all(|(_, dir)| dir.starts_with(root)));
+        assert!(env.iter().any(|(var, _)| *var == "HOME"));
+    }
+
+    #[test]
+    fn synthetic_session_is_valid_and_pairs_its_tool_call() {
+        let session = synthetic_session(Path::new("/tmp/ws"));
+        let validation = crate::pipeline::validate_session(&session);
+        assert!(!validation.has_errors(), "{:?}", validation.errors);
+        let call = &session.messages[1].tool_calls[0];
+        assert_eq!(session.messages[2].tool_results[0].call_id, call.id);
+    }
+}
@@ -0,0 +1,470 @@
+//! Usage analytics across sessions (`casr stats`).
+//!
+//! The CLI reads every discoverable session, reduces each to a
+//! [`SessionRecord`], filters them, and hands them to [`summarize`], which
+//! builds the [`StatsResponse`] printed as tables or JSON.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+
+use crate::model::{CanonicalSession, MessageRole};
+use crate::responses::{
+    ModelStats, ProviderStats, SCHEMA_VERSION, StatsResponse, ToolStats, WeekStats, WorkspaceStats,
+};
+
+/// Calls and errors for one tool name within a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToolTally {
+    pub calls: usize,
+    pub errors: usize,
+}
+
+/// The per-session facts `casr stats` aggregates.
+#[derive(Debug, Clone, Default)]
+pub struct SessionRecord {
+    pub provider: String,
+    pub workspace: Option<PathBuf>,
+    pub model_name: Option<String>,
+    pub started_at: Option<i64>,
+    /// Last conversation timestamp (not file mtime), for session duration.
+    pub ended_at: Option<i64>,
+    /// Latest activity including file mtime, when nothing else dates the session.
+    pub last_active_at: Option<i64>,
+    pub messages: usize,
+    /// Tool calls, including ones only visible in the raw source file.
+    pub tool_uses: usize,
+    pub tool_results: usize,
+    pub tool_errors: usize,
+    /// Per-name tallies from the canonical tool calls; errors are attributed
+    /// through `ToolResult::call_id`.
+    pub tools: BTreeMap<String, ToolTally>,
+}
+
+impl SessionRecord {
+    /// Reduce `session` to a record. `tool_uses` is the caller's tool count
+    /// (which may include calls the reader did not surface).
+    pub fn new(
+        provider: &str,
+        session: &CanonicalSession,
+        last_active_at: Option<i64>,
+        tool_uses: usize,
+    ) -> Self {
+        let mut tools: BTreeMap<String, ToolTally> = BTreeMap::new();
+        let mut names_by_id: HashMap<&str, &str> = HashMap::new();
+        for msg in &session.messages {
+            for call in &msg.tool_calls {
+                tools.entry(call.name.clone()).or_default().calls += 1;
+                if let Some(ref id) = call.id {
+                    names_by_id.insert(id, &call.name);
+                }
+            }
+        }
+
+        let mut tool_results = 0;
+        let mut tool_errors = 0;
+        for result in session.messages.iter().flat_map(|m| &m.tool_results) {
+            tool_results += 1;
+            if !result.is_error {
+                continue;
+            }
+            tool_errors += 1;
+            if let Some(name) = result.call_id.as_deref().and_then(|id| names_by_id.get(id))
+                && let Some(tally) = tools.get_mut(*name)
+            {
+                tally.errors += 1;
+            }
+        }
+
+        Self {
+            provider: provider.to_string(),
+            workspace: session.workspace.clone(),
+            model_name: session.model_name.clone(),
+            started_at: session.started_at,
+            ended_at: session
+                .ended_at
+                .or_else(|| session.messages.iter().filter_map(|m| m.timestamp).max()),
+            last_active_at,
+            messages: session
+                .messages
+                .iter()
+                .filter(|m| m.role != MessageRole::System)
+                .count(),
+            tool_uses,
+            tool_results,
+            tool_errors,
+            tools,
+        }
+    }
+
+    /// When the session happened, for time filters and weekly buckets.
+    pub fn time(&self) -> Option<i64> {
+        self.started_at.or(self.last_active_at)
+    }
+
+    fn duration_secs(&self) -> Option<f64> {
+        let (start, end) = (self.started_at?, self.ended_at?);
+        (end >= start).then(|| (end - start) as f64 / 1000.0)
+    }
+}
+
+/// Parse a `--since`/`--until` bound into epoch millis.
+///
+/// Accepts a relative age (`90m`, `12h`, `7d`, `4w`, counted back from
+/// `now_millis`), a date (`2026-01-31`, UTC), or any timestamp
+/// [`parse_timestamp`](crate::model::parse_timestamp) understands. With
+/// `end_of_day`, a bare date means the end of that day, so `--until
+/// 2026-01-31` includes the 31st.
+pub fn parse_time_bound(value: &str, now_millis: i64, end_of_day: bool) -> anyhow::Result<i64> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic())
+        && let Ok(count) = value[..value.len() - 1].parse::<i64>()
+    {
+        let unit_millis = match unit {
+            'm' => 60_000,
+            'h' => 3_600_000,
+            'd' => 86_400_000,
+            'w' => 7 * 86_400_000,
+            _ => anyhow::bail!("unknown time unit '{unit}' in '{value}' (use m, h, d, or w)"),
+        };
+        return Ok(now_millis.saturating_sub(count.saturating_mul(unit_millis)));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let day = if end_of_day {
+            date.succ_opt().unwrap_or(date)
+        } else {
+            date
+        };
+        let millis = Utc
+            .from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .timestamp_millis();
+        return Ok(if end_of_day { millis - 1 } else { millis });
+    }
+    crate::model::parse_timestamp(&serde_json::Value::String(value.to_string())).ok_or_else(|| {
+        anyhow::anyhow!("invalid time '{value}' (expected e.g. 7d, 2026-01-31, or RFC 3339)")
+    })
+}
+
+/// ISO week label (`2026-W03`) for an epoch-millis timestamp.
+pub fn iso_week_label(millis: i64) -> Option<String> {
+    let week = chrono::DateTime::<Utc>::from_timestamp_millis(millis)?.iso_week();
+    Some(format!("{}-W{:02}", week.year(), week.week()))
+}
+
+/// Aggregate `records` into the `stats` report.
+pub fn summarize(
+    records: &[SessionRecord],
+    since: Option<i64>,
+    until: Option<i64>,
+) -> StatsResponse {
+    let mut providers: BTreeMap<&str, (ProviderStats, Vec<f64>)> = BTreeMap::new();
+    let mut weeks: BTreeMap<String, WeekStats> = BTreeMap::new();
+    let mut tools: BTreeMap<&str, ToolTally> = BTreeMap::new();
+    let mut workspaces: BTreeMap<Option<String>, WorkspaceStats> = BTreeMap::new();
+    let mut models: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+    let mut lengths: Vec<f64> = Vec::with_capacity(records.len());
+    let mut durations: Vec<f64> = Vec::new();
+
+    let mut report = StatsResponse {
+        schema_version: SCHEMA_VERSION,
+        since,
+        until,
+        sessions: records.len(),
+        ..StatsResponse::default()
+    };
+
+    for record in records {
+        report.messages += record.messages;
+        report.tool_calls += record.tool_uses;
+        report.tool_results += record.tool_results;
+        report.tool_errors += record.tool_errors;
+        lengths.push(record.messages as f64);
+        durations.extend(record.duration_secs());
+
+        let (entry, provider_lengths) = providers.entry(&record.provider).or_insert_with(|| {
+            (
+                ProviderStats {
+                    provider: record.provider.clone(),
+                    sessions: 0,
+                    messages: 0,
+                    median_messages: None,
+                    tool_calls: 0,
+                    tool_errors: 0,
+                },
+                Vec::new(),
+            )
+        });
+        entry.sessions += 1;
+        entry.messages += record.messages;
+        entry.tool_calls += record.tool_uses;
+        entry.tool_errors += record.tool_errors;
+        provider_lengths.push(record.messages as f64);
+
+        if let Some(week) = record.time().and_then(iso_week_label) {
+            let bucket = weeks.entry(week.clone()).or_insert_with(|| WeekStats {
+                week,
+                sessions: 0,
+                providers: BTreeMap::new(),
+            });
+            bucket.sessions += 1;
+            *bucket.providers.entry(record.provider.clone()).or_default() += 1;
+        }
+
+        for (name, tally) in &record.tools {
+            let total = tools.entry(name).or_default();
+            total.calls += tally.calls;
+            total.errors += tally.errors;
+        }
+
+        let workspace = record.workspace.as_ref().map(|w| w.display().to_string());
+        let ws = workspaces
+            .entry(workspace.clone())
+            .or_insert_with(|| WorkspaceStats {
+                workspace,
+                sessions: 0,
+                messages: 0,
+            });
+        ws.sessions += 1;
+        ws.messages += record.messages;
+
+        *models.entry(record.model_name.as_deref()).or_default() += 1;
+    }
+
+    report.median_messages = median(&mut lengths);
+    report.median_duration_secs = median(&mut durations);
+    report.tool_error_rate =
+        (report.tool_results > 0).then(|| report.tool_errors as f64 / report.tool_results as f64);
+
+    report.providers = providers
+        .into_values()
+        .map(|(mut stats, mut lengths)| {
+            stats.median_messages = median(&mut lengths);
+            stats
+        })
+        .collect();
+    report
+        .providers
+        .sort_by_key(|p| std::cmp::Reverse(p.sessions));
+    report.weeks = weeks.into_values().collect();
+    report.tools = tools
+        .into_iter()
+        .map(|(name, tally)| ToolStats {
+            name: name.to_string(),
+            calls: tally.calls,
+            errors: tally.errors,
+        })
+        .collect();
+    report.tools.sort_by_key(|t| std::cmp::Reverse(t.calls));
+    report.workspaces = workspaces.into_values().collect();
+    report.workspaces.sort_by(|a, b| {
+        b.sessions
+            .cmp(&a.sessions)
+            .then(b.messages.cmp(&a.messages))
+    });
+    report.models = models
+        .into_iter()
+        .map(|(model, sessions)| ModelStats {
+            model: model.map(String::from),
+            sessions,
+        })
+        .collect();
+    report.models.sort_by_key(|m| std::cmp::Reverse(m.sessions));
+    report
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CanonicalMessage, ToolCall, ToolResult};
+
+    fn msg(role: MessageRole) -> CanonicalMessage {
+        CanonicalMessage {
+            idx: 0,
+            role,
+            content: "x".to_string(),
+            parts: Vec::new(),
+            timestamp: None,
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
+            extra: serde_json::Value::Null,
+        }
+    }
+
+    fn session(messages: Vec<CanonicalMessage>) -> CanonicalSession {
+        CanonicalSession {
+            session_id: "s".to_string(),
+            provider_slug: "claude-code".to_string(),
+            workspace: Some(PathBuf::from("/data/app")),
+            title: None,
+            started_at: Some(1_768_000_000_000),
+            ended_at: None,
+            messages,
+            metadata: serde_json::Value::Null,
+            source_path: PathBuf::from("/tmp/s.jsonl"),
+            model_name: Some("claude-sonnet-4-5".to_string()),
+            usage: None,
+            unrecognized: None,
+        }
+    }
+
+    fn record(provider: &str, messages: usize, started_at: i64) -> SessionRecord {
+        SessionRecord {
+            provider: provider.to_string(),
+            started_at: Some(started_at),
+            messages,
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn record_attributes_tool_errors_by_call_id() {
+        let mut call = msg(MessageRole::Assistant);
+        call.tool_calls = vec![
+            ToolCall::new(
+                Some("a".to_string()),
+                "Bash".to_string(),
+                serde_json::Value::Null,
+            ),
+            ToolCall::new(
+                Some("b".to_string()),
+                "Read".to_string(),
+                serde_json::Value::Null,
+            ),
+        ];
+        let mut results = msg(MessageRole::User);
+        results.tool_results = vec![
+            ToolResult {
+                call_id: Some("a".to_string()),
+                content: "boom".to_string(),
+                is_error: true,
+            },
+            ToolResult {
+                call_id: Some("b".to_string()),
+                content: "ok".to_string(),
+                is_error: false,
+            },
+        ];
+        let mut s = session(vec![msg(MessageRole::System), call, results]);
+        s.ended_at = Some(1_768_000_060_000);
+        let r = SessionRecord::new("claude-code", &s, Some(1_769_000_000_000), 2);
+
+        assert_eq!(r.messages, 2, "system messages are not counted");
+        assert_eq!((r.tool_results, r.tool_errors), (2, 1));
+        assert_eq!(
+            r.tools["Bash"],
+            ToolTally {
+                calls: 1,
+                errors: 1
+            }
+        );
+        assert_eq!(
+            r.tools["Read"],
+            ToolTally {
+                calls: 1,
+                errors: 0
+            }
+        );
+        assert_eq!(r.duration_secs(), Some(60.0));
+    }
+
+    #[test]
+    fn summarize_groups_by_provider_week_and_model() {
+        // 2026-01-05 (Mon, W02) and 2026-01-12 (Mon, W03).
+        let w02 = 1_767_571_200_000;
+        let w03 = w02 + 7 * 86_400_000;
+        let mut a = record("codex", 4, w02);
+        a.model_name = Some("gpt-5".to_string());
+        a.tools.insert(
+            "shell".to_string(),
+            ToolTally {
+                calls: 3,
+                errors: 1,
+            },
+        );
+        a.tool_uses = 3;
+        a.tool_results = 3;
+        a.tool_errors = 1;
+        let b = record("codex", 10, w03);
+        let mut c = record("claude-code", 7, w03);
+        c.workspace = Some(PathBuf::from("/data/app"));
+
+        let report = summarize(&[a, b, c], None, None);
+        assert_eq!(report.sessions, 3);
+        assert_eq!(report.messages, 21);
+        assert_eq!(report.median_messages, Some(7.0));
+        assert_eq!(report.tool_error_rate, Some(1.0 / 3.0));
+        assert_eq!(report.providers[0].provider, "codex");
+        assert_eq!(report.providers[0].median_messages, Some(7.0));
+        let weeks: Vec<&str> = report.weeks.iter().map(|w| w.week.as_str()).collect();
+        assert_eq!(weeks, vec!["2026-W02", "2026-W03"]);
+        assert_eq!(report.weeks[1].providers["claude-code"], 1);
+        assert_eq!(report.tools[0].name, "shell");
+        assert_eq!(report.workspaces[0].workspace, None);
+        assert_eq!(report.workspaces[0].sessions, 2);
+        assert_eq!(report.models[0].model, None);
+        assert_eq!(report.models[0].sessions, 2);
+    }
+
+    #[test]
+    fn summarize_empty_has_no_medians() {
+        let report = summarize(&[], Some(1), None);
+        assert_eq!(report.sessions, 0);
+        assert_eq!(report.median_messages, None);
+        assert_eq!(report.tool_error_rate, None);
+        assert_eq!(report.since, Some(1));
+    }
+
+    #[test]
+    fn parse_time_bound_forms() {
+        let now = 1_768_000_000_000;
+        assert_eq!(
+            parse_time_bound("7d", now, false).unwrap(),
+            now - 7 * 86_400_000
+        );
+        assert_eq!(
+            parse_time_bound("2w", now, false).unwrap(),
+            now - 14 * 86_400_000
+        );
+        let start = parse_time_bound("2026-01-05", now, false).unwrap();
+        assert_eq!(start, 1_767_571_200_000);
+        let end = parse_time_bound("2026-01-05", now, true).unwrap();
+        assert_eq!(end, start + 86_400_000 - 1);
+        assert_eq!(
+            parse_time_bound("2026-01-05T00:00:00Z", now, true).unwrap(),
+            start
+        );
+        assert!(parse_time_bound("7y", now, false).is_err());
+        assert!(parse_time_bound("last tuesday", now, false).is_err());
+    }
+
+    #[test]
+    fn iso_week_crosses_year_boundary() {
+        // 2026-01-01 is a Thursday in ISO week 2026-W01; 2024-12-30 is 2025-W01.
+        assert_eq!(
+            iso_week_label(1_767_225_600_000).as_deref(),
+            Some("2026-W01")
+        );
+        assert_eq!(
+            iso_week_label(1_735_516_800_000).as_deref(),
+            Some("2025-W01")
+        );
+    }
+}
@@ -0,0 +1,1085 @@
+//! Cross-provider tool vocabulary.
+//!
+//! Every agent ships its own tool set: Claude Code calls `Bash`/`Read`/`Edit`,
+//! Codex calls `shell`/`apply_patch`/`update_plan`, Gemini calls
+//! `run_shell_command`/`read_file`/`replace`. Replaying one agent's calls
+//! verbatim into another leaves the target model looking at tools it has
+//! never been given.
+//!
+//! A [`ToolMap`] knows which native tool names mean the same thing (a
+//! [`ToolKind`]) and how their arguments are spelled. The pipeline uses it to
+//! rewrite each call into the target's vocabulary, emulating missing tools
+//! where there is a faithful equivalent (a file read as `cat` for Codex, an
+//! edit as an `apply_patch`), and rendering the rest as `[Tool: …]` text.
+//!
+//! The built-in table covers Claude Code, Codex, Gemini, OpenCode, Cline, and
+//! Pi-Agent. Targets without a vocabulary keep tool calls as they are. A JSON
+//! tool map extends or overrides the table:
+//!
+//! ```json
+//! {
+//!   "claude-code": {
+//!     "mcp__fs__read_text_file": { "kind": "read_file", "args": { "path": "path" } }
+//!   },
+//!   "gemini": { "run_shell_command": "shell" }
+//! }
+//! ```
+//!
+//! Each entry maps a native tool name to a kind, optionally with its argument
+//! names (native → canonical; see [`ToolKind`]). Later entries win when
+//! choosing the name to write.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::edits::{hunk_replacement, render_apply_patch, render_search_replace};
+use crate::model::{
+    CanonicalMessage, CanonicalSession, ContentPart, EditOp, FileEdit, ToolCall, ToolResult,
+};
+
+/// What a tool does, independent of provider naming.
+///
+/// Canonical argument names per kind:
+/// - `shell`: `command`, `workdir`, `timeout_ms`, `description`
+/// - `read_file`: `path`, `offset`, `limit`
+/// - `write_file`: `path`, `content`
+/// - `edit_file`: `path`, `old`, `new`, `replace_all`
+/// - `apply_patch`: `patch`
+/// - `search`: `pattern`, `path`, `glob`
+/// - `find_files`: `pattern`, `path`
+/// - `list_dir`: `path`
+/// - `web_fetch`: `url`, `prompt`
+/// - `web_search`: `query`
+/// - `plan`: `items` (`[{text, status}]`), `explanation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    Shell,
+    ReadFile,
+    WriteFile,
+    EditFile,
+    ApplyPatch,
+    Search,
+    FindFiles,
+    ListDir,
+    WebFetch,
+    WebSearch,
+    Plan,
+}
+
+/// How a native tool's argument values differ from the canonical shape,
+/// beyond key names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArgShape {
+    Plain,
+    /// `command` is an argv array (`["bash", "-lc", "…"]`).
+    Argv,
+    /// Edits are a `SEARCH`/`REPLACE` block in `diff` (Cline).
+    SearchReplace,
+    /// Plan items use this key for their text.
+    Todos(&'static str),
+}
+
+/// One native tool in a provider's vocabulary.
+#[derive(Debug, Clone)]
+struct ToolEntry {
+    /// Provider slug, or `*` for names recognized from any source.
+    provider: String,
+    name: String,
+    kind: ToolKind,
+    /// `(native, canonical)` argument names.
+    args: Vec<(String, String)>,
+    shape: ArgShape,
+}
+
+/// Counts reported by [`ToolMap::translate_session`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolTranslation {
+    /// Calls rewritten into the target's vocabulary.
+    pub translated: usize,
+    /// Names of calls rendered as text, with counts.
+    pub rendered: BTreeMap<String, usize>,
+}
+
+/// Provider tool vocabularies and the mapping between them.
+#[derive(Debug, Clone)]
+pub struct ToolMap {
+    entries: Vec<ToolEntry>,
+}
+
+impl Default for ToolMap {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// `(provider, native name, kind, [(native arg, canonical arg)])`.
+type BuiltinEntry = (
+    &'static str,
+    &'static str,
+    ToolKind,
+    &'static [(&'static str, &'static str)],
+);
+
+#[rustfmt::skip]
+const BUILTIN: &[BuiltinEntry] = &[
+    ("claude-code", "Bash", ToolKind::Shell, &[("command", "command"), ("description", "description"), ("timeout", "timeout_ms")]),
+    ("claude-code", "Read", ToolKind::ReadFile, &[("file_path", "path"), ("offset", "offset"), ("limit", "limit")]),
+    ("claude-code", "Write", ToolKind::WriteFile, &[("file_path", "path"), ("content", "content")]),
+    ("claude-code", "Edit", ToolKind::EditFile, &[("file_path", "path"), ("old_string", "old"), ("new_string", "new"), ("replace_all", "replace_all")]),
+    ("claude-code", "Grep", ToolKind::Search, &[("pattern", "pattern"), ("path", "path"), ("glob", "glob")]),
+    ("claude-code", "Glob", ToolKind::FindFiles, &[("pattern", "pattern"), ("path", "path")]),
+    ("claude-code", "LS", ToolKind::ListDir, &[("path", "path")]),
+    ("claude-code", "WebFetch", ToolKind::WebFetch, &[("url", "url"), ("prompt", "prompt")]),
+    ("claude-code", "WebSearch", ToolKind::WebSearch, &[("query", "query")]),
+    ("claude-code", "TodoWrite", ToolKind::Plan, &[("todos", "items")]),
+    ("codex", "exec_command", ToolKind::Shell, &[("cmd", "command"), ("workdir", "workdir")]),
+    ("codex", "shell", ToolKind::Shell, &[("command", "command"), ("workdir", "workdir"), ("timeout_ms", "timeout_ms")]),
+    ("codex", "apply_patch", ToolKind::ApplyPatch, &[("input", "patch")]),
+    ("codex", "update_plan", ToolKind::Plan, &[("plan", "items"), ("explanation", "explanation")]),
+    ("gemini", "run_shell_command", ToolKind::Shell, &[("command", "command"), ("directory", "workdir"), ("description", "description")]),
+    ("gemini", "read_file", ToolKind::ReadFile, &[("absolute_path", "path"), ("offset", "offset"), ("limit", "limit")]),
+    ("gemini", "write_file", ToolKind::WriteFile, &[("file_path", "path"), ("content", "content")]),
+    ("gemini", "replace", ToolKind::EditFile, &[("file_path", "path"), ("old_string", "old"), ("new_string", "new")]),
+    ("gemini", "search_file_content", ToolKind::Search, &[("pattern", "pattern"), ("path", "path"), ("include", "glob")]),
+    ("gemini", "glob", ToolKind::FindFiles, &[("pattern", "pattern"), ("path", "path")]),
+    ("gemini", "list_directory", ToolKind::ListDir, &[("path", "path")]),
+    ("gemini", "google_web_search", ToolKind::WebSearch, &[("query", "query")]),
+    ("gemini", "write_todos", ToolKind::Plan, &[("todos", "items")]),
+    ("opencode", "bash", ToolKind::Shell, &[("command", "command"), ("description", "description"), ("timeout", "timeout_ms")]),
+    ("opencode", "read", ToolKind::ReadFile, &[("filePath", "path"), ("offset", "offset"), ("limit", "limit")]),
+    ("opencode", "write", ToolKind::WriteFile, &[("filePath", "path"), ("content", "content")]),
+    ("opencode", "edit", ToolKind::EditFile, &[("filePath", "path"), ("oldString", "old"), ("newString", "new"), ("replaceAll", "replace_all")]),
+    ("opencode", "grep", ToolKind::Search, &[("pattern", "pattern"), ("path", "path"), ("include", "glob")]),
+    ("opencode", "glob", ToolKind::FindFiles, &[("pattern", "pattern"), ("path", "path")]),
+    ("opencode", "list", ToolKind::ListDir, &[("path", "path")]),
+    ("opencode", "webfetch", ToolKind::WebFetch, &[("url", "url")]),
+    ("opencode", "todowrite", ToolKind::Plan, &[("todos", "items")]),
+    ("cline", "execute_command", ToolKind::Shell, &[("command", "command")]),
+    ("cline", "read_file", ToolKind::ReadFile, &[("path", "path")]),
+    ("cline", "write_to_file", ToolKind::WriteFile, &[("path", "path"), ("content", "content")]),
+    ("cline", "replace_in_file", ToolKind::EditFile, &[("path", "path"), ("diff", "diff")]),
+    ("cline", "search_files", ToolKind::Search, &[("regex", "pattern"), ("path", "path"), ("file_pattern", "glob")]),
+    ("cline", "list_files", ToolKind::ListDir, &[("path", "path")]),
+    ("pi-agent", "bash", ToolKind::Shell, &[("command", "command"), ("timeout", "timeout_ms")]),
+    ("pi-agent", "read", ToolKind::ReadFile, &[("path", "path"), ("offset", "offset"), ("limit", "limit")]),
+    ("pi-agent", "write", ToolKind::WriteFile, &[("path", "path"), ("content", "content")]),
+    ("pi-agent", "edit", ToolKind::EditFile, &[("path", "path"), ("oldText", "old"), ("newText", "new")]),
+    ("pi-agent", "grep", ToolKind::Search, &[("pattern", "pattern"), ("path", "path"), ("glob", "glob")]),
+    ("pi-agent", "find", ToolKind::FindFiles, &[("pattern", "pattern"), ("path", "path")]),
+    ("pi-agent", "ls", ToolKind::ListDir, &[("path", "path")]),
+];
+
+fn builtin_shape(provider: &str, name: &str) -> ArgShape {
+    match (provider, name) {
+        ("codex", "shell") => ArgShape::Argv,
+        ("cline", "replace_in_file") => ArgShape::SearchReplace,
+        ("claude-code", "TodoWrite") | ("opencode", "todowrite") => ArgShape::Todos("content"),
+        ("codex", "update_plan") => ArgShape::Todos("step"),
+        ("gemini", "write_todos") => ArgShape::Todos("description"),
+        _ => ArgShape::Plain,
+    }
+}
+
+/// A tool-map file entry: a bare kind, or a kind with argument names.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigEntry {
+    Kind(ToolKind),
+    Full {
+        kind: ToolKind,
+        #[serde(default)]
+        args: BTreeMap<String, String>,
+    },
+}
+
+impl ToolMap {
+    /// The built-in vocabularies.
+    pub fn builtin() -> Self {
+        let entries = BUILTIN
+            .iter()
+            .map(|(provider, name, kind, args)| ToolEntry {
+                provider: provider.to_string(),
+                name: name.to_string(),
+                kind: *kind,
+                args: args
+                    .iter()
+                    .map(|(n, c)| (n.to_string(), c.to_string()))
+                    .collect(),
+                shape: builtin_shape(provider, name),
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The built-in vocabularies extended by the tool map file at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read tool map {}", path.display()))?;
+        let config: BTreeMap<String, BTreeMap<String, ConfigEntry>> =
+            serde_json::from_str(&text)
+                .with_context(|| format!("invalid tool map {}", path.display()))?;
+        let mut map = Self::builtin();
+        for (provider, tools) in config {
+            for (name, entry) in tools {
+                let (kind, args) = match entry {
+                    ConfigEntry::Kind(kind) => (kind, BTreeMap::new()),
+                    ConfigEntry::Full { kind, args } => (kind, args),
+                };
+                map.entries.push(ToolEntry {
+                    provider: provider.clone(),
+                    name,
+                    kind,
+                    args: args.into_iter().collect(),
+                    shape: ArgShape::Plain,
+                });
+            }
+        }
+        Ok(map)
+    }
+
+    /// Unfinished items of the latest plan/todo call in `session` (read in
+    /// `provider`'s vocabulary), as `(text, status)` pairs.
+    pub fn open_todos(&self, provider: &str, session: &CanonicalSession) -> Vec<(String, String)> {
+        let latest_plan = session
+            .messages
+            .iter()
+            .rev()
+            .flat_map(|m| m.tool_calls.iter().rev())
+            .find_map(|call| {
+                self.recognize(provider, &call.name)
+                    .filter(|entry| entry.kind == ToolKind::Plan)
+                    .map(|entry| to_canonical(entry, &call.arguments))
+            });
+        let Some(Value::Array(items)) = latest_plan.and_then(|mut args| args.remove("items"))
+        else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter_map(|item| {
+                let text = item.get("text")?.as_str()?.to_string();
+                let status = item
+                    .get("status")
+                    .and_then(Value::as_str)
+                    .unwrap_or("pending")
+                    .to_string();
+                (status != "completed").then_some((text, status))
+            })
+            .collect()
+    }
+
+    /// Whether `provider` has a tool vocabulary to translate into.
+    pub fn has_vocabulary(&self, provider: &str) -> bool {
+        self.entries.iter().any(|e| e.provider == provider)
+    }
+
+    /// The entry describing tool `name` as called from `provider`, falling
+    /// back to any provider's tool of that name.
+    fn recognize(&self, provider: &str, name: &str) -> Option<&ToolEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| (e.provider == provider || e.provider == "*") && e.name == name)
+            .or_else(|| self.entries.iter().rev().find(|e| e.name == name))
+    }
+
+    /// The tool `provider` uses for `kind`.
+    fn native(&self, provider: &str, kind: ToolKind) -> Option<&ToolEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.provider == provider && e.kind == kind)
+    }
+
+    /// Rewrite one call into `target`'s vocabulary. `None` when the target
+    /// has no equivalent.
+    pub fn translate_call(&self, source: &str, target: &str, call: &ToolCall) -> Option<ToolCall> {
+        let names = |provider: &str| {
+            self.entries
+                .iter()
+                .any(|e| e.provider == provider && e.name == call.name)
+        };
+        if names(target) && !names(source) {
+            // Already in the target's vocabulary (e.g. a session converted before).
+            return Some(call.clone());
+        }
+        let entry = self.recognize(source, &call.name);
+        let edits_call = entry.is_none_or(|e| {
+            matches!(
+                e.kind,
+                ToolKind::WriteFile | ToolKind::EditFile | ToolKind::ApplyPatch
+            )
+        });
+        let (name, arguments) = if edits_call && !call.edits.is_empty() {
+            self.edits_to_native(target, &call.edits)?
+        } else {
+            let entry = entry?;
+            let canonical = to_canonical(entry, &call.arguments);
+            self.to_native(target, entry.kind, &canonical)?
+        };
+        Some(ToolCall::new(call.id.clone(), name, arguments))
+    }
+
+    /// Re-express parsed file edits with `target`'s edit tools: a single
+    /// write or replacement maps onto its write/edit tool, anything else
+    /// onto `apply_patch` where the target has one.
+    fn edits_to_native(&self, target: &str, edits: &[FileEdit]) -> Option<(String, Value)> {
+        if let [edit] = edits
+            && edit.move_to.is_none()
+        {
+            let mut args = Map::new();
+            args.insert("path".into(), json!(edit.path));
+            let kind = match &edit.op {
+                EditOp::Create { content } => {
+                    args.insert("content".into(), json!(content));
+                    Some(ToolKind::WriteFile)
+                }
+                EditOp::Replace {
+                    old,
+                    new,
+                    replace_all,
+                } => {
+                    args.insert("old".into(), json!(old));
+                    args.insert("new".into(), json!(new));
+                    if *replace_all {
+                        args.insert("replace_all".into(), json!(true));
+                    }
+                    Some(ToolKind::EditFile)
+                }
+                EditOp::Hunks { hunks } => hunk_replacement(hunks).map(|(old, new)| {
+                    args.insert("old".into(), json!(old));
+                    args.insert("new".into(), json!(new));
+                    ToolKind::EditFile
+                }),
+                EditOp::Delete => None,
+            };
+            if let Some(entry) = kind.and_then(|kind| self.native(target, kind)) {
+                return Some((entry.name.clone(), from_canonical(entry, &args)));
+            }
+        }
+        let entry = self.native(target, ToolKind::ApplyPatch)?;
+        let mut canonical = Map::new();
+        canonical.insert("patch".into(), json!(render_apply_patch(edits)));
+        Some((entry.name.clone(), from_canonical(entry, &canonical)))
+    }
+
+    /// Build the `target` call for a canonical `kind` + arguments, emulating
+    /// missing tools where a faithful equivalent exists.
+    fn to_native(
+        &self,
+        target: &str,
+        kind: ToolKind,
+        args: &Map<String, Value>,
+    ) -> Option<(String, Value)> {
+        if let Some(entry) = self.native(target, kind) {
+            return Some((entry.name.clone(), from_canonical(entry, args)));
+        }
+        match kind {
+            ToolKind::ReadFile | ToolKind::Search | ToolKind::FindFiles | ToolKind::ListDir => {
+                let command = shell_equivalent(kind, args)?;
+                let mut shell = Map::new();
+                shell.insert("command".into(), Value::String(command));
+                self.to_native(target, ToolKind::Shell, &shell)
+            }
+            _ => None,
+        }
+    }
+
+    /// Rewrite every tool call in `session` from `source`'s vocabulary into
+    /// `target`'s. Calls with no equivalent, and their results, become
+    /// `[Tool: …]` / `[Tool Output]` text. No-op for targets without a
+    /// vocabulary.
+    pub fn translate_session(
+        &self,
+        session: &mut CanonicalSession,
+        source: &str,
+        target: &str,
+    ) -> ToolTranslation {
+        let mut report = ToolTranslation::default();
+        if !self.has_vocabulary(target) {
+            return report;
+        }
+
+        let mut rendered_ids: HashMap<String, String> = HashMap::new();
+        let call_outcomes: Vec<Vec<Result<ToolCall, String>>> = session
+            .messages
+            .iter()
+            .map(|msg| {
+                msg.tool_calls
+                    .iter()
+                    .map(|call| match self.translate_call(source, target, call) {
+                        Some(translated) => {
+                            if translated != *call {
+                                report.translated += 1;
+                            }
+                            Ok(translated)
+                        }
+                        None => {
+                            *report.rendered.entry(call.name.clone()).or_default() += 1;
+                            if let Some(ref id) = call.id {
+                                rendered_ids.insert(id.clone(), call.name.clone());
+                            }
+                            Err(render_call(call))
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        if report.rendered.is_empty() {
+            for (msg, calls) in session.messages.iter_mut().zip(call_outcomes) {
+                msg.tool_calls = calls.into_iter().flatten().collect();
+            }
+            return report;
+        }
+
+        for (msg, calls) in session.messages.iter_mut().zip(call_outcomes) {
+            let results: Vec<Result<ToolResult, String>> = msg
+                .tool_results
+                .iter()
+                .map(|result| {
+                    match result
+                        .call_id
+                        .as_ref()
+                        .filter(|id| rendered_ids.contains_key(*id))
+                    {
+                        Some(_) => Err(render_result(result)),
+                        None => Ok(result.clone()),
+                    }
+                })
+                .collect();
+            apply_outcomes(msg, calls, results);
+        }
+        report
+    }
+}
+
+/// Replace rendered calls/results with text, keeping the block order when
+/// the message's parts are current.
+fn apply_outcomes(
+    msg: &mut CanonicalMessage,
+    calls: Vec<Result<ToolCall, String>>,
+    results: Vec<Result<ToolResult, String>>,
+) {
+    fn split<T>(outcomes: Vec<Result<T, String>>) -> (Vec<T>, Vec<Result<usize, String>>) {
+        let mut kept = Vec::new();
+        let mut positions = Vec::new();
+        for outcome in outcomes {
+            positions.push(outcome.map(|item| {
+                kept.push(item);
+                kept.len() - 1
+            }));
+        }
+        (kept, positions)
+    }
+
+    let parts_current = msg.ordered_parts("\n").is_some();
+    let (kept_calls, call_pos) = split(calls);
+    let (kept_results, result_pos) = split(results);
+    msg.tool_calls = kept_calls;
+    msg.tool_results = kept_results;
+    if call_pos.iter().chain(&result_pos).all(Result::is_ok) {
+        return;
+    }
+
+    if parts_current {
+        let remap = |pos: &Result<usize, String>, part: fn(usize) -> ContentPart| match pos {
+            Ok(index) => part(*index),
+            Err(text) => ContentPart::Text { text: text.clone() },
+        };
+        msg.parts = std::mem::take(&mut msg.parts)
+            .into_iter()
+            .map(|part| match part {
+                ContentPart::ToolCall { index } => {
+                    remap(&call_pos[index], |index| ContentPart::ToolCall { index })
+                }
+                ContentPart::ToolResult { index } => remap(&result_pos[index], |index| {
+                    ContentPart::ToolResult { index }
+                }),
+                other => other,
+            })
+            .collect();
+        msg.content = msg
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    } else {
+        let mut lines: Vec<String> = Vec::new();
+        if !msg.content.is_empty() {
+            lines.push(std::mem::take(&mut msg.content));
+        }
+        lines.extend(
+            call_pos
+                .into_iter()
+                .chain(result_pos)
+                .filter_map(Result::err),
+        );
+        msg.content = lines.join("\n");
+        msg.parts.clear();
+    }
+}
+
+fn render_call(call: &ToolCall) -> String {
+    let args = match &call.arguments {
+        Value::Null => String::new(),
+        Value::Object(obj) if obj.is_empty() => String::new(),
+        Value::String(s) => format!(" {s}"),
+        other => format!(" {other}"),
+    };
+    format!("[Tool: {}]{args}", call.name)
+}
+
+fn render_result(result: &ToolResult) -> String {
+    if result.is_error {
+        format!("[Tool Error] {}", result.content)
+    } else {
+        format!("[Tool Output] {}", result.content)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Argument conversion
+// ---------------------------------------------------------------------------
+
+fn to_canonical(entry: &ToolEntry, args: &Value) -> Map<String, Value> {
+    let mut out = Map::new();
+    match args {
+        Value::Object(obj) if entry.args.is_empty() => out = obj.clone(),
+        Value::Object(obj) => {
+            for (native, canonical) in &entry.args {
+                if let Some(v) = obj.get(native) {
+                    out.insert(canonical.clone(), v.clone());
+                }
+            }
+        }
+        // Freeform tools (Codex `apply_patch` as a custom tool) pass a bare string.
+        Value::String(s) => {
+            if let Some((_, canonical)) = entry.args.first() {
+                out.insert(canonical.clone(), Value::String(s.clone()));
+            }
+        }
+        _ => {}
+    }
+
+    match &entry.shape {
+        ArgShape::Argv => {
+            if let Some(Value::Array(argv)) = out.get("command") {
+                let command = argv_to_command(argv);
+                out.insert("command".into(), Value::String(command));
+            }
+        }
+        ArgShape::Todos(text_key) => {
+            if let Some(Value::Array(items)) = out.get("items") {
+                let items = items
+                    .iter()
+                    .map(|item| {
+                        json!({
+                            "text": item.get(*text_key).cloned().unwrap_or(Value::Null),
+                            "status": item.get("status").cloned().unwrap_or(Value::Null),
+                        })
+                    })
+                    .collect();
+                out.insert("items".into(), Value::Array(items));
+            }
+        }
+        // Cline diffs are read through the call's parsed `edits`.
+        ArgShape::SearchReplace | ArgShape::Plain => {}
+    }
+    out
+}
+
+fn from_canonical(entry: &ToolEntry, canonical: &Map<String, Value>) -> Value {
+    let mut canonical = canonical.clone();
+    match &entry.shape {
+        ArgShape::Argv => {
+            if let Some(Value::String(command)) = canonical.get("command") {
+                let argv = json!(["bash", "-lc", command]);
+                canonical.insert("command".into(), argv);
+            }
+        }
+        ArgShape::SearchReplace => {
+            let old = canonical.get("old").and_then(Value::as_str).unwrap_or("");
+            let new = canonical.get("new").and_then(Value::as_str).unwrap_or("");
+            let diff = render_search_replace(old, new);
+            canonical.insert("diff".into(), Value::String(diff));
+        }
+        ArgShape::Todos(text_key) => {
+            if let Some(Value::Array(items)) = canonical.get("items") {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        let text = item.get("text").cloned().unwrap_or(Value::Null);
+                        let status = item.get("status").cloned().unwrap_or(json!("pending"));
+                        let mut native = json!({ *text_key: text, "status": status });
+                        match (entry.provider.as_str(), *text_key) {
+                            ("claude-code", _) => native["activeForm"] = text,
+                            ("opencode", _) => {
+                                native["id"] = json!((i + 1).to_string());
+                                native["priority"] = json!("medium");
+                            }
+                            _ => {}
+                        }
+                        native
+                    })
+                    .collect();
+                canonical.insert("items".into(), Value::Array(items));
+            }
+        }
+        ArgShape::Plain => {}
+    }
+
+    if entry.args.is_empty() {
+        return Value::Object(canonical);
+    }
+    let mut out = Map::new();
+    for (native, canonical_key) in &entry.args {
+        if let Some(v) = canonical.get(canonical_key) {
+            out.insert(native.clone(), v.clone());
+        }
+    }
+    Value::Object(out)
+}
+
+/// `["bash", "-lc", "ls -la"]` → `ls -la`; other argv arrays are shell-quoted.
+fn argv_to_command(argv: &[Value]) -> String {
+    let words: Vec<&str> = argv.iter().filter_map(Value::as_str).collect();
+    match words.as_slice() {
+        [shell, flag, script]
+            if matches!(
+                *shell,
+                "bash" | "sh" | "zsh" | "/bin/bash" | "/bin/sh" | "/bin/zsh"
+            ) && matches!(*flag, "-c" | "-lc") =>
+        {
+            script.to_string()
+        }
+        _ => words
+            .iter()
+            .map(|w| sh_quote(w))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn sh_quote(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// The shell command an agent with only a shell would run for `kind`.
+fn shell_equivalent(kind: ToolKind, args: &Map<String, Value>) -> Option<String> {
+    let arg = |key: &str| args.get(key).and_then(Value::as_str);
+    let number = |key: &str| args.get(key).and_then(Value::as_u64);
+    let path_suffix = arg("path")
+        .map(|p| format!(" {}", sh_quote(p)))
+        .unwrap_or_default();
+    Some(match kind {
+        ToolKind::ReadFile => {
+            let path = sh_quote(arg("path")?);
+            match (number("offset"), number("limit")) {
+                (None, None) => format!("cat {path}"),
+                (offset, limit) => {
+                    let start = offset.unwrap_or(1).max(1);
+                    let end = limit.map(|l| (start + l - 1).to_string());
+                    format!("sed -n '{start},{}p' {path}", end.as_deref().unwrap_or("$"))
+                }
+            }
+        }
+        ToolKind::Search => {
+            let glob = arg("glob")
+                .map(|g| format!(" -g {}", sh_quote(g)))
+                .unwrap_or_default();
+            format!("rg -n{glob} {}{path_suffix}", sh_quote(arg("pattern")?))
+        }
+        ToolKind::FindFiles => {
+            format!("rg --files -g {}{path_suffix}", sh_quote(arg("pattern")?))
+        }
+        ToolKind::ListDir => format!("ls -la{path_suffix}"),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::MessageRole;
+    use std::path::PathBuf;
+
+    fn call(name: &str, arguments: Value) -> ToolCall {
+        ToolCall::new(Some("c1".to_string()), name.to_string(), arguments)
+    }
+
+    fn msg(role: MessageRole, content: &str) -> CanonicalMessage {
+        CanonicalMessage {
+            idx: 0,
+            role,
+            content: content.to_string(),
+            parts: Vec::new(),
+            timestamp: None,
+            author: None,
+            reasoning: Vec::new(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            attachments: Vec::new(),
+            usage: None,
+            extra: Value::Null,
+        }
+    }
+
+    fn session(messages: Vec<CanonicalMessage>) -> CanonicalSession {
+        CanonicalSession {
+            session_id: "s".to_string(),
+            provider_slug: "claude-code".to_string(),
+            workspace: None,
+            title: None,
+            started_at: None,
+            ended_at: None,
+            messages,
+            metadata: Value::Null,
+            source_path: PathBuf::from("/tmp/s.jsonl"),
+            model_name: None,
+            usage: None,
+            unrecognized: None,
+        }
+    }
+
+    #[test]
+    fn claude_tools_map_to_gemini_names_and_args() {
+        let map = ToolMap::builtin();
+        let bash = map
+            .translate_call(
+                "claude-code",
+                "gemini",
+                &call("Bash", json!({"command": "ls", "timeout": 5})),
+            )
+            .unwrap();
+        assert_eq!(bash.name, "run_shell_command");
+        assert_eq!(bash.arguments, json!({"command": "ls"}));
+        assert_eq!(bash.id.as_deref(), Some("c1"));
+
+        let edit = map
+            .translate_call(
+                "claude-code",
+                "gemini",
+                &call(
+                    "Edit",
+                    json!({"file_path": "/a.rs", "old_string": "x", "new_string": "y"}),
+                ),
+            )
+            .unwrap();
+        assert_eq!(edit.name, "replace");
+        assert_eq!(
+            edit.arguments,
+            json!({"file_path": "/a.rs", "old_string": "x", "new_string": "y"})
+        );
+    }
+
+    #[test]
+    fn codex_shell_argv_becomes_a_command_string() {
+        let map = ToolMap::builtin();
+        let bash = map
+            .translate_call(
+                "codex",
+                "claude-code",
+                &call(
+                    "shell",
+                    json!({"command": ["bash", "-lc", "cargo test"], "workdir": "/w"}),
+                ),
+            )
+            .unwrap();
+        assert_eq!(bash.name, "Bash");
+        assert_eq!(bash.arguments, json!({"command": "cargo test"}));
+
+        let quoted = map
+            .translate_call(
+                "codex",
+                "claude-code",
+                &call("shell", json!({"command": ["rg", "a b", "src"]})),
+            )
+            .unwrap();
+        assert_eq!(quoted.arguments["command"], "rg 'a b' src");
+
+        let back = map
+            .translate_call(
+                "claude-code",
+                "codex",
+                &call("Bash", json!({"command": "ls"})),
+            )
+            .unwrap();
+        assert_eq!(back.arguments["command"], json!(["bash", "-lc", "ls"]));
+    }
+
+    #[test]
+    fn codex_target_emulates_reads_and_edits() {
+        let map = ToolMap::builtin();
+        let read = map
+            .translate_call(
+                "claude-code",
+                "codex",
+                &call(
+                    "Read",
+                    json!({"file_path": "/src/my file.rs", "offset": 10, "limit": 5}),
+                ),
+            )
+            .unwrap();
+        assert_eq!(read.name, "shell");
+        assert_eq!(
+            read.arguments["command"],
+            json!(["bash", "-lc", "sed -n '10,14p' '/src/my file.rs'"])
+        );
+
+        let edit = map
+            .translate_call(
+                "claude-code",
+                "codex",
+                &call("Edit", json!({"file_path": "/a.rs", "old_string": "let x = 1;", "new_string": "let x = 2;"})),
+            )
+            .unwrap();
+        assert_eq!(edit.name, "apply_patch");
+        assert_eq!(
+            edit.arguments["input"],
+            "*** Begin Patch\n*** Update File: /a.rs\n@@\n-let x = 1;\n+let x = 2;\n*** End Patch"
+        );
+    }
+
+    #[test]
+    fn single_file_patches_become_writes_and_edits() {
+        let map = ToolMap::builtin();
+        let add = "*** Begin Patch\n*** Add File: /n.txt\n+hello\n+world\n*** End Patch";
+        let write = map
+            .translate_call("codex", "claude-code", &call("apply_patch", json!(add)))
+            .unwrap();
+        assert_eq!(write.name, "Write");
+        assert_eq!(
+            write.arguments,
+            json!({"file_path": "/n.txt", "content": "hello\nworld\n"})
+        );
+
+        let update = "*** Begin Patch\n*** Update File: /a.rs\n@@ fn main\n fn main() {\n-    old();\n+    new();\n }\n*** End Patch";
+        let edit = map
+            .translate_call(
+                "codex",
+                "gemini",
+                &call("apply_patch", json!({"input": update})),
+            )
+            .unwrap();
+        assert_eq!(edit.name, "replace");
+        assert_eq!(edit.arguments["old_string"], "fn main() {\n    old();\n}");
+        assert_eq!(edit.arguments["new_string"], "fn main() {\n    new();\n}");
+
+        let two_files =
+            "*** Begin Patch\n*** Add File: /a\n+a\n*** Add File: /b\n+b\n*** End Patch";
+        assert!(
+            map.translate_call(
+                "codex",
+                "claude-code",
+                &call("apply_patch", json!(two_files))
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn multi_edits_become_one_patch_or_stay_text() {
+        let map = ToolMap::builtin();
+        let multi = call(
+            "MultiEdit",
+            json!({"file_path": "/a.rs", "edits": [
+                {"old_string": "a", "new_string": "b"},
+                {"old_string": "c", "new_string": "d"}
+            ]}),
+        );
+        let patch = map.translate_call("claude-code", "codex", &multi).unwrap();
+        assert_eq!(patch.name, "apply_patch");
+        assert_eq!(
+            patch.arguments["input"],
+            "*** Begin Patch\n*** Update File: /a.rs\n@@\n-a\n+b\n@@\n-c\n+d\n*** End Patch"
+        );
+        assert_eq!(patch.edits.len(), 1, "re-parsed as one Update File section");
+        assert!(
+            map.translate_call("claude-code", "gemini", &multi)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn plans_and_cline_diffs_convert_shapes() {
+        let map = ToolMap::builtin();
+        let plan = map
+            .translate_call(
+                "codex",
+                "claude-code",
+                &call(
+                    "update_plan",
+                    json!({"plan": [{"step": "Write tests", "status": "in_progress"}]}),
+                ),
+            )
+            .unwrap();
+        assert_eq!(plan.name, "TodoWrite");
+        assert_eq!(
+            plan.arguments,
+            json!({"todos": [{"content": "Write tests", "status": "in_progress", "activeForm": "Write tests"}]})
+        );
+
+        let diff = "<<<<<<< SEARCH\na\n=======\nb\n>>>>>>> REPLACE";
+        let edit = map
+            .translate_call(
+                "cline",
+                "claude-code",
+                &call("replace_in_file", json!({"path": "/f", "diff": diff})),
+            )
+            .unwrap();
+        assert_eq!(
+            edit.arguments,
+            json!({"file_path": "/f", "old_string": "a", "new_string": "b"})
+        );
+        let back = map.translate_call("claude-code", "cline", &edit).unwrap();
+        assert_eq!(back.arguments, json!({"path": "/f", "diff": diff}));
+    }
+
+    #[test]
+    fn open_todos_reads_the_latest_plan_in_the_source_vocabulary() {
+        let map = ToolMap::builtin();
+        let plan = |items: Value| {
+            let mut m = msg(MessageRole::Assistant, "");
+            m.tool_calls
+                .push(call("update_plan", json!({ "plan": items })));
+            m
+        };
+        let s = session(vec![
+            plan(json!([{"step": "Stale", "status": "pending"}])),
+            plan(json!([
+                {"step": "Parse config", "status": "completed"},
+                {"step": "Write tests", "status": "in_progress"},
+                {"step": "Ship"}
+            ])),
+        ]);
+        assert_eq!(
+            map.open_todos("codex", &s),
+            vec![
+                ("Write tests".to_string(), "in_progress".to_string()),
+                ("Ship".to_string(), "pending".to_string()),
+            ]
+        );
+        assert!(map.open_todos("codex", &session(Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn translate_session_renders_unknown_tools_and_their_results() {
+        let map = ToolMap::builtin();
+        let mut assistant = msg(MessageRole::Assistant, "Checking.");
+        assistant.tool_calls = vec![
+            call("mcp__github__search", json!({"q": "bug"})),
+            ToolCall::new(
+                Some("c2".to_string()),
+                "Bash".to_string(),
+                json!({"command": "ls"}),
+            ),
+        ];
+        assistant.parts = vec![
+            ContentPart::Text {
+                text: "Checking.".to_string(),
+            },
+            ContentPart::ToolCall { index: 0 },
+            ContentPart::ToolCall { index: 1 },
+        ];
+        let mut user = msg(MessageRole::User, "");
+        user.tool_results = vec![
+            ToolResult {
+                call_id: Some("c1".to_string()),
+                content: "3 issues".to_string(),
+                is_error: false,
+            },
+            ToolResult {
+                call_id: Some("c2".to_string()),
+                content: "src".to_string(),
+                is_error: false,
+            },
+        ];
+        let mut s = session(vec![assistant, user]);
+
+        let report = map.translate_session(&mut s, "claude-code", "gemini");
+        assert_eq!(report.translated, 1);
+        assert_eq!(report.rendered["mcp__github__search"], 1);
+
+        let assistant = &s.messages[0];
+        assert_eq!(assistant.tool_calls.len(), 1);
+        assert_eq!(assistant.tool_calls[0].name, "run_shell_command");
+        assert_eq!(
+            assistant.content,
+            "Checking.\n[Tool: mcp__github__search] {\"q\":\"bug\"}"
+        );
+        assert_eq!(assistant.parts[2], ContentPart::ToolCall { index: 0 });
+        assert!(assistant.ordered_parts("\n").is_some());
+
+        let user = &s.messages[1];
+        assert_eq!(user.content, "[Tool Output] 3 issues");
+        assert_eq!(user.tool_results.len(), 1);
+        assert_eq!(user.tool_results[0].call_id.as_deref(), Some("c2"));
+    }
+
+    #[test]
+    fn targets_without_vocabulary_are_untouched() {
+        let map = ToolMap::builtin();
+        let mut assistant = msg(MessageRole::Assistant, "");
+        assistant.tool_calls = vec![call("mcp__x", json!({}))];
+        let mut s = session(vec![assistant]);
+        let report = map.translate_session(&mut s, "claude-code", "aider");
+        assert_eq!(report, ToolTranslation::default());
+        assert_eq!(s.messages[0].tool_calls[0].name, "mcp__x");
+    }
+
+    #[test]
+    fn load_adds_custom_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("tools.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "claude-code": {"mcp__fs__read_text_file": {"kind": "read_file", "args": {"path": "path"}}},
+                "gemini": {"shell_v2": "shell"}
+            }"#,
+        )
+        .unwrap();
+        let map = ToolMap::load(&path).unwrap();
+        let read = map
+            .translate_call(
+                "claude-code",
+                "gemini",
+                &call("mcp__fs__read_text_file", json!({"path": "/x"})),
+            )
+            .unwrap();
+        assert_eq!(read.name, "read_file");
+        assert_eq!(read.arguments, json!({"absolute_path": "/x"}));
+
+        let shell = map
+            .translate_call(
+                "claude-code",
+                "gemini",
+                &call("Bash", json!({"command": "ls"})),
+            )
+            .unwrap();
+        assert_eq!(shell.name, "shell_v2");
+        assert_eq!(shell.arguments, json!({"command": "ls"}));
+
+        std::fs::write(&path, r#"{"gemini": {"x": "teleport"}}"#).unwrap();
+        assert!(ToolMap::load(&path).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+//! Minimal localhost HTTP server behind `casr serve`.
+//!
+//! casr is a synchronous CLI, so this is a small blocking HTTP/1.1 server on
+//! [`std::net`]: one request per connection (`Connection: close`), handled in
+//! turn. A client gets [`IO_TIMEOUT`] in total to send its request and again
+//! to read the response, however it paces its bytes, so a stalled client
+//! holds up the others for at most that long. That is fine for one person's
+//! browser but not for shared use. It binds to `127.0.0.1` only and refuses
+//! requests whose `Host` or `Origin` isn't local, so a web page can't reach
+//! it through DNS rebinding or cross-site form posts. Routing and the JSON
+//! envelopes live in the CLI; this module parses requests, writes responses,
+//! and embeds the web UI.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+/// The single-page UI served at `/`.
+pub const INDEX_HTML: &str = include_str!("web/index.html");
+
+/// Largest request body accepted.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Largest request line or header line accepted.
+const MAX_LINE_BYTES: usize = 16 * 1024;
+const MAX_HEADERS: usize = 100;
+/// How long a connection has to send its whole request, and then to read
+/// the whole response. Connections are served one at a time, so this bounds
+/// how long a stalled client blocks the others.
+pub const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Percent-decoded path, without the query string.
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    /// Header names are lowercased.
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// A non-empty query parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .get(name)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// A boolean query parameter: present as `1`, `true`, `yes` or `on`.
+    pub fn flag(&self, name: &str) -> bool {
+        self.param(name)
+            .is_some_and(|v| matches!(v, "1" | "true" | "yes" | "on"))
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// The path split on `/` into its non-empty segments.
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+
+    /// Parse the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        serde_json::from_slice(&self.body).context("invalid JSON request body")
+    }
+}
+
+/// An HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec_pretty(value).unwrap_or_default(),
+        }
+    }
+
+    pub fn html(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Bind the server to the loopback interface; port 0 picks a free port.
+pub fn bind(port: u16) -> anyhow::Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .with_context(|| format!("failed to listen on 127.0.0.1:{port}"))
+}
+
+/// Accept connections on `listener` forever, answering each request with
+/// `handler`.
+pub fn serve(listener: TcpListener, handler: impl Fn(&Request) -> Response) -> anyhow::Result<()> {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, &handler) {
+                    debug!(error = %e, "connection failed");
+                }
+            }
+            Err(e) => warn!(error = %e, "failed to accept connection"),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    handler: &impl Fn(&Request) -> Response,
+) -> anyhow::Result<()> {
+    let mut request_io = Deadline::new(&stream, IO_TIMEOUT);
+    let response = match read_request(&mut BufReader::new(&mut request_io)) {
+        Ok(request) if !is_local(&request) => {
+            warn!(host = ?request.header("host"), origin = ?request.header("origin"), "refused non-local request");
+            Response::text(403, "casr serve only answers requests from localhost pages")
+        }
+        Ok(request) => {
+            debug!(method = %request.method, path = %request.path, "request");
+            handler(&request)
+        }
+        Err(e) => Response::text(400, &format!("bad request: {e}")),
+    };
+    write_response(&mut Deadline::new(&stream, IO_TIMEOUT), &response)
+}
+
+/// A stream that fails once `timeout` has passed since it was created.
+/// A per-call socket timeout alone would let a client that sends a byte just
+/// inside it hold the connection forever.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl<'a> Deadline<'a> {
+    fn new(stream: &'a TcpStream, timeout: Duration) -> Self {
+        Self {
+            stream,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    /// Time left before the deadline, or a `TimedOut` error.
+    fn remaining(&self) -> std::io::Result<Duration> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "client too slow",
+            ));
+        }
+        Ok(remaining)
+    }
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stream.set_read_timeout(Some(self.remaining()?))?;
+        self.stream.read(buf)
+    }
+}
+
+impl Write for Deadline<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stream.set_write_timeout(Some(self.remaining()?))?;
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Read one request from `reader`.
+pub fn read_request(reader: &mut impl BufRead) -> anyhow::Result<Request> {
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("malformed request line");
+    };
+
+    let mut headers = BTreeMap::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            anyhow::bail!("too many headers");
+        }
+        let (name, value) = line.split_once(':').context("malformed header")?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let length: usize = match headers.get("content-length") {
+        Some(value) => value.parse().context("invalid Content-Length")?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        anyhow::bail!("request body larger than {MAX_BODY_BYTES} bytes");
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_ascii_uppercase(),
+        path: decode(path),
+        query: parse_query(query),
+        headers,
+        body,
+    })
+}
+
+fn read_line(reader: &mut impl BufRead) -> anyhow::Result<String> {
+    let mut line = Vec::new();
+    reader
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_until(b'\n', &mut line)?;
+    if line.len() > MAX_LINE_BYTES {
+        anyhow::bail!("line longer than {MAX_LINE_BYTES} bytes");
+    }
+    if line.is_empty() {
+        anyhow::bail!("connection closed mid-request");
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+/// `a=1&b=two+words` → `{a: "1", b: "two words"}`.
+pub fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+fn decode(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or(value)
+}
+
+/// Whether the request came from a page served by this machine: `Host`
+/// must name the loopback interface, and a browser's `Origin` (sent with
+/// cross-site requests) must too.
+pub fn is_local(request: &Request) -> bool {
+    let local_host = |authority: &str| {
+        let host = match authority.strip_prefix('[') {
+            Some(rest) => rest.split(']').next().unwrap_or(""),
+            None => authority.split(':').next().unwrap_or(""),
+        };
+        matches!(host, "127.0.0.1" | "localhost" | "::1")
+    };
+    let host_ok = request.header("host").is_some_and(local_host);
+    let origin_ok = request
+        .header("origin")
+        .is_none_or(|origin| origin.strip_prefix("http://").is_some_and(local_host));
+    host_ok && origin_ok
+}
+
+fn write_response(stream: &mut impl Write, response: &Response) -> anyhow::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        415 => "Unsupported Media Type",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+Cache-Control: no-store\r\nX-Content-Type-Options: nosniff\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(&response.body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_request_parses_path_query_headers_and_body() {
+        let raw = "POST /api/sessions/abc%20def?provider=cc&q=two+words&dry_run=1 HTTP/1.1\r\n\
+Host: 127.0.0.1:8080\r\nContent-Type: application/json\r\nContent-Length: 13\r\n\r\n{\"ok\": true}\n";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.segments(), ["api", "sessions", "abc def"]);
+        assert_eq!(request.param("provider"), Some("cc"));
+        assert_eq!(request.param("q"), Some("two words"));
+        assert!(request.flag("dry_run") && !request.flag("force"));
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        let body: serde_json::Value = request.json().unwrap();
+        assert_eq!(body["ok"], true);
+
+        let err = read_request(&mut "GET\r\n\r\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("malformed"), "{err}");
+    }
+
+    #[test]
+    fn only_loopback_hosts_and_origins_are_local() {
+        let request = |host: &str, origin: Option<&str>| {
+            let mut headers = BTreeMap::new();
+            headers.insert("host".to_string(), host.to_string());
+            if let Some(origin) = origin {
+                headers.insert("origin".to_string(), origin.to_string());
+            }
+            Request {
+                headers,
+                ..Request::default()
+            }
+        };
+        assert!(is_local(&request("127.0.0.1:7777", None)));
+        assert!(is_local(&request(
+            "localhost:7777",
+            Some("http://localhost:7777")
+        )));
+        assert!(is_local(&request("[::1]:7777", None)));
+        // DNS rebinding: a hostile name resolving to 127.0.0.1.
+        assert!(!is_local(&request("evil.example:7777", None)));
+        // A cross-site page posting to the local port.
+        assert!(!is_local(&request(
+            "127.0.0.1:7777",
+            Some("https://evil.example")
+        )));
+        assert!(!is_local(&Request::default()));
+    }
+
+    #[test]
+    fn deadline_bounds_a_client_that_trickles_its_request() {
+        let listener = bind(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // Each byte arrives well within any per-read timeout, and the
+            // header line never ends.
+            let head = b"GET / HTTP/1.1\r\nX-Slow: ".iter();
+            for byte in head.chain(std::iter::repeat(&b'a')) {
+                if stream.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let started = Instant::now();
+        let err = read_request(&mut BufReader::new(Deadline::new(
+            &stream,
+            Duration::from_millis(300),
+        )))
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(err.to_string().contains("too slow"), "{err}");
+        drop(stream);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn responses_carry_length_and_close() {
+        let mut out = Vec::new();
+        write_response(
+            &mut out,
+            &Response::json(404, &serde_json::json!({"ok": false})),
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(text.contains("Content-Type: application/json\r\n"));
+        assert!(text.contains("Connection: close\r\n"));
+        assert!(text.ends_with("{\n  \"ok\": false\n}"));
+    }
+}
@@ -85,26 +85,36 @@ mod atomic_write_integration {
                     idx: 0,
                     role: MessageRole::User,
                     content: "What is 2+2?".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "4".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_010_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
             ],
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -119,10 +129,14 @@ mod atomic_write_integration {
                     MessageRole::Assistant
                 },
                 content: format!("Message number {i} with some padding content for testing"),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000 + i as i64),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             })
             .collect();
@@ -138,6 +152,8 @@ mod atomic_write_integration {
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -155,7 +171,7 @@ mod atomic_write_integration {
 
         // First write succeeds.
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("first write should succeed");
         assert!(!written.paths.is_empty());
 
@@ -177,7 +193,7 @@ mod atomic_write_integration {
         let session = make_session("/tmp");
 
         let first = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("first write");
         let first_path = first.paths[0].clone();
         let first_content = fs::read_to_string(&first_path).expect("read first");
@@ -194,7 +210,7 @@ mod atomic_write_integration {
         fs::write(&conflict_path, &first_content).expect("seed conflict file");
 
         let written = Codex
-            .write_session(&second_session, &WriteOptions { force: false })
+            .write_session(&second_session, &WriteOptions::default())
             .expect("second write to different path");
         assert!(written.paths[0].exists());
     }
@@ -218,7 +234,7 @@ mod atomic_write_integration {
         };
 
         let session = make_session("/tmp");
-        let err = Codex.write_session(&session, &WriteOptions { force: false });
+        let err = Codex.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "writing to read-only dir should fail; got: {:?}",
@@ -241,7 +257,7 @@ mod atomic_write_integration {
         };
 
         let session = make_session("/tmp");
-        let err = ClaudeCode.write_session(&session, &WriteOptions { force: false });
+        let err = ClaudeCode.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "CC writing to read-only dir should fail; got: {:?}",
@@ -264,7 +280,7 @@ mod atomic_write_integration {
         };
 
         let session = make_session("/tmp");
-        let err = Gemini.write_session(&session, &WriteOptions { force: false });
+        let err = Gemini.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "Gemini writing to read-only dir should fail; got: {:?}",
@@ -284,7 +300,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC write");
         let readback = ClaudeCode
             .read_session(&written.paths[0])
@@ -304,7 +320,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Codex write");
         let readback = Codex
             .read_session(&written.paths[0])
@@ -324,7 +340,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Gemini
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Gemini write");
         let readback = Gemini
             .read_session(&written.paths[0])
@@ -348,7 +364,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = ClawdBot
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("ClawdBot write");
         let readback = ClawdBot
             .read_session(&written.paths[0])
@@ -368,7 +384,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Vibe
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Vibe write");
         let readback = Vibe.read_session(&written.paths[0]).expect("Vibe readback");
         assert_eq!(
@@ -386,7 +402,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Factory
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Factory write");
         let readback = Factory
             .read_session(&written.paths[0])
@@ -406,7 +422,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = OpenClaw
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("OpenClaw write");
         let readback = OpenClaw
             .read_session(&written.paths[0])
@@ -426,7 +442,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = PiAgent
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("PiAgent write");
         let readback = PiAgent
             .read_session(&written.paths[0])
@@ -456,7 +472,7 @@ mod atomic_write_integration {
                     ..make_session("/tmp")
                 };
                 Codex
-                    .write_session(&session, &WriteOptions { force: false })
+                    .write_session(&session, &WriteOptions::default())
                     .unwrap_or_else(|e| panic!("write {i} failed: {e}"))
             })
             .collect();
@@ -491,7 +507,7 @@ mod atomic_write_integration {
                     ..make_session("/tmp")
                 };
                 ClaudeCode
-                    .write_session(&session, &WriteOptions { force: false })
+                    .write_session(&session, &WriteOptions::default())
                     .unwrap_or_else(|e| panic!("CC write {i} failed: {e}"))
             })
             .collect();
@@ -520,17 +536,21 @@ mod atomic_write_integration {
                 idx: 0,
                 role: MessageRole::User,
                 content: "solo message".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             }],
             ..make_session("/tmp")
         };
 
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("single-message write");
         let readback = Codex
             .read_session(&written.paths[0])
@@ -549,17 +569,21 @@ mod atomic_write_integration {
                 idx: 0,
                 role: MessageRole::User,
                 content: "solo CC message".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             }],
             ..make_session("/tmp")
         };
 
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC single-message write");
         let readback = ClaudeCode
             .read_session(&written.paths[0])
@@ -579,7 +603,7 @@ mod atomic_write_integration {
 
         let session = make_large_session("/tmp", 200);
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("large session write");
         let readback = Codex
             .read_session(&written.paths[0])
@@ -599,7 +623,7 @@ mod atomic_write_integration {
 
         let session = make_large_session("/tmp", 200);
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC large session write");
         let readback = ClaudeCode
             .read_session(&written.paths[0])
@@ -619,7 +643,7 @@ mod atomic_write_integration {
 
         let session = make_large_session("/tmp", 200);
         let written = Gemini
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Gemini large session write");
         let readback = Gemini
             .read_session(&written.paths[0])
@@ -643,7 +667,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("write");
 
         let parent = written.paths[0].parent().expect("parent dir");
@@ -666,7 +690,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC write");
 
         let parent = written.paths[0].parent().expect("parent dir");
@@ -693,7 +717,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC write");
         let readback = ClaudeCode
             .read_session(&written.paths[0])
@@ -714,7 +738,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Codex write");
         let readback = Codex
             .read_session(&written.paths[0])
@@ -739,7 +763,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = ClaudeCode
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("CC write");
         assert!(
             written.resume_command.contains("claude"),
@@ -760,7 +784,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Codex
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Codex write");
         assert!(
             written.resume_command.contains("codex"),
@@ -777,7 +801,7 @@ mod atomic_write_integration {
 
         let session = make_session("/tmp");
         let written = Gemini
-            .write_session(&session, &WriteOptions { force: false })
+            .write_session(&session, &WriteOptions::default())
             .expect("Gemini write");
         assert!(
             written.resume_command.contains("gemini"),
@@ -803,7 +827,7 @@ mod atomic_write_integration {
                 ..make_session("/tmp")
             };
             let written = ClaudeCode
-                .write_session(&session, &WriteOptions { force: false })
+                .write_session(&session, &WriteOptions::default())
                 .unwrap_or_else(|e| panic!("CC write {i} failed: {e}"));
             assert!(
                 ids.insert(written.session_id.clone()),
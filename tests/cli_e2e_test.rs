@@ -232,6 +232,53 @@ fn cli_providers_json_is_valid() {
     assert!(parsed.is_array(), "providers JSON should be an array");
 }
 
+#[test]
+fn cli_providers_and_doctor_inspect_the_given_home() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path().join("old-home");
+    let session = home.join(".claude/projects/-work-app/sess-1.jsonl");
+    fs::create_dir_all(session.parent().unwrap()).unwrap();
+    fs::copy(fixtures_dir().join("claude_code/cc_simple.jsonl"), &session).unwrap();
+    let home_str = home.display().to_string();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "--home", &home_str, "providers"])
+        .output()
+        .expect("providers should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cc = parsed
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["slug"] == "claude-code")
+        .unwrap();
+    assert_eq!(cc["home"], home_str);
+    assert!(cc["evidence"].to_string().contains(&home_str));
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "--home", &home_str, "doctor", "--provider", "cc"])
+        .output()
+        .expect("doctor should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cc = &parsed["providers"][0];
+    assert_eq!(cc["home"], home_str);
+    assert_eq!(cc["session_files"], 1);
+    assert!(
+        cc["roots"][0]["path"]
+            .as_str()
+            .unwrap()
+            .starts_with(&home_str)
+    );
+
+    casr_cmd(&tmp)
+        .args(["--home", "/definitely/not/here", "doctor"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a directory"));
+}
+
 // ---------------------------------------------------------------------------
 // List command
 // ---------------------------------------------------------------------------
@@ -488,6 +535,198 @@ fn cli_info_json_is_valid() {
     assert_eq!(parsed["provider"].as_str().unwrap(), "claude-code");
 }
 
+#[test]
+fn cli_info_lists_files_changed_by_edit_tools() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_complex");
+    casr_cmd(&tmp)
+        .args(["info", &session_id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files changed:"))
+        .stdout(predicate::str::contains(
+            "/data/projects/webapp/src/handler.rs",
+        ));
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "info", &session_id])
+        .output()
+        .expect("info should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        parsed["files_changed"],
+        serde_json::json!(["/data/projects/webapp/src/handler.rs"])
+    );
+}
+
+#[test]
+fn cli_doctor_reports_healthy_provider_with_sessions() {
+    let tmp = TempDir::new().unwrap();
+    setup_cc_fixture(&tmp, "cc_simple");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "doctor", "--provider", "cc"])
+        .output()
+        .expect("doctor should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["ok"], true);
+    let cc = &parsed["providers"][0];
+    assert_eq!(cc["slug"], "claude-code");
+    assert_eq!(cc["status"], "ok");
+    assert_eq!(cc["session_files"], 1);
+    assert_eq!(cc["parse"]["parsed"], 1);
+    assert_eq!(cc["roots"][0]["readable"], true);
+
+    casr_cmd(&tmp)
+        .args(["doctor", "--provider", "cod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Codex (codex)"))
+        .stdout(predicate::str::contains("set CODEX_HOME"));
+}
+
+#[test]
+fn cli_info_verbose_and_doctor_report_unrecognized_constructs() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let session_file = fs::read_dir(tmp.path().join("claude/projects"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+        .join(format!("{session_id}.jsonl"));
+    let mut content = fs::read_to_string(&session_file).unwrap();
+    content.push_str(&format!(
+        "{{\"type\":\"hologram\",\"sessionId\":\"{session_id}\"}}\n"
+    ));
+    fs::write(&session_file, content).unwrap();
+
+    // Only reported with --verbose.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "info", &session_id])
+        .output()
+        .expect("info should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed.get("unrecognized").is_none());
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "--verbose", "info", &session_id])
+        .output()
+        .expect("info should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["unrecognized"]["entry_types"]["hologram"], 1);
+
+    casr_cmd(&tmp)
+        .args(["--verbose", "info", &session_id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Entry types: hologram ×1"));
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "doctor", "--provider", "cc"])
+        .output()
+        .expect("doctor should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cc = &parsed["providers"][0];
+    // Drift is a warning, not a failure: the session still parsed.
+    assert_eq!(cc["status"], "ok");
+    assert_eq!(cc["parse"]["with_unrecognized"], 1);
+    assert_eq!(cc["parse"]["unrecognized"]["entry_types"]["hologram"], 1);
+    assert!(
+        cc["hints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|h| h.as_str().unwrap().contains("constructs casr skips")),
+        "{cc}"
+    );
+}
+
+#[test]
+fn cli_doctor_flags_corrupt_cursor_database() {
+    let tmp = TempDir::new().unwrap();
+    let db = tmp.path().join("cursor/User/globalStorage/state.vscdb");
+    fs::create_dir_all(db.parent().unwrap()).unwrap();
+    fs::write(&db, "this is not a sqlite database at all, not even close").unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "doctor", "--provider", "cur"])
+        .output()
+        .expect("doctor should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["ok"], false);
+    let cursor = &parsed["providers"][0];
+    assert_eq!(cursor["status"], "problems");
+    assert_eq!(cursor["databases"][0]["ok"], false);
+    assert!(
+        cursor["hints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|h| h.as_str().unwrap().contains("integrity check")),
+        "{cursor}"
+    );
+}
+
+#[test]
+fn cli_selftest_round_trips_every_writable_provider_in_a_sandbox() {
+    let tmp = TempDir::new().unwrap();
+    let output = casr_cmd(&tmp)
+        .args(["--json", "selftest"])
+        .output()
+        .expect("selftest should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("Invalid JSON from selftest: {e}"));
+
+    assert!(parsed["session_id"].is_null());
+    assert_eq!(parsed["origin_provider"], "claude-code");
+    let results = parsed["results"].as_array().unwrap();
+    let providers: Vec<&str> = results
+        .iter()
+        .map(|r| r["provider"].as_str().unwrap())
+        .collect();
+    assert_eq!(providers.len(), 14, "{providers:?}");
+    assert!(!providers.contains(&"antigravity"), "read-only provider");
+    assert!(!providers.contains(&"claude-code"), "origin provider");
+    let codex = &results[providers.iter().position(|p| *p == "codex").unwrap()];
+    assert_eq!(codex["outbound"]["ok"], true, "{codex}");
+    assert_eq!(codex["inbound"]["ok"], true, "{codex}");
+    assert_eq!(output.status.success(), parsed["ok"] == true);
+
+    // Nothing escapes the sandbox into the configured provider homes.
+    assert!(!tmp.path().join("claude").exists());
+    assert!(!tmp.path().join("codex").exists());
+}
+
+#[test]
+fn cli_selftest_uses_a_real_session_without_touching_it() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let claude_files = || {
+        walkdir::WalkDir::new(tmp.path().join("claude"))
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .count()
+    };
+    let before = claude_files();
+
+    let output = casr_cmd(&tmp)
+        .args(["selftest", "--session", &session_id])
+        .output()
+        .expect("selftest should run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("session {session_id}")),
+        "{stdout}"
+    );
+    assert!(stdout.contains("round trips passed"), "{stdout}");
+    assert_eq!(claude_files(), before);
+    assert!(!tmp.path().join("codex").exists());
+}
+
 #[test]
 fn cli_info_unknown_session_fails() {
     let tmp = TempDir::new().unwrap();
@@ -514,6 +753,55 @@ fn cli_info_unknown_session_json_error() {
     assert!(parsed["error_type"].as_str().is_some());
 }
 
+// ---------------------------------------------------------------------------
+// Stats command
+// ---------------------------------------------------------------------------
+
+#[test]
+fn cli_stats_prints_tables() {
+    let tmp = TempDir::new().unwrap();
+    setup_cc_fixture(&tmp, "cc_complex");
+    setup_codex_fixture(&tmp, "codex_modern", "jsonl");
+    casr_cmd(&tmp)
+        .args(["stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("across 2 session(s)"))
+        .stdout(predicate::str::contains("Sessions by Provider"))
+        .stdout(predicate::str::contains("Most-Used Tools"))
+        .stdout(predicate::str::contains("Model Mix"));
+}
+
+#[test]
+fn cli_stats_filters_by_provider_and_time() {
+    let tmp = TempDir::new().unwrap();
+    setup_cc_fixture(&tmp, "cc_simple");
+    setup_codex_fixture(&tmp, "codex_modern", "jsonl");
+
+    let stats = |args: &[&str]| -> serde_json::Value {
+        let output = casr_cmd(&tmp)
+            .args(args)
+            .output()
+            .expect("stats should run");
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("stats --json should emit valid JSON")
+    };
+
+    let cc_only = stats(&["--json", "stats", "--provider", "cc"]);
+    assert_eq!(cc_only["sessions"], 1);
+    assert_eq!(cc_only["providers"][0]["provider"], "claude-code");
+
+    let future = stats(&["--json", "stats", "--since", "2999-01-01"]);
+    assert_eq!(future["sessions"], 0);
+    assert!(future["since"].is_i64());
+
+    casr_cmd(&tmp)
+        .args(["stats", "--since", "yesterday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time"));
+}
+
 // ---------------------------------------------------------------------------
 // Resume command
 // ---------------------------------------------------------------------------
@@ -579,6 +867,255 @@ fn cli_resume_writes_target_session() {
     );
 }
 
+#[test]
+fn cli_resume_report_lists_fidelity_losses() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_complex");
+
+    casr_cmd(&tmp)
+        .args(["resume", "cod", &session_id, "--report"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fidelity:"))
+        .stdout(predicate::str::contains(
+            "tool call(s) translated to the target's tools",
+        ))
+        .stdout(predicate::str::contains(
+            "Metadata keys ignored by the target:",
+        ));
+}
+
+#[test]
+fn cli_resume_strict_refuses_lossy_handoff_with_typed_error() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_complex");
+
+    // Codex isn't installed here; keep its warning out of the JSON on stderr.
+    let output = casr_cmd(&tmp)
+        .env("RUST_LOG", "error")
+        .args([
+            "--json",
+            "resume",
+            "cod",
+            &session_id,
+            "--strict=roles,metadata",
+        ])
+        .output()
+        .expect("resume should run");
+    assert!(!output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(parsed["error_type"], "StrictMetadataDropped");
+    let codex_files = walkdir::WalkDir::new(tmp.path().join("codex"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .count();
+    assert_eq!(codex_files, 0, "strict failure should leave nothing behind");
+
+    // Bare --strict enables every check; budget trips before anything is written.
+    let output = casr_cmd(&tmp)
+        .env("RUST_LOG", "error")
+        .args([
+            "--json",
+            "resume",
+            "cod",
+            &session_id,
+            "--max-tool-output",
+            "10",
+            "--strict",
+        ])
+        .output()
+        .expect("resume should run");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(parsed["error_type"], "StrictBudget");
+
+    casr_cmd(&tmp)
+        .args(["resume", "cod", &session_id, "--strict=bogus"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown strict check 'bogus'"));
+}
+
+#[test]
+fn cli_edit_in_place_rewrites_session_and_keeps_bak() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_complex");
+    let session_path = walkdir::WalkDir::new(tmp.path().join("claude"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|e| e.file_name().to_string_lossy() == format!("{session_id}.jsonl"))
+        .expect("seeded session")
+        .into_path();
+    let original = fs::read_to_string(&session_path).unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "edit",
+            &session_id,
+            "--drop",
+            "4-5",
+            "--redact",
+            "the",
+            "--title",
+            "Redacted run",
+            "--in-place",
+        ])
+        .output()
+        .expect("edit should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["target_session_id"], session_id.as_str());
+    assert_eq!(parsed["changes"]["messages_dropped"], 2);
+    assert_eq!(parsed["changes"]["retitled"], true);
+    let backup = parsed["backup_path"].as_str().expect("backup path");
+    assert_eq!(fs::read_to_string(backup).unwrap(), original);
+    let rewritten = fs::read_to_string(&session_path).unwrap();
+    assert!(rewritten.contains("[REDACTED]"));
+
+    // Edits that match nothing and ranges past the end are typed errors.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "edit", &session_id, "--drop", "40"])
+        .output()
+        .expect("edit should run");
+    assert!(!output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(parsed["error_type"], "EditRejected");
+
+    casr_cmd(&tmp)
+        .args(["edit", &session_id])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to edit"));
+}
+
+#[test]
+fn cli_resume_enrich_includes_git_state_of_the_workspace() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=casr", "-c", "user.email=casr@example.com"])
+            .args(args)
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-q", "-b", "main"]);
+    fs::write(repo.join("lib.rs"), "fn a() {}\n").unwrap();
+    git(&["add", "lib.rs"]);
+    git(&["commit", "-q", "-m", "Add lib"]);
+    fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+
+    let session_id = setup_cc_fixture_custom(&tmp, "cc_simple", Some(repo.to_str().unwrap()), None);
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "resume",
+            "cod",
+            &session_id,
+            "--enrich",
+            "--diff-stat",
+        ])
+        .output()
+        .expect("resume should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let written = fs::read_to_string(parsed["written_paths"][0].as_str().unwrap()).unwrap();
+    assert!(written.contains("Repository state at conversion time"));
+    assert!(written.contains("Branch: main"));
+    assert!(written.contains("Add lib"));
+    assert!(written.contains("M lib.rs"));
+    assert!(written.contains("1 file changed"));
+
+    casr_cmd(&tmp)
+        .args(["resume", "cod", &session_id, "--diff-stat"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--enrich"));
+}
+
+#[test]
+fn cli_resume_renders_enrich_template_and_continue_prompt() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let template = tmp.path().join("handoff.txt");
+    fs::write(
+        &template,
+        "TEAM HANDOFF from {{source_provider}} session {{session_id}} ({{message_count}} messages)\n\
+Recent:\n{{recent_summary}}\n",
+    )
+    .unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cod", &session_id, "--enrich"])
+        .arg("--enrich-template")
+        .arg(&template)
+        .args([
+            "--continue-prompt",
+            "Pick up {{session_id}} where it stopped.",
+        ])
+        .output()
+        .expect("resume should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let written = fs::read_to_string(parsed["written_paths"][0].as_str().unwrap()).unwrap();
+    assert!(written.contains(&format!(
+        "TEAM HANDOFF from claude-code session {session_id}"
+    )));
+    assert!(
+        !written.contains("was originally created in"),
+        "template replaces the built-in notice"
+    );
+    let last = written.lines().last().unwrap();
+    assert!(
+        last.contains(&format!("Pick up {session_id} where it stopped.")),
+        "{last}"
+    );
+
+    // A continuation prompt alone still writes a new same-provider session.
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "resume",
+            "cc",
+            &session_id,
+            "--continue-prompt",
+            "Go on.",
+        ])
+        .output()
+        .expect("resume should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_ne!(parsed["target_session_id"], session_id.as_str());
+
+    fs::write(&template, "Hi {{sesion_id}}").unwrap();
+    casr_cmd(&tmp)
+        .args(["resume", "cod", &session_id, "--enrich"])
+        .arg("--enrich-template")
+        .arg(&template)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown placeholder '{{sesion_id}}'",
+        ));
+}
+
 #[test]
 fn cli_resume_json_output_is_valid() {
     let tmp = TempDir::new().unwrap();
@@ -782,119 +1319,704 @@ fn cli_list_defaults_to_current_workspace_and_top_10() {
         );
     }
 
-    // Explicit workspace override should include the out-of-scope session.
+    // Explicit workspace override should include the out-of-scope session.
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "list",
+            "--workspace",
+            "/tmp/not-current-casr-workspace",
+        ])
+        .output()
+        .expect("list --workspace should run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout).expect("list --workspace --json should emit valid JSON");
+    let arr = parsed["items"]
+        .as_array()
+        .expect("list --workspace --json items should be an array");
+    assert!(
+        arr.iter()
+            .any(|e| e["session_id"].as_str() == Some(out_of_scope_sid)),
+        "explicit workspace override should include out-of-scope fixture"
+    );
+}
+
+#[test]
+fn cli_list_provider_filter_accepts_claude_standard_name() {
+    let tmp = TempDir::new().unwrap();
+    setup_cc_fixture(&tmp, "cc_simple");
+
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "list",
+            "--provider",
+            "claude",
+            "--workspace",
+            "/data/projects/myapp",
+        ])
+        .output()
+        .expect("list --provider claude should run");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout).expect("list --json should emit valid JSON");
+    let arr = parsed["items"]
+        .as_array()
+        .expect("list --json items should be an array");
+    assert!(!arr.is_empty(), "should find at least one Claude session");
+    assert!(
+        arr.iter()
+            .all(|entry| entry["provider"].as_str() == Some("claude-code")),
+        "provider filter should only return Claude sessions"
+    );
+}
+
+#[test]
+fn cli_resume_cc_to_cursor_works_and_is_discoverable() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cur", &session_id])
+        .output()
+        .expect("resume should run");
+    assert!(
+        output.status.success(),
+        "CC→Cursor conversion should succeed"
+    );
+
+    let parsed: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("resume --json output should parse");
+    assert_eq!(parsed["ok"], true);
+    assert_eq!(parsed["target_provider"].as_str().unwrap(), "cursor");
+    let cursor_session_id = parsed["target_session_id"]
+        .as_str()
+        .expect("target_session_id should be present for non-dry-run");
+
+    let cursor_db = tmp.path().join("cursor/User/globalStorage/state.vscdb");
+    assert!(
+        cursor_db.exists(),
+        "Cursor DB should exist after CC→Cursor conversion"
+    );
+
+    casr_cmd(&tmp)
+        .args(["--json", "info", cursor_session_id])
+        .assert()
+        .success();
+}
+
+#[test]
+fn cli_resume_cursor_to_cc_works_with_source_hint() {
+    let tmp = TempDir::new().unwrap();
+    let source_id = setup_cc_fixture(&tmp, "cc_simple");
+
+    let cursor_result = casr_cmd(&tmp)
+        .args(["--json", "resume", "cur", &source_id])
+        .output()
+        .expect("CC→Cursor seed conversion should run");
+    assert!(cursor_result.status.success());
+    let cursor_json: serde_json::Value =
+        serde_json::from_slice(&cursor_result.stdout).expect("seed conversion JSON should parse");
+    let cursor_session_id = cursor_json["target_session_id"]
+        .as_str()
+        .expect("cursor target_session_id should be present");
+
+    casr_cmd(&tmp)
+        .args(["resume", "cc", cursor_session_id, "--source", "cur"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Converted"))
+        .stdout(predicate::str::contains("cursor"))
+        .stdout(predicate::str::contains("claude-code"));
+}
+
+#[test]
+fn cli_resume_chain_records_lineage_and_list_dedupes_copies() {
+    let tmp = TempDir::new().unwrap();
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+
+    let resume = |target: &str, id: &str, source: &str| -> String {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "resume", target, id, "--source", source])
+            .output()
+            .expect("resume should run");
+        assert!(
+            output.status.success(),
+            "resume {target} {id} should succeed"
+        );
+        let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        parsed["target_session_id"].as_str().unwrap().to_string()
+    };
+    let codex_id = resume("cod", &cc_id, "cc");
+    let gemini_id = resume("gmi", &codex_id, "cod");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "info", &gemini_id, "--source", "gmi"])
+        .output()
+        .expect("info should run");
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hops = info["lineage"]
+        .as_array()
+        .expect("lineage should be present");
+    assert_eq!(hops.len(), 2);
+    assert_eq!(hops[0]["source_provider"], "claude-code");
+    assert_eq!(hops[0]["source_session_id"], cc_id.as_str());
+    assert_eq!(hops[1]["source_provider"], "codex");
+    assert_eq!(hops[1]["source_session_id"], codex_id.as_str());
+
+    casr_cmd(&tmp)
+        .args(["info", &gemini_id, "--source", "gmi"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "claude-code {cc_id} → codex {codex_id} → this session"
+        )));
+
+    let list = |extra: &[&str]| -> Vec<serde_json::Value> {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "list", "--workspace", "/data/projects/myapp"])
+            .args(extra)
+            .output()
+            .expect("list should run");
+        assert!(output.status.success());
+        let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        parsed["items"].as_array().unwrap().clone()
+    };
+    let origin = format!("claude-code:{cc_id}");
+    let all = list(&[]);
+    assert_eq!(all.len(), 3, "original plus two copies: {all:?}");
+    assert_eq!(
+        all.iter()
+            .filter(|item| item["origin"] == origin.as_str())
+            .count(),
+        2
+    );
+
+    let deduped = list(&["--dedupe"]);
+    assert_eq!(deduped.len(), 1, "{deduped:?}");
+    assert_eq!(deduped[0]["copies_hidden"], 2);
+}
+
+#[test]
+fn cli_sync_back_appends_new_turns_to_the_source_once() {
+    let tmp = TempDir::new().unwrap();
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+    let cc_path = fs::read_dir(tmp.path().join("claude/projects"))
+        .unwrap()
+        .map(|dir| dir.unwrap().path().join(format!("{cc_id}.jsonl")))
+        .find(|path| path.is_file())
+        .expect("CC fixture file");
+    let original = fs::read_to_string(&cc_path).unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cod", &cc_id])
+        .output()
+        .expect("resume should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let codex_id = parsed["target_session_id"].as_str().unwrap().to_string();
+    let rollout = PathBuf::from(parsed["written_paths"][0].as_str().unwrap());
+
+    // Continue the conversation in Codex.
+    let mut content = fs::read_to_string(&rollout).unwrap();
+    for line in [
+        r#"{"type":"event_msg","timestamp":1900000000.0,"payload":{"type":"user_message","message":"Now add a test for it"}}"#,
+        r#"{"type":"response_item","timestamp":1900000001.0,"payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Added tests/parse.rs."}]}}"#,
+    ] {
+        content.push('\n');
+        content.push_str(line);
+    }
+    fs::write(&rollout, content).unwrap();
+
+    let sync_back = || -> serde_json::Value {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "sync-back", &codex_id])
+            .output()
+            .expect("sync-back should run");
+        assert!(
+            output.status.success(),
+            "sync-back failed: {}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let first = sync_back();
+    assert_eq!(first["source_provider"], "claude-code");
+    assert_eq!(first["source_session_id"], cc_id.as_str());
+    assert_eq!(first["appended"], 2);
+    assert!(first["backup_path"].as_str().is_some());
+
+    let synced = fs::read_to_string(&cc_path).unwrap();
+    assert!(
+        synced.starts_with(&original),
+        "existing entries are kept byte-for-byte"
+    );
+    assert!(synced.contains("Now add a test for it"));
+    assert!(synced.contains("Added tests/parse.rs."));
+
+    let second = sync_back();
+    assert_eq!(second["appended"], 0);
+    assert_eq!(second["already_synced"], 2);
+    assert_eq!(fs::read_to_string(&cc_path).unwrap(), synced);
+
+    // The source itself was never converted, so it has nothing to sync.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "sync-back", &cc_id])
+        .output()
+        .expect("sync-back should run");
+    assert!(!output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(parsed["error_type"], "SyncBackRejected");
+}
+
+#[test]
+fn cli_sync_back_keeps_the_history_of_a_compacted_codex_source() {
+    let tmp = TempDir::new().unwrap();
+    let sessions = tmp.path().join("codex/sessions/2026/01/01");
+    fs::create_dir_all(&sessions).unwrap();
+    let rollout = sessions.join("rollout-2026-01-01T00-00-00-codex-compacted-002.jsonl");
+    let lines = [
+        r#"{"type":"session_meta","timestamp":"2026-01-01T00:00:00Z","payload":{"id":"codex-compacted-002","cwd":"/tmp/p"}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:01Z","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Early question"}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:02Z","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Early answer"}]}}"#,
+        r#"{"type":"compacted","timestamp":"2026-01-01T00:00:03Z","payload":{"replacement_history":[{"type":"message","role":"user","content":[{"type":"input_text","text":"Summary of the early work"}]}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:04Z","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Later question"}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:05Z","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Later answer"}]}}"#,
+    ];
+    let original = lines.join("\n") + "\n";
+    fs::write(&rollout, &original).unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cc", "codex-compacted-002"])
+        .output()
+        .expect("resume should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cc_id = parsed["target_session_id"].as_str().unwrap().to_string();
+    let cc_path = PathBuf::from(parsed["written_paths"][0].as_str().unwrap());
+
+    // Continue the conversation in Claude Code.
+    let mut cc = fs::read_to_string(&cc_path).unwrap();
+    for (uuid, role, text, ts) in [
+        (
+            "u-new-1",
+            "user",
+            "One more change",
+            "2026-01-02T00:00:00.000Z",
+        ),
+        ("u-new-2", "assistant", "Done.", "2026-01-02T00:00:01.000Z"),
+    ] {
+        cc.push_str(&format!(
+            r#"{{"isSidechain":false,"userType":"external","cwd":"/tmp/p","sessionId":"{cc_id}","type":"{role}","message":{{"role":"{role}","content":"{text}"}},"uuid":"{uuid}","timestamp":"{ts}"}}"#
+        ));
+        cc.push('\n');
+    }
+    fs::write(&cc_path, cc).unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "sync-back", &cc_id])
+        .output()
+        .expect("sync-back should run");
+    assert!(
+        output.status.success(),
+        "sync-back failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["appended"], 2);
+
+    let synced = fs::read_to_string(&rollout).unwrap();
+    assert!(
+        synced.starts_with(&original),
+        "the compacted log is kept as is"
+    );
+    let output = casr_cmd(&tmp)
+        .args(["--json", "info", "codex-compacted-002", "--full-history"])
+        .output()
+        .expect("info should run");
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // Two early turns, the compaction summary, two later turns, two synced.
+    assert_eq!(info["messages"], 7, "{info}");
+}
+
+#[test]
+fn cli_sync_back_skips_compaction_summaries_from_the_target() {
+    let tmp = TempDir::new().unwrap();
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+    let cc_path = fs::read_dir(tmp.path().join("claude/projects"))
+        .unwrap()
+        .map(|dir| dir.unwrap().path().join(format!("{cc_id}.jsonl")))
+        .find(|path| path.is_file())
+        .expect("CC fixture file");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cod", &cc_id])
+        .output()
+        .expect("resume should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let codex_id = parsed["target_session_id"].as_str().unwrap().to_string();
+    let rollout = PathBuf::from(parsed["written_paths"][0].as_str().unwrap());
+
+    // Codex compacts its context, then the conversation goes on.
+    let mut content = fs::read_to_string(&rollout).unwrap();
+    for line in [
+        r#"{"type":"compacted","timestamp":1900000000.0,"payload":{"message":"CODEX SUMMARY of the login fix","replacement_history":[{"type":"message","role":"user","content":[{"type":"input_text","text":"CODEX REPLACEMENT of the login fix"}]}]}}"#,
+        r#"{"type":"event_msg","timestamp":1900000001.0,"payload":{"type":"user_message","message":"Now add a test for it"}}"#,
+        r#"{"type":"response_item","timestamp":1900000002.0,"payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Added tests/parse.rs."}]}}"#,
+    ] {
+        content.push('\n');
+        content.push_str(line);
+    }
+    fs::write(&rollout, content).unwrap();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "sync-back", &codex_id])
+        .output()
+        .expect("sync-back should run");
+    assert!(
+        output.status.success(),
+        "sync-back failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["appended"], 2);
+    let synced = fs::read_to_string(&cc_path).unwrap();
+    assert!(synced.contains("Now add a test for it"));
+    assert!(!synced.contains("CODEX SUMMARY") && !synced.contains("CODEX REPLACEMENT"));
+
+    // The same for a Claude Code target compacted with `/compact`.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cc", &codex_id])
+        .output()
+        .expect("resume should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cc2_id = parsed["target_session_id"].as_str().unwrap().to_string();
+    let cc2_path = PathBuf::from(parsed["written_paths"][0].as_str().unwrap());
+    let mut cc2 = fs::read_to_string(&cc2_path).unwrap();
+    for line in [
+        format!(
+            r#"{{"type":"system","subtype":"compact_boundary","sessionId":"{cc2_id}","uuid":"c-1","timestamp":"2030-01-01T00:00:00.000Z"}}"#
+        ),
+        format!(
+            r#"{{"type":"user","isCompactSummary":true,"sessionId":"{cc2_id}","cwd":"/tmp","message":{{"role":"user","content":"This session is being continued from a previous conversation. CC SUMMARY"}},"uuid":"c-2","timestamp":"2030-01-01T00:00:01.000Z"}}"#
+        ),
+        format!(
+            r#"{{"type":"user","sessionId":"{cc2_id}","cwd":"/tmp","message":{{"role":"user","content":"Ship it"}},"uuid":"c-3","timestamp":"2030-01-01T00:00:02.000Z"}}"#
+        ),
+    ] {
+        cc2.push_str(&line);
+        cc2.push('\n');
+    }
+    fs::write(&cc2_path, cc2).unwrap();
+
     let output = casr_cmd(&tmp)
-        .args([
-            "--json",
-            "list",
-            "--workspace",
-            "/tmp/not-current-casr-workspace",
-        ])
+        .args(["--json", "sync-back", &cc2_id])
         .output()
-        .expect("list --workspace should run");
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let parsed: serde_json::Value =
-        serde_json::from_str(&stdout).expect("list --workspace --json should emit valid JSON");
-    let arr = parsed["items"]
-        .as_array()
-        .expect("list --workspace --json items should be an array");
+        .expect("sync-back should run");
     assert!(
-        arr.iter()
-            .any(|e| e["session_id"].as_str() == Some(out_of_scope_sid)),
-        "explicit workspace override should include out-of-scope fixture"
+        output.status.success(),
+        "sync-back failed: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["appended"], 1);
+    let synced = fs::read_to_string(&rollout).unwrap();
+    assert!(synced.contains("Ship it"));
+    assert!(!synced.contains("CC SUMMARY"));
 }
 
 #[test]
-fn cli_list_provider_filter_accepts_claude_standard_name() {
+fn cli_resume_append_to_extends_the_existing_target_session() {
     let tmp = TempDir::new().unwrap();
-    setup_cc_fixture(&tmp, "cc_simple");
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+    let cc_path = fs::read_dir(tmp.path().join("claude/projects"))
+        .unwrap()
+        .map(|dir| dir.unwrap().path().join(format!("{cc_id}.jsonl")))
+        .find(|path| path.is_file())
+        .expect("CC fixture file");
 
     let output = casr_cmd(&tmp)
-        .args([
-            "--json",
-            "list",
-            "--provider",
-            "claude",
-            "--workspace",
-            "/data/projects/myapp",
-        ])
+        .args(["--json", "resume", "cod", &cc_id])
         .output()
-        .expect("list --provider claude should run");
+        .expect("resume should run");
     assert!(output.status.success());
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let parsed: serde_json::Value =
-        serde_json::from_str(&stdout).expect("list --json should emit valid JSON");
-    let arr = parsed["items"]
-        .as_array()
-        .expect("list --json items should be an array");
-    assert!(!arr.is_empty(), "should find at least one Claude session");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let codex_id = parsed["target_session_id"].as_str().unwrap().to_string();
+    let rollout = PathBuf::from(parsed["written_paths"][0].as_str().unwrap());
+    let original = fs::read_to_string(&rollout).unwrap();
+
+    // Keep working in Claude Code, then hand the session to Codex again.
+    let mut cc = fs::read_to_string(&cc_path).unwrap();
+    cc.push_str(&format!(
+        r#"{{"parentUuid":"u-004","isSidechain":false,"userType":"external","cwd":"/data/projects/myapp","sessionId":"{cc_id}","version":"1.0.27","gitBranch":"main","type":"user","message":{{"role":"user","content":"Now run the whole suite."}},"uuid":"u-005","timestamp":"2026-01-15T10:05:00.000Z"}}"#
+    ));
+    cc.push('\n');
+    fs::write(&cc_path, cc).unwrap();
+
+    let append = || -> serde_json::Value {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "resume", "cod", &cc_id, "--append-to", &codex_id])
+            .output()
+            .expect("resume --append-to should run");
+        assert!(
+            output.status.success(),
+            "resume --append-to failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let first = append();
+    assert_eq!(first["target_session_id"], codex_id.as_str());
+    assert_eq!(first["append"]["appended"], 1);
+    assert_eq!(first["append"]["already_present"], 4);
+    let extended = fs::read_to_string(&rollout).unwrap();
     assert!(
-        arr.iter()
-            .all(|entry| entry["provider"].as_str() == Some("claude-code")),
-        "provider filter should only return Claude sessions"
+        extended.starts_with(&original),
+        "session_meta and existing events are kept"
     );
+    assert!(extended.contains("Now run the whole suite."));
+
+    let second = append();
+    assert_eq!(second["append"]["appended"], 0);
+    assert_eq!(fs::read_to_string(&rollout).unwrap(), extended);
+
+    // Gemini rewrites whole JSON documents; there is nothing to append to.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "gmi", &cc_id, "--append-to", "anything"])
+        .output()
+        .expect("resume should run");
+    assert!(!output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(parsed["error_type"], "AppendRejected");
 }
 
 #[test]
-fn cli_resume_cc_to_cursor_works_and_is_discoverable() {
+fn cli_mcp_serves_sessions_over_stdio() {
     let tmp = TempDir::new().unwrap();
-    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+
+    let call = |id: i64, name: &str, arguments: serde_json::Value| {
+        serde_json::json!({
+            "jsonrpc": "2.0", "id": id, "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+        .to_string()
+    };
+    let input = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#.to_string(),
+        call(2, "list_sessions", serde_json::json!({ "provider": "cc" })),
+        call(3, "search_sessions", serde_json::json!({ "query": "LOGIN bug" })),
+        call(4, "get_session_messages", serde_json::json!({ "session_id": cc_id, "start": -2 })),
+        call(5, "get_session_summary", serde_json::json!({ "session_id": cc_id, "provider": "cc" })),
+        call(6, "handoff_brief", serde_json::json!({ "session_id": cc_id, "target": "cod" })),
+    ]
+    .join("\n");
 
     let output = casr_cmd(&tmp)
-        .args(["--json", "resume", "cur", &session_id])
+        .arg("mcp")
+        .write_stdin(input + "\n")
         .output()
-        .expect("resume should run");
-    assert!(
-        output.status.success(),
-        "CC→Cursor conversion should succeed"
-    );
+        .expect("casr mcp should run");
+    assert!(output.status.success());
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("one JSON-RPC message per line"))
+        .collect();
+    // The notification gets no reply.
+    assert_eq!(responses.len(), 6);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    let result = |idx: usize| {
+        let result = &responses[idx]["result"];
+        assert_eq!(result["isError"], false, "{result}");
+        result["structuredContent"].clone()
+    };
 
-    let parsed: serde_json::Value =
-        serde_json::from_slice(&output.stdout).expect("resume --json output should parse");
-    assert_eq!(parsed["ok"], true);
-    assert_eq!(parsed["target_provider"].as_str().unwrap(), "cursor");
-    let cursor_session_id = parsed["target_session_id"]
-        .as_str()
-        .expect("target_session_id should be present for non-dry-run");
+    let listed = result(1);
+    assert_eq!(listed["sessions"][0]["session_id"], cc_id.as_str());
+    assert_eq!(listed["sessions"][0]["provider"], "claude-code");
 
-    let cursor_db = tmp.path().join("cursor/User/globalStorage/state.vscdb");
+    let found = result(2);
+    let hit = &found["results"][0];
+    assert_eq!(hit["session"]["session_id"], cc_id.as_str());
+    assert_eq!(hit["hits"][0]["idx"], 0);
     assert!(
-        cursor_db.exists(),
-        "Cursor DB should exist after CC→Cursor conversion"
+        hit["hits"][0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("login bug")
     );
 
-    casr_cmd(&tmp)
-        .args(["--json", "info", cursor_session_id])
-        .assert()
-        .success();
+    let messages = result(3);
+    assert_eq!(messages["total"], 4);
+    assert_eq!(messages["start"], 2);
+    assert_eq!(
+        messages["messages"][0]["content"],
+        "Great, can you also add a test for it?"
+    );
+
+    let summary = result(4);
+    assert_eq!(summary["by_role"]["user"], 2);
+    assert_eq!(summary["first_request"], "Fix the login bug in auth.rs");
+
+    let brief = responses[5]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(brief.starts_with(&format!("Handoff brief for claude-code session {cc_id}")));
+    assert!(brief.contains("- user: Great, can you also add a test for it?"));
+}
+
+/// One HTTP/1.1 request to a local `casr serve`: (status, body).
+fn http(addr: &str, method: &str, path: &str, body: Option<&str>) -> (u16, String) {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(addr).expect("connect to casr serve");
+    let body = body.unwrap_or("");
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("HTTP response");
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
 }
 
 #[test]
-fn cli_resume_cursor_to_cc_works_with_source_hint() {
+fn cli_serve_exposes_the_web_ui_and_json_api() {
+    use std::io::{BufRead, BufReader};
     let tmp = TempDir::new().unwrap();
-    let source_id = setup_cc_fixture(&tmp, "cc_simple");
+    let cc_id = setup_cc_fixture(&tmp, "cc_simple");
+
+    /// Stops the server even when an assertion fails.
+    struct Server(std::process::Child);
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
 
-    let cursor_result = casr_cmd(&tmp)
-        .args(["--json", "resume", "cur", &source_id])
-        .output()
-        .expect("CC→Cursor seed conversion should run");
-    assert!(cursor_result.status.success());
-    let cursor_json: serde_json::Value =
-        serde_json::from_slice(&cursor_result.stdout).expect("seed conversion JSON should parse");
-    let cursor_session_id = cursor_json["target_session_id"]
-        .as_str()
-        .expect("cursor target_session_id should be present");
+    let mut server = Server(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin!("casr"))
+            .args(["--json", "serve", "--port", "0"])
+            .envs(casr_cmd(&tmp).get_envs().filter_map(|(k, v)| Some((k, v?))))
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .expect("casr serve should start"),
+    );
+    let mut line = String::new();
+    BufReader::new(server.0.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let started: serde_json::Value = serde_json::from_str(&line).unwrap();
+    let url = started["url"].as_str().unwrap().to_string();
+    let addr = url
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string();
+    assert!(addr.starts_with("127.0.0.1:"), "{url}");
 
-    casr_cmd(&tmp)
-        .args(["resume", "cc", cursor_session_id, "--source", "cur"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("Converted"))
-        .stdout(predicate::str::contains("cursor"))
-        .stdout(predicate::str::contains("claude-code"));
+    let (status, page) = http(&addr, "GET", "/", None);
+    assert_eq!(status, 200);
+    assert!(page.contains("<title>casr</title>"));
+
+    let (status, body) = http(&addr, "GET", "/api/providers", None);
+    assert_eq!(status, 200, "{body}");
+    let providers: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(
+        providers
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|p| p["slug"] == "claude-code")
+    );
+
+    let (status, body) = http(&addr, "GET", "/api/sessions?provider=cc", None);
+    assert_eq!(status, 200, "{body}");
+    let listed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(listed["items"][0]["session_id"], cc_id.as_str());
+
+    let (status, body) = http(
+        &addr,
+        "GET",
+        &format!("/api/sessions/{cc_id}/messages?source=cc"),
+        None,
+    );
+    assert_eq!(status, 200, "{body}");
+    let transcript: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        transcript["messages"][0]["content"],
+        "Fix the login bug in auth.rs"
+    );
+
+    let (status, body) = http(&addr, "GET", "/api/sessions/nope-404", None);
+    assert_eq!(status, 404);
+    let error: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["error_type"], "SessionNotFound");
+
+    let request = serde_json::json!({ "target": "cod", "session_id": cc_id, "dry_run": true });
+    let (status, body) = http(&addr, "POST", "/api/resume", Some(&request.to_string()));
+    assert_eq!(status, 200, "{body}");
+    let resumed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(resumed["ok"], true);
+    assert_eq!(resumed["dry_run"], true);
+    assert_eq!(resumed["target_provider"], "codex");
+
+    let request = serde_json::json!({ "target": "cod", "session_id": cc_id });
+    let (status, body) = http(&addr, "POST", "/api/resume", Some(&request.to_string()));
+    assert_eq!(status, 200, "{body}");
+    let resumed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let rollout = resumed["written_paths"][0].as_str().unwrap();
+    assert!(std::path::Path::new(rollout).is_file());
+
+    // The remaining `resume` flags: templates as text, `strict` as a list.
+    let request = serde_json::json!({
+        "target": "cod",
+        "session_id": cc_id,
+        "continue_prompt": "PICK UP FROM HERE",
+        "strict": ["tools"],
+    });
+    let (status, body) = http(&addr, "POST", "/api/resume", Some(&request.to_string()));
+    assert_eq!(status, 200, "{body}");
+    let resumed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(resumed["fidelity"].is_object(), "{body}");
+    let rollout = resumed["written_paths"][0].as_str().unwrap();
+    assert!(
+        fs::read_to_string(rollout)
+            .unwrap()
+            .contains("PICK UP FROM HERE")
+    );
+    for bad in [
+        serde_json::json!({ "target": "cod", "session_id": cc_id, "strict": ["bogus"] }),
+        serde_json::json!({ "target": "cod", "session_id": cc_id, "enrich_template": "{{nope}}" }),
+    ] {
+        let (status, body) = http(&addr, "POST", "/api/resume", Some(&bad.to_string()));
+        assert_eq!(status, 400, "{body}");
+    }
+
+    // A bad `--home` is caught at startup, not on every request.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "--home"])
+        .arg(tmp.path().join("missing"))
+        .args(["serve", "--port", "0"])
+        .output()
+        .expect("casr serve should run");
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["ok"], false);
 }
 
 #[test]
@@ -1614,3 +2736,341 @@ fn cli_trace_emits_trace_logs() {
         .success()
         .stderr(predicate::str::contains("TRACE").and(predicate::str::contains("searching")));
 }
+
+#[test]
+fn cli_home_finds_and_marks_sessions_under_an_alternate_home() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    // Move the session into a backup of another machine's home.
+    let backup = tmp.path().join("backup");
+    fs::create_dir_all(&backup).unwrap();
+    fs::rename(tmp.path().join("claude"), backup.join(".claude")).unwrap();
+    let backup_str = backup.display().to_string();
+
+    casr_cmd(&tmp)
+        .args(["info", &session_id])
+        .assert()
+        .failure();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "list", "--provider", "cc", "--home", &backup_str])
+        .output()
+        .expect("list should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let item = parsed["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["session_id"] == session_id.as_str())
+        .expect("backup session listed");
+    assert_eq!(item["home"], backup_str.as_str());
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "--root", &backup_str, "info", &session_id])
+        .output()
+        .expect("info should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["home"], backup_str.as_str());
+
+    // Resuming copies the session into the current home.
+    let output = casr_cmd(&tmp)
+        .args(["--json", "resume", "cc", &session_id, "--home", &backup_str])
+        .output()
+        .expect("resume should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let written = parsed["written_paths"][0].as_str().expect("written path");
+    assert!(written.starts_with(&tmp.path().join("claude").display().to_string()));
+
+    casr_cmd(&tmp)
+        .args(["list", "--home", "/definitely/not/here"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a directory"));
+}
+
+#[test]
+fn cli_reads_sessions_from_tar_gz_and_zip_archives() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let session_path = walkdir::WalkDir::new(tmp.path().join("claude"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|e| e.file_name().to_string_lossy() == format!("{session_id}.jsonl"))
+        .expect("seeded session")
+        .into_path();
+    let member = format!(
+        ".claude/{}",
+        session_path
+            .strip_prefix(tmp.path().join("claude"))
+            .unwrap()
+            .display()
+    );
+    let content = fs::read(&session_path).unwrap();
+    fs::remove_dir_all(tmp.path().join("claude")).unwrap();
+
+    // A `tar czf laptop.tar.gz -C ~ .` snapshot.
+    let tar_gz = tmp.path().join("laptop.tar.gz");
+    let gz = flate2::write::GzEncoder::new(
+        fs::File::create(&tar_gz).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut tar = tar::Builder::new(gz);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, format!("./{member}"), content.as_slice())
+        .unwrap();
+    tar.into_inner().unwrap().finish().unwrap();
+    let tar_gz_str = tar_gz.display().to_string();
+    let virtual_path = format!("{tar_gz_str}!/{member}");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "list", "--root", &tar_gz_str])
+        .output()
+        .expect("list should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let item = &parsed["items"][0];
+    assert_eq!(item["session_id"], session_id.as_str());
+    assert_eq!(item["provider"], "claude-code");
+    assert_eq!(item["path"], virtual_path.as_str());
+    assert_eq!(item["home"], tar_gz_str.as_str());
+
+    // `--source` takes the archive (searched by member name) or a virtual path.
+    for source in [&tar_gz_str, &virtual_path] {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "info", &session_id, "--source", source])
+            .output()
+            .expect("info should run");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(parsed["provider"], "claude-code");
+        assert_eq!(parsed["source_path"], virtual_path.as_str());
+    }
+
+    // The same session zipped, resumed into Codex without unpacking.
+    let zip_path = tmp.path().join("laptop.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    zip.start_file(member.as_str(), zip::write::SimpleFileOptions::default())
+        .unwrap();
+    std::io::Write::write_all(&mut zip, &content).unwrap();
+    zip.finish().unwrap();
+    let output = casr_cmd(&tmp)
+        .args([
+            "--json",
+            "resume",
+            "cod",
+            &session_id,
+            "--source",
+            &zip_path.display().to_string(),
+        ])
+        .output()
+        .expect("resume should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["source_provider"], "claude-code");
+    assert!(
+        parsed["written_paths"][0]
+            .as_str()
+            .unwrap()
+            .starts_with(&tmp.path().join("codex").display().to_string())
+    );
+}
+
+#[test]
+fn cli_finds_archived_sessions_not_named_after_their_id() {
+    let tmp = TempDir::new().unwrap();
+    let gemini = fs::read(fixtures_dir().join("gemini/gmi_simple.json")).unwrap();
+    let cline =
+        fs::read(fixtures_dir().join("cline/tasks/1700001234567/api_conversation_history.json"))
+            .unwrap();
+    // Same-named members in different directories must not clash.
+    let other_cline = br#"[{"role":"user","content":"another task"}]"#;
+    let archive = tmp.path().join("laptop.tar.gz");
+    let gz = flate2::write::GzEncoder::new(
+        fs::File::create(&archive).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut tar = tar::Builder::new(gz);
+    for (member, content) in [
+        (
+            ".gemini/tmp/hash/chats/session-2026-01-14T16-00-gmi.json",
+            gemini.as_slice(),
+        ),
+        (
+            ".cline/data/tasks/1700001234567/api_conversation_history.json",
+            cline.as_slice(),
+        ),
+        (
+            ".cline/data/tasks/1700009999999/api_conversation_history.json",
+            other_cline.as_slice(),
+        ),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, member, content).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap();
+    let archive_str = archive.display().to_string();
+
+    for (session_id, provider, member) in [
+        (
+            "gmi-simple-001",
+            "gemini",
+            ".gemini/tmp/hash/chats/session-2026-01-14T16-00-gmi.json",
+        ),
+        (
+            "1700001234567",
+            "cline",
+            ".cline/data/tasks/1700001234567/api_conversation_history.json",
+        ),
+    ] {
+        let output = casr_cmd(&tmp)
+            .args(["--json", "info", session_id, "--source", &archive_str])
+            .output()
+            .expect("info should run");
+        assert!(
+            output.status.success(),
+            "{session_id}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(parsed["provider"], provider);
+        assert_eq!(parsed["source_path"], format!("{archive_str}!/{member}"));
+    }
+}
+
+#[test]
+fn cli_backup_and_restore_round_trip_with_conflict_detection() {
+    let tmp = TempDir::new().unwrap();
+    let session_id = setup_cc_fixture(&tmp, "cc_simple");
+    let session_path = walkdir::WalkDir::new(tmp.path().join("claude"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|e| e.file_type().is_file())
+        .expect("fixture written")
+        .into_path();
+    let original = fs::read_to_string(&session_path).unwrap();
+    let archive = tmp.path().join("snap.tar.gz");
+    let archive_str = archive.display().to_string();
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "backup", "--provider", "cc", "-o", &archive_str])
+        .output()
+        .expect("backup should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["files"], 1);
+    assert_eq!(parsed["providers"][0]["provider"], "claude-code");
+
+    // The backup is an ordinary archive: list can read it.
+    casr_cmd(&tmp)
+        .args(["list", "--provider", "cc", "--root", &archive_str])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(session_id.as_str()));
+
+    fs::remove_file(&session_path).unwrap();
+    casr_cmd(&tmp)
+        .args(["restore", &archive_str, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("would restore"));
+    assert!(!session_path.exists());
+    casr_cmd(&tmp)
+        .args(["restore", &archive_str, "--session", &session_id])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&session_path).unwrap(), original);
+
+    fs::write(&session_path, "edited\n").unwrap();
+    casr_cmd(&tmp)
+        .args(["restore", &archive_str])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    assert_eq!(fs::read_to_string(&session_path).unwrap(), "edited\n");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "restore", &archive_str, "--force"])
+        .output()
+        .expect("restore should run");
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["files"][0]["action"], "replaced");
+    assert_eq!(fs::read_to_string(&session_path).unwrap(), original);
+}
+
+#[test]
+fn cli_restore_writes_into_the_given_home() {
+    let tmp = TempDir::new().unwrap();
+    let old_home = tmp.path().join("old-home");
+    let new_home = tmp.path().join("new-home");
+    let session = ".claude/projects/-work-app/sess-1.jsonl";
+    fs::create_dir_all(old_home.join(session).parent().unwrap()).unwrap();
+    fs::create_dir_all(&new_home).unwrap();
+    fs::copy(
+        fixtures_dir().join("claude_code/cc_simple.jsonl"),
+        old_home.join(session),
+    )
+    .unwrap();
+    let archive = tmp.path().join("snap.tar.gz");
+    let archive_str = archive.display().to_string();
+
+    casr_cmd(&tmp)
+        .args([
+            "--home",
+            old_home.to_str().unwrap(),
+            "backup",
+            "-o",
+            &archive_str,
+        ])
+        .assert()
+        .success();
+    casr_cmd(&tmp)
+        .args([
+            "--home",
+            new_home.to_str().unwrap(),
+            "restore",
+            &archive_str,
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read(new_home.join(session)).unwrap(),
+        fs::read(old_home.join(session)).unwrap()
+    );
+    assert!(!tmp.path().join("claude/projects").exists());
+
+    casr_cmd(&tmp)
+        .args(["--home", "/definitely/not/here", "restore", &archive_str])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at most one --home"));
+}
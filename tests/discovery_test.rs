@@ -93,26 +93,36 @@ impl Provider for MockProvider {
                     idx: 0,
                     role: MessageRole::User,
                     content: "hi".to_string(),
+                    parts: Vec::new(),
                     timestamp: None,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: Vec::new(),
                     tool_results: Vec::new(),
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::json!({}),
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "hello".to_string(),
+                    parts: Vec::new(),
                     timestamp: None,
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: Vec::new(),
                     tool_results: Vec::new(),
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::json!({}),
                 },
             ],
             metadata: serde_json::json!({}),
             source_path: _path.to_path_buf(),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         })
     }
 
@@ -88,26 +88,36 @@ mod unix_error_paths {
                     idx: 0,
                     role: MessageRole::User,
                     content: "test question".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_000_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
                 CanonicalMessage {
                     idx: 1,
                     role: MessageRole::Assistant,
                     content: "test answer".to_string(),
+                    parts: Vec::new(),
                     timestamp: Some(1_700_000_010_000),
                     author: None,
+                    reasoning: Vec::new(),
                     tool_calls: vec![],
                     tool_results: vec![],
+                    attachments: Vec::new(),
+                    usage: None,
                     extra: serde_json::Value::Null,
                 },
             ],
             metadata: serde_json::Value::Null,
             source_path: PathBuf::from("/tmp/source.jsonl"),
             model_name: None,
+            usage: None,
+            unrecognized: None,
         }
     }
 
@@ -389,7 +399,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = Codex.write_session(&session, &WriteOptions { force: false });
+        let err = Codex.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "Codex: writing to read-only dir should fail; got {:?}",
@@ -411,7 +421,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = ClawdBot.write_session(&session, &WriteOptions { force: false });
+        let err = ClawdBot.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "ClawdBot: writing to read-only dir should fail; got {:?}",
@@ -433,7 +443,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = Vibe.write_session(&session, &WriteOptions { force: false });
+        let err = Vibe.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "Vibe: writing to read-only dir should fail; got {:?}",
@@ -455,7 +465,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = Factory.write_session(&session, &WriteOptions { force: false });
+        let err = Factory.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "Factory: writing to read-only dir should fail; got {:?}",
@@ -477,7 +487,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = OpenClaw.write_session(&session, &WriteOptions { force: false });
+        let err = OpenClaw.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "OpenClaw: writing to read-only dir should fail; got {:?}",
@@ -500,7 +510,7 @@ mod unix_error_paths {
         };
 
         let session = make_session("/tmp");
-        let err = PiAgent.write_session(&session, &WriteOptions { force: false });
+        let err = PiAgent.write_session(&session, &WriteOptions::default());
         assert!(
             err.is_err(),
             "PiAgent: writing to read-only dir should fail; got {:?}",
@@ -612,7 +622,7 @@ mod unix_error_paths {
         session.workspace = None;
 
         // CC writer should handle None workspace gracefully.
-        let result = ClaudeCode.write_session(&session, &WriteOptions { force: false });
+        let result = ClaudeCode.write_session(&session, &WriteOptions::default());
         // Either succeeds with a fallback workspace or errors — but should not panic.
         match result {
             Ok(written) => {
@@ -637,7 +647,7 @@ mod unix_error_paths {
         let mut session = make_session("/tmp");
         session.workspace = None;
 
-        let result = Codex.write_session(&session, &WriteOptions { force: false });
+        let result = Codex.write_session(&session, &WriteOptions::default());
         match result {
             Ok(written) => {
                 assert!(!written.paths.is_empty());
@@ -662,7 +672,7 @@ mod unix_error_paths {
         session.messages.clear();
 
         // Should either produce a file or error — never panic.
-        let _ = ClaudeCode.write_session(&session, &WriteOptions { force: false });
+        let _ = ClaudeCode.write_session(&session, &WriteOptions::default());
     }
 
     #[test]
@@ -674,7 +684,7 @@ mod unix_error_paths {
         let mut session = make_session("/tmp");
         session.messages.clear();
 
-        let _ = Codex.write_session(&session, &WriteOptions { force: false });
+        let _ = Codex.write_session(&session, &WriteOptions::default());
     }
 
     #[test]
@@ -686,7 +696,7 @@ mod unix_error_paths {
         let mut session = make_session("/tmp");
         session.messages.clear();
 
-        let _ = Gemini.write_session(&session, &WriteOptions { force: false });
+        let _ = Gemini.write_session(&session, &WriteOptions::default());
     }
 
     // =========================================================================
@@ -5,7 +5,7 @@
 
 use std::path::{Path, PathBuf};
 
-use casr::model::{CanonicalSession, MessageRole};
+use casr::model::{AttachmentSource, CanonicalSession, MessageRole};
 use casr::providers::Provider;
 use casr::providers::aider::Aider;
 use casr::providers::amp::Amp;
@@ -231,6 +231,32 @@ fn fixture_cc_unicode() {
     );
 }
 
+#[test]
+fn fixture_cc_with_image() {
+    let path = fixtures_dir().join("claude_code/cc_with_image.jsonl");
+    let session = ClaudeCode
+        .read_session(&path)
+        .expect("cc_with_image should parse");
+    let expected = load_expected("cc_with_image");
+    assert_session_matches(&session, &expected, "cc_with_image");
+
+    // Extra: the screenshot is captured as an attachment, not flattened into text.
+    let screenshot = &session.messages[0].attachments;
+    assert_eq!(screenshot.len(), 1);
+    assert_eq!(screenshot[0].media_type.as_deref(), Some("image/png"));
+    assert_eq!(screenshot[0].size_bytes, Some(70));
+    assert!(!session.messages[0].content.contains("iVBOR"));
+
+    // The image-only turn survives with empty text.
+    assert!(session.messages[2].content.is_empty());
+    assert_eq!(
+        session.messages[2].attachments[0].source,
+        AttachmentSource::Url {
+            url: "https://example.com/after.png".to_string()
+        }
+    );
+}
+
 #[test]
 fn fixture_cc_malformed() {
     let path = fixtures_dir().join("claude_code/cc_malformed.jsonl");
@@ -284,16 +310,21 @@ fn fixture_codex_reasoning() {
     let expected = load_expected("codex_reasoning");
     assert_session_matches(&session, &expected, "codex_reasoning");
 
-    // Extra: verify reasoning messages have author="reasoning".
+    // Extra: verify each assistant turn carries its reasoning trace.
     let reasoning_msgs: Vec<_> = session
         .messages
         .iter()
-        .filter(|m| m.author.as_deref() == Some("reasoning"))
+        .filter(|m| !m.reasoning.is_empty())
         .collect();
     assert_eq!(
         reasoning_msgs.len(),
         2,
-        "codex_reasoning should have exactly 2 reasoning messages"
+        "codex_reasoning should have exactly 2 messages with reasoning"
+    );
+    assert!(
+        reasoning_msgs
+            .iter()
+            .all(|m| m.role == MessageRole::Assistant && !m.content.is_empty())
     );
 }
 
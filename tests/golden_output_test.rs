@@ -21,7 +21,9 @@ use std::path::PathBuf;
 
 use tempfile::TempDir;
 
-use casr::model::{CanonicalMessage, CanonicalSession, MessageRole, ToolCall, ToolResult};
+use casr::model::{
+    CanonicalMessage, CanonicalSession, MessageRole, Reasoning, ToolCall, ToolResult,
+};
 use casr::providers::{Provider, WriteOptions};
 
 // ---------------------------------------------------------------------------
@@ -73,36 +75,50 @@ fn simple_session() -> CanonicalSession {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hello, please help me.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Sure, I can help.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_030_000),
                 author: Some("test-model".to_string()),
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 2,
                 role: MessageRole::User,
                 content: "Thanks!".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_060_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ],
         metadata: serde_json::json!({"source": "test"}),
         source_path: PathBuf::from("/tmp/golden-test.jsonl"),
         model_name: Some("test-model".to_string()),
+        usage: None,
+        unrecognized: None,
     }
 }
 
@@ -119,54 +135,72 @@ fn tool_call_session() -> CanonicalSession {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Read the file".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Reading main.rs now.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_030_000),
                 author: Some("test-model".to_string()),
-                tool_calls: vec![ToolCall {
-                    id: Some("call-1".to_string()),
-                    name: "Read".to_string(),
-                    arguments: serde_json::json!({"file_path": "src/main.rs"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    Some("call-1".to_string()),
+                    "Read".to_string(),
+                    serde_json::json!({"file_path": "src/main.rs"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 2,
                 role: MessageRole::User,
                 content: "".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_060_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![ToolResult {
                     call_id: Some("call-1".to_string()),
                     content: "fn main() {}".to_string(),
                     is_error: false,
                 }],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 3,
                 role: MessageRole::Assistant,
                 content: "The file is very simple.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_090_000),
                 author: Some("test-model".to_string()),
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ],
         metadata: serde_json::json!({"source": "test"}),
         source_path: PathBuf::from("/tmp/golden-tools.jsonl"),
         model_name: Some("test-model".to_string()),
+        usage: None,
+        unrecognized: None,
     }
 }
 
@@ -183,36 +217,39 @@ fn reasoning_session() -> CanonicalSession {
                 idx: 0,
                 role: MessageRole::User,
                 content: "What is 2+2?".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
-                content: "Let me think about this...".to_string(),
-                timestamp: Some(1_700_000_030_000),
-                author: Some("reasoning".to_string()),
-                tool_calls: vec![],
-                tool_results: vec![],
-                extra: serde_json::Value::Null,
-            },
-            CanonicalMessage {
-                idx: 2,
-                role: MessageRole::Assistant,
                 content: "The answer is 4.".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_060_000),
                 author: Some("test-model".to_string()),
+                reasoning: vec![Reasoning {
+                    text: "Let me think about this...".to_string(),
+                    ..Reasoning::default()
+                }],
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ],
         metadata: serde_json::json!({"source": "test"}),
         source_path: PathBuf::from("/tmp/golden-reasoning.jsonl"),
         model_name: Some("test-model".to_string()),
+        usage: None,
+        unrecognized: None,
     }
 }
 
@@ -230,26 +267,36 @@ fn unicode_session() -> CanonicalSession {
                 role: MessageRole::User,
                 content: "Translate: \u{4f60}\u{597d}\u{4e16}\u{754c} and \u{1f600}\u{1f389}"
                     .to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: None,
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Hello World and \u{1f600}\u{1f389}".to_string(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_030_000),
                 author: Some("test-model".to_string()),
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ],
         metadata: serde_json::json!({"source": "test"}),
         source_path: PathBuf::from("/tmp/golden-unicode.jsonl"),
         model_name: Some("test-model".to_string()),
+        usage: None,
+        unrecognized: None,
     }
 }
 
@@ -285,7 +332,7 @@ mod cc_golden {
         let tmp = TempDir::new().unwrap();
         let _guard = EnvGuard::set("CLAUDE_HOME", tmp.path());
 
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = ClaudeCode.write_session(session, &opts).unwrap();
 
         let path = written.paths[0].clone();
@@ -617,7 +664,7 @@ mod codex_golden {
         let tmp = TempDir::new().unwrap();
         let _guard = EnvGuard::set("CODEX_HOME", tmp.path());
 
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = Codex.write_session(session, &opts).unwrap();
 
         let path = written.paths[0].clone();
@@ -907,7 +954,7 @@ mod gemini_golden {
         let tmp = TempDir::new().unwrap();
         let _guard = EnvGuard::set("GEMINI_HOME", tmp.path());
 
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = Gemini.write_session(session, &opts).unwrap();
 
         let path = written.paths[0].clone();
@@ -1157,7 +1204,7 @@ mod negative {
         let _guard = EnvGuard::set("CLAUDE_HOME", tmp.path());
 
         let session = simple_session();
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = ClaudeCode.write_session(&session, &opts).unwrap();
 
         let content = std::fs::read_to_string(&written.paths[0]).unwrap();
@@ -1178,7 +1225,7 @@ mod negative {
         let _guard = EnvGuard::set("CODEX_HOME", tmp.path());
 
         let session = simple_session();
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = Codex.write_session(&session, &opts).unwrap();
 
         let content = std::fs::read_to_string(&written.paths[0]).unwrap();
@@ -1207,7 +1254,7 @@ mod negative {
         let _guard = EnvGuard::set("GEMINI_HOME", tmp.path());
 
         let session = simple_session();
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = Gemini.write_session(&session, &opts).unwrap();
 
         let content = std::fs::read_to_string(&written.paths[0]).unwrap();
@@ -1232,7 +1279,7 @@ mod negative {
         let _guard = EnvGuard::set("CLAUDE_HOME", tmp.path());
 
         let session = tool_call_session();
-        let opts = WriteOptions { force: false };
+        let opts = WriteOptions::default();
         let written = ClaudeCode.write_session(&session, &opts).unwrap();
 
         let content = std::fs::read_to_string(&written.paths[0]).unwrap();
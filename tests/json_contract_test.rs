@@ -653,11 +653,79 @@ fn contract_info_json_source_path_is_absolute() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// Contract: `stats --json`
+// ---------------------------------------------------------------------------
+
+#[test]
+fn contract_stats_json_shape() {
+    let tmp = TempDir::new().unwrap();
+    setup_cc_fixture(&tmp, "cc_simple");
+    setup_codex_fixture(&tmp, "codex_modern", "jsonl");
+
+    let output = casr_cmd(&tmp)
+        .args(["--json", "stats"])
+        .output()
+        .expect("stats should run");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("Invalid JSON from stats: {e}\nOutput: {stdout}"));
+
+    let ctx = "stats";
+    assert_exact_keys(
+        &parsed,
+        &[
+            "schema_version",
+            "since",
+            "until",
+            "sessions",
+            "messages",
+            "median_messages",
+            "median_duration_secs",
+            "tool_calls",
+            "tool_results",
+            "tool_errors",
+            "tool_error_rate",
+            "providers",
+            "weeks",
+            "tools",
+            "workspaces",
+            "models",
+        ],
+        ctx,
+    );
+    assert_eq!(parsed["schema_version"], 2);
+    assert_eq!(parsed["sessions"], 2);
+    for field in ["providers", "weeks", "tools", "workspaces", "models"] {
+        assert_array(&parsed[field], field, ctx);
+    }
+    for provider in parsed["providers"].as_array().unwrap() {
+        assert_exact_keys(
+            provider,
+            &[
+                "provider",
+                "sessions",
+                "messages",
+                "median_messages",
+                "tool_calls",
+                "tool_errors",
+            ],
+            "stats.providers",
+        );
+    }
+    for week in parsed["weeks"].as_array().unwrap() {
+        assert_exact_keys(week, &["week", "sessions", "providers"], "stats.weeks");
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Contract: `resume --json` (success)
 // ---------------------------------------------------------------------------
 // Expected shape: {ok, source_provider, target_provider, source_session_id,
-//                  target_session_id, written_paths, resume_command, dry_run, warnings}
+//                  target_session_id, written_paths, resume_command, dry_run, warnings,
+//                  fidelity}
 
 fn assert_resume_success_object(obj: &serde_json::Value) {
     let ctx = "resume_success";
@@ -673,6 +741,7 @@ fn assert_resume_success_object(obj: &serde_json::Value) {
             "resume_command",
             "dry_run",
             "warnings",
+            "fidelity",
         ],
         ctx,
     );
@@ -686,6 +755,29 @@ fn assert_resume_success_object(obj: &serde_json::Value) {
     assert_string_or_null(&obj["resume_command"], "resume_command", ctx);
     assert_bool(&obj["dry_run"], "dry_run", ctx);
     assert_array(&obj["warnings"], "warnings", ctx);
+    let fidelity = &obj["fidelity"];
+    for key in [
+        "reasoning_dropped",
+        "reasoning_rendered_as_text",
+        "tool_outputs_truncated",
+        "turns_dropped",
+        "tool_calls_translated",
+        "tool_text_synthesized",
+        "attachments_replaced",
+        "timestamps_synthesized",
+    ] {
+        assert_uint(&fidelity[key], key, ctx);
+    }
+    for key in [
+        "tool_calls_rendered_as_text",
+        "roles_collapsed",
+        "extra_keys_ignored",
+    ] {
+        assert!(
+            fidelity[key].is_object(),
+            "{ctx}: fidelity.{key} should be an object"
+        );
+    }
 }
 
 #[test]
@@ -70,7 +70,13 @@ fn full_filesystem_round_trip_preserves_history_and_state() {
     let original = Kiro.read_session(&fixture_json()).expect("read original");
 
     let written = Kiro
-        .write_session(&original, &WriteOptions { force: true })
+        .write_session(
+            &original,
+            &WriteOptions {
+                force: true,
+                ..Default::default()
+            },
+        )
         .expect("write session");
 
     // .json + .jsonl + .history (history was present).
@@ -229,34 +235,50 @@ fn foreign_session_writes_and_rereads() {
                 idx: 0,
                 role: MessageRole::User,
                 content: "Hi there".into(),
+                parts: Vec::new(),
                 timestamp: Some(1_700_000_000_000),
                 author: Some("user".into()),
+                reasoning: Vec::new(),
                 tool_calls: vec![],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
             CanonicalMessage {
                 idx: 1,
                 role: MessageRole::Assistant,
                 content: "Hello back".into(),
+                parts: Vec::new(),
                 timestamp: None,
                 author: None,
-                tool_calls: vec![ToolCall {
-                    id: Some("t1".into()),
-                    name: "shell".into(),
-                    arguments: serde_json::json!({"command": "ls"}),
-                }],
+                reasoning: Vec::new(),
+                tool_calls: vec![ToolCall::new(
+                    Some("t1".into()),
+                    "shell".into(),
+                    serde_json::json!({"command": "ls"}),
+                )],
                 tool_results: vec![],
+                attachments: Vec::new(),
+                usage: None,
                 extra: serde_json::Value::Null,
             },
         ],
         metadata: serde_json::Value::Object(serde_json::Map::new()),
         source_path: PathBuf::from("/nonexistent"),
         model_name: None,
+        usage: None,
+        unrecognized: None,
     };
 
     let written = Kiro
-        .write_session(&session, &WriteOptions { force: true })
+        .write_session(
+            &session,
+            &WriteOptions {
+                force: true,
+                ..Default::default()
+            },
+        )
         .expect("write foreign session");
     // No history present → only .json + .jsonl.
     assert_eq!(written.paths.len(), 2);
@@ -17,12 +17,13 @@ use std::{
 use casr::{
     discovery::{DetectionResult, ProviderRegistry},
     error::CasrError,
-    model::{CanonicalMessage, CanonicalSession, MessageRole, ToolResult},
-    pipeline::{ConversionPipeline, ConvertOptions, validate_session},
+    model::{Attachment, CanonicalMessage, CanonicalSession, MessageRole, ToolResult},
+    pipeline::{ConversionPipeline, ConvertOptions, EditOptions, StrictCheck, validate_session},
     providers::claude_code::ClaudeCode,
     providers::codex::Codex,
     providers::gemini::Gemini,
-    providers::{Provider, WriteOptions, WrittenSession},
+    providers::{HistoryMode, Provider, ReadOptions, WriteOptions, WrittenSession},
+    rewrite::{MessageRange, SessionEdit},
 };
 
 #[derive(Clone)]
@@ -212,10 +213,14 @@ fn msg(idx: usize, role: MessageRole, content: &str, ts: Option<i64>) -> Canonic
         idx,
         role,
         content: content.to_string(),
+        parts: Vec::new(),
         timestamp: ts,
         author: None,
+        reasoning: Vec::new(),
         tool_calls: vec![],
         tool_results: vec![],
+        attachments: Vec::new(),
+        usage: None,
         extra: serde_json::Value::Null,
     }
 }
@@ -257,6 +262,8 @@ fn valid_session_with_id(session_id: &str) -> CanonicalSession {
         metadata: serde_json::Value::Null,
         source_path: PathBuf::from("/tmp/mock-source.json"),
         model_name: Some("mock-model".to_string()),
+        usage: None,
+        unrecognized: None,
     }
 }
 
@@ -321,6 +328,71 @@ fn pipeline_convert_happy_path_writes_and_verifies() {
     );
 }
 
+#[test]
+fn pipeline_folds_attachments_into_placeholders_for_text_only_targets() {
+    let src = MockProvider::new(
+        "Mock Source",
+        "mock-source",
+        "src",
+        vec![PathBuf::from("/tmp/src-root")],
+    );
+    let dst = MockProvider::new(
+        "Mock Target",
+        "mock-target",
+        "tgt",
+        vec![PathBuf::from("/tmp/tgt-root")],
+    );
+
+    let source_path = PathBuf::from("/tmp/src-root/session-img.json");
+    let written_path = PathBuf::from("/tmp/tgt-root/session-img-out.json");
+    let mut session = valid_session_with_id("sid-img");
+    session.messages[0].attachments = vec![Attachment::inline(
+        Some("image/png".to_string()),
+        "AAAA",
+        serde_json::Value::Null,
+    )];
+
+    let mut expected_written = session.clone();
+    expected_written.messages[0].content = format!(
+        "{}\n[Image: image/png, 3 bytes]",
+        session.messages[0].content
+    );
+    expected_written.messages[0].attachments.clear();
+
+    src.set_owned_session("sid-img", source_path.clone());
+    src.set_read_session(source_path, session);
+    dst.set_write_success(WrittenSession {
+        paths: vec![written_path.clone()],
+        session_id: "target-sid-img".to_string(),
+        resume_command: "tgt --resume target-sid-img".to_string(),
+        backup_path: None,
+    });
+    dst.set_read_session(written_path, expected_written.clone());
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(src.clone()), Box::new(dst.clone())]),
+    };
+
+    let result = pipeline
+        .convert("tgt", "sid-img", options(false, None))
+        .expect("placeholder fallback should verify cleanly");
+
+    let written = dst.last_written().expect("target should capture session");
+    assert_eq!(
+        written.messages[0].content,
+        expected_written.messages[0].content
+    );
+    assert!(written.messages[0].attachments.is_empty());
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|w| w.contains("1 attachment(s) replaced with text placeholders")),
+        "fallback should be surfaced: {:?}",
+        result.warnings
+    );
+}
+
 #[test]
 fn pipeline_dry_run_skips_write() {
     let src = MockProvider::new(
@@ -668,6 +740,159 @@ fn pipeline_readback_mismatch_fails_and_removes_unverified_output() {
     );
 }
 
+#[test]
+fn pipeline_strict_roles_fails_and_removes_output_when_target_collapses_roles() {
+    let tmp = tempfile::TempDir::new().expect("tempdir");
+    let src_root = tmp.path().join("src");
+    let dst_root = tmp.path().join("dst");
+    fs::create_dir_all(&src_root).expect("create src root");
+    fs::create_dir_all(&dst_root).expect("create dst root");
+
+    let src = MockProvider::new("Source", "src", "src", vec![src_root.clone()]);
+    let dst = MockProvider::new("Target", "dst", "tgt", vec![dst_root.clone()]);
+
+    let source_path = src_root.join("session-strict-roles.json");
+    let written_path = dst_root.join("out-strict-roles.json");
+    let mut session = valid_session_with_id("sid-strict-roles");
+    session.messages[2].role = MessageRole::System;
+    src.set_owned_session("sid-strict-roles", source_path.clone());
+    src.set_read_session(source_path, session);
+    dst.set_write_success(WrittenSession {
+        paths: vec![written_path.clone()],
+        session_id: "target-strict-roles".to_string(),
+        resume_command: "tgt --resume target-strict-roles".to_string(),
+        backup_path: None,
+    });
+    // The target has no system role and reads the message back as a user turn.
+    dst.set_read_session(
+        written_path.clone(),
+        valid_session_with_id("sid-strict-roles"),
+    );
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(src), Box::new(dst)]),
+    };
+
+    // Without --strict the collapse is only reported.
+    fs::write(&written_path, "converted").expect("seed output");
+    let result = pipeline
+        .convert("tgt", "sid-strict-roles", options(false, None))
+        .expect("non-strict convert should succeed");
+    assert_eq!(result.fidelity.roles_collapsed["system → user"], 1);
+    assert!(written_path.exists());
+
+    // Other checks don't trip on it.
+    let budget_only = ConvertOptions {
+        strict: [StrictCheck::Budget, StrictCheck::Tools].into(),
+        ..options(false, None)
+    };
+    pipeline
+        .convert("tgt", "sid-strict-roles", budget_only)
+        .expect("unrelated strict checks should pass");
+
+    let strict = ConvertOptions {
+        strict: [StrictCheck::Roles].into(),
+        ..options(false, None)
+    };
+    let err = pipeline
+        .convert("tgt", "sid-strict-roles", strict)
+        .expect_err("--strict=roles should refuse the collapse");
+    match err.downcast_ref::<CasrError>() {
+        Some(CasrError::StrictRolesCollapsed {
+            provider,
+            roles,
+            rollback,
+        }) => {
+            assert_eq!(provider, "dst");
+            assert_eq!(roles, &["system → user"]);
+            assert_eq!(rollback, "rollback succeeded");
+        }
+        other => panic!("expected StrictRolesCollapsed, got {other:?}"),
+    }
+    assert!(
+        !written_path.exists(),
+        "strict failure should remove the written session"
+    );
+}
+
+#[test]
+fn pipeline_strict_tools_rejects_orphan_tool_results_before_writing() {
+    let src = MockProvider::new("Source", "src", "src", vec![PathBuf::from("/tmp/src-root")]);
+    let dst = MockProvider::new("Target", "dst", "tgt", vec![PathBuf::from("/tmp/tgt-root")]);
+    let source_path = PathBuf::from("/tmp/src-root/session-orphan.json");
+    let mut session = valid_session_with_id("sid-orphan");
+    session.messages[2].tool_results.push(ToolResult {
+        call_id: Some("call-missing".to_string()),
+        content: "output".to_string(),
+        is_error: false,
+    });
+    src.set_owned_session("sid-orphan", source_path.clone());
+    src.set_read_session(source_path, session);
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(src), Box::new(dst.clone())]),
+    };
+    let strict = ConvertOptions {
+        strict: [StrictCheck::Tools].into(),
+        ..options(false, None)
+    };
+    let err = pipeline
+        .convert("tgt", "sid-orphan", strict)
+        .expect_err("--strict=tools should refuse orphan tool results");
+    match err.downcast_ref::<CasrError>() {
+        Some(CasrError::StrictOrphanToolResults { call_ids }) => {
+            assert_eq!(call_ids, &["call-missing"]);
+        }
+        other => panic!("expected StrictOrphanToolResults, got {other:?}"),
+    }
+    assert_eq!(dst.write_calls(), 0, "nothing should be written");
+}
+
+#[test]
+fn pipeline_edit_in_place_rejects_writer_that_picks_a_new_id() {
+    let tmp = tempfile::TempDir::new().expect("tempdir");
+    let root = tmp.path().join("src");
+    fs::create_dir_all(&root).expect("create root");
+
+    let src = MockProvider::new("Source", "src", "src", vec![root.clone()]);
+    let source_path = root.join("session-edit.json");
+    let written_path = root.join("out-edit.json");
+    fs::write(&source_path, "original").expect("seed original");
+    fs::write(&written_path, "unverified-output").expect("seed output");
+    src.set_owned_session("sid-edit", source_path.clone());
+    src.set_read_session(source_path.clone(), valid_session_with_id("sid-edit"));
+    src.set_write_success(WrittenSession {
+        paths: vec![written_path.clone()],
+        session_id: "fresh-id".to_string(),
+        resume_command: "src --resume fresh-id".to_string(),
+        backup_path: None,
+    });
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(src.clone())]),
+    };
+    let err = pipeline
+        .edit(
+            "sid-edit",
+            &[SessionEdit::DropMessages(MessageRange { start: 2, end: 3 })],
+            EditOptions {
+                in_place: true,
+                ..Default::default()
+            },
+        )
+        .expect_err("in-place edit under a new ID should fail");
+
+    match err.downcast_ref::<CasrError>() {
+        Some(CasrError::EditRejected { reason, .. }) => {
+            assert!(reason.contains("fresh-id"), "unexpected reason: {reason}");
+        }
+        other => panic!("expected EditRejected, got {other:?}"),
+    }
+    assert_eq!(src.last_written().expect("written").messages.len(), 2);
+    assert!(!written_path.exists(), "rejected output should be removed");
+    assert_eq!(fs::read_to_string(&source_path).unwrap(), "original");
+}
+
 #[test]
 fn pipeline_readback_content_mismatch_fails_and_removes_unverified_output() {
     let tmp = tempfile::TempDir::new().expect("tempdir");
@@ -721,6 +946,71 @@ fn pipeline_readback_content_mismatch_fails_and_removes_unverified_output() {
     );
 }
 
+#[test]
+fn pipeline_readback_reports_all_mismatches_including_dropped_tool_calls() {
+    let tmp = tempfile::TempDir::new().expect("tempdir");
+    let src_root = tmp.path().join("src");
+    let dst_root = tmp.path().join("dst");
+    fs::create_dir_all(&src_root).expect("create src root");
+    fs::create_dir_all(&dst_root).expect("create dst root");
+
+    let src = MockProvider::new("Source", "src", "src", vec![src_root.clone()]);
+    let dst = MockProvider::new("Target", "dst", "tgt", vec![dst_root.clone()]);
+
+    let mut session = valid_session_with_id("sid-deep-verify");
+    session.messages[1]
+        .tool_calls
+        .push(casr::model::ToolCall::new(
+            Some("call-1".to_string()),
+            "Bash".to_string(),
+            serde_json::json!({"command": "ls"}),
+        ));
+    session.messages[2].tool_results.push(ToolResult {
+        call_id: Some("call-1".to_string()),
+        content: "README.md".to_string(),
+        is_error: false,
+    });
+
+    let source_path = src_root.join("session-deep-verify.json");
+    let written_path = dst_root.join("out-deep-verify.json");
+    src.set_owned_session("sid-deep-verify", source_path.clone());
+    src.set_read_session(source_path, session.clone());
+    dst.set_write_success(WrittenSession {
+        paths: vec![written_path.clone()],
+        session_id: "target-deep-verify".to_string(),
+        resume_command: "tgt --resume target-deep-verify".to_string(),
+        backup_path: None,
+    });
+
+    // Same text, but the writer silently dropped every tool call and result,
+    // and shifted a timestamp by an hour.
+    let mut readback = session;
+    readback.messages[1].tool_calls.clear();
+    readback.messages[2].tool_results.clear();
+    readback.messages[3].timestamp = readback.messages[3].timestamp.map(|t| t + 3_600_000);
+    dst.set_read_session(written_path.clone(), readback);
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(src), Box::new(dst)]),
+    };
+    let err = pipeline
+        .convert("tgt", "sid-deep-verify", options(false, None))
+        .expect_err("dropped tool calls should fail verification");
+
+    match err.downcast_ref::<CasrError>() {
+        Some(CasrError::VerifyFailed {
+            detail, mismatches, ..
+        }) => {
+            assert_eq!(mismatches.len(), 3, "{mismatches:?}");
+            assert!(detail.starts_with("3 read-back mismatches"), "{detail}");
+            assert!(detail.contains("tool call count mismatch at idx 1"));
+            assert!(detail.contains("tool result count mismatch at idx 2"));
+            assert!(detail.contains("timestamp mismatch at idx 3"));
+        }
+        other => panic!("expected VerifyFailed, got {other:?}"),
+    }
+}
+
 #[test]
 fn pipeline_readback_error_restores_backup_and_returns_verify_failed() {
     let tmp = tempfile::TempDir::new().expect("tempdir");
@@ -834,11 +1124,13 @@ fn validate_session_warnings_and_info_for_quality_issues() {
 #[test]
 fn validate_session_reports_tool_call_info_when_present() {
     let mut session = valid_session_with_id("tool-calls");
-    session.messages[1].tool_calls.push(casr::model::ToolCall {
-        id: Some("call-1".to_string()),
-        name: "Read".to_string(),
-        arguments: serde_json::json!({"file":"src/lib.rs"}),
-    });
+    session.messages[1]
+        .tool_calls
+        .push(casr::model::ToolCall::new(
+            Some("call-1".to_string()),
+            "Read".to_string(),
+            serde_json::json!({"file":"src/lib.rs"}),
+        ));
     let validation = validate_session(&session);
     assert!(
         validation
@@ -891,7 +1183,11 @@ fn fixtures_dir() -> PathBuf {
 }
 
 fn seed_cc_fixture(claude_home: &Path) -> String {
-    let src = fixtures_dir().join("claude_code/cc_simple.jsonl");
+    seed_named_cc_fixture(claude_home, "cc_simple")
+}
+
+fn seed_named_cc_fixture(claude_home: &Path, name: &str) -> String {
+    let src = fixtures_dir().join(format!("claude_code/{name}.jsonl"));
     let first_line: serde_json::Value = {
         let content = std::fs::read_to_string(&src).expect("read cc_simple fixture");
         serde_json::from_str(content.lines().next().unwrap()).expect("parse first line")
@@ -979,6 +1275,110 @@ fn pipeline_real_cc_to_gemini_happy_path() {
     assert!(result.written.is_some());
 }
 
+#[test]
+fn pipeline_real_cc_to_gemini_translates_tool_names() {
+    let _cc_lock = CC_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let _gemini_lock = GEMINI_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempfile::TempDir::new().unwrap();
+    let _cc_env = EnvGuard::set("CLAUDE_HOME", &tmp.path().join("claude"));
+    let _gemini_env = EnvGuard::set("GEMINI_HOME", &tmp.path().join("gemini"));
+
+    let cc_sid = seed_named_cc_fixture(&tmp.path().join("claude"), "cc_complex");
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(ClaudeCode), Box::new(Gemini)]),
+    };
+
+    let result = pipeline
+        .convert("gmi", &cc_sid, ConvertOptions::default())
+        .expect("real CC→Gemini pipeline with tools should succeed");
+
+    let calls: Vec<_> = result
+        .canonical_session
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_calls)
+        .collect();
+    let names: Vec<&str> = calls.iter().map(|tc| tc.name.as_str()).collect();
+    assert_eq!(names, ["read_file", "replace"]);
+    assert!(calls[1].arguments.get("file_path").is_some());
+    assert!(calls[1].arguments.get("old_string").is_some());
+
+    let readback = Gemini
+        .read_session(&result.written.unwrap().paths[0])
+        .expect("Gemini read-back should succeed");
+    let readback_names: Vec<&str> = readback
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_calls)
+        .map(|tc| tc.name.as_str())
+        .collect();
+    assert_eq!(readback_names, names);
+}
+
+#[test]
+fn pipeline_real_cc_to_gemini_reports_fidelity() {
+    let _cc_lock = CC_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let _gemini_lock = GEMINI_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempfile::TempDir::new().unwrap();
+    let _cc_env = EnvGuard::set("CLAUDE_HOME", &tmp.path().join("claude"));
+    let _gemini_env = EnvGuard::set("GEMINI_HOME", &tmp.path().join("gemini"));
+
+    let cc_sid = seed_named_cc_fixture(&tmp.path().join("claude"), "cc_complex");
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(ClaudeCode), Box::new(Gemini)]),
+    };
+    let opts = ConvertOptions {
+        max_tool_output: 20,
+        ..ConvertOptions::default()
+    };
+    let result = pipeline
+        .convert("gmi", &cc_sid, opts)
+        .expect("real CC→Gemini pipeline should succeed");
+
+    let fidelity = &result.fidelity;
+    assert!(!fidelity.is_lossless());
+    assert_eq!(fidelity.tool_calls_translated, 2);
+    assert!(fidelity.tool_outputs_truncated > 0, "{fidelity:?}");
+    assert_eq!(fidelity.tool_text_synthesized, 2);
+    assert!(fidelity.tool_calls_rendered_as_text.is_empty());
+    // Gemini keeps the source record's fields, so no metadata is lost.
+    assert!(fidelity.extra_keys_ignored.is_empty(), "{fidelity:?}");
+
+    let dry = pipeline
+        .convert(
+            "gmi",
+            &cc_sid,
+            ConvertOptions {
+                dry_run: true,
+                ..ConvertOptions::default()
+            },
+        )
+        .unwrap();
+    assert!(dry.fidelity.is_lossless());
+
+    let strict = ConvertOptions {
+        max_tool_output: 20,
+        strict: [StrictCheck::Budget].into(),
+        ..ConvertOptions::default()
+    };
+    let err = pipeline
+        .convert("gmi", &cc_sid, strict)
+        .expect_err("--strict=budget should refuse truncation");
+    match err.downcast_ref::<CasrError>() {
+        Some(CasrError::StrictBudget {
+            tool_outputs_truncated,
+            turns_dropped,
+            ..
+        }) => {
+            assert!(*tool_outputs_truncated > 0);
+            assert_eq!(*turns_dropped, 0);
+        }
+        other => panic!("expected StrictBudget, got {other:?}"),
+    }
+}
+
 #[test]
 fn pipeline_real_dry_run_skips_write() {
     let _cc_lock = CC_ENV.lock().unwrap_or_else(|e| e.into_inner());
@@ -1054,6 +1454,147 @@ fn pipeline_real_same_provider_short_circuit() {
     );
 }
 
+#[test]
+fn pipeline_real_edit_writes_new_cc_session_and_leaves_original() {
+    let _cc_lock = CC_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempfile::TempDir::new().unwrap();
+    let _cc_env = EnvGuard::set("CLAUDE_HOME", &tmp.path().join("claude"));
+
+    let cc_sid = seed_named_cc_fixture(&tmp.path().join("claude"), "cc_complex");
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(ClaudeCode)]),
+    };
+    let original = pipeline
+        .registry
+        .resolve_session(&cc_sid, None)
+        .unwrap()
+        .path;
+    let before = fs::read_to_string(&original).unwrap();
+
+    let result = pipeline
+        .edit(
+            &cc_sid,
+            &[
+                SessionEdit::DropToolOutputs,
+                SessionEdit::Retitle("Trimmed".to_string()),
+            ],
+            EditOptions::default(),
+        )
+        .expect("CC edit should succeed");
+
+    let written = result.written.expect("edit should write");
+    assert_ne!(written.session_id, cc_sid);
+    assert!(result.summary.tool_outputs_dropped > 0);
+    assert_eq!(fs::read_to_string(&original).unwrap(), before);
+    let readback = ClaudeCode.read_session(&written.paths[0]).unwrap();
+    assert!(
+        readback
+            .messages
+            .iter()
+            .flat_map(|m| &m.tool_results)
+            .all(|tr| tr.content == casr::rewrite::TOOL_OUTPUT_REMOVED)
+    );
+}
+
+#[test]
+fn pipeline_real_edit_in_place_redacts_codex_session_and_keeps_bak() {
+    let _codex_lock = CODEX_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempfile::TempDir::new().unwrap();
+    let _codex_env = EnvGuard::set("CODEX_HOME", &tmp.path().join("codex"));
+
+    let sessions = tmp.path().join("codex/sessions/2026/01/01");
+    fs::create_dir_all(&sessions).unwrap();
+    let original = sessions.join("rollout-2026-01-01T00-00-00-codex-modern-001.jsonl");
+    fs::copy(fixtures_dir().join("codex/codex_modern.jsonl"), &original).unwrap();
+    let before = fs::read_to_string(&original).unwrap();
+    let needle = "the";
+    assert!(before.contains(needle));
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(Codex)]),
+    };
+    let result = pipeline
+        .edit(
+            "codex-modern-001",
+            &[SessionEdit::Redact(needle.to_string())],
+            EditOptions {
+                in_place: true,
+                ..Default::default()
+            },
+        )
+        .expect("in-place Codex edit should succeed");
+
+    let written = result.written.expect("edit should write");
+    assert_eq!(written.session_id, "codex-modern-001");
+    let backup = result
+        .original_backup
+        .expect("original should be set aside");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), before);
+    assert!(!original.exists());
+    let rewritten = fs::read_to_string(&written.paths[0]).unwrap();
+    assert!(rewritten.contains("[REDACTED]"));
+    // The ID now resolves to the rewrite alone.
+    let resolved = pipeline
+        .registry
+        .resolve_session("codex-modern-001", None)
+        .unwrap();
+    assert_eq!(resolved.path, written.paths[0]);
+}
+
+#[test]
+fn pipeline_real_edit_in_place_keeps_pre_compaction_codex_turns() {
+    let _codex_lock = CODEX_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempfile::TempDir::new().unwrap();
+    let _codex_env = EnvGuard::set("CODEX_HOME", &tmp.path().join("codex"));
+
+    let sessions = tmp.path().join("codex/sessions/2026/01/01");
+    fs::create_dir_all(&sessions).unwrap();
+    let original = sessions.join("rollout-2026-01-01T00-00-00-codex-compacted-001.jsonl");
+    let lines = [
+        r#"{"type":"session_meta","timestamp":"2026-01-01T00:00:00Z","payload":{"id":"codex-compacted-001","cwd":"/tmp/p"}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:01Z","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Early secret question"}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:02Z","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Early answer"}]}}"#,
+        r#"{"type":"compacted","timestamp":"2026-01-01T00:00:03Z","payload":{"replacement_history":[{"type":"message","role":"user","content":[{"type":"input_text","text":"Summary of the early work"}]}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:04Z","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Later question"}]}}"#,
+        r#"{"type":"response_item","timestamp":"2026-01-01T00:00:05Z","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Later secret answer"}]}}"#,
+    ];
+    fs::write(&original, lines.join("\n") + "\n").unwrap();
+
+    let pipeline = ConversionPipeline {
+        registry: ProviderRegistry::new(vec![Box::new(Codex)]),
+    };
+    let result = pipeline
+        .edit(
+            "codex-compacted-001",
+            &[SessionEdit::Redact("secret".to_string())],
+            EditOptions {
+                in_place: true,
+                ..Default::default()
+            },
+        )
+        .expect("in-place edit of a compacted Codex session should succeed");
+
+    let written = result.written.expect("edit should write");
+    let readback = Codex
+        .read_session_with(
+            &written.paths[0],
+            &ReadOptions {
+                history: HistoryMode::Full,
+            },
+        )
+        .unwrap();
+    let texts: Vec<&str> = readback
+        .messages
+        .iter()
+        .map(|m| m.content.as_str())
+        .collect();
+    assert!(
+        texts.contains(&"Early [REDACTED] question") && texts.contains(&"Early answer"),
+        "pre-compaction turns must survive the rewrite: {texts:?}"
+    );
+    assert!(texts.contains(&"Later [REDACTED] answer"), "{texts:?}");
+}
+
 #[test]
 fn pipeline_real_source_hint_narrows_resolution() {
     let _cc_lock = CC_ENV.lock().unwrap_or_else(|e| e.into_inner());